@@ -68,7 +68,7 @@ pub trait DataTypeTrait<UserState>: PartialEq + Eq {
 ///     }
 /// }
 /// ```
-    fn name(&self) -> std::borrow::Cow<str>;
+    fn name(&self) -> std::borrow::Cow<'_, str>;
 }
 
 /// 此trait必须为[`Graph`]的`NodeData`泛型参数实现。此trait允许自定义节点绘制的某些方面。
@@ -219,7 +219,7 @@ pub trait NodeTemplateTrait: Clone {
     /// 返回节点类型的描述性名称，用于节点查找器。
 ///
 /// 返回类型为Cow<str>，以允许更灵活地返回拥有或借用的值。有关更多信息，请参阅`DataTypeTrait::name`的文档
-    fn node_finder_label(&self, user_state: &mut Self::UserState) -> std::borrow::Cow<str>;
+    fn node_finder_label(&self, user_state: &mut Self::UserState) -> std::borrow::Cow<'_, str>;
 
     /// 节点所属的类别向量。
 ///
@@ -1,7 +1,7 @@
 // 配电回路节点功能验证脚本
 
-use power_distribution_system_diagram::editor::business::{CircuitNode};
-use power_distribution_system_diagram::core_lib::data_types::{CircuitNodeProperties, CircuitType};
+use Power_Distribution_System_Diagram::editor::business::{CircuitNode};
+use Power_Distribution_System_Diagram::core_lib::data_types::CircuitType;
 
 fn main() {
     println!("=== 配电回路节点功能验证 ===");
@@ -17,7 +17,7 @@ fn main() {
     
     // 2. 测试三相信路节点创建
     println!("\n2. 创建三相信路节点");
-    let mut three_phase_node = CircuitNode::new_three_phase("动力回路", 20.0);
+    let three_phase_node = CircuitNode::new_three_phase("动力回路", 20.0);
     println!("   - 节点ID: {}", three_phase_node.id);
     println!("   - 回路名称: {}", three_phase_node.parameters.name);
     println!("   - 回路类型: {}", three_phase_node.properties.circuit_type.to_str());
@@ -1,12 +1,8 @@
-/// 节点编辑器示例程序
-/// 演示如何使用配电系统图编辑器的节点编辑器组件
+//! 节点编辑器示例程序
+//! 演示如何使用配电系统图编辑器的节点编辑器组件
 
-// 注意：此示例目前处于开发阶段，暂不提供完整功能
-// 一旦核心组件实现完成，可以取消下面的注释并完善示例
-
-/*
 use eframe::{run_native, App, Frame}; 
-use egui::{Context, Ui}; 
+use eframe::egui::{self, Context, Ui}; 
 use Power_Distribution_System_Diagram::editor::ui::NodeEditor;
 
 // 定义应用程序状态
@@ -51,7 +47,7 @@ impl NodeEditorExampleApp {
 fn main() {
     // 设置窗口选项
     let options = eframe::NativeOptions {
-        initial_window_size: Some(egui::vec2(800.0, 600.0)),
+        viewport: egui::ViewportBuilder::default().with_inner_size([800.0, 600.0]),
         ..Default::default()
     };
     
@@ -59,7 +55,6 @@ fn main() {
     run_native(
         "配电系统图节点编辑器示例",
         options,
-        Box::new(|_cc| Box::new(NodeEditorExampleApp::default())),
+        Box::new(|_cc| Ok(Box::new(NodeEditorExampleApp::default()))),
     ).unwrap();
 }
-*/
\ No newline at end of file
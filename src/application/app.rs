@@ -1,18 +1,27 @@
-use crate::editor::{DataType, UIValueType, UIUserState, UIResponse};
-use crate::editor::business::{CircuitNode, DistributionBoxNodeUI, PowerGraphNode, DataFlowManager, AutoConnectionManager, CircuitType};
-use crate::core_lib::data_types::ElectricValueType;
+use crate::editor::{UIValueType, UIResponse};
+use crate::editor::business::{PowerGraphNode, DataFlowManager, AutoConnectionManager};
 use crate::editor::graph::PowerDistributionGraphEditorState;
-use crate::editor::business::{all_electric_templates, ElectricNodeTemplate};
-use crate::editor::ui::{NodeEditor, custom_connections::draw_custom_connection, node_groups::NodeGroupManager, node_search_ui};
+use crate::editor::business::{all_electric_templates, AllElectricTemplates, ElectricNodeTemplate};
+use crate::editor::ui::{NodeEditor, node_groups::NodeGroupManager, node_search::{node_search_ui, NodeSearcher}};
+use crate::editor::ui::{DiagramViewer, DiagramViewerResponse};
 use crate::editor::ui::debug_tools::{log_panel_ui, LOGGER, LogLevel};
+use crate::{log_debug, log_error, log_info, log_trace, log_warn};
 use crate::editor::ui::performance_optimization::{PerformanceOptimizer, performance_settings_ui, performance_stats_ui};
 use crate::application::debug_logger::DebugLogger;
+use crate::application::project::{calculate_boxes, ProjectFile, ProjectIssue};
+use crate::editor::business::{BoxData, Building, ElectricalFirePlanner, MainSystemNodeData, ModuleRegistry};
+use crate::editor::business::building::level_name;
+use crate::export::drawing_frame::{DrawingFrame, SheetSize, TitleBlock};
+use crate::export::load_schedule::LoadSchedule;
+use crate::export::pdf_export::PdfExporter;
+use crate::export::report::CalculationBook;
+use crate::export::svg_export::{CanvasSnapshot, SvgExporter, SvgTheme};
+use crate::import::{read_table, ImportPreview, LoadListImporter};
 use eframe::{App, egui};
 use uuid::Uuid;
 use std::collections::HashMap;
-use std::time::{Instant, Duration};
+use std::time::Instant;
 use chrono::Local;
-use rand; // 添加随机数库导入
 
 /// 电力配电系统设计应用程序主结构体
 pub struct PDSDApp {
@@ -31,18 +40,36 @@ pub struct PDSDApp {
     pub debug_logger: DebugLogger,
     /// 节点分组管理器
     pub group_manager: NodeGroupManager,
-    /// 节点搜索文本
-    pub node_search_text: String,
-    /// 是否显示节点查找器
-    pub show_node_finder: bool,
+    /// 节点搜索器
+    pub node_searcher: NodeSearcher,
     /// 右侧面板激活的标签页
     pub active_right_tab: String,
+    /// 中央画布激活的标签页（节点图/干线系统图）
+    pub active_canvas_tab: String,
+    /// 干线系统图查看器
+    pub diagram_viewer: DiagramViewer,
     /// 数据流向管理器
     pub data_flow_manager: DataFlowManager,
     /// 自动连接管理器
     pub auto_connection_manager: AutoConnectionManager,
     /// 性能优化管理器
     pub performance_optimizer: PerformanceOptimizer,
+    /// 建筑楼层与竖井（配电箱楼层归属、干线系统图分层及进线长度估算）
+    pub building: Building,
+    /// 按统一层高生成楼层的参数（地下层数、地上层数、层高m）
+    pub floor_template: (u32, u32, f64),
+    /// 配电箱功能模块注册表，属性面板从中选择要添加的模块
+    pub module_registry: ModuleRegistry,
+    /// 项目计算及校验发现的问题
+    pub project_issues: Vec<ProjectIssue>,
+    /// 项目设置窗口是否打开
+    pub show_project_settings: bool,
+    /// 负荷清单导入窗口是否打开
+    pub show_import_window: bool,
+    /// 负荷清单文件路径（CSV/XLSX）
+    pub import_path: String,
+    /// 负荷清单导入预览，确认后写入画布
+    pub import_preview: Option<ImportPreview>,
     /// 上次更新时间
     pub last_update_time: Instant,
     /// 帧率计算相关
@@ -61,12 +88,21 @@ impl Default for PDSDApp {
             node_editor: NodeEditor::new(),
             debug_logger: DebugLogger::new(),
             group_manager: NodeGroupManager::default(),
-            node_search_text: String::new(),
-            show_node_finder: false,
+            node_searcher: NodeSearcher::new(),
             active_right_tab: "属性".to_string(),
+            active_canvas_tab: "节点图".to_string(),
+            diagram_viewer: DiagramViewer::new(),
             data_flow_manager: DataFlowManager::new(),
             auto_connection_manager: AutoConnectionManager::new(),
             performance_optimizer: PerformanceOptimizer::new(),
+            building: Building::default(),
+            floor_template: (1, 6, 4.5),
+            module_registry: ModuleRegistry::builtin(),
+            project_issues: Vec::new(),
+            show_project_settings: false,
+            show_import_window: false,
+            import_path: String::new(),
+            import_preview: None,
             last_update_time: Instant::now(),
             frame_count: 0,
             last_fps_update_time: Instant::now(),
@@ -96,11 +132,11 @@ impl App for PDSDApp {
                 ui.collapsing("配电回路", |ui| {
                     if ui.button("单相配电回路").clicked() {
                         // 添加单相配电回路节点
-                        self.add_circuit_node(CircuitType::SinglePhase);
+                        self.add_template_node(ElectricNodeTemplate::SinglePhaseCircuitNode);
                     }
                     if ui.button("三相配电回路").clicked() {
                         // 添加三相配电回路节点
-                        self.add_circuit_node(CircuitType::ThreePhase);
+                        self.add_template_node(ElectricNodeTemplate::ThreePhaseCircuitNode);
                     }
                 });
 
@@ -108,7 +144,7 @@ impl App for PDSDApp {
                 ui.collapsing("配电箱", |ui| {
                     if ui.button("标准配电箱").clicked() {
                         // 添加标准配电箱节点
-                        self.add_distribution_box_node();
+                        self.add_template_node(ElectricNodeTemplate::DistributionBoxNode);
                     }
                 });
 
@@ -116,7 +152,7 @@ impl App for PDSDApp {
                 ui.collapsing("干线系统", |ui| {
                     if ui.button("干线系统图").clicked() {
                         // 添加干线系统图节点
-                        self.add_main_system_node();
+                        self.add_template_node(ElectricNodeTemplate::MainSystemNode);
                     }
                 });
             });
@@ -155,12 +191,10 @@ impl App for PDSDApp {
                     },
                     "搜索" => {
                         // 节点搜索面板
-                        node_search_ui(
-                            ui,
-                            &mut self.node_search_text,
-                            &mut self.show_node_finder,
-                            &self.editor_state.graph
-                        );
+                        if let Some(node_id) = node_search_ui(ui, &mut self.node_searcher, &self.editor_state.editor_state) {
+                            self.editor_state.focus_node(node_id, ctx.screen_rect().size());
+                            self.active_right_tab = "属性".to_string();
+                        }
                     },
                     "性能" => {
                         // 性能设置和统计
@@ -196,7 +230,7 @@ impl App for PDSDApp {
                 
                 if ui.button("项目设置").clicked() {
                     // 打开项目设置对话框
-                    self.open_project_settings(ui);
+                    self.open_project_settings();
                 }
             });
             
@@ -204,39 +238,42 @@ impl App for PDSDApp {
 
             // 创建工具栏
             ui.horizontal(|ui| {
-                // 添加节点按钮
-                if ui.button("添加节点").clicked() {
-                    // 创建上下文菜单显示可用节点类型，按类别分组
-                    ui.menu_button("选择节点类型", |ui| {
-                        // 按类别组织节点模板
-                        let templates = all_electric_templates();
-
-                        // 收集所有唯一类别
-                        let mut categories = std::collections::HashSet::new();
-                        for template in &templates {
-                            categories.insert(template.category());
+                // 添加节点菜单，按类别分组显示可用节点类型
+                ui.menu_button("添加节点", |ui| {
+                    let templates = all_electric_templates();
+
+                    // 按模板顺序收集类别
+                    let mut categories = Vec::new();
+                    for template in &templates {
+                        if !categories.contains(&template.category()) {
+                            categories.push(template.category());
                         }
+                    }
 
-                        // 按类别显示节点
-                        for category in categories.into_iter() {
-                            ui.menu_button(category, |ui| {
-                                for template in &templates {
-                                    if template.category() == category {
-                                        if ui.button(template.node_label()).clicked() {
-                                            // 添加新节点
-                                            let _ = self.editor_state.graph.add_node(
-                                                template.node_label().to_string(),
-                                                template.user_data(&mut self.editor_state.user_state),
-                                                |graph, node_id| template.build_node(graph, &mut self.editor_state.user_state, node_id),
-                                            );
-                                            ui.close_menu();
-                                        }
-                                    }
+                    // 按类别显示节点
+                    for category in categories {
+                        ui.menu_button(category, |ui| {
+                            for template in templates.iter().filter(|template| template.category() == category) {
+                                if ui.button(template.node_label()).clicked() {
+                                    self.add_template_node(*template);
+                                    ui.close();
                                 }
-                            });
-                        }
-                    });
-                }
+                            }
+                        });
+                    }
+                });
+
+                // 编辑菜单：复制粘贴选中节点
+                ui.menu_button("编辑", |ui| {
+                    if ui.add_enabled(self.editor_state.selected_node().is_some(), egui::Button::new("复制节点")).clicked() {
+                        self.copy_selected_node();
+                        ui.close();
+                    }
+                    if ui.add_enabled(self.editor_state.clipboard.is_some(), egui::Button::new("粘贴节点")).clicked() {
+                        self.paste_node();
+                        ui.close();
+                    }
+                });
 
                 // 保存和加载按钮
                 if ui.button("保存项目").clicked() {
@@ -257,11 +294,14 @@ impl App for PDSDApp {
                     }
                 }
 
+                if ui.button("导入负荷清单").clicked() {
+                    self.show_import_window = true;
+                }
+
                 // 运行计算按钮
                 if ui.button("运行计算").clicked() {
                     self.run_calculations();
                     // 触发数据流向更新
-                    self.data_flow_manager.propagate_updates(&mut self.editor_state.graph);
                     self.error_message = Some("计算完成".to_string());
                 }
 
@@ -273,6 +313,50 @@ impl App for PDSDApp {
                         self.error_message = Some("报告生成成功".to_string());
                     }
                 }
+
+                // 导出菜单
+                ui.menu_button("导出", |ui| {
+                    if ui.button("画布SVG").clicked() {
+                        self.error_message = Some(match self.export_canvas_svg() {
+                            Ok(path) => format!("画布已导出到: {}", path.display()),
+                            Err(e) => format!("画布导出失败: {}", e),
+                        });
+                        ui.close();
+                    }
+                    if ui.button("干线系统图SVG").clicked() {
+                        self.error_message = Some(match self.export_diagram_svgs() {
+                            Ok(paths) => format!("已导出{}张系统图", paths.len()),
+                            Err(e) => format!("系统图导出失败: {}", e),
+                        });
+                        ui.close();
+                    }
+                    for (label, trunk) in [("配电箱系统图PDF", false), ("干线系统图PDF", true)] {
+                        if ui.button(label).clicked() {
+                            self.error_message = Some(match self.export_diagram_pdf(trunk) {
+                                Ok(path) => format!("系统图已导出到: {}", path.display()),
+                                Err(e) => format!("系统图导出失败: {}", e),
+                            });
+                            ui.close();
+                        }
+                    }
+                    ui.separator();
+                    for (label, xlsx) in [("负荷一览表XLSX", true), ("负荷一览表CSV", false)] {
+                        if ui.button(label).clicked() {
+                            self.error_message = Some(match self.export_load_schedule(xlsx) {
+                                Ok(path) => format!("负荷一览表已导出到: {}", path.display()),
+                                Err(e) => format!("负荷一览表导出失败: {}", e),
+                            });
+                            ui.close();
+                        }
+                    }
+                    if ui.button("电气火灾监控点位表").clicked() {
+                        self.error_message = Some(match self.export_detector_schedule() {
+                            Ok(path) => format!("点位表已导出到: {}", path.display()),
+                            Err(e) => format!("点位表导出失败: {}", e),
+                        });
+                        ui.close();
+                    }
+                });
             });
 
             // 显示错误信息
@@ -285,47 +369,60 @@ impl App for PDSDApp {
                 }
             }
 
-            // 创建一个可滚动区域
-            egui::ScrollArea::both().show(ui, |ui| {
+            // 画布标签页：节点图编辑器或干线系统图查看器
+            ui.horizontal(|ui| {
+                for tab in ["节点图", "干线系统图"] {
+                    if ui.selectable_label(self.active_canvas_tab == tab, tab).clicked() {
+                        self.active_canvas_tab = tab.to_string();
+                    }
+                }
+            });
+
+            if self.active_canvas_tab == "干线系统图" {
+                self.show_diagram_viewer(ui);
+            } else {
                 // 更新性能优化器的视口信息
                 let viewport = ui.ctx().input(|i| i.screen_rect);
                 self.performance_optimizer.update_viewport(viewport);
-                
-                // 调整容器大小
-                let (_id, response) = ui.allocate_space(egui::Vec2::new(
-                    ui.available_width(),
-                    ui.available_height() - 50.0, // 留出状态栏空间
-                ));
-
-                // 记录渲染开始时间
-                let render_start_time = Instant::now();
-                
-                // 绘制节点图编辑器
-                let node_responses = egui_node_graph::draw_graph_editor(
-                    ui,
-                    &mut self.editor_state.graph,
-                    &mut self.editor_state.editor_state,
-                    &mut self.editor_state.user_state,
-                    None, // 可选的背景
-                );
-
-                // 处理节点响应事件
-                self.handle_node_responses(node_responses);
-                
-                // 记录渲染时间
-                self.performance_optimizer.update_render_time(render_start_time.elapsed());
-            });
+
+                // 节点图占用除状态栏以外的剩余空间
+                let editor_size = egui::vec2(ui.available_width(), (ui.available_height() - 30.0).max(0.0));
+                ui.allocate_ui(editor_size, |ui| {
+                    ui.set_clip_rect(ui.max_rect());
+
+                    // 记录渲染开始时间
+                    let render_start_time = Instant::now();
+
+                    // 绘制节点图编辑器
+                    let graph_response = self.editor_state.editor_state.draw_graph_editor(
+                        ui,
+                        AllElectricTemplates,
+                        &mut self.editor_state.user_state,
+                        Vec::new(),
+                    );
+
+                    // 处理节点响应事件
+                    self.handle_node_responses(graph_response.node_responses);
+
+                    // 记录渲染时间
+                    self.performance_optimizer.update_render_time(render_start_time.elapsed());
+                });
+            }
 
             // 状态栏显示
             ui.separator();
             ui.horizontal(|ui| {
-                ui.label(format!("节点数量: {}", self.editor_state.graph.nodes.len()));
-                ui.label(format!("连接数量: {}", self.editor_state.graph.connections.len()));
+                ui.label(format!("节点数量: {}", self.editor_state.editor_state.graph.nodes.len()));
+                ui.label(format!("连接数量: {}", self.editor_state.editor_state.graph.iter_connections().count()));
                 ui.label(format!("计算缓存: {}", self.calculation_cache.len()));
                 ui.label(format!("FPS: {:.1}", self.performance_optimizer.get_stats().current_fps));
             });
         });
         
+        self.show_import_window(ctx);
+        self.show_project_settings(ctx);
+        self.handle_copy_paste(ctx);
+
         // 记录更新时间
         self.performance_optimizer.update_update_time(update_start_time.elapsed());
     }
@@ -334,16 +431,16 @@ impl App for PDSDApp {
 impl PDSDApp {
     // 绘制节点属性面板
     fn draw_node_properties(&mut self, ui: &mut egui::Ui, node_id: egui_node_graph::NodeId) {
-        if let Some(node) = self.editor_state.graph.nodes.get_mut(node_id) {
+        let mut recalculate = false;
+        if let Some(node) = self.editor_state.editor_state.graph.nodes.get_mut(node_id) {
             match &mut node.user_data {
                 PowerGraphNode::CircuitNode(circuit) => {
                     ui.heading("配电回路属性");
-                    ui.text_edit_singleline(&mut circuit.name);
+                    ui.text_edit_singleline(&mut circuit.circuit_name);
                     ui.add(egui::Slider::new(&mut circuit.power, 0.1..=100.0).text("功率 (kW)"));
                     if ui.button("更新计算").clicked() {
                         // 执行计算逻辑
-                        self.data_flow_manager.mark_node_for_update(node_id);
-                        self.run_calculations();
+                        recalculate = true;
                     }
 
                     // 显示计算结果
@@ -363,13 +460,91 @@ impl PDSDApp {
                 PowerGraphNode::DistributionBoxNode(box_node) => {
                     ui.heading("配电箱属性");
                     ui.text_edit_singleline(&mut box_node.name);
-                    ui.add(egui::Slider::new(&mut box_node.floor, 1..=50).text("所在楼层"));
+                    // 建筑已定义楼层时从楼层列表选择，否则按楼层号输入（地下室为负数，跳过0）
+                    if self.building.is_empty() {
+                        let previous = box_node.floor;
+                        ui.add(egui::Slider::new(&mut box_node.floor, -5..=50).text("所在楼层"));
+                        if box_node.floor == 0 {
+                            box_node.floor = if previous > 0 { -1 } else { 1 };
+                        }
+                    } else {
+                        egui::ComboBox::from_label("所在楼层")
+                            .selected_text(self.building.floor_name(box_node.floor))
+                            .show_ui(ui, |ui| {
+                                for floor in &self.building.floors {
+                                    ui.selectable_value(&mut box_node.floor, floor.level, &floor.name);
+                                }
+                            });
+                    }
+                    if !self.building.shafts.is_empty() {
+                        let shaft_name = box_node
+                            .shaft
+                            .as_ref()
+                            .and_then(|id| self.building.shaft(id))
+                            .map_or_else(|| "按名称推导".to_string(), |shaft| shaft.name.clone());
+                        egui::ComboBox::from_label("竖井").selected_text(shaft_name).show_ui(ui, |ui| {
+                            ui.selectable_value(&mut box_node.shaft, None, "按名称推导");
+                            for shaft in self.building.shafts.iter().filter(|shaft| shaft.serves(box_node.floor)) {
+                                ui.selectable_value(&mut box_node.shaft, Some(shaft.id.clone()), &shaft.name);
+                            }
+                        });
+                    }
+
+                    // 功能模块：从模块注册表选择添加，同类模块只保留一个
+                    ui.label("功能模块:");
+                    let mut removed = None;
+                    for (index, module) in box_node.modules.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(module.name());
+                            if ui.small_button("移除").clicked() {
+                                removed = Some(index);
+                            }
+                        });
+                    }
+                    if let Some(index) = removed {
+                        box_node.modules.remove(index);
+                        recalculate = true;
+                    }
+                    egui::ComboBox::from_label("添加模块").selected_text("选择模块").show_ui(ui, |ui| {
+                        for definition in self.module_registry.definitions() {
+                            if ui.selectable_label(false, &definition.name).clicked() {
+                                box_node.add_module((definition.create)());
+                                recalculate = true;
+                            }
+                        }
+                    });
+
+                    // 平面位置用于估算进线电缆长度，手动输入的长度优先于估算值
+                    ui.horizontal(|ui| {
+                        let mut placed = box_node.position.is_some();
+                        if ui.checkbox(&mut placed, "平面位置(m)").changed() {
+                            box_node.position = placed.then_some((0.0, 0.0));
+                        }
+                        if let Some((x, y)) = &mut box_node.position {
+                            ui.add(egui::DragValue::new(x).prefix("x "));
+                            ui.add(egui::DragValue::new(y).prefix("y "));
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        let mut entered = box_node.feeder_length.is_some();
+                        if ui.checkbox(&mut entered, "手动输入进线长度").changed() {
+                            let estimate = box_node.feeder_estimate.as_ref().map_or(0.0, |e| e.total());
+                            box_node.feeder_length = entered.then_some(estimate);
+                        }
+                        if let Some(length) = &mut box_node.feeder_length {
+                            ui.add(egui::DragValue::new(length).range(0.0..=5000.0).suffix(" m"));
+                        }
+                    });
 
                     // 显示计算结果
                     ui.group(|ui| {
                         ui.label(format!("总功率: {:.2} kW", box_node.total_power));
                         ui.label(format!("总电流: {:.2} A", box_node.total_current));
                         ui.label(format!("进线保护电流: {:.0} A", box_node.incoming_current));
+                        match box_node.incoming_length() {
+                            Some((length, source)) => ui.label(format!("进线长度: {:.1} m（{}）", length, source.to_str())),
+                            None => ui.label("进线长度: 未统计"),
+                        };
                         ui.label(format!("L1相负载: {:.2} kW", box_node.phase_loads[0]));
                         ui.label(format!("L2相负载: {:.2} kW", box_node.phase_loads[1]));
                         ui.label(format!("L3相负载: {:.2} kW", box_node.phase_loads[2]));
@@ -377,8 +552,7 @@ impl PDSDApp {
 
                     // 三相平衡控制
                     if ui.button("重新平衡三相").clicked() {
-                        self.data_flow_manager.mark_node_for_update(node_id);
-                        self.run_calculations();
+                        recalculate = true;
                     }
                 },
                 PowerGraphNode::TrunkLineNode(system_node) => {
@@ -390,33 +564,221 @@ impl PDSDApp {
                     // 这里需要根据实际的数据结构实现系统类型选择
                     
                     if ui.button("更新系统图").clicked() {
-                        self.data_flow_manager.mark_node_for_update(node_id);
-                        self.run_calculations();
+                        recalculate = true;
                     }
                 },
-                _ => {
+                PowerGraphNode::Other(data) => {
                     // 其他类型节点的属性显示
                     ui.label(format!("节点名称: {}", node.label));
-                    ui.label(format!("节点类型: {:?}", node.user_data.node_type));
+                    ui.label(format!("节点类型: {}", data.get_type_name()));
                 }
             }
         }
+
+        // 属性修改后重新计算（需在释放节点借用后进行）
+        if recalculate {
+            self.data_flow_manager.mark_node_for_update(node_id);
+            self.run_calculations();
+        }
     }
     
-    // 保存项目
+    // 保存项目：将画布中的配电箱和干线系统图（含手动调整的位置）写入项目文件，命令行工具可直接读取计算和导出
     fn save_project(&self) -> Result<(), Box<dyn std::error::Error>> {
-        // 创建项目数据结构
-        // 这里需要实现项目数据的序列化和保存逻辑
-        // 目前仅作为占位符实现
-        println!("保存项目: {}", self.project_name);
+        let mut project = self.to_project_file();
+        project.info.last_modified = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        project.save(std::path::Path::new(&format!("{}.json", self.project_name)))?;
         Ok(())
     }
 
-    // 加载项目
+    // 按画布内容生成项目文件：配电箱及干线系统图
+    fn to_project_file(&self) -> ProjectFile {
+        let mut project = ProjectFile::new(&self.project_name);
+        project.info.project_id = self.project_id.to_string();
+        project.building = self.building.clone();
+        project.boxes = self
+            .editor_state
+            .editor_state
+            .graph
+            .nodes
+            .values()
+            .filter_map(|node| match &node.user_data {
+                PowerGraphNode::DistributionBoxNode(box_node) => Some(box_node.clone()),
+                _ => None,
+            })
+            .collect();
+        project.diagrams = self
+            .editor_state
+            .editor_state
+            .graph
+            .nodes
+            .values()
+            .filter_map(|node| match &node.user_data {
+                PowerGraphNode::TrunkLineNode(system_node) => Some(system_node.diagrams.clone()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        project
+    }
+
+    // 将配电箱数据写回画布：按持久标识更新已有配电箱节点，其余新建节点
+    // 回路随配电箱数据一起写入节点，不单独创建回路节点（见import::load_list模块说明）
+    fn sync_box_nodes(&mut self, boxes: &[BoxData]) {
+        for box_data in boxes {
+            match self.find_box_node(&box_data.id) {
+                Some(node_id) => {
+                    let node = &mut self.editor_state.editor_state.graph.nodes[node_id];
+                    node.label = box_data.name.clone();
+                    node.user_data = PowerGraphNode::DistributionBoxNode(box_data.clone());
+                    self.data_flow_manager.mark_node_for_update(node_id);
+                },
+                None => {
+                    let offset = (self.editor_state.editor_state.graph.nodes.len() % 10) as f32 * 30.0;
+                    let position = egui::pos2(120.0 + offset, 120.0 + offset);
+                    self.editor_state.add_node(
+                        ElectricNodeTemplate::DistributionBoxNode,
+                        PowerGraphNode::DistributionBoxNode(box_data.clone()),
+                        position,
+                    );
+                },
+            }
+        }
+    }
+
+    // 负荷清单导入窗口：读取表格生成预览，确认后写入画布中的配电箱节点
+    fn show_import_window(&mut self, ctx: &egui::Context) {
+        if !self.show_import_window {
+            return;
+        }
+        let (mut open, mut preview, mut apply) = (true, false, false);
+        egui::Window::new("导入负荷清单").open(&mut open).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("文件（CSV/XLSX）:");
+                ui.text_edit_singleline(&mut self.import_path);
+                preview = ui.button("预览").clicked();
+            });
+            if let Some(import_preview) = &self.import_preview {
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    ui.monospace(import_preview.to_text());
+                });
+                apply = ui
+                    .add_enabled(!import_preview.rows.is_empty(), egui::Button::new("确认导入"))
+                    .clicked();
+            }
+        });
+
+        if preview {
+            self.preview_import();
+        }
+        if apply {
+            self.apply_import();
+            open = false;
+        }
+        self.show_import_window = open;
+    }
+
+    // 生成负荷清单导入预览，同名配电箱在画布中已有回路之后追加
+    fn preview_import(&mut self) {
+        let existing = self.to_project_file().boxes;
+        let preview = read_table(std::path::Path::new(self.import_path.trim()))
+            .and_then(|table| LoadListImporter::preview(&table, None, &existing));
+        match preview {
+            Ok(preview) => self.import_preview = Some(preview),
+            Err(e) => {
+                self.import_preview = None;
+                self.error_message = Some(format!("读取负荷清单失败: {}", e));
+            },
+        }
+    }
+
+    // 确认导入：预览结果写入项目后同步到画布并重新计算
+    fn apply_import(&mut self) {
+        let Some(preview) = self.import_preview.take() else {
+            return;
+        };
+        let mut project = self.to_project_file();
+        let count = preview.apply(&mut project);
+        self.sync_box_nodes(&project.boxes);
+        self.run_calculations();
+        self.debug_logger.info(&format!("导入负荷清单: {}，回路{}个", self.import_path, count));
+        self.error_message = Some(format!("已导入{}个回路", count));
+    }
+
+    // 干线系统图查看器：显示各干线系统图节点生成的系统图，双击配电箱跳转到对应节点
+    fn show_diagram_viewer(&mut self, ui: &mut egui::Ui) {
+        let view_size = ui.available_size();
+        let mut diagrams: Vec<_> = self
+            .editor_state
+            .editor_state
+            .graph
+            .nodes
+            .values_mut()
+            .filter_map(|node| match &mut node.user_data {
+                PowerGraphNode::TrunkLineNode(system_node) => Some(system_node.diagrams.iter_mut()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+
+        match self.diagram_viewer.ui(ui, &mut diagrams) {
+            Some(DiagramViewerResponse::OpenBox(box_id)) => {
+                if let Some(node_id) = self.find_box_node(&box_id) {
+                    self.editor_state.focus_node(node_id, view_size);
+                    self.active_canvas_tab = "节点图".to_string();
+                    self.active_right_tab = "属性".to_string();
+                } else {
+                    self.error_message = Some("节点图中未找到该配电箱".to_string());
+                }
+            },
+            Some(DiagramViewerResponse::ComponentMoved(name)) => {
+                self.debug_logger.info(&format!("手动调整系统图'{}'的组件位置", name));
+            },
+            None => {}
+        }
+    }
+
+    // 按配电箱持久标识查找配电箱节点
+    fn find_box_node(&self, box_id: &str) -> Option<egui_node_graph::NodeId> {
+        self.editor_state
+            .graph()
+            .nodes
+            .iter()
+            .find(|(_, node)| matches!(&node.user_data, PowerGraphNode::DistributionBoxNode(box_node) if box_node.id == box_id))
+            .map(|(node_id, _)| node_id)
+    }
+
+    // 加载项目：读取保存的项目文件，按其中的配电箱和干线系统图重建画布
     fn load_project(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        // 这里需要实现项目数据的加载和反序列化逻辑
-        // 目前仅作为占位符实现
-        println!("加载项目");
+        let project = ProjectFile::load(std::path::Path::new(&format!("{}.json", self.project_name)))?;
+
+        self.editor_state.clear();
+        self.data_flow_manager.clear_cache();
+        self.calculation_cache.clear();
+        self.project_issues.clear();
+        if !project.info.name.is_empty() {
+            self.project_name = project.info.name.clone();
+        }
+        if let Ok(project_id) = Uuid::parse_str(&project.info.project_id) {
+            self.project_id = project_id;
+        }
+
+        self.building = project.building.clone();
+
+        self.sync_box_nodes(&project.boxes);
+        if !project.diagrams.is_empty() {
+            let system_node = MainSystemNodeData {
+                diagrams: project.diagrams.clone(),
+                building: project.building.clone(),
+                ..MainSystemNodeData::default()
+            };
+            self.editor_state.add_node(
+                ElectricNodeTemplate::MainSystemNode,
+                PowerGraphNode::TrunkLineNode(system_node),
+                egui::pos2(480.0, 120.0),
+            );
+        }
+        self.debug_logger.info(&format!("加载项目: {}，配电箱{}个", self.project_name, project.boxes.len()));
         Ok(())
     }
 
@@ -429,8 +791,8 @@ impl PDSDApp {
             ui.horizontal(|ui| {
                 // 日志记录控制
                 if ui.checkbox(&mut self.debug_logger.is_recording, "启用日志记录").changed() {
-                    log_info!(format!("日志记录已{}", 
-                        if self.debug_logger.is_recording { "开启" } else { "关闭" }));
+                    log_info!("日志记录已{}", 
+                        if self.debug_logger.is_recording { "开启" } else { "关闭" });
                 }
                 
                 // 日志级别控制
@@ -463,8 +825,8 @@ impl PDSDApp {
             egui::CollapsingHeader::new("应用状态信息")
                 .default_open(false)
                 .show(ui, |ui| {
-                    ui.label(format!("节点总数: {}", self.editor_state.graph.nodes.len()));
-                    ui.label(format!("连接总数: {}", self.editor_state.graph.connections.len()));
+                    ui.label(format!("节点总数: {}", self.editor_state.editor_state.graph.nodes.len()));
+                    ui.label(format!("连接总数: {}", self.editor_state.editor_state.graph.iter_connections().count()));
                     ui.label(format!("计算缓存条目: {}", self.calculation_cache.len()));
                     ui.label(format!("当前FPS: {:.1}", self.performance_optimizer.get_stats().current_fps));
                     
@@ -488,8 +850,8 @@ impl PDSDApp {
                     // 这里应该打开文件对话框让用户选择保存位置
                     // 目前使用一个固定的文件名作为示例
                     let log_file = format!("pdsd_logs_{}.txt", chrono::Local::now().format("%Y%m%d_%H%M%S"));
-                    if let Ok(_) = LOGGER.export_logs(&log_file) {
-                        log_info!(format!("日志已导出到: {}", log_file));
+                    if LOGGER.export_logs(&log_file).is_ok() {
+                        log_info!("日志已导出到: {}", log_file);
                         self.error_message = Some(format!("日志已导出到: {}", log_file));
                     } else {
                         log_error!("日志导出失败");
@@ -511,7 +873,7 @@ impl PDSDApp {
     fn log_node_execution(&self, node_id: egui_node_graph::NodeId, inputs: &[UIValueType], 
                         result: Option<UIValueType>, level: LogLevel) {
         // 根据日志级别记录执行信息
-        let node_info = match self.editor_state.graph.nodes.get(node_id) {
+        let node_info = match self.editor_state.editor_state.graph.nodes.get(node_id) {
             Some(node) => format!("{} (ID: {:?})", node.label, node_id),
             None => format!("节点ID: {:?}", node_id)
         };
@@ -549,86 +911,313 @@ impl PDSDApp {
         // 记录计算开始时间
         let calc_start_time = Instant::now();
         
-        // 1. 标记所有节点需要更新
-        for node_id in self.editor_state.graph.nodes.keys() {
-            self.data_flow_manager.mark_node_for_update(*node_id);
+        // 1. 标记所有节点需要更新，干线系统图按项目的建筑楼层与竖井分层
+        for node in self.editor_state.editor_state.graph.nodes.values_mut() {
+            if let PowerGraphNode::TrunkLineNode(system_node) = &mut node.user_data {
+                system_node.building = self.building.clone();
+            }
         }
+        let node_ids: Vec<_> = self.editor_state.editor_state.graph.iter_nodes().collect();
+        self.data_flow_manager.mark_nodes_for_update(node_ids);
         
         // 2. 执行图计算逻辑
         self.execute_graph();
         
         // 3. 触发数据流向更新
-        self.data_flow_manager.propagate_updates(&mut self.editor_state.graph);
+        self.data_flow_manager.propagate_updates(&mut self.editor_state.editor_state.graph);
+
+        // 4. 配电箱按项目计算流程计算并校验（与项目文件、命令行工具共用）
+        self.project_issues = self.calculate_boxes();
         
         // 记录计算耗时
         let calc_duration = calc_start_time.elapsed();
-        log_info!(format!("电气系统计算完成，耗时: {:.2} ms", calc_duration.as_secs_f64() * 1000.0));
+        log_info!("电气系统计算完成，耗时: {:.2} ms", calc_duration.as_secs_f64() * 1000.0);
         self.debug_logger.info("电气系统计算完成");
         
         // 记录操作耗时到性能监控器
         self.performance_optimizer.update_update_time(calc_duration);
     }
 
-    // 生成报告
+    // 对画布中的配电箱执行calculate_boxes，计算结果写回节点
+    fn calculate_boxes(&mut self) -> Vec<ProjectIssue> {
+        let graph = &mut self.editor_state.editor_state.graph;
+        let box_ids: Vec<_> = graph
+            .iter_nodes()
+            .filter(|&node_id| matches!(graph.nodes[node_id].user_data, PowerGraphNode::DistributionBoxNode(_)))
+            .collect();
+        let mut boxes: Vec<BoxData> = box_ids
+            .iter()
+            .filter_map(|&node_id| match &mut graph.nodes[node_id].user_data {
+                PowerGraphNode::DistributionBoxNode(box_node) => Some(std::mem::take(box_node)),
+                _ => None,
+            })
+            .collect();
+
+        let issues = calculate_boxes(&self.building, &mut boxes);
+        for (node_id, box_data) in box_ids.into_iter().zip(boxes) {
+            graph.nodes[node_id].user_data = PowerGraphNode::DistributionBoxNode(box_data);
+        }
+        issues
+    }
+
+    // 生成报告：按画布中的配电箱生成计算书，输出Markdown、HTML和PDF到当前目录
     fn generate_report(&self) -> Result<(), Box<dyn std::error::Error>> {
-        println!("生成系统报告");
+        let boxes: Vec<&BoxData> = self
+            .editor_state
+            .editor_state
+            .graph
+            .nodes
+            .values()
+            .filter_map(|node| match &node.user_data {
+                PowerGraphNode::DistributionBoxNode(box_node) => Some(box_node),
+                _ => None,
+            })
+            .collect();
+
+        let book = CalculationBook::from_boxes(&self.project_name, &boxes);
+        let frame = DrawingFrame::new(
+            SheetSize::A3,
+            TitleBlock { project_name: self.project_name.clone(), ..TitleBlock::default() },
+        );
+        let stem = format!("{}计算书", self.project_name);
+        book.export_markdown(std::path::Path::new(&format!("{}.md", stem)))?;
+        book.export_html(std::path::Path::new(&format!("{}.html", stem)))?;
+        book.export_pdf(&frame, std::path::Path::new(&format!("{}.pdf", stem)))?;
         Ok(())
     }
 
+    // 导出画布SVG：节点框、端口、节点图形及连线，输出到当前目录
+    fn export_canvas_svg(&self) -> Result<std::path::PathBuf, Box<dyn std::error::Error>> {
+        let snapshot = CanvasSnapshot::from_graph(
+            &self.editor_state.editor_state.graph,
+            &self.editor_state.editor_state.node_positions,
+        );
+        let path = std::path::PathBuf::from(format!("{}画布.svg", self.project_name));
+        SvgExporter::export_canvas(&snapshot, &path, SvgTheme::Light)?;
+        Ok(path)
+    }
+
+    // 导出干线系统图SVG：各干线系统图节点生成的系统图逐张写入"项目名称系统图"目录
+    fn export_diagram_svgs(&self) -> Result<Vec<std::path::PathBuf>, Box<dyn std::error::Error>> {
+        let diagrams: Vec<_> = self
+            .editor_state
+            .editor_state
+            .graph
+            .nodes
+            .values()
+            .filter_map(|node| match &node.user_data {
+                PowerGraphNode::TrunkLineNode(system_node) => Some(system_node.diagrams.clone()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        let dir = std::path::PathBuf::from(format!("{}系统图", self.project_name));
+        std::fs::create_dir_all(&dir)?;
+        Ok(SvgExporter::export_diagrams(&diagrams, &dir, SvgTheme::Light)?)
+    }
+
+    // 导出系统图PDF：配电箱系统图按回路数分页，干线系统图每张一页，均套A3图框
+    fn export_diagram_pdf(&self, trunk: bool) -> Result<std::path::PathBuf, Box<dyn std::error::Error>> {
+        let project = self.to_project_file();
+        let frame = DrawingFrame::new(
+            SheetSize::A3,
+            TitleBlock { project_name: self.project_name.clone(), ..TitleBlock::default() },
+        );
+        let path = std::path::PathBuf::from(format!("{}{}.pdf", self.project_name, if trunk { "干线系统图" } else { "配电箱系统图" }));
+        if trunk {
+            PdfExporter::export_trunk(&project.diagrams, &frame, &path)?;
+        } else {
+            let boxes: Vec<&BoxData> = project.boxes.iter().collect();
+            PdfExporter::export_boxes(&boxes, &frame, &path)?;
+        }
+        Ok(path)
+    }
+
+    // 导出负荷一览表：XLSX为汇总表加每箱一张工作表，CSV为汇总在前、各箱依次排列
+    fn export_load_schedule(&self, xlsx: bool) -> Result<std::path::PathBuf, Box<dyn std::error::Error>> {
+        let project = self.to_project_file();
+        let boxes: Vec<&BoxData> = project.boxes.iter().collect();
+        let schedule = LoadSchedule::from_boxes(&boxes);
+        let path = std::path::PathBuf::from(format!("{}负荷一览表.{}", self.project_name, if xlsx { "xlsx" } else { "csv" }));
+        if xlsx {
+            schedule.export_xlsx(&path)?;
+        } else {
+            schedule.export_csv(&path)?;
+        }
+        Ok(path)
+    }
+
+    // 导出电气火灾监控探测器点位表，探测器地址与计算后的项目一致
+    fn export_detector_schedule(&self) -> std::io::Result<std::path::PathBuf> {
+        let project = self.to_project_file();
+        let boxes: Vec<&BoxData> = project.boxes.iter().collect();
+        let schedule = ElectricalFirePlanner::detector_schedule(&boxes);
+        let path = std::path::PathBuf::from(format!("{}电气火灾监控点位表.txt", self.project_name));
+        std::fs::write(&path, ElectricalFirePlanner::schedule_to_text(&schedule))?;
+        Ok(path)
+    }
+
     // 打开项目设置对话框
-    fn open_project_settings(&mut self, ui: &mut egui::Ui) {
-        // 实现项目设置对话框将在后续开发
-        println!("打开项目设置");
+    fn open_project_settings(&mut self) {
+        self.show_project_settings = true;
+    }
+
+    // 项目设置窗口：项目名称（同时决定项目文件名）及计算校验问题
+    fn show_project_settings(&mut self, ctx: &egui::Context) {
+        if !self.show_project_settings {
+            return;
+        }
+        let mut open = true;
+        egui::Window::new("项目设置").open(&mut open).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("项目名称:");
+                ui.text_edit_singleline(&mut self.project_name);
+            });
+            ui.label(format!("项目文件: {}.json", self.project_name));
+
+            ui.separator();
+            self.building_settings_ui(ui);
+
+            ui.separator();
+            ui.label(format!("计算校验问题: {}", self.project_issues.len()));
+            egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                for issue in &self.project_issues {
+                    ui.label(egui::RichText::new(issue.to_string()).color(egui::Color32::RED));
+                }
+            });
+        });
+        self.show_project_settings = open;
+    }
+
+    // 建筑楼层与竖井设置：按统一层高生成楼层，逐层调整名称、标高和层高，编辑竖井位置及贯通楼层
+    fn building_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("建筑楼层与竖井");
+        ui.horizontal(|ui| {
+            let (basements, storeys, height) = &mut self.floor_template;
+            ui.add(egui::DragValue::new(basements).range(0..=10).prefix("地下 ").suffix(" 层"));
+            ui.add(egui::DragValue::new(storeys).range(1..=100).prefix("地上 ").suffix(" 层"));
+            ui.add(egui::DragValue::new(height).range(2.0..=20.0).speed(0.1).prefix("层高 ").suffix(" m"));
+            if ui.button("生成楼层").clicked() {
+                let shafts = std::mem::take(&mut self.building.shafts);
+                let source = self.building.source;
+                self.building = Building { shafts, source, ..Building::uniform(*basements, *storeys, *height) };
+            }
+        });
+
+        let mut removed_floor = None;
+        egui::Grid::new("building_floors").striped(true).show(ui, |ui| {
+            ui.label("楼层");
+            ui.label("名称");
+            ui.label("标高(m)");
+            ui.label("层高(m)");
+            ui.end_row();
+            for (i, floor) in self.building.floors.iter_mut().enumerate() {
+                ui.label(level_name(floor.level));
+                ui.text_edit_singleline(&mut floor.name);
+                ui.add(egui::DragValue::new(&mut floor.elevation).speed(0.1));
+                ui.add(egui::DragValue::new(&mut floor.height).range(0.1..=50.0).speed(0.1));
+                if ui.button("删除").clicked() {
+                    removed_floor = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(i) = removed_floor {
+            self.building.floors.remove(i);
+        }
+
+        let mut removed_shaft = None;
+        egui::Grid::new("building_shafts").striped(true).show(ui, |ui| {
+            ui.label("竖井");
+            ui.label("平面位置(m)");
+            ui.label("贯通楼层");
+            ui.end_row();
+            for (i, shaft) in self.building.shafts.iter_mut().enumerate() {
+                ui.text_edit_singleline(&mut shaft.name);
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut shaft.position.0).prefix("x "));
+                    ui.add(egui::DragValue::new(&mut shaft.position.1).prefix("y "));
+                });
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut shaft.levels.0).custom_formatter(|v, _| level_name(v as i32)));
+                    ui.label("~");
+                    ui.add(egui::DragValue::new(&mut shaft.levels.1).custom_formatter(|v, _| level_name(v as i32)));
+                });
+                if ui.button("删除").clicked() {
+                    removed_shaft = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(i) = removed_shaft {
+            self.building.shafts.remove(i);
+        }
+        if ui.button("添加竖井").clicked() {
+            let lowest = self.building.floors.first().map_or(1, |floor| floor.level);
+            let highest = self.building.floors.last().map_or(1, |floor| floor.level);
+            let name = format!("强电井{}", self.building.shafts.len() + 1);
+            self.building.add_shaft(&name, (0.0, 0.0), (lowest, highest));
+        }
+
+        for error in self.building.validate() {
+            ui.label(egui::RichText::new(error.to_string()).color(egui::Color32::RED));
+        }
     }
 
     // 处理节点响应事件
     fn handle_node_responses(&mut self, responses: Vec<egui_node_graph::NodeResponse<UIResponse, PowerGraphNode>>) {
         for response in responses {
             match response {
-                egui_node_graph::NodeResponse::NodeSelected(node_id) => {
+                egui_node_graph::NodeResponse::SelectNode(node_id) |
+                egui_node_graph::NodeResponse::User(UIResponse::NodeSelected(node_id)) => {
                     // 处理节点选择
                     self.editor_state.set_selected_node(Some(node_id));
                 },
-                egui_node_graph::NodeResponse::NodeDeselected => {
-                    // 处理节点取消选择
-                    self.editor_state.set_selected_node(None);
-                },
-                egui_node_graph::NodeResponse::ConnectionCreated(from, to) => {
+                egui_node_graph::NodeResponse::ConnectEventEnded { output, input } => {
                     // 处理连接创建
-                    self.handle_new_connection(from, to);
+                    self.handle_new_connection(output, input);
                 },
-                egui_node_graph::NodeResponse::NodeMoved(node_id, _position) => {
+                egui_node_graph::NodeResponse::DisconnectEvent { input, .. } => {
+                    // 断开连接后目标节点需要重新计算
+                    let node_id = self.editor_state.editor_state.graph.get_input(input).node;
+                    self.data_flow_manager.mark_node_for_update(node_id);
+                },
+                egui_node_graph::NodeResponse::MoveNode { node, .. } => {
                     // 处理节点移动，检查是否拖入配电箱
-                    if let Some(target_box) = self.check_if_node_dropped_into_box(node_id) {
-                        self.move_circuit_to_box(node_id, target_box);
+                    if let Some(target_box) = self.check_if_node_dropped_into_box(node) {
+                        self.move_circuit_to_box(node, target_box);
                     }
                 },
-                egui_node_graph::NodeResponse::NodeDeleted(node_id) => {
+                egui_node_graph::NodeResponse::DeleteNodeFull { node_id, node } => {
                     // 处理节点删除
-                    self.handle_node_deletion(node_id);
+                    self.handle_node_deletion(node_id, &node);
                 },
                 _ => {}
             }
         }
+
+        // 在画布空白处点击会清空选择，同步到当前选中节点
+        if self.editor_state.editor_state.selected_nodes.is_empty() {
+            self.editor_state.selected_node_id = None;
+        }
     }
     
     // 检查节点是否拖入配电箱
     fn check_if_node_dropped_into_box(&self, node_id: egui_node_graph::NodeId) -> Option<egui_node_graph::NodeId> {
         // 1. 获取被移动节点的位置
-        let node_pos = match self.editor_state.editor_state.node_positions.get(&node_id) {
-            Some(pos) => pos,
-            None => return None,
-        };
+        let node_pos = self.editor_state.editor_state.node_positions.get(node_id)?;
 
         // 2. 检查是否在某个配电箱节点范围内
-        for (box_id, box_node) in &self.editor_state.graph.nodes {
-            if let Some(box_pos) = self.editor_state.editor_state.node_positions.get(box_id) {
-                if let PowerGraphNode::DistributionBoxNode(_) = &box_node.user_data {
-                    // 简化处理，实际应用中应检查精确的边界
-                    if (node_pos.0 - box_pos.0).abs() < 100.0 &&
-                       (node_pos.1 - box_pos.1).abs() < 100.0 {
-                        return Some(*box_id);
-                    }
+        for (box_id, box_node) in &self.editor_state.editor_state.graph.nodes {
+            if box_id == node_id {
+                continue;
+            }
+            if let Some(box_pos) = self.editor_state.editor_state.node_positions.get(box_id)
+                && let PowerGraphNode::DistributionBoxNode(_) = &box_node.user_data {
+                // 简化处理，实际应用中应检查精确的边界
+                if (node_pos.x - box_pos.x).abs() < 100.0 &&
+                   (node_pos.y - box_pos.y).abs() < 100.0 {
+                    return Some(box_id);
                 }
             }
         }
@@ -639,52 +1228,59 @@ impl PDSDApp {
     // 移动配电回路到配电箱
     fn move_circuit_to_box(&mut self, circuit_id: egui_node_graph::NodeId, box_id: egui_node_graph::NodeId) {
         // 1. 检查节点类型
-        if !matches!(&self.editor_state.graph.nodes[circuit_id].user_data, PowerGraphNode::CircuitNode(_)) ||
-           !matches!(&self.editor_state.graph.nodes[box_id].user_data, PowerGraphNode::DistributionBoxNode(_)) {
+        if !matches!(&self.editor_state.editor_state.graph.nodes[circuit_id].user_data, PowerGraphNode::CircuitNode(_)) ||
+           !matches!(&self.editor_state.editor_state.graph.nodes[box_id].user_data, PowerGraphNode::DistributionBoxNode(_)) {
             return;
         }
 
         // 2. 创建连接（如果存在输入和输出端口）
-        if let Some(circuit_output) = self.editor_state.graph.nodes[circuit_id].outputs.values().next() {
-            if let Some(box_input) = self.editor_state.graph.nodes[box_id].inputs.values().next() {
-                // 移除可能存在的旧连接
-                self.editor_state.graph.connections.remove(box_input);
-                // 创建新连接
-                self.editor_state.graph.connections.insert(*box_input, *circuit_output);
-
-                // 3. 标记节点需要更新
-                self.data_flow_manager.mark_node_for_update(box_id);
-
-                // 4. 触发更新传播
-                self.run_calculations();
+        let graph = &self.editor_state.editor_state.graph;
+        let circuit_output = graph.nodes[circuit_id].outputs.first().map(|(_, output_id)| *output_id);
+        let box_input = graph.nodes[box_id].inputs.first().map(|(_, input_id)| *input_id);
+        if let (Some(circuit_output), Some(box_input)) = (circuit_output, box_input) {
+            // 拖动过程中会持续产生移动事件，已连接时不重复处理
+            if graph.connection(box_input) == Some(circuit_output) {
+                return;
             }
+
+            // 替换可能存在的旧连接
+            self.editor_state.disconnect_nodes(box_input);
+            self.editor_state.connect_nodes(circuit_output, box_input);
+
+            // 3. 标记节点需要更新
+            self.data_flow_manager.mark_node_for_update(box_id);
+
+            // 4. 触发更新传播
+            self.run_calculations();
         }
     }
 
     // 处理新连接创建
-    fn handle_new_connection(&mut self, from: egui_node_graph::Connection, to: egui_node_graph::Connection) {
+    fn handle_new_connection(&mut self, output: egui_node_graph::OutputId, input: egui_node_graph::InputId) {
+        let graph = &self.editor_state.editor_state.graph;
+        let from = graph.get_output(output).node;
+        let to = graph.get_input(input).node;
+
         // 记录连接创建日志
-        self.debug_logger.info(&format!("创建连接: 从节点{}到节点{}", 
-            from.node_id.0, to.node_id.0));
+        self.debug_logger.info(&format!("创建连接: 从节点{:?}到节点{:?}", from, to));
         
         // 标记目标节点需要更新
-        self.data_flow_manager.mark_node_for_update(to.node_id);
+        self.data_flow_manager.mark_node_for_update(to);
     }
 
     // 处理节点删除
-    fn handle_node_deletion(&mut self, node_id: egui_node_graph::NodeId) {
+    fn handle_node_deletion(&mut self, node_id: egui_node_graph::NodeId, node: &egui_node_graph::Node<PowerGraphNode>) {
         // 记录节点删除日志
-        if let Some(node) = self.editor_state.graph.nodes.get(&node_id) {
-            self.debug_logger.info(&format!("删除节点: {}, 类型: {:?}", 
-                node.label, node.user_data.node_type));
-        }
+        self.debug_logger.info(&format!("删除节点: {}, 类型: {}", 
+            node.label, node.user_data.type_name()));
         
         // 清除相关缓存
         self.data_flow_manager.clear_node_cache(node_id);
         
         // 从计算缓存中移除相关条目
+        let prefix = format!("{:?}_", node_id);
         let cache_keys_to_remove: Vec<String> = self.calculation_cache.keys()
-            .filter(|key| key.starts_with(&node_id.0.to_string()))
+            .filter(|key| key.starts_with(&prefix))
             .cloned()
             .collect();
             
@@ -693,131 +1289,6 @@ impl PDSDApp {
         }
     }
 
-    // 构建拓扑排序以确定节点执行顺序
-    fn topological_sort(&self) -> Vec<egui_node_graph::NodeId> {
-        let mut visited = std::collections::HashSet::new();
-        let mut result = Vec::new();
-
-        // 从没有输入的节点开始（通常是输入节点）
-        for node_id in self.editor_state.graph.nodes.keys() {
-            if !visited.contains(&node_id) && self.is_node_independent(node_id) {
-                self.dfs_visit(node_id, &mut visited, &mut result);
-            }
-        }
-
-        // 确保所有节点都被访问
-        for node_id in self.editor_state.graph.nodes.keys() {
-            if !visited.contains(&node_id) {
-                self.dfs_visit(node_id, &mut visited, &mut result);
-            }
-        }
-
-        result
-    }
-
-    // 深度优先搜索访问节点
-    fn dfs_visit(
-        &self,
-        node_id: egui_node_graph::NodeId,
-        visited: &mut std::collections::HashSet<egui_node_graph::NodeId>,
-        result: &mut Vec<egui_node_graph::NodeId>,
-    ) {
-        visited.insert(node_id);
-
-        // 找到所有依赖当前节点的节点
-        if let Some(node) = self.editor_state.graph.nodes.get(node_id) {
-            for (_, output_id) in &node.outputs {
-                // 找到连接到这个输出的所有输入
-                for (input_id, connected_output_id) in &self.editor_state.graph.connections {
-                    if connected_output_id == output_id {
-                        // 找到输入所属的节点
-                        if let Some(input_param) = self.editor_state.graph.inputs.get(*input_id) {
-                            let input_node_id = input_param.node;
-                            if !visited.contains(&input_node_id) {
-                                self.dfs_visit(input_node_id, visited, result);
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        // 将当前节点添加到结果中（后序遍历确保依赖节点先执行）
-        result.push(node_id);
-    }
-
-    // 判断节点是否没有依赖（独立节点）
-    fn is_node_independent(&self, node_id: egui_node_graph::NodeId) -> bool {
-        // 检查节点的所有输入参数
-        if let Some(node) = self.editor_state.graph.nodes.get(node_id) {
-            for (_, input_id) in &node.inputs {
-                // 如果有任何一个输入被连接，则节点不是独立的
-                if self.editor_state.graph.connections.contains_key(input_id) {
-                    return false;
-                }
-            }
-        }
-        true
-    }
-
-    // 获取节点输入参数的值
-    fn get_input_value(
-        &self,
-        input_id: egui_node_graph::InputId,
-    ) -> Option<UIValueType> {
-        // 首先检查是否有连接
-        if let Some(output_id) = self.editor_state.graph.connections.get(&input_id) {
-            // 如果有连接，需要找到对应的输出节点并获取其计算结果
-            if let Some(output_param) = self.editor_state.graph.outputs.get(*output_id) {
-                // 找到输出所属的节点
-                let output_node_id = self.find_node_by_output(*output_id)?;
-                
-                // 尝试从缓存中获取计算结果
-                let cache_key = format!("{}_{}", output_node_id.0, output_id.0);
-                if let Some(cached_value) = self.calculation_cache.get(&cache_key) {
-                    // 根据输出参数类型返回适当的值
-                    return match output_param.typ {
-                        DataType::Float => Some(UIValueType::Float(*cached_value)),
-                        DataType::Integer => Some(UIValueType::Integer(*cached_value as i64)),
-                        _ => Some(UIValueType::Float(*cached_value)), // 默认返回浮点数
-                    };
-                }
-
-                // 由于计算可能很复杂，这里返回一个默认值作为示例
-                return match output_param.typ {
-                    DataType::Float => Some(UIValueType::Float(0.0)),
-                    DataType::Integer => Some(UIValueType::Integer(0)),
-                    _ => Some(UIValueType::Float(0.0)), // 默认返回浮点数
-                };
-            }
-        } else {
-            // 如果没有连接，返回输入参数的默认值
-            if let Some(input_param) = self.editor_state.graph.inputs.get(input_id) {
-                match input_param.kind {
-                    egui_node_graph::InputParamKind::Constant |
-                    egui_node_graph::InputParamKind::ConnectionOrConstant => {
-                        Some(input_param.value.clone())
-                    },
-                    _ => None,
-                }
-            } else {
-                None
-            }
-        }
-    }
-
-    // 根据输出ID查找对应的节点
-    fn find_node_by_output(&self, output_id: egui_node_graph::OutputId) -> Option<egui_node_graph::NodeId> {
-        for (node_id, node) in &self.editor_state.graph.nodes {
-            for (_, out_id) in &node.outputs {
-                if *out_id == output_id {
-                    return Some(node_id);
-                }
-            }
-        }
-        None
-    }
-
     // 执行图计算 - 利用数据流向管理器优化计算顺序和缓存
     fn execute_graph(&mut self) {
         log_debug!("开始执行图计算");
@@ -826,7 +1297,7 @@ impl PDSDApp {
         // 这里我们复用数据流向管理器中的拓扑排序结果，避免重复计算
         
         // 1. 获取拓扑排序后的节点执行顺序
-        let execution_order = self.data_flow_manager.perform_topological_sort(&self.editor_state.graph);
+        let execution_order = self.data_flow_manager.perform_topological_sort(&self.editor_state.editor_state.graph);
         
         // 2. 按顺序执行每个节点的计算
         for node_id in execution_order {
@@ -842,12 +1313,12 @@ impl PDSDApp {
     // 执行单个节点的计算
     fn execute_node_calculation(&mut self, node_id: egui_node_graph::NodeId) {
         // 记录节点计算开始
-        log_trace!(format!("开始计算节点: {:?}", node_id));
+        log_trace!("开始计算节点: {:?}", node_id);
         self.debug_logger.trace(&format!("开始计算节点: {:?}", node_id));
         
         // 收集输入参数
-        let mut inputs = Vec::new();
-        if let Some(node) = self.editor_state.graph.nodes.get(node_id) {
+        let inputs = Vec::new();
+        if let Some(node) = self.editor_state.editor_state.graph.nodes.get(node_id) {
             // 记录计算开始时间
             let calc_start_time = Instant::now();
             
@@ -855,59 +1326,59 @@ impl PDSDApp {
             match &node.user_data {
                 PowerGraphNode::CircuitNode(circuit) => {
                     // 执行回路计算
-                    log_debug!(format!("计算回路节点: {}, 功率: {:.2}kW", 
-                        circuit.name, circuit.power));
+                    log_debug!("计算回路节点: {}, 功率: {:.2}kW", 
+                        circuit.circuit_name, circuit.power);
                     self.debug_logger.debug(&format!("计算回路节点: {}, 功率: {:.2}kW", 
-                        circuit.name, circuit.power));
+                        circuit.circuit_name, circuit.power));
                 },
                 PowerGraphNode::DistributionBoxNode(box_node) => {
                     // 执行配电箱计算
-                    log_debug!(format!("计算配电箱节点: {}, 回路数: {}", 
-                        box_node.name, box_node.circuits.len()));
+                    log_debug!("计算配电箱节点: {}, 回路数: {}", 
+                        box_node.name, box_node.circuits.len());
                     self.debug_logger.debug(&format!("计算配电箱节点: {}, 回路数: {}", 
                         box_node.name, box_node.circuits.len()));
                 },
                 PowerGraphNode::TrunkLineNode(system_node) => {
                     // 执行干线系统图计算
-                    log_debug!(format!("计算干线系统图节点: {}, 类型: {:?}", 
-                        system_node.name, system_node.system_type));
+                    log_debug!("计算干线系统图节点: {}, 类型: {:?}", 
+                        system_node.name, system_node.systems);
                     self.debug_logger.debug(&format!("计算干线系统图节点: {}, 类型: {:?}", 
-                        system_node.name, system_node.system_type));
+                        system_node.name, system_node.systems));
                 },
-                _ => {
+                PowerGraphNode::Other(_) => {
                     // 其他类型节点的计算
-                    log_debug!(format!("计算其他类型节点: {:?}", node_id));
+                    log_debug!("计算其他类型节点: {:?}", node_id);
                     self.debug_logger.debug(&format!("计算其他类型节点: {:?}", node_id));
                 }
             }
             
             // 记录计算耗时
             let calc_duration = calc_start_time.elapsed();
-            log_trace!(format!("节点 {:?} 计算耗时: {:.2} ms", node_id, calc_duration.as_secs_f64() * 1000.0));
+            log_trace!("节点 {:?} 计算耗时: {:.2} ms", node_id, calc_duration.as_secs_f64() * 1000.0);
             
             // 3. 从数据流向管理器获取计算结果并更新本地缓存
             if let Some(node_cache) = self.data_flow_manager.get_node_cache(node_id) {
                 // 记录缓存条目数
-                log_trace!(format!("节点 {:?} 获取到 {} 个缓存条目", node_id, node_cache.len()));
+                log_trace!("节点 {:?} 获取到 {} 个缓存条目", node_id, node_cache.len());
                 
                 // 将数据流向管理器中的计算结果同步到应用的计算缓存
                 for (key, value) in node_cache {
                     // 转换缓存键格式
-                    let cache_key = format!("{}_{}", node_id.0, key);
+                    let cache_key = format!("{:?}_{}", node_id, key);
                     
                     // 根据值类型提取数值
                     match value {
-                        ElectricValueType::Float(val) => {
+                        UIValueType::Float(val) => {
                             self.calculation_cache.insert(cache_key, *val);
-                            log_trace!(format!("缓存节点 {:?} 输出 {} = {:.2}", node_id, key, val));
+                            log_trace!("缓存节点 {:?} 输出 {} = {:.2}", node_id, key, val);
                         },
-                        ElectricValueType::Integer(val) => {
+                        UIValueType::Integer(val) => {
                             self.calculation_cache.insert(cache_key, *val as f64);
-                            log_trace!(format!("缓存节点 {:?} 输出 {} = {}", node_id, key, val));
+                            log_trace!("缓存节点 {:?} 输出 {} = {}", node_id, key, val);
                         },
                         _ => {
                             // 不处理非数值类型的缓存
-                            log_trace!(format!("忽略节点 {:?} 非数值类型输出: {}", node_id, key));
+                            log_trace!("忽略节点 {:?} 非数值类型输出: {}", node_id, key);
                         }
                     }
                 }
@@ -916,88 +1387,53 @@ impl PDSDApp {
             // 记录节点执行情况
             self.log_node_execution(node_id, &inputs, Some(UIValueType::Float(0.0)), LogLevel::Debug);
         } else {
-            log_warn!(format!("尝试计算不存在的节点: {:?}", node_id));
+            log_warn!("尝试计算不存在的节点: {:?}", node_id);
             self.debug_logger.warning(&format!("尝试计算不存在的节点: {:?}", node_id));
         }
     }
     
-    // 计算输出值（示例实现）
-    fn calculate_output_value(&self, node_id: egui_node_graph::NodeId, output_id: egui_node_graph::OutputId) -> f64 {
-        // 获取节点数据
-        if let Some(node) = self.editor_state.graph.nodes.get(node_id) {
-            // 根据节点类型执行不同的计算逻辑
-            match node.user_data.node_type {
-                // 这里应该根据实际的节点类型实现具体计算
-                // 目前返回随机值作为示例
-                _ => {
-                    // 简单示例：生成0-100之间的随机值
-                    (rand::random::<f64>() * 100.0).round()
-                }
-            }
-        } else {
-            0.0
+    // 快捷键复制粘贴选中节点，文本框获得焦点时交给文本框处理
+    fn handle_copy_paste(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
         }
-    }
-    
-    // 添加配电回路节点
-    fn add_circuit_node(&mut self, circuit_type: CircuitType) {
-        // 获取所有电路节点模板
-        let templates = all_electric_templates();
-        
-        // 查找匹配的模板
-        let template_name = match circuit_type {
-            CircuitType::SinglePhase => "单相配电回路",
-            CircuitType::ThreePhase => "三相配电回路",
-        };
-        
-        if let Some(template) = templates.iter().find(|t| t.node_label() == template_name) {
-            // 添加新节点
-            let _ = self.editor_state.graph.add_node(
-                template.node_label().to_string(),
-                template.user_data(&mut self.editor_state.user_state),
-                |graph, node_id| template.build_node(graph, &mut self.editor_state.user_state, node_id),
-            );
-            
-            // 记录日志
-            self.debug_logger.info(&format!("添加节点: {}", template_name));
+        let (copy, paste) = ctx.input(|i| {
+            (
+                i.events.iter().any(|e| matches!(e, egui::Event::Copy)),
+                i.events.iter().any(|e| matches!(e, egui::Event::Paste(_))) || (i.modifiers.command && i.key_pressed(egui::Key::V)),
+            )
+        });
+        if copy {
+            self.copy_selected_node();
+        }
+        if paste {
+            self.paste_node();
         }
     }
-    
-    // 添加配电箱节点
-    fn add_distribution_box_node(&mut self) {
-        // 获取所有模板
-        let templates = all_electric_templates();
-        
-        // 查找配电箱模板
-        if let Some(template) = templates.iter().find(|t| t.node_label() == "标准配电箱") {
-            // 添加新节点
-            let _ = self.editor_state.graph.add_node(
-                template.node_label().to_string(),
-                template.user_data(&mut self.editor_state.user_state),
-                |graph, node_id| template.build_node(graph, &mut self.editor_state.user_state, node_id),
-            );
-            
-            // 记录日志
-            self.debug_logger.info("添加节点: 标准配电箱");
+
+    // 复制选中节点
+    fn copy_selected_node(&mut self) {
+        if self.editor_state.copy_selected() {
+            self.debug_logger.info("复制节点");
         }
     }
-    
-    // 添加干线系统图节点
-    fn add_main_system_node(&mut self) {
-        // 获取所有模板
-        let templates = all_electric_templates();
-        
-        // 查找干线系统图模板
-        if let Some(template) = templates.iter().find(|t| t.node_label() == "干线系统图") {
-            // 添加新节点
-            let _ = self.editor_state.graph.add_node(
-                template.node_label().to_string(),
-                template.user_data(&mut self.editor_state.user_state),
-                |graph, node_id| template.build_node(graph, &mut self.editor_state.user_state, node_id),
-            );
-            
-            // 记录日志
-            self.debug_logger.info("添加节点: 干线系统图");
+
+    // 粘贴节点：配电箱复制品分配新的标识和编号
+    fn paste_node(&mut self) {
+        if let Some(node_id) = self.editor_state.paste() {
+            self.data_flow_manager.mark_node_for_update(node_id);
+            self.debug_logger.info(&format!("粘贴节点: {}", self.editor_state.editor_state.graph.nodes[node_id].label));
         }
     }
+
+    // 按模板在画布中添加节点，新节点依次错开放置
+    fn add_template_node(&mut self, template: ElectricNodeTemplate) {
+        let offset = (self.editor_state.editor_state.graph.nodes.len() % 10) as f32 * 30.0;
+        let position = egui::pos2(120.0 + offset, 120.0 + offset);
+        let node_id = self.editor_state.add_template_node(template, position);
+        self.editor_state.set_selected_node(Some(node_id));
+
+        // 记录日志
+        self.debug_logger.info(&format!("添加节点: {}", template.node_label()));
+    }
 }
@@ -1,9 +1,7 @@
 use std::fmt::Debug;
 use std::sync::mpsc::{Sender, Receiver, channel};
-use std::thread;
-use std::time::Duration;
 use chrono::Local;
-use egui_node_graph::{NodeId, InputId, OutputId};
+use egui_node_graph::NodeId;
 use crate::editor::UIValueType;
 
 /// 日志级别枚举
@@ -32,7 +30,7 @@ pub struct DebugLogger {
     pub max_logs: usize,
     pub current_level: LogLevel,
     pub is_recording: bool,
-34| }
+}
 
 impl Default for DebugLogger {
     fn default() -> Self {
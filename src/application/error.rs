@@ -218,13 +218,13 @@ mod tests {
             "导入DWG", 
             Some("此功能在后续版本中提供")
         );
-        assert!(error_with_reason.to_string().contains("功能未实现: 导入DWG - Some(\"此功能在后续版本中提供\")"));
+        assert!(error_with_reason.to_string().contains("功能未实现: 导入DWG - 此功能在后续版本中提供"));
         
         // 没有reason的情况
         let error_no_reason = ApplicationError::not_implemented(
             "批量处理", 
             None::<String>
         );
-        assert!(error_no_reason.to_string().contains("功能未实现: 批量处理 - None"));
+        assert!(error_no_reason.to_string().contains("功能未实现: 批量处理 - 无具体原因"));
     }
 }
\ No newline at end of file
@@ -1,12 +1,13 @@
 use eframe::{App, egui};
-use egui_node_graph::{Graph, NodeId};
-use crate::editor::business::{CircuitNodeData, DistributionBoxNodeData, MainSystemNodeData, MainSystemType, SystemDiagram, DataFlowManager, AutoConnectionManager, ConnectionInfo};
-use crate::core_lib::data_types::{ElectricNodeData, ElectricDataType, ElectricValueType};
+use egui_node_graph::{Graph, NodeId, NodeTemplateTrait};
+use crate::editor::{DataType, UIUserState, UIValueType};
+use crate::editor::business::{BoxData, MainSystemNodeData, MainSystemType, DataFlowManager, AutoConnectionManager, BoxModule, ModuleKind, ElectricNodeTemplate, PowerGraphNode};
+use crate::core_lib::data_types::{CircuitNodeProperties, LoadClass, LoadLevel};
 
 /// 集成示例应用 - 展示所有功能模块的组合使用
 pub struct IntegrationExampleApp {
     /// 节点图数据
-    graph: Graph<ElectricNodeData, ElectricDataType, ElectricValueType>,
+    graph: Graph<PowerGraphNode, DataType, UIValueType>,
     /// 数据流向管理器
     data_flow_manager: DataFlowManager,
     /// 自动连接管理器
@@ -30,6 +31,14 @@ impl Default for IntegrationExampleApp {
 }
 
 impl IntegrationExampleApp {
+    /// 按模板添加节点，端口由模板生成
+    fn add_node(&mut self, template: ElectricNodeTemplate, label: &str, node_data: PowerGraphNode) -> NodeId {
+        let mut user_state = UIUserState::default();
+        self.graph.add_node(label.to_string(), node_data, |graph, node_id| {
+            template.build_node(graph, &mut user_state, node_id)
+        })
+    }
+    
     /// 重置示例状态
     fn reset(&mut self) {
         self.graph = Graph::new();
@@ -82,89 +91,56 @@ impl IntegrationExampleApp {
     /// 创建示例配电回路节点
     fn create_circuit_nodes(&mut self) {
         // 创建一些示例配电回路
-        let circuit1 = CircuitNodeData {
-            name: "照明回路1".to_string(),
-            power: 5.5,
-            power_factor: 0.9,
-            purpose: Some("普通照明".to_string()),
-            ..Default::default()
-        };
-        
-        let circuit2 = CircuitNodeData {
-            name: "插座回路1".to_string(),
-            power: 3.0,
-            power_factor: 0.85,
-            purpose: Some("办公室插座".to_string()),
-            ..Default::default()
-        };
-        
-        let circuit3 = CircuitNodeData {
-            name: "空调回路1".to_string(),
-            power: 8.0,
-            power_factor: 0.8,
-            purpose: Some("办公室空调".to_string()),
-            ..Default::default()
-        };
+        let circuits = [
+            ("照明回路1", 5.5, 0.9),
+            ("插座回路1", 3.0, 0.85),
+            ("空调回路1", 8.0, 0.8),
+        ];
         
         // 将回路节点添加到图中
-        let _ = self.graph.add_node(
-            "照明回路1".to_string(),
-            ElectricNodeData::CircuitNode(circuit1),
-            |_, _| {},
-        );
-        
-        let _ = self.graph.add_node(
-            "插座回路1".to_string(),
-            ElectricNodeData::CircuitNode(circuit2),
-            |_, _| {},
-        );
-        
-        let _ = self.graph.add_node(
-            "空调回路1".to_string(),
-            ElectricNodeData::CircuitNode(circuit3),
-            |_, _| {},
-        );
+        for (name, power, power_factor) in circuits {
+            let circuit = CircuitNodeProperties {
+                circuit_name: name.to_string(),
+                power,
+                power_factor,
+                ..Default::default()
+            };
+            self.add_node(ElectricNodeTemplate::SinglePhaseCircuitNode, name, PowerGraphNode::CircuitNode(circuit));
+        }
     }
     
     /// 创建配电箱节点并连接回路
     fn create_distribution_box_nodes(&mut self) {
         // 创建两个配电箱：一个普通配电箱和一个双电源切换配电箱
-        let box1 = DistributionBoxNodeData {
+        let box1 = BoxData {
             name: "配电箱AL1".to_string(),
             floor: 1,
-            modules: vec!["普通配电模块".to_string()],
+            modules: Vec::new(),
             ..Default::default()
         };
         
-        let box2 = DistributionBoxNodeData {
+        let box2 = BoxData {
             name: "消防配电箱XF1".to_string(),
             floor: 2,
-            modules: vec!["双电源切换模块".to_string()],
+            modules: vec![BoxModule::default_for(ModuleKind::DualPowerSwitch)],
+            load_class: LoadClass::fire(LoadLevel::First),
             ..Default::default()
         };
         
         // 添加配电箱节点
-        let box1_id = self.graph.add_node(
-            "配电箱AL1".to_string(),
-            ElectricNodeData::DistributionBoxNode(box1),
-            |_, _| {},
-        ).unwrap();
-        
-        let box2_id = self.graph.add_node(
-            "消防配电箱XF1".to_string(),
-            ElectricNodeData::DistributionBoxNode(box2),
-            |_, _| {},
-        ).unwrap();
+        let box1_id = self.add_node(ElectricNodeTemplate::DistributionBoxNode, "配电箱AL1", PowerGraphNode::DistributionBoxNode(box1));
+        self.add_node(ElectricNodeTemplate::DistributionBoxNode, "消防配电箱XF1", PowerGraphNode::DistributionBoxNode(box2));
         
-        // 简单连接：第一个回路连接到第一个配电箱，其他连接到第二个
-        let mut circuit_nodes = Vec::new();
-        for (node_id, node) in &self.graph.nodes {
-            if let ElectricNodeData::CircuitNode(_) = &node.user_data {
-                circuit_nodes.push(*node_id);
+        // 简单连接：第一个回路的计算电流接入第一个配电箱的进线
+        let first_circuit = self.graph.iter_nodes()
+            .find(|node_id| matches!(&self.graph[*node_id].user_data, PowerGraphNode::CircuitNode(_)));
+        if let Some(circuit_id) = first_circuit {
+            let output = self.graph[circuit_id].outputs.first().map(|(_, output_id)| *output_id);
+            let input = self.graph[box1_id].inputs.first().map(|(_, input_id)| *input_id);
+            if let (Some(output), Some(input)) = (output, input) {
+                self.graph.add_connection(output, input);
             }
         }
-        
-        // 这里应该实现真正的连接逻辑，目前简化处理
     }
     
     /// 创建干线系统图节点
@@ -183,11 +159,7 @@ impl IntegrationExampleApp {
         };
         
         // 添加系统图节点
-        let _ = self.graph.add_node(
-            "干线系统图MS1".to_string(),
-            ElectricNodeData::TrunkLineNode(system_node),
-            |_, _| {},
-        );
+        self.add_node(ElectricNodeTemplate::MainSystemNode, "干线系统图MS1", PowerGraphNode::TrunkLineNode(system_node));
     }
     
     /// 运行数据流向更新
@@ -200,24 +172,24 @@ impl IntegrationExampleApp {
     fn execute_auto_connections(&mut self) {
         // 收集所有配电箱节点数据
         let mut distribution_boxes = Vec::new();
-        let mut system_node_id: Option<NodeId> = None;
+        let mut system_node = None;
         
-        for (node_id, node) in &self.graph.nodes {
+        for node in self.graph.nodes.values() {
             match &node.user_data {
-                ElectricNodeData::DistributionBoxNode(box_data) => {
+                PowerGraphNode::DistributionBoxNode(box_data) => {
                     distribution_boxes.push(box_data.clone());
                 },
-                ElectricNodeData::TrunkLineNode(_) => {
-                    system_node_id = Some(*node_id);
+                PowerGraphNode::TrunkLineNode(system_data) => {
+                    system_node = Some(system_data.clone());
                 },
                 _ => {},
             }
         }
         
         // 生成自动连接
-        if let Some(node_id) = system_node_id {
+        if let Some(mut system_node) = system_node {
             let connections = self.auto_connection_manager.auto_connect_for_system(
-                &mut MainSystemNodeData::default(), // 简化处理
+                &mut system_node,
                 &distribution_boxes
             );
             
@@ -231,11 +203,11 @@ impl IntegrationExampleApp {
     fn show_summary(&mut self) {
         // 收集系统状态信息
         let node_count = self.graph.nodes.len();
-        let distribution_boxes: Vec<_> = self.graph.nodes.values()
-            .filter(|node| matches!(&node.user_data, ElectricNodeData::DistributionBoxNode(_)))
+        let distribution_boxes = self.graph.nodes.values()
+            .filter(|node| matches!(&node.user_data, PowerGraphNode::DistributionBoxNode(_)))
             .count();
-        let circuit_count: Vec<_> = self.graph.nodes.values()
-            .filter(|node| matches!(&node.user_data, ElectricNodeData::CircuitNode(_)))
+        let circuit_count = self.graph.nodes.values()
+            .filter(|node| matches!(&node.user_data, PowerGraphNode::CircuitNode(_)))
             .count();
         
         println!("=== 系统摘要 ===");
@@ -272,7 +244,7 @@ impl App for IntegrationExampleApp {
             ui.add_space(20.0);
             ui.collapsing("当前节点列表", |ui| {
                 for (node_id, node) in &self.graph.nodes {
-                    ui.label(format!("节点 ID: {}, 名称: {}", node_id.index(), node.title));
+                    ui.label(format!("节点 ID: {:?}, 名称: {}", node_id, node.label));
                 }
             });
             
@@ -295,7 +267,7 @@ impl App for IntegrationExampleApp {
 
 /// 启动集成示例应用的函数
 pub fn start_integration_example() {
-    let options = eframe::NativeOptions {
+    let _options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([800.0, 600.0])
             .with_title("PDSD集成示例"),
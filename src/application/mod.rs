@@ -1,6 +1,8 @@
 /// 应用程序核心模块
 pub mod app;
 pub mod state;
+pub mod project;
+pub mod project_merge;
 pub mod error;
 pub mod debug_logger;
 pub mod integration_example;
@@ -8,6 +10,8 @@ pub mod integration_example;
 // 重新导出主要结构体和类型
 pub use app::PDSDApp;
 pub use state::AppState;
+pub use project::{calculate_boxes, ProjectFile, ProjectIssue, PROJECT_FILE_VERSION};
+pub use project_merge::{MergeConflict, ProjectDiff, ProjectMerge, Resolution};
 pub use error::ApplicationError;
 pub use debug_logger::{DebugLogger, LogLevel, LogEntry};
 pub use integration_example::start_integration_example;
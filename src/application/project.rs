@@ -0,0 +1,357 @@
+//! 项目文件
+//!
+//! 项目以JSON文件保存：文件版本、项目信息、建筑楼层与竖井、各配电箱（含回路和功能模块）及干线系统图。
+//! 界面和命令行工具共用同一计算流程——逐箱执行回路校验、自动编号、三相平衡、保护配合及
+//! 穿管选型、消防模块生成，按建筑几何估算进线电缆长度，再按消防设计规则和建筑楼层、竖井归属校验。
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::application::error::{ApplicationError, Result};
+use crate::application::state::ProjectInfo;
+use crate::core_lib::utils::{IdUtils, ValidationUtils};
+use crate::editor::business::electrical_fire_monitoring::ElectricalFirePlanner;
+use crate::editor::business::fire_power_monitoring::FireModuleGenerator;
+use crate::editor::business::{BoxData, Building, CableLengthConfig, CableLengthError, CableLengthEstimator, DistributionBoxCalculator, DistributionBoxNodeUI, FireDesignRuleChecker, SystemDiagram};
+
+/// 当前项目文件版本（版本2起配电箱带有持久标识，版本3起带有建筑模型，楼层号可为负数）
+pub const PROJECT_FILE_VERSION: u32 = 3;
+
+/// 项目校验问题
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectIssue {
+    /// 配电箱名称
+    pub box_name: String,
+    /// 问题说明
+    pub message: String,
+}
+
+impl fmt::Display for ProjectIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.box_name, self.message)
+    }
+}
+
+/// 项目文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectFile {
+    /// 文件版本
+    pub version: u32,
+    /// 项目信息
+    pub info: ProjectInfo,
+    /// 建筑楼层与竖井
+    #[serde(default)]
+    pub building: Building,
+    /// 配电箱列表
+    #[serde(default)]
+    pub boxes: Vec<BoxData>,
+    /// 干线系统图
+    #[serde(default)]
+    pub diagrams: Vec<SystemDiagram>,
+}
+
+impl ProjectFile {
+    /// 创建空项目
+    ///
+    /// # 参数
+    /// * `name` - 项目名称
+    pub fn new(name: &str) -> Self {
+        Self {
+            version: PROJECT_FILE_VERSION,
+            info: ProjectInfo { name: name.to_string(), ..ProjectInfo::default() },
+            building: Building::default(),
+            boxes: Vec::new(),
+            diagrams: Vec::new(),
+        }
+    }
+
+    /// 读取项目文件
+    ///
+    /// # 参数
+    /// * `path` - 项目文件路径
+    ///
+    /// # 返回值
+    /// * `Ok(ProjectFile)` - 读取成功
+    /// * `Err(ApplicationError)` - 文件无法读取、格式错误或版本高于当前程序支持的版本
+    pub fn load(path: &Path) -> Result<Self> {
+        let name = path.display().to_string();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| ApplicationError::resource_loading("项目文件", &name, Some(e)))?;
+        let mut project: Self = serde_json::from_str(&text)
+            .map_err(|e| ApplicationError::resource_loading("项目文件", &name, Some(e)))?;
+        if project.version > PROJECT_FILE_VERSION {
+            return Err(ApplicationError::version_compatibility(
+                project.version.to_string(),
+                PROJECT_FILE_VERSION.to_string(),
+            ));
+        }
+        project.ensure_ids();
+        Ok(project)
+    }
+
+    /// 补齐对象标识
+    ///
+    /// 早期版本的项目文件没有配电箱标识，读取时已分配；空白或在项目内重复的配电箱、
+    /// 回路标识重新分配，其余标识（包括早期版本生成的回路标识）保持不变
+    ///
+    /// # 返回值
+    /// 返回重新分配的标识数
+    pub fn ensure_ids(&mut self) -> usize {
+        let mut box_ids = HashSet::new();
+        let mut circuit_ids = HashSet::new();
+        let mut count = 0;
+        for box_data in &mut self.boxes {
+            count += IdUtils::ensure_unique(&mut box_data.id, &mut box_ids) as usize;
+            for circuit in &mut box_data.circuits {
+                count += IdUtils::ensure_unique(&mut circuit.circuit_id, &mut circuit_ids) as usize;
+            }
+        }
+        count
+    }
+
+    /// 保存项目文件
+    ///
+    /// # 参数
+    /// * `path` - 输出文件路径
+    ///
+    /// # 返回值
+    /// * `Ok(())` - 保存成功
+    /// * `Err(ApplicationError)` - 序列化或写文件失败
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let text = serde_json::to_string_pretty(self)
+            .map_err(|e| ApplicationError::state_management("保存项目", e.to_string()))?;
+        std::fs::write(path, text)
+            .map_err(|e| ApplicationError::state_management("保存项目", format!("{}: {}", path.display(), e)))
+    }
+
+    /// 项目中的全部配电箱引用（供导出使用）
+    pub fn box_refs(&self) -> Vec<&BoxData> {
+        self.boxes.iter().collect()
+    }
+
+    /// 执行项目计算并校验，计算结果写回项目
+    ///
+    /// # 返回值
+    /// 返回计算及校验发现的问题，为空表示全部通过
+    pub fn run_calculations(&mut self) -> Vec<ProjectIssue> {
+        calculate_boxes(&self.building, &mut self.boxes)
+    }
+}
+
+/// 配电箱计算流程（项目文件、界面和命令行工具共用）
+///
+/// 校验配电箱名称是否重复，逐箱执行与配电箱节点相同的重新计算流程，为消防模块分配总线地址，按建筑几何估算进线电缆长度
+/// （手动输入的长度优先），随后按消防设计规则校验，并校验建筑定义及配电箱的楼层、竖井归属
+///
+/// # 参数
+/// * `building` - 建筑楼层与竖井
+/// * `boxes` - 配电箱，计算结果写回
+///
+/// # 返回值
+/// 返回计算及校验发现的问题，为空表示全部通过
+pub fn calculate_boxes(building: &Building, boxes: &mut [BoxData]) -> Vec<ProjectIssue> {
+    let mut issues: Vec<ProjectIssue> = boxes
+        .iter()
+        .filter(|b| boxes.iter().filter(|other| other.name == b.name).count() > 1)
+        .map(|b| ProjectIssue { box_name: b.name.clone(), message: "配电箱名称重复".to_string() })
+        .collect();
+    for box_data in boxes.iter_mut() {
+        let mut node = DistributionBoxNodeUI::new(std::mem::take(box_data));
+        node.recalculate();
+        issues.extend(node.errors.into_iter().map(|message| ProjectIssue { box_name: node.data.name.clone(), message }));
+        *box_data = node.data;
+    }
+
+    // 消防模块、电气火灾监控探测器总线地址按楼层、配电箱名称统一编址，与干线图一致
+    let mut box_refs: Vec<&mut BoxData> = boxes.iter_mut().collect();
+    FireModuleGenerator::assign_addresses(&mut box_refs, 1);
+    ElectricalFirePlanner::assign_addresses(&mut box_refs, 1);
+
+    // 楼层、竖井无效的问题由下方建筑校验列出
+    let length_errors = CableLengthEstimator::apply(building, boxes, &CableLengthConfig::default());
+    issues.extend(length_errors.into_iter().filter(|(_, e)| !matches!(e, CableLengthError::Building(_))).map(
+        |(box_name, e)| ProjectIssue { box_name, message: format!("无法估算进线电缆长度: {}", e) },
+    ));
+
+    // 进线长度取手动输入值，未输入时取上面的估算值
+    for box_data in boxes.iter_mut() {
+        let Some((length, source)) = box_data.incoming_length() else { continue };
+        let Some(section) = box_data.incoming_cable.as_ref().and_then(|c| c.size.trim_end_matches("mm²").parse::<f64>().ok()) else {
+            continue;
+        };
+        let drop = DistributionBoxCalculator::feeder_voltage_drop(box_data.total_current, section, length, 0.85);
+        box_data.coordination_log.push(format!("进线: 电压损失{:.2}%（{}长度{:.1}m）", drop, source.to_str(), length));
+        if !ValidationUtils::is_valid_voltage_drop(drop) {
+            issues.push(ProjectIssue {
+                box_name: box_data.name.clone(),
+                message: format!("进线电缆电压损失{:.2}%超过5%（{}长度{:.1}m）", drop, source.to_str(), length),
+            });
+        }
+    }
+
+    let box_refs: Vec<&BoxData> = boxes.iter().collect();
+    let report = FireDesignRuleChecker::check_boxes(&box_refs);
+    issues.extend(report.violations.into_iter().map(|v| ProjectIssue {
+        message: match &v.circuit_id {
+            Some(id) => format!("[{}] {}：{}", v.rule.to_str(), id, v.message),
+            None => format!("[{}] {}", v.rule.to_str(), v.message),
+        },
+        box_name: v.box_name,
+    }));
+
+    issues.extend(building.validate().into_iter().map(|e| ProjectIssue {
+        box_name: "建筑".to_string(),
+        message: e.to_string(),
+    }));
+    issues.extend(boxes.iter().filter_map(|b| {
+        building.check_box(b).err().map(|e| ProjectIssue { box_name: b.name.clone(), message: e.to_string() })
+    }));
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::business::{CircuitInfo, LengthSource};
+
+    fn sample_project() -> ProjectFile {
+        let mut project = ProjectFile::new("测试项目");
+        let mut box_data = BoxData::new("AL1".to_string(), 1);
+        box_data.circuits.push(CircuitInfo::new("c1".to_string(), "照明".to_string(), 2.0, 10.0));
+        box_data.circuits.push(CircuitInfo::new("c2".to_string(), "插座".to_string(), 3.0, 15.0));
+        project.boxes.push(box_data);
+        project
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("pdsd_project_{}.json", std::process::id()));
+        let project = sample_project();
+        project.save(&path).unwrap();
+        let loaded = ProjectFile::load(&path).unwrap();
+        assert_eq!(loaded.info.name, "测试项目");
+        assert_eq!(loaded.boxes, project.boxes);
+
+        let mut newer = project.clone();
+        newer.version = PROJECT_FILE_VERSION + 1;
+        newer.save(&path).unwrap();
+        assert!(matches!(ProjectFile::load(&path), Err(ApplicationError::VersionCompatibility { .. })));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_ids_kept_and_repaired() {
+        let path = std::env::temp_dir().join(format!("pdsd_project_ids_{}.json", std::process::id()));
+        let mut project = sample_project();
+        project.boxes.push(project.boxes[0].duplicate());
+        let copy = &project.boxes[1];
+        assert_ne!(copy.id, project.boxes[0].id);
+        assert!(copy.circuits.iter().all(|c| IdUtils::is_uuid(&c.circuit_id)));
+        project.save(&path).unwrap();
+        assert_eq!(ProjectFile::load(&path).unwrap().boxes, project.boxes);
+
+        // 早期版本的文件：配电箱无标识，回路标识重复
+        let mut legacy: serde_json::Value = serde_json::to_value(sample_project()).unwrap();
+        legacy["version"] = 1.into();
+        legacy["boxes"][0].as_object_mut().unwrap().remove("id");
+        legacy["boxes"][0]["circuits"][1]["circuit_id"] = "c1".into();
+        std::fs::write(&path, legacy.to_string()).unwrap();
+        let loaded = ProjectFile::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(IdUtils::is_uuid(&loaded.boxes[0].id));
+        assert_eq!(loaded.boxes[0].circuits[0].circuit_id, "c1");
+        assert!(IdUtils::is_uuid(&loaded.boxes[0].circuits[1].circuit_id));
+    }
+
+    #[test]
+    fn test_run_calculations() {
+        let mut project = sample_project();
+        assert!(project.run_calculations().is_empty());
+        assert!(project.boxes[0].total_power > 0.0);
+        assert!(project.boxes[0].incoming_current > 0.0);
+        assert_eq!(project.boxes[0].fire_cut_off().and_then(|m| m.address), Some(1));
+
+        // 电气火灾监控探测器跨配电箱统一编址
+        let mut project = sample_project();
+        project.boxes[0].circuits[1].power = 25.0;
+        project.boxes[0].circuits[1].three_phase = true;
+        let mut upper = project.boxes[0].duplicate();
+        upper.name = "AL2".to_string();
+        upper.floor = 2;
+        project.boxes.push(upper);
+        project.run_calculations();
+        let ranges: Vec<_> = project
+            .boxes
+            .iter()
+            .map(|b| b.electrical_fire_monitoring().and_then(|m| m.address_range()))
+            .collect();
+        assert!(ranges.iter().all(Option::is_some));
+        assert_eq!(ranges[0].unwrap().0, 1);
+        assert_eq!(ranges[1].unwrap().0, ranges[0].unwrap().1 + 1);
+
+        project.boxes[0].circuits[0].power = 0.0;
+        project.boxes.push(BoxData::new("AL1".to_string(), 2));
+        let issues = project.run_calculations();
+        assert!(issues.iter().any(|i| i.message.contains("功率必须大于0")));
+        assert!(issues.iter().any(|i| i.message == "配电箱名称重复"));
+    }
+
+    #[test]
+    fn test_building_assignment_checked() {
+        let mut project = sample_project();
+        project.building = Building::uniform(1, 3, 4.2);
+        let shaft = project.building.add_shaft("强电井1", (10.0, 20.0), (1, 3));
+        project.boxes[0].shaft = Some(shaft);
+        assert!(project.run_calculations().is_empty());
+
+        project.boxes.push(BoxData::new("B1AL1".to_string(), -1));
+        project.boxes[1].shaft = project.boxes[0].shaft.clone();
+        let issues = project.run_calculations();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].box_name, "B1AL1");
+        assert!(issues[0].message.contains("未贯通"));
+
+        // 版本2的项目文件没有建筑模型
+        let mut legacy: serde_json::Value = serde_json::to_value(sample_project()).unwrap();
+        legacy["version"] = 2.into();
+        legacy.as_object_mut().unwrap().remove("building");
+        let loaded: ProjectFile = serde_json::from_value(legacy).unwrap();
+        assert!(loaded.building.is_empty());
+    }
+
+    #[test]
+    fn test_feeder_length_estimated() {
+        let mut project = sample_project();
+        project.building = Building::uniform(1, 3, 4.0);
+        let shaft = project.building.add_shaft("强电井1", (10.0, 0.0), (-1, 3));
+        project.boxes[0].floor = 3;
+        project.boxes[0].shaft = Some(shaft);
+        project.boxes[0].position = Some((10.0, 5.0));
+        let issues = project.run_calculations();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("变配电所"));
+
+        project.building.source = Some((-1, (0.0, 0.0)));
+        assert!(project.run_calculations().is_empty());
+        let cable = project.boxes[0].incoming_cable.as_ref().unwrap();
+        // 水平10m+5m，B1至3层12m，两端预留3m
+        assert_eq!(cable.length, 30.0);
+        assert_eq!(project.boxes[0].incoming_length(), Some((30.0, LengthSource::Estimated)));
+    }
+
+    #[test]
+    fn test_feeder_voltage_drop_checked() {
+        let mut project = sample_project();
+        project.boxes[0].feeder_length = Some(30.0);
+        assert!(project.run_calculations().is_empty());
+        assert!(project.boxes[0].coordination_log.iter().any(|l| l.contains("电压损失") && l.contains("输入长度30.0m")));
+
+        project.boxes[0].feeder_length = Some(2000.0);
+        let issues = project.run_calculations();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("超过5%"));
+    }
+}
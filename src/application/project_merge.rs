@@ -0,0 +1,765 @@
+//! 项目对比与合并
+//!
+//! 将项目展开为按稳定标识索引的对象（项目信息、建筑、楼层、竖井、配电箱、回路、系统图及其组件和连接），
+//! 楼层按楼层号、竖井、配电箱和回路按持久标识（UUID）、系统图中的配电箱组件按所属配电箱的持久标识、
+//! 其余组件按类型和标签索引，不依赖画布节点的NodeId，因此配电箱或回路改名只是一处修改，不会被当作删除后新增。
+//! 对比逐字段列出新增、删除和修改；三方合并以共同的基础版本为参照，只有一方修改的
+//! 字段自动合并，双方改成不同值的字段及"一方删除、另一方修改"的对象列为冲突，由使用者
+//! 逐项选择保留哪一方。总功率、相负荷等计算结果不参与对比，合并后重新计算即可。
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::application::error::{ApplicationError, Result};
+use crate::application::project::{ProjectFile, PROJECT_FILE_VERSION};
+
+/// 对象类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ObjectKind {
+    Project,
+    Building,
+    Floor,
+    Shaft,
+    Box,
+    Circuit,
+    Diagram,
+    DiagramComponent,
+    DiagramConnection,
+}
+
+impl ObjectKind {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            ObjectKind::Project => "项目信息",
+            ObjectKind::Building => "建筑",
+            ObjectKind::Floor => "楼层",
+            ObjectKind::Shaft => "竖井",
+            ObjectKind::Box => "配电箱",
+            ObjectKind::Circuit => "回路",
+            ObjectKind::Diagram => "系统图",
+            ObjectKind::DiagramComponent => "系统图组件",
+            ObjectKind::DiagramConnection => "系统图连接",
+        }
+    }
+
+    /// 由计算得出的字段，不参与对比与合并
+    fn derived_fields(&self) -> &'static [&'static str] {
+        match self {
+            ObjectKind::Box => &[
+                "total_power",
+                "total_current",
+                "incoming_current",
+                "phase_loads",
+                "coordination_log",
+                "incoming_cable",
+                "feeder_estimate",
+            ],
+            ObjectKind::Circuit => &["number"],
+            ObjectKind::Diagram => &["junctions"],
+            ObjectKind::DiagramConnection => &["route"],
+            _ => &[],
+        }
+    }
+}
+
+/// 对象标识：类型、所属对象及自身标识
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ObjectKey {
+    pub kind: ObjectKind,
+    /// 所属对象标识（回路所在配电箱、组件所在系统图），顶层对象为空
+    pub parent: String,
+    pub id: String,
+}
+
+impl ObjectKey {
+    fn new(kind: ObjectKind, parent: &str, id: &str) -> Self {
+        Self { kind, parent: parent.to_string(), id: id.to_string() }
+    }
+}
+
+type Fields = Map<String, Value>;
+
+/// 展开后的项目对象，保持原有顺序
+#[derive(Debug, Clone, Default)]
+struct ProjectObjects {
+    order: Vec<ObjectKey>,
+    objects: BTreeMap<ObjectKey, Fields>,
+}
+
+fn to_fields<T: Serialize>(value: &T) -> Result<Fields> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(fields)) => Ok(fields),
+        Ok(_) => Err(ApplicationError::state_management("展开项目", "对象不是结构体")),
+        Err(e) => Err(ApplicationError::state_management("展开项目", e.to_string())),
+    }
+}
+
+fn from_fields<T: DeserializeOwned>(key: &ObjectKey, fields: Fields) -> Result<T> {
+    serde_json::from_value(Value::Object(fields))
+        .map_err(|e| ApplicationError::state_management("还原项目", format!("{} {}: {}", key.kind.to_str(), key.id, e)))
+}
+
+/// 同名对象依次加序号，保证标识唯一
+fn unique_id(used: &mut BTreeMap<String, usize>, id: &str) -> String {
+    let count = used.entry(id.to_string()).or_insert(0);
+    *count += 1;
+    if *count == 1 {
+        id.to_string()
+    } else {
+        format!("{}#{}", id, count)
+    }
+}
+
+impl ProjectObjects {
+    fn insert(&mut self, key: ObjectKey, fields: Fields) {
+        self.order.push(key.clone());
+        self.objects.insert(key, fields);
+    }
+
+    fn from_project(project: &ProjectFile) -> Result<Self> {
+        let mut objects = Self::default();
+        objects.insert(ObjectKey::new(ObjectKind::Project, "", ""), to_fields(&project.info)?);
+
+        // 建筑本身只保留变配电所位置等属性，楼层按楼层号标识，竖井按持久标识
+        let mut fields = to_fields(&project.building)?;
+        fields.remove("floors");
+        fields.remove("shafts");
+        objects.insert(ObjectKey::new(ObjectKind::Building, "", ""), fields);
+        for floor in &project.building.floors {
+            objects.insert(ObjectKey::new(ObjectKind::Floor, "", &floor.level.to_string()), to_fields(floor)?);
+        }
+        for shaft in &project.building.shafts {
+            objects.insert(ObjectKey::new(ObjectKind::Shaft, "", &shaft.id), to_fields(shaft)?);
+        }
+
+        for box_data in &project.boxes {
+            let mut fields = to_fields(box_data)?;
+            fields.remove("circuits");
+            objects.insert(ObjectKey::new(ObjectKind::Box, "", &box_data.id), fields);
+            for circuit in &box_data.circuits {
+                let key = ObjectKey::new(ObjectKind::Circuit, &box_data.id, &circuit.circuit_id);
+                objects.insert(key, to_fields(circuit)?);
+            }
+        }
+
+        for diagram in &project.diagrams {
+            let mut fields = to_fields(diagram)?;
+            fields.remove("components");
+            fields.remove("connections");
+            objects.insert(ObjectKey::new(ObjectKind::Diagram, "", &diagram.name), fields);
+
+            // 组件序号在重新生成后会变化，改用组件的持久标识（配电箱组件按配电箱标识，其余按类型和标签），
+            // 连接按两端组件标识
+            let mut used = BTreeMap::new();
+            let component_ids: Vec<String> =
+                diagram.components.iter().map(|c| unique_id(&mut used, &c.key())).collect();
+            for (component, id) in diagram.components.iter().zip(&component_ids) {
+                let mut fields = to_fields(component)?;
+                fields.remove("id");
+                objects.insert(ObjectKey::new(ObjectKind::DiagramComponent, &diagram.name, id), fields);
+            }
+            let mut used = BTreeMap::new();
+            for connection in &diagram.connections {
+                let (Some(from), Some(to)) = (component_ids.get(connection.from), component_ids.get(connection.to)) else {
+                    continue;
+                };
+                let id = unique_id(&mut used, &format!("{} → {}", from, to));
+                let mut fields = to_fields(connection)?;
+                fields.insert("from".to_string(), Value::String(from.clone()));
+                fields.insert("to".to_string(), Value::String(to.clone()));
+                objects.insert(ObjectKey::new(ObjectKind::DiagramConnection, &diagram.name, &id), fields);
+            }
+        }
+        Ok(objects)
+    }
+
+    /// 按顺序列出指定类型、指定所属对象的对象
+    fn children<'a>(&'a self, kind: ObjectKind, parent: &'a str) -> impl Iterator<Item = (&'a ObjectKey, Fields)> + 'a {
+        self.order
+            .iter()
+            .filter(move |key| key.kind == kind && key.parent == parent)
+            .filter_map(|key| self.objects.get(key).map(|fields| (key, fields.clone())))
+    }
+
+    /// 对象描述，使用名称而非标识（如"配电箱 AL1 回路 照明"）
+    fn describe(&self, key: &ObjectKey) -> String {
+        let name = |key: &ObjectKey| {
+            self.objects.get(key).and_then(object_label).unwrap_or(&key.id).to_string()
+        };
+        match key.kind {
+            ObjectKind::Project | ObjectKind::Building => key.kind.to_str().to_string(),
+            ObjectKind::Floor | ObjectKind::Shaft | ObjectKind::Box | ObjectKind::Diagram => format!("{} {}", key.kind.to_str(), name(key)),
+            ObjectKind::Circuit => {
+                let parent = name(&ObjectKey::new(ObjectKind::Box, "", &key.parent));
+                format!("配电箱 {} 回路 {}", parent, name(key))
+            }
+            ObjectKind::DiagramComponent => format!("系统图 {} 组件 {}", key.parent, self.component_title(&key.parent, &key.id)),
+            ObjectKind::DiagramConnection => {
+                let end = |field: &str| {
+                    let id = self.objects.get(key).and_then(|fields| fields.get(field)).and_then(Value::as_str).unwrap_or("");
+                    self.component_title(&key.parent, id)
+                };
+                format!("系统图 {} 连接 {} → {}", key.parent, end("from"), end("to"))
+            }
+        }
+    }
+
+    /// 系统图组件的显示名称：标签首行，组件不存在时取其标识
+    fn component_title(&self, diagram: &str, id: &str) -> String {
+        self.objects
+            .get(&ObjectKey::new(ObjectKind::DiagramComponent, diagram, id))
+            .and_then(object_label)
+            .and_then(|label| label.lines().next())
+            .unwrap_or(id)
+            .to_string()
+    }
+
+    /// 还原为项目文件，所属对象已不存在的回路、组件和连接被丢弃
+    fn to_project(&self) -> Result<ProjectFile> {
+        let mut project = ProjectFile::new("");
+        project.version = PROJECT_FILE_VERSION;
+        if let Some((key, fields)) = self.children(ObjectKind::Project, "").next() {
+            project.info = from_fields(key, fields)?;
+        }
+        if let Some((key, fields)) = self.children(ObjectKind::Building, "").next() {
+            project.building = from_fields(key, fields)?;
+        }
+        for (key, fields) in self.children(ObjectKind::Floor, "") {
+            project.building.floors.push(from_fields(key, fields)?);
+        }
+        project.building.floors.sort_by_key(|floor| floor.level);
+        for (key, fields) in self.children(ObjectKind::Shaft, "") {
+            project.building.shafts.push(from_fields(key, fields)?);
+        }
+        for (key, mut fields) in self.children(ObjectKind::Box, "") {
+            let circuits = self.children(ObjectKind::Circuit, &key.id).map(|(_, fields)| Value::Object(fields)).collect();
+            fields.insert("circuits".to_string(), Value::Array(circuits));
+            project.boxes.push(from_fields(key, fields)?);
+        }
+        for (key, mut fields) in self.children(ObjectKind::Diagram, "") {
+            let mut index = BTreeMap::new();
+            let mut components = Vec::new();
+            for (i, (component_key, mut component)) in self.children(ObjectKind::DiagramComponent, &key.id).enumerate() {
+                component.insert("id".to_string(), Value::from(i));
+                index.insert(component_key.id.clone(), i);
+                components.push(Value::Object(component));
+            }
+            let mut connections = Vec::new();
+            for (_, mut connection) in self.children(ObjectKind::DiagramConnection, &key.id) {
+                let end = |field: &str| connection.get(field).and_then(Value::as_str).and_then(|id| index.get(id)).copied();
+                let (Some(from), Some(to)) = (end("from"), end("to")) else { continue };
+                connection.insert("from".to_string(), Value::from(from));
+                connection.insert("to".to_string(), Value::from(to));
+                connections.push(Value::Object(connection));
+            }
+            fields.insert("components".to_string(), Value::Array(components));
+            fields.insert("connections".to_string(), Value::Array(connections));
+            project.diagrams.push(from_fields(key, fields)?);
+        }
+        Ok(project)
+    }
+}
+
+/// 对象的显示名称（回路名称、组件标签等）
+fn object_label(fields: &Fields) -> Option<&str> {
+    fields.get("name").or_else(|| fields.get("label")).and_then(Value::as_str)
+}
+
+/// 字段值的显示文字
+fn display_value(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => "-".to_string(),
+        Some(Value::String(text)) => text.clone(),
+        Some(value) => value.to_string(),
+    }
+}
+
+/// 变化类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl DiffKind {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            DiffKind::Added => "新增",
+            DiffKind::Removed => "删除",
+            DiffKind::Modified => "修改",
+        }
+    }
+}
+
+/// 字段变化
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDiff {
+    pub field: String,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+/// 对象变化
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectDiff {
+    pub key: ObjectKey,
+    pub kind: DiffKind,
+    /// 对象描述
+    pub label: String,
+    /// 变化的字段（仅修改时有内容）
+    pub fields: Vec<FieldDiff>,
+}
+
+/// 对比两个对象的字段，计算结果字段除外
+fn field_diffs(kind: ObjectKind, old: &Fields, new: &Fields) -> Vec<FieldDiff> {
+    let mut names: Vec<&String> = old.keys().chain(new.keys()).collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .filter(|name| !kind.derived_fields().contains(&name.as_str()))
+        .filter(|name| old.get(*name) != new.get(*name))
+        .filter(|name| !(name.as_str() == "phase" && is_balanced_phase(old) && is_balanced_phase(new)))
+        .map(|name| FieldDiff { field: name.clone(), old: old.get(name).cloned(), new: new.get(name).cloned() })
+        .collect()
+}
+
+/// 回路的相由三相平衡自动分配（未手动指定）时，相的变化不视为修改
+fn is_balanced_phase(fields: &Fields) -> bool {
+    fields.get("phase_pinned").and_then(Value::as_bool) != Some(true)
+}
+
+/// 两个项目之间的差异
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectDiff {
+    pub objects: Vec<ObjectDiff>,
+}
+
+impl ProjectDiff {
+    /// 对比两个项目
+    ///
+    /// # 参数
+    /// * `old` - 原项目
+    /// * `new` - 新项目
+    ///
+    /// # 返回值
+    /// * `Ok(ProjectDiff)` - 按新项目中的顺序列出变化，删除的对象排在最后
+    /// * `Err(ApplicationError)` - 项目无法展开
+    pub fn between(old: &ProjectFile, new: &ProjectFile) -> Result<Self> {
+        let old = ProjectObjects::from_project(old)?;
+        let new = ProjectObjects::from_project(new)?;
+        let mut objects = Vec::new();
+        for key in &new.order {
+            let new_fields = &new.objects[key];
+            let label = new.describe(key);
+            match old.objects.get(key) {
+                None => objects.push(ObjectDiff { key: key.clone(), kind: DiffKind::Added, label, fields: Vec::new() }),
+                Some(old_fields) => {
+                    let fields = field_diffs(key.kind, old_fields, new_fields);
+                    if !fields.is_empty() {
+                        objects.push(ObjectDiff { key: key.clone(), kind: DiffKind::Modified, label, fields });
+                    }
+                }
+            }
+        }
+        for key in old.order.iter().filter(|key| !new.objects.contains_key(key)) {
+            let label = old.describe(key);
+            objects.push(ObjectDiff { key: key.clone(), kind: DiffKind::Removed, label, fields: Vec::new() });
+        }
+        Ok(Self { objects })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// 变化报告文本
+    pub fn to_report(&self) -> String {
+        if self.objects.is_empty() {
+            return "两个项目没有差异\n".to_string();
+        }
+        let count = |kind: DiffKind| self.objects.iter().filter(|o| o.kind == kind).count();
+        let mut text = format!(
+            "新增{}项，删除{}项，修改{}项\n",
+            count(DiffKind::Added),
+            count(DiffKind::Removed),
+            count(DiffKind::Modified)
+        );
+        for object in &self.objects {
+            text.push_str(&format!("  [{}] {}\n", object.kind.to_str(), object.label));
+            for field in &object.fields {
+                text.push_str(&format!(
+                    "      {}: {} → {}\n",
+                    field.field,
+                    display_value(field.old.as_ref()),
+                    display_value(field.new.as_ref())
+                ));
+            }
+        }
+        text
+    }
+}
+
+/// 冲突的取舍
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// 保留本方
+    Ours,
+    /// 采用对方
+    Theirs,
+}
+
+/// 合并冲突
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict {
+    pub key: ObjectKey,
+    /// 对象描述
+    pub label: String,
+    /// 冲突字段，None表示一方删除而另一方修改了该对象
+    pub field: Option<String>,
+    pub base: Option<Value>,
+    pub ours: Option<Value>,
+    pub theirs: Option<Value>,
+    /// 当前取舍，默认保留本方
+    pub resolution: Resolution,
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label)?;
+        let choice = match self.resolution {
+            Resolution::Ours => "保留本方",
+            Resolution::Theirs => "采用对方",
+        };
+        match &self.field {
+            Some(field) => write!(
+                f,
+                " {}: 基础 {}，本方 {}，对方 {}［{}］",
+                field,
+                display_value(self.base.as_ref()),
+                display_value(self.ours.as_ref()),
+                display_value(self.theirs.as_ref()),
+                choice
+            ),
+            None => {
+                let side = if self.ours.is_none() { "本方删除、对方修改" } else { "本方修改、对方删除" };
+                write!(f, " {}［{}］", side, choice)
+            }
+        }
+    }
+}
+
+/// 三方合并结果
+#[derive(Debug, Clone)]
+pub struct ProjectMerge {
+    merged: ProjectObjects,
+    /// 自动采用的对方修改数
+    pub applied: usize,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl ProjectMerge {
+    /// 以共同的基础版本为参照合并本方与对方的修改
+    ///
+    /// # 参数
+    /// * `base` - 双方共同的基础版本
+    /// * `ours` - 本方项目
+    /// * `theirs` - 对方项目
+    ///
+    /// # 返回值
+    /// * `Ok(ProjectMerge)` - 合并结果，冲突默认保留本方
+    /// * `Err(ApplicationError)` - 项目无法展开
+    pub fn merge(base: &ProjectFile, ours: &ProjectFile, theirs: &ProjectFile) -> Result<Self> {
+        let base = ProjectObjects::from_project(base)?;
+        let ours = ProjectObjects::from_project(ours)?;
+        let theirs = ProjectObjects::from_project(theirs)?;
+
+        let mut merge = Self { merged: ProjectObjects::default(), applied: 0, conflicts: Vec::new() };
+        let theirs_only = theirs.order.iter().filter(|key| !ours.objects.contains_key(key));
+        for key in ours.order.iter().chain(theirs_only) {
+            let label = if ours.objects.contains_key(key) { ours.describe(key) } else { theirs.describe(key) };
+            let b = base.objects.get(key);
+            match (b, ours.objects.get(key), theirs.objects.get(key)) {
+                (_, Some(o), Some(t)) => {
+                    let fields = merge.merge_fields(key, &label, b.cloned().unwrap_or_default(), o, t);
+                    merge.merged.insert(key.clone(), fields);
+                }
+                (None, Some(o), None) => merge.merged.insert(key.clone(), o.clone()),
+                (None, None, Some(t)) => {
+                    merge.applied += 1;
+                    merge.merged.insert(key.clone(), t.clone());
+                }
+                // 对方删除
+                (Some(b), Some(o), None) => {
+                    if field_diffs(key.kind, b, o).is_empty() {
+                        merge.applied += 1;
+                    } else {
+                        merge.object_conflict(key, label, b, Some(o), None);
+                        merge.merged.insert(key.clone(), o.clone());
+                    }
+                }
+                // 本方删除
+                (Some(b), None, Some(t)) => {
+                    if !field_diffs(key.kind, b, t).is_empty() {
+                        merge.object_conflict(key, label, b, None, Some(t));
+                    }
+                }
+                (_, None, None) => {}
+            }
+        }
+        Ok(merge)
+    }
+
+    fn object_conflict(&mut self, key: &ObjectKey, label: String, base: &Fields, ours: Option<&Fields>, theirs: Option<&Fields>) {
+        let object = |fields: Option<&Fields>| fields.map(|f| Value::Object(f.clone()));
+        self.conflicts.push(MergeConflict {
+            key: key.clone(),
+            label,
+            field: None,
+            base: object(Some(base)),
+            ours: object(ours),
+            theirs: object(theirs),
+            resolution: Resolution::Ours,
+        });
+    }
+
+    /// 逐字段合并，计算结果字段取本方（合并后重新计算）
+    fn merge_fields(&mut self, key: &ObjectKey, label: &str, base: Fields, ours: &Fields, theirs: &Fields) -> Fields {
+        let mut merged = ours.clone();
+        for (name, diff) in field_diffs(key.kind, ours, theirs).into_iter().map(|d| (d.field.clone(), d)) {
+            let base_value = base.get(&name);
+            if diff.old.as_ref() == base_value {
+                // 仅对方修改
+                match diff.new {
+                    Some(value) => merged.insert(name, value),
+                    None => merged.remove(&name),
+                };
+                self.applied += 1;
+            } else if diff.new.as_ref() != base_value {
+                self.conflicts.push(MergeConflict {
+                    key: key.clone(),
+                    label: label.to_string(),
+                    field: Some(name),
+                    base: base_value.cloned(),
+                    ours: diff.old,
+                    theirs: diff.new,
+                    resolution: Resolution::Ours,
+                });
+            }
+        }
+        merged
+    }
+
+    /// 设置冲突的取舍
+    ///
+    /// # 参数
+    /// * `index` - 冲突序号（从0开始）
+    /// * `resolution` - 取舍
+    ///
+    /// # 返回值
+    /// 序号有效返回true
+    pub fn resolve(&mut self, index: usize, resolution: Resolution) -> bool {
+        match self.conflicts.get_mut(index) {
+            Some(conflict) => {
+                conflict.resolution = resolution;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 合并报告文本，冲突前加序号（从1开始）
+    pub fn to_report(&self) -> String {
+        let mut text = format!("自动合并对方修改{}项，冲突{}项\n", self.applied, self.conflicts.len());
+        for (index, conflict) in self.conflicts.iter().enumerate() {
+            text.push_str(&format!("  [{}] {}\n", index + 1, conflict));
+        }
+        text
+    }
+
+    /// 按冲突取舍生成合并后的项目
+    ///
+    /// # 返回值
+    /// * `Ok(ProjectFile)` - 合并后的项目（计算结果需重新计算）
+    /// * `Err(ApplicationError)` - 项目无法还原
+    pub fn into_project(self) -> Result<ProjectFile> {
+        let mut merged = self.merged;
+        for conflict in self.conflicts.iter().filter(|c| c.resolution == Resolution::Theirs) {
+            match &conflict.field {
+                Some(field) => {
+                    if let Some(fields) = merged.objects.get_mut(&conflict.key) {
+                        match &conflict.theirs {
+                            Some(value) => fields.insert(field.clone(), value.clone()),
+                            None => fields.remove(field),
+                        };
+                    }
+                }
+                None => match &conflict.theirs {
+                    Some(Value::Object(fields)) => {
+                        if !merged.objects.contains_key(&conflict.key) {
+                            merged.order.push(conflict.key.clone());
+                        }
+                        merged.objects.insert(conflict.key.clone(), fields.clone());
+                    }
+                    _ => {
+                        merged.objects.remove(&conflict.key);
+                    }
+                },
+            }
+        }
+        merged.to_project()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::business::{BoxData, Building, CableLengthEstimate, CircuitInfo, SystemDiagram};
+    use crate::editor::business::main_system_node::ComponentType;
+
+    fn base_project() -> ProjectFile {
+        let mut project = ProjectFile::new("合并测试");
+        let mut box_data = BoxData::new("AL1".to_string(), 1);
+        box_data.circuits.push(CircuitInfo::new("c1".to_string(), "照明".to_string(), 2.0, 10.0));
+        box_data.circuits.push(CircuitInfo::new("c2".to_string(), "插座".to_string(), 3.0, 15.0));
+        project.boxes.push(box_data);
+        project.boxes.push(BoxData::new("AP1".to_string(), 2));
+        let mut diagram = SystemDiagram::new("配电干线图".to_string());
+        let busbar = diagram.add_component(ComponentType::Busbar, "主母线".to_string());
+        let al1 = diagram.add_component(ComponentType::DistributionBox, "AL1".to_string());
+        diagram.link_box(al1, &project.boxes[0].id);
+        diagram.add_connection(busbar, al1);
+        project.diagrams.push(diagram);
+        project
+    }
+
+    #[test]
+    fn test_diff_report() {
+        let old = base_project();
+        let mut new = old.clone();
+        new.boxes[0].circuits[0].power = 2.5;
+        new.boxes[0].total_power = 5.5;
+        new.boxes[0].circuits.remove(1);
+        new.boxes[1].floor = 3;
+        new.boxes[1].name = "AP1-1".to_string();
+        new.diagrams[0].add_component(ComponentType::DistributionBox, "AP1".to_string());
+
+        let diff = ProjectDiff::between(&old, &new).unwrap();
+        let summary: Vec<(DiffKind, ObjectKind)> = diff.objects.iter().map(|o| (o.kind, o.key.kind)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (DiffKind::Modified, ObjectKind::Circuit),
+                (DiffKind::Modified, ObjectKind::Box),
+                (DiffKind::Added, ObjectKind::DiagramComponent),
+                (DiffKind::Removed, ObjectKind::Circuit),
+            ]
+        );
+        let report = diff.to_report();
+        assert!(report.contains("配电箱 AL1 回路 照明"));
+        assert!(report.contains("power: 2.0 → 2.5"));
+        assert!(report.contains("配电箱 AP1-1\n      floor: 2 → 3\n      name: AP1 → AP1-1"));
+        assert!(!report.contains("total_power"));
+        assert!(ProjectDiff::between(&old, &old.clone()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_three_way_merge() {
+        let base = base_project();
+        let mut ours = base.clone();
+        ours.boxes[0].circuits[0].power = 2.5;
+        ours.boxes[1].floor = 3;
+        ours.boxes[0].circuits.push(CircuitInfo::new("c3".to_string(), "空调".to_string(), 5.0, 20.0));
+
+        let mut theirs = base.clone();
+        theirs.boxes[0].circuits[1].power = 4.0;
+        theirs.boxes[1].floor = 4;
+        theirs.boxes.push(BoxData::new("AL2".to_string(), 5));
+        theirs.diagrams[0].components[0].label = "1#母线".to_string();
+
+        let mut merge = ProjectMerge::merge(&base, &ours, &theirs).unwrap();
+        assert_eq!(merge.conflicts.len(), 1);
+        assert_eq!(merge.conflicts[0].field.as_deref(), Some("floor"));
+        assert!(merge.to_report().contains("本方 3，对方 4"));
+
+        let kept = merge.clone().into_project().unwrap();
+        assert_eq!(kept.boxes.len(), 3);
+        assert_eq!(kept.boxes[1].floor, 3);
+        let circuits = &kept.boxes[0].circuits;
+        assert_eq!((circuits[0].power, circuits[1].power, circuits.len()), (2.5, 4.0, 3));
+        // 组件改名视为删除旧组件、新增新组件，连接随组件一并变化
+        assert_eq!(kept.diagrams[0].components.len(), 2);
+        assert!(kept.diagrams[0].components.iter().any(|c| c.label == "1#母线"));
+
+        assert!(merge.resolve(0, Resolution::Theirs));
+        assert_eq!(merge.into_project().unwrap().boxes[1].floor, 4);
+    }
+
+    #[test]
+    fn test_box_component_keyed_by_box_id() {
+        // 配电箱组件按配电箱标识对应：改名后的标签变化是一处修改，可与另一方移动位置合并
+        let base = base_project();
+        let mut ours = base.clone();
+        ours.diagrams[0].components[1].position = (200.0, 80.0);
+        let mut theirs = base.clone();
+        theirs.boxes[0].name = "AL1-1".to_string();
+        theirs.diagrams[0].components[1].label = "AL1-1\n功率:5.00kW".to_string();
+
+        let diff = ProjectDiff::between(&base, &theirs).unwrap();
+        assert!(diff.objects.iter().all(|o| o.kind == DiffKind::Modified));
+        assert!(diff.to_report().contains("系统图 配电干线图 组件 AL1-1"));
+
+        let merge = ProjectMerge::merge(&base, &ours, &theirs).unwrap();
+        assert!(merge.conflicts.is_empty());
+        let merged = merge.into_project().unwrap();
+        let components = &merged.diagrams[0].components;
+        assert_eq!(components.len(), 2);
+        assert_eq!((components[1].label.as_str(), components[1].position), ("AL1-1\n功率:5.00kW", (200.0, 80.0)));
+        assert_eq!(merged.diagrams[0].connections.len(), 1);
+    }
+
+    #[test]
+    fn test_building_merged() {
+        let base = base_project();
+        let mut ours = base.clone();
+        ours.building = Building::uniform(1, 3, 4.0);
+        ours.building.add_shaft("强电井1", (10.0, 0.0), (-1, 3));
+        let mut theirs = base.clone();
+        theirs.building.source = Some((-1, (0.0, 0.0)));
+        theirs.boxes[0].feeder_length = Some(45.0);
+        theirs.boxes[0].feeder_estimate = Some(CableLengthEstimate { horizontal: 30.0, riser: 8.0, allowance: 3.0 });
+
+        let merge = ProjectMerge::merge(&base, &ours, &theirs).unwrap();
+        assert!(merge.conflicts.is_empty());
+        let merged = merge.into_project().unwrap();
+        assert_eq!(merged.building.floors, ours.building.floors);
+        assert_eq!(merged.building.shafts, ours.building.shafts);
+        assert_eq!(merged.building.source, Some((-1, (0.0, 0.0))));
+        assert_eq!(merged.boxes[0].feeder_length, Some(45.0));
+        // 估算长度由计算得出，不参与合并
+        assert_eq!(merged.boxes[0].feeder_estimate, None);
+    }
+
+    #[test]
+    fn test_delete_modify_conflict() {
+        let base = base_project();
+        let mut ours = base.clone();
+        ours.boxes[0].circuits.remove(1);
+        let mut theirs = base.clone();
+        theirs.boxes[0].circuits[1].power = 4.0;
+
+        let mut merge = ProjectMerge::merge(&base, &ours, &theirs).unwrap();
+        assert_eq!(merge.conflicts.len(), 1);
+        assert_eq!(merge.conflicts[0].field, None);
+        assert_eq!(merge.clone().into_project().unwrap().boxes[0].circuits.len(), 1);
+        merge.resolve(0, Resolution::Theirs);
+        let restored = merge.into_project().unwrap();
+        assert_eq!(restored.boxes[0].circuits.len(), 2);
+        assert_eq!(restored.boxes[0].circuits[1].power, 4.0);
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 /// 应用状态管理模块
 use std::sync::{Arc, Mutex};
+use crate::editor::business::DataFlowManager;
 use crate::editor::graph::PowerDistributionGraphEditorState;
 use crate::editor::ui::{NodeSearcher, PerformanceOptimizer, Logger};
 
@@ -30,6 +31,22 @@ impl AppState {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// 对节点图中的全部节点执行一次计算
+    ///
+    /// # 返回值
+    /// 节点图状态被其他线程占用时返回错误信息
+    pub fn perform_calculations(&mut self) -> Result<(), String> {
+        let mut graph_state = self
+            .graph_editor_state
+            .lock()
+            .map_err(|_| "节点图状态不可用".to_string())?;
+        let mut manager = DataFlowManager::new();
+        let node_ids: Vec<_> = graph_state.graph().iter_nodes().collect();
+        manager.mark_nodes_for_update(node_ids);
+        manager.propagate_updates(graph_state.graph_mut());
+        Ok(())
+    }
 }
 
 impl Default for AppState {
@@ -48,7 +65,7 @@ impl Default for AppState {
 }
 
 /// 项目信息
-#[derive(Default, Debug, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProjectInfo {
     /// 项目名称
     pub name: String,
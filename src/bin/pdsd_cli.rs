@@ -0,0 +1,363 @@
+//! PDSD命令行工具
+//!
+//! 不打开界面读取项目文件，执行与界面相同的计算流程（回路编号、三相平衡、保护配合选型、
+//! 消防模块生成及消防设计规则校验），输出校验问题，并按需导出负荷一览表、材料表、计算书、
+//! 电气火灾监控点位表或DXF、PDF系统图，供持续集成和批处理使用。也可先导入负荷清单批量创建回路，导入预览及逐行
+//! 错误输出后再参与计算，确认无误后用 --save 写回项目；负荷清单更新后可重新导入，列出
+//! 新增、修改和删除的回路，用 --reject 拒绝其中的部分变更。多人分别修改同一项目时，可用
+//! --diff 对比两个项目文件，或用 --merge 以共同的基础版本三方合并对方的修改，冲突默认
+//! 保留本方，用 --theirs 指定改用对方的冲突项。
+//!
+//! 退出码：0表示全部通过，1表示校验发现问题，2表示参数错误或读写失败。
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use Power_Distribution_System_Diagram::application::{ProjectDiff, ProjectFile, ProjectMerge, Resolution};
+use Power_Distribution_System_Diagram::editor::business::ElectricalFirePlanner;
+use Power_Distribution_System_Diagram::export::report::CalculationBook;
+use Power_Distribution_System_Diagram::export::{
+    BillOfMaterials, BomGrouping, DrawingFrame, DxfExporter, LoadSchedule, PdfExporter, SheetSize, TitleBlock,
+};
+use Power_Distribution_System_Diagram::import::{read_table, LoadListImporter, ReimportDiff};
+
+/// 校验发现问题
+const EXIT_ISSUES: u8 = 1;
+/// 参数错误或读写失败
+const EXIT_FAILURE: u8 = 2;
+
+const USAGE: &str = "\
+用法: pdsd-cli <项目文件> [选项]
+
+选项:
+  --import <路径>         导入负荷清单（.csv 或 .xlsx），有错误行时需 --force
+  --reimport <路径>       重新导入更新后的负荷清单，与已有回路对比后应用变更
+  --reject <序号,...>     重新导入时拒绝的变更序号（见变更列表）
+  --diff <路径>           列出本项目到另一项目文件的差异
+  --merge <基础> <对方>   以基础版本为参照合并对方项目的修改，有冲突时需 --force
+  --theirs <序号,...>     合并时改用对方的冲突序号（见冲突列表）
+  --load-schedule <路径>  导出负荷一览表（.csv 或 .xlsx）
+  --bom <路径>            导出材料表（.csv 或 .xlsx）
+  --bom-group <分组>      材料表分组：project（默认）、floor 或 system
+  --report <路径>         导出计算书（.md、.html 或 .pdf）
+  --dxf <路径>            导出配电箱系统图（.dxf）
+  --pdf <路径>            导出配电箱系统图（.pdf，A3图框）
+  --trunk-pdf <路径>      导出干线系统图（.pdf，A3图框）
+  --detector-schedule <路径>  导出电气火灾监控点位表（.txt）
+  --save <路径>           保存计算后的项目文件
+  --force                 校验发现问题时仍然导出
+  -h, --help              显示帮助";
+
+/// 命令行选项
+#[derive(Debug, Default, PartialEq)]
+struct CliOptions {
+    project: PathBuf,
+    import: Option<PathBuf>,
+    reimport: Option<PathBuf>,
+    reject: Vec<usize>,
+    diff: Option<PathBuf>,
+    merge: Option<(PathBuf, PathBuf)>,
+    theirs: Vec<usize>,
+    load_schedule: Option<PathBuf>,
+    bom: Option<PathBuf>,
+    bom_grouping: BomGrouping,
+    report: Option<PathBuf>,
+    dxf: Option<PathBuf>,
+    pdf: Option<PathBuf>,
+    trunk_pdf: Option<PathBuf>,
+    detector_schedule: Option<PathBuf>,
+    save: Option<PathBuf>,
+    force: bool,
+}
+
+/// 解析结果
+#[derive(Debug, PartialEq)]
+enum Command {
+    Help,
+    Run(Box<CliOptions>),
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Ok(Command::Run(options)) => options,
+        Err(message) => {
+            eprintln!("错误: {}\n\n{}", message, USAGE);
+            return ExitCode::from(EXIT_FAILURE);
+        }
+    };
+
+    match run(&options) {
+        Ok(0) => ExitCode::SUCCESS,
+        Ok(_) => ExitCode::from(EXIT_ISSUES),
+        Err(message) => {
+            eprintln!("错误: {}", message);
+            ExitCode::from(EXIT_FAILURE)
+        }
+    }
+}
+
+/// 解析命令行参数，导出文件格式按扩展名确定
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut options = CliOptions::default();
+    let mut project = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{} 缺少参数值", name));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--import" => options.import = Some(with_extension(value(&arg)?, &["csv", "xlsx"])?),
+            "--reimport" => options.reimport = Some(with_extension(value(&arg)?, &["csv", "xlsx"])?),
+            "--reject" => options.reject = parse_indices(&value(&arg)?, "变更")?,
+            "--diff" => options.diff = Some(PathBuf::from(value(&arg)?)),
+            "--merge" => {
+                let base = PathBuf::from(value(&arg)?);
+                options.merge = Some((base, PathBuf::from(value(&arg)?)));
+            }
+            "--theirs" => options.theirs = parse_indices(&value(&arg)?, "冲突")?,
+            "--load-schedule" => options.load_schedule = Some(with_extension(value(&arg)?, &["csv", "xlsx"])?),
+            "--bom" => options.bom = Some(with_extension(value(&arg)?, &["csv", "xlsx"])?),
+            "--bom-group" => {
+                options.bom_grouping = match value(&arg)?.as_str() {
+                    "project" => BomGrouping::Project,
+                    "floor" => BomGrouping::Floor,
+                    "system" => BomGrouping::System,
+                    other => return Err(format!("未知的材料表分组: {}", other)),
+                }
+            }
+            "--report" => options.report = Some(with_extension(value(&arg)?, &["md", "html", "pdf"])?),
+            "--dxf" => options.dxf = Some(with_extension(value(&arg)?, &["dxf"])?),
+            "--pdf" => options.pdf = Some(with_extension(value(&arg)?, &["pdf"])?),
+            "--trunk-pdf" => options.trunk_pdf = Some(with_extension(value(&arg)?, &["pdf"])?),
+            "--detector-schedule" => options.detector_schedule = Some(with_extension(value(&arg)?, &["txt"])?),
+            "--save" => options.save = Some(PathBuf::from(value(&arg)?)),
+            "--force" => options.force = true,
+            other if other.starts_with('-') => return Err(format!("未知选项: {}", other)),
+            other if project.is_none() => project = Some(PathBuf::from(other)),
+            other => return Err(format!("多余的参数: {}", other)),
+        }
+    }
+    options.project = project.ok_or("缺少项目文件")?;
+    if options.import.is_some() && options.reimport.is_some() {
+        return Err("--import 与 --reimport 不能同时使用".to_string());
+    }
+    Ok(Command::Run(Box::new(options)))
+}
+
+/// 解析逗号分隔的序号列表
+fn parse_indices(value: &str, what: &str) -> Result<Vec<usize>, String> {
+    value
+        .split(',')
+        .filter(|item| !item.trim().is_empty())
+        .map(|item| item.trim().parse().map_err(|_| format!("无效的{}序号: {}", what, item)))
+        .collect()
+}
+
+fn with_extension(path: String, allowed: &[&str]) -> Result<PathBuf, String> {
+    let path = PathBuf::from(path);
+    match extension(&path) {
+        Some(ext) if allowed.contains(&ext.as_str()) => Ok(path),
+        _ => Err(format!("{} 的扩展名应为 {}", path.display(), allowed.join("/"))),
+    }
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension().map(|ext| ext.to_string_lossy().to_lowercase())
+}
+
+/// 读取项目、计算校验并导出，返回校验问题数
+fn run(options: &CliOptions) -> Result<usize, String> {
+    let mut project = ProjectFile::load(&options.project).map_err(|e| e.to_string())?;
+    if let Some(path) = &options.diff {
+        let other = ProjectFile::load(path).map_err(|e| e.to_string())?;
+        let diff = ProjectDiff::between(&project, &other).map_err(|e| e.to_string())?;
+        print!("对比 {}: {}", path.display(), diff.to_report());
+    }
+    if let Some((base, theirs)) = &options.merge {
+        let base_project = ProjectFile::load(base).map_err(|e| e.to_string())?;
+        let theirs_project = ProjectFile::load(theirs).map_err(|e| e.to_string())?;
+        let mut merge = ProjectMerge::merge(&base_project, &project, &theirs_project).map_err(|e| e.to_string())?;
+        for &index in &options.theirs {
+            if index == 0 || !merge.resolve(index - 1, Resolution::Theirs) {
+                return Err(format!("冲突序号 {} 超出范围（共{}项）", index, merge.conflicts.len()));
+            }
+        }
+        print!("合并 {}: {}", theirs.display(), merge.to_report());
+        if !merge.conflicts.is_empty() && !options.force {
+            eprintln!("合并有{}项冲突，未合并；确认取舍后加 --force", merge.conflicts.len());
+            return Ok(merge.conflicts.len());
+        }
+        project = merge.into_project().map_err(|e| e.to_string())?;
+    }
+    if let Some(path) = &options.import {
+        let table = read_table(path).map_err(|e| e.to_string())?;
+        let preview = LoadListImporter::preview(&table, None, &project.boxes).map_err(|e| e.to_string())?;
+        print!("负荷清单 {}: {}", path.display(), preview.to_text());
+        if !preview.errors.is_empty() && !options.force {
+            eprintln!("负荷清单有{}项错误，未导入", preview.errors.len());
+            return Ok(preview.errors.len());
+        }
+        let count = preview.apply(&mut project);
+        println!("已导入回路{}个", count);
+    }
+    if let Some(path) = &options.reimport {
+        let table = read_table(path).map_err(|e| e.to_string())?;
+        let mut diff = LoadListImporter::diff(&table, None, &project.boxes).map_err(|e| e.to_string())?;
+        reject_changes(&mut diff, &options.reject)?;
+        print!("重新导入 {}: {}", path.display(), diff.to_text());
+        if !diff.errors.is_empty() && !options.force {
+            eprintln!("负荷清单有{}项错误，未应用变更", diff.errors.len());
+            return Ok(diff.errors.len());
+        }
+        let count = diff.apply(&mut project);
+        println!("已应用变更{}项", count);
+    }
+    let issues = project.run_calculations();
+    println!("项目: {}（配电箱{}个）", project.info.name, project.boxes.len());
+    if issues.is_empty() {
+        println!("计算及校验通过");
+    } else {
+        for issue in &issues {
+            eprintln!("  {}", issue);
+        }
+        eprintln!("校验发现{}项问题", issues.len());
+        if !options.force {
+            return Ok(issues.len());
+        }
+    }
+
+    if let Some(path) = &options.save {
+        project.save(path).map_err(|e| e.to_string())?;
+        println!("已保存项目: {}", path.display());
+    }
+    export(&project, options).map_err(|e| e.to_string())?;
+    Ok(issues.len())
+}
+
+/// 按变更列表中的序号（从1开始）拒绝变更
+fn reject_changes(diff: &mut ReimportDiff, reject: &[usize]) -> Result<(), String> {
+    for &index in reject {
+        if index == 0 || !diff.set_accepted(index - 1, false) {
+            return Err(format!("变更序号 {} 超出范围（共{}项）", index, diff.changes.len()));
+        }
+    }
+    Ok(())
+}
+
+fn export(project: &ProjectFile, options: &CliOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let boxes = project.box_refs();
+
+    if let Some(path) = &options.load_schedule {
+        let schedule = LoadSchedule::from_boxes(&boxes);
+        match extension(path).as_deref() {
+            Some("xlsx") => schedule.export_xlsx(path)?,
+            _ => schedule.export_csv(path)?,
+        }
+        println!("已导出负荷一览表: {}", path.display());
+    }
+
+    if let Some(path) = &options.bom {
+        let bom = BillOfMaterials::from_boxes(&boxes, options.bom_grouping);
+        match extension(path).as_deref() {
+            Some("xlsx") => bom.export_xlsx(path)?,
+            _ => bom.export_csv(path)?,
+        }
+        println!("已导出材料表: {}", path.display());
+    }
+
+    if let Some(path) = &options.report {
+        let book = CalculationBook::from_boxes(&project.info.name, &boxes);
+        match extension(path).as_deref() {
+            Some("pdf") => {
+                let frame = DrawingFrame::new(
+                    SheetSize::A3,
+                    TitleBlock { project_name: project.info.name.clone(), ..TitleBlock::default() },
+                );
+                book.export_pdf(&frame, path)?
+            }
+            Some("html") => book.export_html(path)?,
+            _ => book.export_markdown(path)?,
+        }
+        println!("已导出计算书: {}", path.display());
+    }
+
+    if let Some(path) = &options.dxf {
+        DxfExporter::export_boxes(&boxes, path)?;
+        println!("已导出系统图: {}", path.display());
+    }
+
+    if options.pdf.is_some() || options.trunk_pdf.is_some() {
+        let frame = DrawingFrame::new(
+            SheetSize::A3,
+            TitleBlock { project_name: project.info.name.clone(), ..TitleBlock::default() },
+        );
+        if let Some(path) = &options.pdf {
+            PdfExporter::export_boxes(&boxes, &frame, path)?;
+            println!("已导出系统图: {}", path.display());
+        }
+        if let Some(path) = &options.trunk_pdf {
+            PdfExporter::export_trunk(&project.diagrams, &frame, path)?;
+            println!("已导出干线系统图: {}", path.display());
+        }
+    }
+
+    if let Some(path) = &options.detector_schedule {
+        let schedule = ElectricalFirePlanner::detector_schedule(&boxes);
+        std::fs::write(path, ElectricalFirePlanner::schedule_to_text(&schedule))?;
+        println!("已导出电气火灾监控点位表: {}", path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let command = parse_args(args(&[
+            "p.json", "--import", "负荷清单.csv", "--bom", "out/材料表.XLSX", "--bom-group", "floor", "--force",
+        ]))
+        .unwrap();
+        let Command::Run(options) = command else { panic!("应解析为执行命令") };
+        assert_eq!(options.project, PathBuf::from("p.json"));
+        assert_eq!(options.import, Some(PathBuf::from("负荷清单.csv")));
+        assert_eq!(options.bom, Some(PathBuf::from("out/材料表.XLSX")));
+        assert_eq!(options.bom_grouping, BomGrouping::Floor);
+        assert!(options.force);
+        assert_eq!(parse_args(args(&["--help"])).unwrap(), Command::Help);
+        let command = parse_args(args(&["p.json", "--reimport", "负荷清单.xlsx", "--reject", "2, 5"])).unwrap();
+        let Command::Run(options) = command else { panic!("应解析为执行命令") };
+        assert_eq!(options.reject, vec![2, 5]);
+        let command = parse_args(args(&["p.json", "--merge", "base.json", "b.json", "--theirs", "1,3"])).unwrap();
+        let Command::Run(options) = command else { panic!("应解析为执行命令") };
+        assert_eq!(options.merge, Some((PathBuf::from("base.json"), PathBuf::from("b.json"))));
+        assert_eq!(options.theirs, vec![1, 3]);
+        let command = parse_args(args(&["p.json", "--pdf", "系统图.PDF", "--trunk-pdf", "干线图.pdf"])).unwrap();
+        let Command::Run(options) = command else { panic!("应解析为执行命令") };
+        assert_eq!(options.pdf, Some(PathBuf::from("系统图.PDF")));
+        assert_eq!(options.trunk_pdf, Some(PathBuf::from("干线图.pdf")));
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert!(parse_args(args(&[])).is_err());
+        assert!(parse_args(args(&["p.json", "--report", "book.doc"])).is_err());
+        assert!(parse_args(args(&["p.json", "--dxf"])).is_err());
+        assert!(parse_args(args(&["p.json", "--pdf", "系统图.dxf"])).is_err());
+        assert!(parse_args(args(&["p.json", "--detector-schedule", "点位表.csv"])).is_err());
+        assert!(parse_args(args(&["p.json", "--import", "负荷清单.xls"])).is_err());
+        assert!(parse_args(args(&["p.json", "--import", "a.csv", "--reimport", "b.csv"])).is_err());
+        assert!(parse_args(args(&["p.json", "--reimport", "b.csv", "--reject", "1,x"])).is_err());
+        assert!(parse_args(args(&["p.json", "--merge", "base.json"])).is_err());
+        assert!(parse_args(args(&["p.json", "q.json"])).is_err());
+        assert!(parse_args(args(&["p.json", "--bom-group", "shaft"])).is_err());
+    }
+}
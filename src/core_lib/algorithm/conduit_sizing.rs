@@ -0,0 +1,281 @@
+use super::super::error::{CoreError, Result};
+use super::protection_coordination::STANDARD_SECTIONS;
+use crate::core_lib::data_types::{CableInfo, LayingArea, LayingMethod, PipeSpecification};
+
+/// 450/750V单芯导线(BV、BYJ)外径(mm)，与STANDARD_SECTIONS对应
+const WIRE_OUTER_DIAMETER: [f64; 16] = [
+    3.3, 3.9, 4.4, 4.9, 6.6, 7.8, 9.6, 10.9, 13.2, 14.9, 17.3, 18.9, 21.1, 23.5, 26.6, 29.6,
+];
+
+/// 0.6/1kV三芯电缆外径(mm)，按相线截面，与STANDARD_SECTIONS对应（两芯电缆按三芯取值）
+const CABLE_3CORE_OUTER_DIAMETER: [f64; 16] = [
+    10.5, 11.5, 12.8, 14.0, 16.5, 18.8, 22.5, 25.0, 28.5, 32.5, 37.0, 40.5, 44.5, 49.5, 55.5, 61.0,
+];
+
+/// 0.6/1kV五芯(4+1芯)电缆外径(mm)，按相线截面，与STANDARD_SECTIONS对应（四芯电缆按五芯取值）
+const CABLE_5CORE_OUTER_DIAMETER: [f64; 16] = [
+    12.5, 13.8, 15.5, 17.0, 20.0, 23.0, 27.0, 30.0, 35.0, 40.0, 46.0, 51.0, 56.0, 62.0, 70.0, 77.0,
+];
+
+/// 多根导线穿管时导线总截面（含外护层）与管内截面之比的上限
+pub const CONDUIT_FILL_RATIO: f64 = 0.4;
+
+/// 单根电缆穿管时管内径与电缆外径之比的下限
+pub const SINGLE_CABLE_DIAMETER_RATIO: f64 = 1.5;
+
+/// 导线/电缆芯数规格，如"BV-3x2.5"、"YJV-4x25+1x16"
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConductorSpec {
+    /// 型号（如BV、YJV、NH-YJV）
+    pub model: String,
+    /// 主芯数
+    pub phase_cores: u32,
+    /// 主芯截面(mm²)
+    pub phase_section: f64,
+    /// 附加芯（PE线或减小截面的N线）截面(mm²)，None表示无附加芯
+    pub extra_section: Option<f64>,
+}
+
+impl ConductorSpec {
+    /// 创建芯数规格
+    pub fn new(model: &str, phase_cores: u32, phase_section: f64, extra_section: Option<f64>) -> Self {
+        Self {
+            model: model.to_string(),
+            phase_cores,
+            phase_section,
+            extra_section,
+        }
+    }
+
+    /// 解析形如"YJV-4x25+1x16"的规格字符串，截面可带"mm²"后缀
+    pub fn parse(spec: &str) -> Result<Self> {
+        let invalid = || CoreError::validation("线缆规格", format!("无法识别的线缆规格: {}", spec));
+        let (model, cores) = spec.trim().rsplit_once('-').ok_or_else(invalid)?;
+        let cores = cores.trim_end_matches("mm²");
+
+        let mut parts = cores.split('+');
+        let (count, section) = parts.next().ok_or_else(invalid)?.split_once(['x', 'X', '×']).ok_or_else(invalid)?;
+        let extra_section = match parts.next() {
+            Some(extra) => {
+                let extra = extra.trim_start_matches("PE");
+                let section = extra.split_once(['x', 'X', '×']).map_or(extra, |(_, s)| s);
+                Some(section.parse().map_err(|_| invalid())?)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            model: model.to_string(),
+            phase_cores: count.parse().map_err(|_| invalid())?,
+            phase_section: section.parse().map_err(|_| invalid())?,
+            extra_section,
+        })
+    }
+
+    /// 是否为单芯导线（BV、BYJ等，每芯单独穿管）
+    pub fn is_single_core_wire(&self) -> bool {
+        let model = self.model.to_uppercase();
+        model.contains("BV") || model.contains("BYJ")
+    }
+
+    /// 总芯数
+    pub fn total_cores(&self) -> u32 {
+        self.phase_cores + self.extra_section.is_some() as u32
+    }
+
+    /// 规格字符串，如"YJV-4x25+1x16"
+    pub fn to_spec_str(&self) -> String {
+        let mut spec = format!("{}-{}x{}", self.model, self.phase_cores, self.phase_section);
+        if let Some(extra) = self.extra_section {
+            spec.push_str(&format!("+1x{}", extra));
+        }
+        spec
+    }
+}
+
+/// 导管选择结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConduitSelection {
+    /// 选定的管径，桥架/线槽敷设为None
+    pub pipe: Option<PipeSpecification>,
+    /// 导线（含外护层）总截面(mm²)
+    pub bundle_area: f64,
+    /// 完整标注，如"YJV-4x25+1x16 SC50 WC"
+    pub annotation: String,
+}
+
+/// 导管管径选择工具
+///
+/// 多根导线按总截面不超过管内截面40%选管，单根电缆按管内径不小于电缆外径1.5倍选管
+pub struct ConduitSizing;
+
+impl ConduitSizing {
+    /// 查询单芯导线外径(mm)
+    pub fn wire_outer_diameter(section: f64) -> Option<f64> {
+        Self::lookup(&WIRE_OUTER_DIAMETER, section)
+    }
+
+    /// 查询多芯电缆外径(mm)
+    pub fn cable_outer_diameter(total_cores: u32, phase_section: f64) -> Option<f64> {
+        if total_cores <= 3 {
+            Self::lookup(&CABLE_3CORE_OUTER_DIAMETER, phase_section)
+        } else {
+            Self::lookup(&CABLE_5CORE_OUTER_DIAMETER, phase_section)
+        }
+    }
+
+    fn lookup(table: &[f64; 16], section: f64) -> Option<f64> {
+        STANDARD_SECTIONS
+            .iter()
+            .position(|&s| (s - section).abs() < 1e-6)
+            .map(|i| table[i])
+    }
+
+    fn circle_area(diameter: f64) -> f64 {
+        std::f64::consts::PI * diameter * diameter / 4.0
+    }
+
+    /// 计算导线（含外护层）总截面(mm²)
+    pub fn bundle_area(spec: &ConductorSpec) -> Result<f64> {
+        let missing = |section: f64| CoreError::missing_data("导线外径", format!("{}mm²无外径数据", section));
+
+        if spec.is_single_core_wire() {
+            let phase = Self::wire_outer_diameter(spec.phase_section).ok_or_else(|| missing(spec.phase_section))?;
+            let mut area = spec.phase_cores as f64 * Self::circle_area(phase);
+            if let Some(extra) = spec.extra_section {
+                area += Self::circle_area(Self::wire_outer_diameter(extra).ok_or_else(|| missing(extra))?);
+            }
+            Ok(area)
+        } else {
+            let diameter = Self::cable_outer_diameter(spec.total_cores(), spec.phase_section)
+                .ok_or_else(|| missing(spec.phase_section))?;
+            Ok(Self::circle_area(diameter))
+        }
+    }
+
+    /// 选择满足要求的最小管径
+    ///
+    /// # 参数
+    /// * `spec` - 导线/电缆芯数规格
+    /// * `method` - 敷设方式
+    ///
+    /// # 返回值
+    /// * `Ok(Some(PipeSpecification))` - 穿管敷设选定的管径
+    /// * `Ok(None)` - 桥架、线槽敷设，无需选管
+    /// * `Err(CoreError)` - 缺少外径数据或超出最大管径
+    pub fn select_pipe(spec: &ConductorSpec, method: LayingMethod) -> Result<Option<PipeSpecification>> {
+        if !method.is_conduit() {
+            return Ok(None);
+        }
+
+        let required_diameter = if spec.is_single_core_wire() {
+            let required_area = Self::bundle_area(spec)? / CONDUIT_FILL_RATIO;
+            (required_area * 4.0 / std::f64::consts::PI).sqrt()
+        } else {
+            let diameter = Self::cable_outer_diameter(spec.total_cores(), spec.phase_section).ok_or_else(|| {
+                CoreError::missing_data("电缆外径", format!("{}mm²无外径数据", spec.phase_section))
+            })?;
+            diameter * SINGLE_CABLE_DIAMETER_RATIO
+        };
+
+        PipeSpecification::all()
+            .into_iter()
+            .find(|pipe| pipe.to_f64() >= required_diameter)
+            .map(Some)
+            .ok_or_else(|| {
+                CoreError::calculation(
+                    format!("所需管径{:.1}mm超出最大管径{}mm", required_diameter, PipeSpecification::P200.to_str()),
+                    Some(spec.to_spec_str()),
+                )
+            })
+    }
+
+    /// 选择管径并生成完整标注
+    ///
+    /// # 参数
+    /// * `spec` - 导线/电缆芯数规格
+    /// * `method` - 敷设方式
+    /// * `area` - 敷设部位，None表示不标注部位
+    ///
+    /// # 返回值
+    /// 返回导管选择结果，标注如"YJV-4x25+1x16 SC50 WC"，桥架敷设为"YJV-4x25+1x16 CT"
+    pub fn size(spec: &ConductorSpec, method: LayingMethod, area: Option<LayingArea>) -> Result<ConduitSelection> {
+        let pipe = Self::select_pipe(spec, method)?;
+        let bundle_area = Self::bundle_area(spec)?;
+
+        let mut annotation = format!("{} {}", spec.to_spec_str(), method.to_str());
+        if let Some(pipe) = pipe {
+            annotation.push_str(pipe.to_str());
+        }
+        if let Some(area) = area {
+            annotation.push(' ');
+            annotation.push_str(area.to_str());
+        }
+
+        Ok(ConduitSelection { pipe, bundle_area, annotation })
+    }
+
+    /// 将管径选择结果写入线缆信息的敷设方式和穿管管径
+    pub fn apply_to_cable(cable: &mut CableInfo, method: LayingMethod, selection: &ConduitSelection) {
+        match selection.pipe {
+            Some(pipe) => {
+                cable.laying_method = format!("{}{}", method.to_str(), pipe.to_str());
+                cable.pipe_diameter = pipe.to_str().to_string();
+            }
+            None => {
+                cable.laying_method = method.to_str().to_string();
+                cable.pipe_diameter = String::new();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spec() {
+        let spec = ConductorSpec::parse("YJV-4x25+1x16").unwrap();
+        assert_eq!(spec.model, "YJV");
+        assert_eq!(spec.phase_cores, 4);
+        assert_eq!(spec.phase_section, 25.0);
+        assert_eq!(spec.extra_section, Some(16.0));
+        assert_eq!(spec.to_spec_str(), "YJV-4x25+1x16");
+
+        let spec = ConductorSpec::parse("NH-BV-3x2.5mm²").unwrap();
+        assert_eq!(spec.model, "NH-BV");
+        assert!(spec.is_single_core_wire());
+        assert!(ConductorSpec::parse("YJV").is_err());
+    }
+
+    #[test]
+    fn test_cable_annotation() {
+        let spec = ConductorSpec::new("YJV", 4, 25.0, Some(16.0));
+        let selection = ConduitSizing::size(&spec, LayingMethod::SC, Some(LayingArea::WC)).unwrap();
+        assert_eq!(selection.pipe, Some(PipeSpecification::P50));
+        assert_eq!(selection.annotation, "YJV-4x25+1x16 SC50 WC");
+    }
+
+    #[test]
+    fn test_wire_fill_ratio() {
+        // 3根2.5mm²导线总截面约35.8mm²，按40%填充需管径约10.7mm
+        let spec = ConductorSpec::new("BV", 3, 2.5, None);
+        assert_eq!(ConduitSizing::select_pipe(&spec, LayingMethod::JDG).unwrap(), Some(PipeSpecification::P15));
+
+        let spec = ConductorSpec::new("BV", 4, 25.0, Some(16.0));
+        assert_eq!(ConduitSizing::select_pipe(&spec, LayingMethod::SC).unwrap(), Some(PipeSpecification::P40));
+    }
+
+    #[test]
+    fn test_tray_has_no_pipe() {
+        let spec = ConductorSpec::new("YJV", 4, 95.0, Some(50.0));
+        let selection = ConduitSizing::size(&spec, LayingMethod::CT, None).unwrap();
+        assert_eq!(selection.pipe, None);
+        assert_eq!(selection.annotation, "YJV-4x95+1x50 CT");
+
+        let mut cable = CableInfo::default();
+        ConduitSizing::apply_to_cable(&mut cable, LayingMethod::CT, &selection);
+        assert_eq!(cable.laying_method, "CT");
+    }
+}
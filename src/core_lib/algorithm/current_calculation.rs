@@ -1,4 +1,5 @@
 /// 电流计算工具
+#[derive(Default)]
 pub struct CurrentCalculator;
 
 impl CurrentCalculator {
@@ -11,6 +12,7 @@ impl CurrentCalculator {
     /// - pe: 额定功率
     /// - kx: 需要系数
     /// - cos: 余弦值
+    ///
     /// 返回值 ijs: 计算电流
     pub fn single_phase_current_calculation(pe: f32, kx: f32, cos: f32) -> f32 {
         const U: f32 = 0.22; // 额定电压(单位: kV)
@@ -22,6 +24,7 @@ impl CurrentCalculator {
     /// - pe: 额定功率
     /// - kx: 需要系数
     /// - cos: 余弦值
+    ///
     /// 返回值 ijs: 计算电流
     pub fn three_phase_current_calculation(pe: f32, kx: f32, cos: f32) -> f32 {
         const U: f32 = 0.38; // 额定电压(单位: kV)
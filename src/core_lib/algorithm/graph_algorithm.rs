@@ -1,7 +1,5 @@
-/// 图算法模块
-/// 提供配电系统图的图算法实现
+//! 图算法模块
+//! 提供配电系统图的图算法实现
 
 // 空模块实现，后续可以添加实际的图算法代码
-pub mod graph_algorithm {
-    // TODO: 实现图算法相关功能
-}
\ No newline at end of file
+// TODO: 实现图算法相关功能
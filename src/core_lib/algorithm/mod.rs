@@ -1,9 +1,11 @@
-/// 算法模块
-/// 包含各种电气计算算法和图算法
+//! 算法模块
+//! 包含各种电气计算算法和图算法
 
 // 只导入实际存在的模块
+pub mod conduit_sizing;
 pub mod current_calculation;
 pub mod graph_algorithm;
+pub mod protection_coordination;
 pub mod three_phase_balance;
 
 // 重新导出常用组件
@@ -0,0 +1,265 @@
+//! 保护配合校验模块
+//! 按 Ib ≤ In ≤ Iz、I2 ≤ 1.45Iz 及干线短路热稳定 k²S² ≥ I²t 校验保护电器与导体的配合，
+//! 提供标准截面及载流量表，供出线回路、进线电缆和干线选型共用
+
+use super::super::error::{CoreError, Result};
+
+/// 标准导体截面(mm²)
+pub const STANDARD_SECTIONS: [f64; 16] = [
+    1.5, 2.5, 4.0, 6.0, 10.0, 16.0, 25.0, 35.0, 50.0, 70.0, 95.0, 120.0, 150.0, 185.0, 240.0, 300.0,
+];
+
+/// 聚氯乙烯绝缘铜芯导线穿管敷设载流量(A)，环境温度30℃，与STANDARD_SECTIONS对应
+const PVC_AMPACITY: [f64; 16] = [
+    15.0, 21.0, 28.0, 36.0, 50.0, 68.0, 89.0, 110.0, 134.0, 171.0, 207.0, 239.0, 275.0, 314.0, 369.0, 420.0,
+];
+
+/// 交联聚乙烯绝缘铜芯电缆载流量(A)，环境温度30℃，与STANDARD_SECTIONS对应
+const XLPE_AMPACITY: [f64; 16] = [
+    19.0, 26.0, 35.0, 45.0, 61.0, 81.0, 106.0, 131.0, 158.0, 200.0, 241.0, 278.0, 318.0, 362.0, 424.0, 486.0,
+];
+
+/// 导体绝缘类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConductorInsulation {
+    Pvc,  // 聚氯乙烯(BV、VV)
+    Xlpe, // 交联聚乙烯(YJV、YJY)
+}
+
+impl ConductorInsulation {
+    /// 根据线缆型号判断绝缘类型，含"YJ"的为交联聚乙烯
+    pub fn from_model(model: &str) -> Self {
+        if model.to_uppercase().contains("YJ") {
+            ConductorInsulation::Xlpe
+        } else {
+            ConductorInsulation::Pvc
+        }
+    }
+
+    /// 铜导体热稳定系数k
+    pub fn k_factor(&self) -> f64 {
+        match self {
+            ConductorInsulation::Pvc => 115.0,
+            ConductorInsulation::Xlpe => 143.0,
+        }
+    }
+
+    /// 标准截面对应的载流量表
+    fn ampacity_table(&self) -> &'static [f64; 16] {
+        match self {
+            ConductorInsulation::Pvc => &PVC_AMPACITY,
+            ConductorInsulation::Xlpe => &XLPE_AMPACITY,
+        }
+    }
+}
+
+/// 短路热稳定校验参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FaultWithstand {
+    /// 预期短路电流(A)
+    pub fault_current: f64,
+    /// 保护电器切断时间(s)
+    pub clearing_time: f64,
+}
+
+impl Default for FaultWithstand {
+    fn default() -> Self {
+        Self {
+            fault_current: 6000.0,
+            clearing_time: 0.1,
+        }
+    }
+}
+
+/// 保护配合校验输入
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoordinationInput {
+    /// 设计电流Ib(A)
+    pub design_current: f64,
+    /// 保护电器额定电流/整定电流In(A)
+    pub rated_current: f64,
+    /// 约定动作电流倍数，I2 = 倍数 × In（微型断路器1.45，塑壳断路器1.30）
+    pub tripping_factor: f64,
+    /// 初选导体截面(mm²)
+    pub section: f64,
+    /// 导体绝缘类型
+    pub insulation: ConductorInsulation,
+    /// 载流量校正系数（环境温度、并列敷设等综合）
+    pub derating_factor: f64,
+    /// 短路热稳定校验参数，None表示不校验（用于末端回路）
+    pub fault: Option<FaultWithstand>,
+}
+
+/// 保护配合校验结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoordinationResult {
+    /// 最终导体截面(mm²)
+    pub section: f64,
+    /// 校正后载流量Iz(A)
+    pub iz: f64,
+    /// Ib ≤ In 是否满足
+    pub design_current_ok: bool,
+    /// 校验及调整过程记录
+    pub log: Vec<String>,
+}
+
+/// 线缆保护配合校验工具
+///
+/// 校验 Ib ≤ In ≤ Iz、I2 ≤ 1.45Iz，以及干线的短路热稳定 k²S² ≥ I²t，
+/// 不满足时自动加大导体截面
+pub struct ProtectionCoordinator;
+
+impl ProtectionCoordinator {
+    /// 查询标准截面的载流量(A)，非标准截面返回None
+    pub fn ampacity(section: f64, insulation: ConductorInsulation) -> Option<f64> {
+        STANDARD_SECTIONS
+            .iter()
+            .position(|&s| (s - section).abs() < 1e-6)
+            .map(|i| insulation.ampacity_table()[i])
+    }
+
+    /// 将截面向上取整到标准截面，超出最大截面返回None
+    pub fn round_up_section(section: f64) -> Option<f64> {
+        STANDARD_SECTIONS.iter().copied().find(|&s| s + 1e-6 >= section)
+    }
+
+    /// 满足短路热稳定所需的最小截面 S = I·√t / k (mm²)
+    pub fn thermal_min_section(fault: &FaultWithstand, insulation: ConductorInsulation) -> f64 {
+        fault.fault_current * fault.clearing_time.sqrt() / insulation.k_factor()
+    }
+
+    /// 执行保护配合校验，不满足时逐级加大截面
+    ///
+    /// # 参数
+    /// * `input` - 校验输入
+    ///
+    /// # 返回值
+    /// * `Ok(CoordinationResult)` - 校验结果及调整记录；Ib > In 无法通过加大截面解决，记录在结果中
+    /// * `Err(CoreError)` - 参数无效或最大截面仍不满足要求
+    pub fn coordinate(input: &CoordinationInput) -> Result<CoordinationResult> {
+        if input.design_current <= 0.0 || input.rated_current <= 0.0 {
+            return Err(CoreError::validation("电流", "设计电流和保护电器额定电流必须大于0"));
+        }
+        if input.derating_factor <= 0.0 || input.derating_factor > 1.0 {
+            return Err(CoreError::validation("载流量校正系数", "校正系数应在(0, 1]范围内"));
+        }
+
+        let mut log = Vec::new();
+        let ib = input.design_current;
+        let in_ = input.rated_current;
+        let i2 = input.tripping_factor * in_;
+
+        let design_current_ok = ib <= in_;
+        if !design_current_ok {
+            log.push(format!("Ib={:.1}A > In={:.0}A，应加大保护电器规格", ib, in_));
+        }
+
+        let mut section = Self::round_up_section(input.section).ok_or_else(|| {
+            CoreError::calculation(format!("导体截面{}mm²超出标准截面范围", input.section), None::<String>)
+        })?;
+
+        if let Some(fault) = &input.fault {
+            let min_section = Self::thermal_min_section(fault, input.insulation);
+            if section < min_section {
+                let bumped = Self::round_up_section(min_section).ok_or_else(|| {
+                    CoreError::calculation(
+                        format!("短路热稳定要求截面{:.1}mm²超出标准截面范围", min_section),
+                        None::<String>,
+                    )
+                })?;
+                log.push(format!(
+                    "热稳定：k²S²={:.0} < I²t={:.0}，截面由{}mm²加大至{}mm²",
+                    (input.insulation.k_factor() * section).powi(2),
+                    fault.fault_current.powi(2) * fault.clearing_time,
+                    section,
+                    bumped
+                ));
+                section = bumped;
+            }
+        }
+
+        loop {
+            let iz = Self::ampacity(section, input.insulation).unwrap_or(0.0) * input.derating_factor;
+            let in_ok = in_ <= iz;
+            let i2_ok = i2 <= 1.45 * iz;
+            if in_ok && i2_ok {
+                log.push(format!(
+                    "Ib={:.1}A ≤ In={:.0}A ≤ Iz={:.1}A，I2={:.1}A ≤ 1.45Iz={:.1}A，截面{}mm²满足要求",
+                    ib, in_, iz, i2, 1.45 * iz, section
+                ));
+                return Ok(CoordinationResult { section, iz, design_current_ok, log });
+            }
+
+            let next = STANDARD_SECTIONS.iter().copied().find(|&s| s > section + 1e-6).ok_or_else(|| {
+                CoreError::calculation(
+                    format!("In={:.0}A超出最大截面{}mm²的载流量，需采用并联电缆或母线槽", in_, section),
+                    None::<String>,
+                )
+            })?;
+            if !in_ok {
+                log.push(format!("In={:.0}A > Iz={:.1}A，截面由{}mm²加大至{}mm²", in_, iz, section, next));
+            } else {
+                log.push(format!("I2={:.1}A > 1.45Iz={:.1}A，截面由{}mm²加大至{}mm²", i2, 1.45 * iz, section, next));
+            }
+            section = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(ib: f64, in_: f64, section: f64) -> CoordinationInput {
+        CoordinationInput {
+            design_current: ib,
+            rated_current: in_,
+            tripping_factor: 1.45,
+            section,
+            insulation: ConductorInsulation::Pvc,
+            derating_factor: 1.0,
+            fault: None,
+        }
+    }
+
+    #[test]
+    fn test_coordination_satisfied() {
+        let result = ProtectionCoordinator::coordinate(&input(14.0, 16.0, 2.5)).unwrap();
+        assert_eq!(result.section, 2.5);
+        assert!(result.design_current_ok);
+        assert_eq!(result.log.len(), 1);
+    }
+
+    #[test]
+    fn test_bump_section_when_in_exceeds_iz() {
+        // 40A断路器配4mm²(28A)导线不满足，应加大至10mm²(50A)
+        let result = ProtectionCoordinator::coordinate(&input(35.0, 40.0, 4.0)).unwrap();
+        assert_eq!(result.section, 10.0);
+        assert!(result.log.iter().any(|l| l.contains("加大")));
+    }
+
+    #[test]
+    fn test_derating_and_thermal_withstand() {
+        let mut feeder = input(60.0, 63.0, 16.0);
+        feeder.insulation = ConductorInsulation::Xlpe;
+        feeder.derating_factor = 0.8;
+        feeder.fault = Some(FaultWithstand { fault_current: 10000.0, clearing_time: 0.2 });
+
+        // 热稳定最小截面 10000×√0.2/143 ≈ 31.3mm² → 35mm²，Iz=131×0.8=104.8A
+        let result = ProtectionCoordinator::coordinate(&feeder).unwrap();
+        assert_eq!(result.section, 35.0);
+        assert!((result.iz - 104.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_design_current_above_rating_is_logged() {
+        let result = ProtectionCoordinator::coordinate(&input(130.0, 125.0, 50.0)).unwrap();
+        assert!(!result.design_current_ok);
+        assert!(result.log[0].contains("Ib"));
+    }
+
+    #[test]
+    fn test_exceeding_max_section() {
+        assert!(ProtectionCoordinator::coordinate(&input(500.0, 630.0, 300.0)).is_err());
+    }
+}
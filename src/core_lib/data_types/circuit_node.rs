@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::core_lib::algorithm::protection_coordination::{ConductorInsulation, CoordinationInput, ProtectionCoordinator};
+
 /// 回路类型枚举
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CircuitType {
@@ -75,6 +77,12 @@ pub struct CircuitNodeProperties {
     pub component_current: f64,
     /// 线缆规格
     pub cable_spec: String,
+    /// 线缆载流量校正系数（环境温度、并列敷设等综合）
+    #[serde(default = "default_cable_derating_factor")]
+    pub cable_derating_factor: f64,
+    /// 保护配合校验记录
+    #[serde(default)]
+    pub coordination_log: Vec<String>,
     /// 相序标识
     pub phase: Option<char>,
     /// 回路编号
@@ -83,6 +91,10 @@ pub struct CircuitNodeProperties {
     pub circuit_name: String,
 }
 
+fn default_cable_derating_factor() -> f64 {
+    1.0
+}
+
 impl Default for CircuitNodeProperties {
     fn default() -> Self {
         Self {
@@ -98,6 +110,8 @@ impl Default for CircuitNodeProperties {
             component_type: "微型断路器".to_string(),
             component_current: 16.0,
             cable_spec: "BV-2.5mm²".to_string(),
+            cable_derating_factor: 1.0,
+            coordination_log: Vec::new(),
             phase: Some('L'),
             circuit_number: 1,
             circuit_name: "新建回路".to_string(),
@@ -174,11 +188,98 @@ impl CircuitNodeProperties {
         }
     }
     
+    /// 线缆规格中的导体截面(mm²)，如"BV-10mm²"返回10.0
+    pub fn cable_section(&self) -> Option<f64> {
+        self.cable_spec
+            .rsplit('-')
+            .next()?
+            .trim_end_matches("mm²")
+            .parse()
+            .ok()
+    }
+
+    /// 校验保护电器与线缆的配合（Ib ≤ In ≤ Iz，I2 ≤ 1.45Iz）
+    ///
+    /// 不满足时自动加大线缆截面，并将校验过程写入`coordination_log`
+    pub fn check_protection_coordination(&mut self) {
+        self.coordination_log.clear();
+        let Some(section) = self.cable_section() else {
+            self.coordination_log.push(format!("无法识别线缆规格{}，未进行保护配合校验", self.cable_spec));
+            return;
+        };
+        if self.current <= 0.0 {
+            return;
+        }
+
+        // 63A及以下按微型断路器(I2=1.45In)，以上按塑壳断路器(I2=1.30In)
+        let tripping_factor = if self.component_current <= 63.0 { 1.45 } else { 1.30 };
+        let model = self.cable_spec.split('-').next().unwrap_or("BV").to_string();
+        let input = CoordinationInput {
+            design_current: self.current,
+            rated_current: self.component_current,
+            tripping_factor,
+            section,
+            insulation: ConductorInsulation::from_model(&model),
+            derating_factor: self.cable_derating_factor,
+            fault: None,
+        };
+
+        match ProtectionCoordinator::coordinate(&input) {
+            Ok(result) => {
+                if (result.section - section).abs() > 1e-6 {
+                    self.cable_spec = format!("{}-{}mm²", model, result.section);
+                }
+                self.coordination_log = result.log;
+            }
+            Err(e) => self.coordination_log.push(e.to_string()),
+        }
+    }
+
     /// 执行所有自动计算和选型
     pub fn perform_all_calculations(&mut self) {
         self.calculate_current();
         self.select_component_type();
         self.select_component_current();
         self.select_cable_spec();
+        self.check_protection_coordination();
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protection_coordination_bumps_cable() {
+        let mut circuit = CircuitNodeProperties {
+            current: 25.0,
+            component_current: 32.0,
+            cable_spec: "BV-2.5mm²".to_string(),
+            ..Default::default()
+        };
+        circuit.check_protection_coordination();
+        // BV-6mm²载流量36A ≥ In=32A
+        assert_eq!(circuit.cable_spec, "BV-6mm²");
+        assert!(circuit.coordination_log.last().unwrap().contains("满足要求"));
+
+        // 校正系数0.8时6mm²载流量不足，加大至10mm²
+        circuit.cable_spec = "BV-2.5mm²".to_string();
+        circuit.cable_derating_factor = 0.8;
+        circuit.check_protection_coordination();
+        assert_eq!(circuit.cable_spec, "BV-10mm²");
+    }
+
+    #[test]
+    fn test_protection_coordination_unknown_spec() {
+        let mut circuit = CircuitNodeProperties { current: 10.0, cable_spec: "BV".to_string(), ..Default::default() };
+        circuit.check_protection_coordination();
+        assert_eq!(circuit.cable_spec, "BV");
+        assert!(circuit.coordination_log[0].contains("无法识别线缆规格"));
+
+        // 自动选型的结果本身满足配合要求，不再加大截面
+        let mut circuit = CircuitNodeProperties { power: 5.0, ..Default::default() };
+        circuit.perform_all_calculations();
+        assert_eq!(circuit.component_current, 32.0);
+        assert_eq!(circuit.cable_spec, "BV-16mm²");
+        assert!(circuit.coordination_log.iter().all(|l| !l.contains("加大")));
     }
-}
\ No newline at end of file
+}
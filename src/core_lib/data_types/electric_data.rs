@@ -47,6 +47,26 @@ impl LayingMethod {
             LayingMethod::RC => "RC",
         }
     }
+
+    /// 从敷设方式标注（如"SC20"、"CT"）中解析敷设方式
+    pub fn from_annotation(annotation: &str) -> Option<Self> {
+        let prefix: String = annotation.trim().chars().take_while(|c| c.is_ascii_alphabetic()).collect();
+        match prefix.to_uppercase().as_str() {
+            "SC" => Some(LayingMethod::SC),
+            "JDG" => Some(LayingMethod::JDG),
+            "PC" => Some(LayingMethod::PC),
+            "PVC" => Some(LayingMethod::PVC),
+            "CT" => Some(LayingMethod::CT),
+            "MR" => Some(LayingMethod::MR),
+            "RC" => Some(LayingMethod::RC),
+            _ => None,
+        }
+    }
+
+    /// 是否为穿管敷设（桥架、线槽不穿管）
+    pub fn is_conduit(&self) -> bool {
+        !matches!(self, LayingMethod::CT | LayingMethod::MR)
+    }
 }
 
 ///穿管规格
@@ -81,6 +101,40 @@ impl PipeSpecification {
             PipeSpecification::P200 => "200",
         }
     }
+
+    /// 管径数值(mm)
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            PipeSpecification::P15 => 15.0,
+            PipeSpecification::P20 => 20.0,
+            PipeSpecification::P25 => 25.0,
+            PipeSpecification::P32 => 32.0,
+            PipeSpecification::P40 => 40.0,
+            PipeSpecification::P50 => 50.0,
+            PipeSpecification::P65 => 65.0,
+            PipeSpecification::P70 => 70.0,
+            PipeSpecification::P100 => 100.0,
+            PipeSpecification::P150 => 150.0,
+            PipeSpecification::P200 => 200.0,
+        }
+    }
+
+    /// 由小到大排列的全部管径规格
+    pub fn all() -> [PipeSpecification; 11] {
+        [
+            PipeSpecification::P15,
+            PipeSpecification::P20,
+            PipeSpecification::P25,
+            PipeSpecification::P32,
+            PipeSpecification::P40,
+            PipeSpecification::P50,
+            PipeSpecification::P65,
+            PipeSpecification::P70,
+            PipeSpecification::P100,
+            PipeSpecification::P150,
+            PipeSpecification::P200,
+        ]
+    }
 }
 
 /// 敷设部位
@@ -130,7 +184,6 @@ impl Phase {
     }
 }
 
-
 /// 回路编号 - 用于按照输出端口连接顺序为回路分配编号
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CircuitNumber {
@@ -144,14 +197,13 @@ impl CircuitNumber {
     }
 
     /// 根据字符串生成回路编号
-    pub fn from_str(s: &str) -> Option<Self> {
+    pub fn parse(s: &str) -> Option<Self> {
         // 检查字符串是否以"WL"开头
         if s.starts_with("WL") {
             // 尝试解析"WL"后面的数字部分
-            if let Some(num_str) = s.get(2..) {
-                if let Ok(num) = num_str.parse::<u32>() {
-                    return Some(Self { number: num });
-                }
+            if let Some(num_str) = s.get(2..)
+                && let Ok(num) = num_str.parse::<u32>() {
+                return Some(Self { number: num });
             }
         }
         None
@@ -212,6 +264,16 @@ pub enum FrameCurrent {
 }
 
 impl FrameCurrent {
+    /// 可容纳给定整定电流的最小壳架，超出最大壳架时返回None
+    pub fn for_setting(setting: SettingValue) -> Option<FrameCurrent> {
+        [
+            FrameCurrent::A63, FrameCurrent::A100, FrameCurrent::A160, FrameCurrent::A250,
+            FrameCurrent::A400, FrameCurrent::A630, FrameCurrent::A800,
+        ]
+        .into_iter()
+        .find(|frame| frame.to_f64() >= setting.to_f64())
+    }
+
     pub fn to_str(&self) -> &'static str {
         match self {
             FrameCurrent::A63 => "63",
@@ -296,6 +358,16 @@ impl Pole {
             Pole::P3N => "3P+N",
         }
     } 
+
+    /// 极数对应的导体数量
+    pub fn to_u32(&self) -> u32 {
+        match self {
+            Pole::P1 => 1,
+            Pole::P2 | Pole::P1N => 2,
+            Pole::P3 => 3,
+            Pole::P4 | Pole::P3N => 4,
+        }
+    }
 }
 
 /// 脱扣曲线
@@ -350,6 +422,21 @@ pub enum SettingValue {
 }
 
 impl SettingValue {
+    /// 全部整定电流值，由小到大排列
+    pub const ALL: [SettingValue; 28] = [
+        SettingValue::A6, SettingValue::A10, SettingValue::A16, SettingValue::A20, SettingValue::A25,
+        SettingValue::A32, SettingValue::A40, SettingValue::A50, SettingValue::A63, SettingValue::A80,
+        SettingValue::A100, SettingValue::A125, SettingValue::A140, SettingValue::A160, SettingValue::A180,
+        SettingValue::A200, SettingValue::A225, SettingValue::A250, SettingValue::A315, SettingValue::A350,
+        SettingValue::A400, SettingValue::A500, SettingValue::A630, SettingValue::A800, SettingValue::A1000,
+        SettingValue::A1250, SettingValue::A1600, SettingValue::A2000,
+    ];
+
+    /// 不小于给定电流的最小整定电流值，超出最大值时返回None
+    pub fn from_current(current: f64) -> Option<SettingValue> {
+        Self::ALL.into_iter().find(|value| value.to_f64() >= current)
+    }
+
     pub fn to_str(&self) -> &'static str {
         match self {
             SettingValue::A6 => "6",
@@ -418,16 +505,58 @@ impl SettingValue {
 }
 
 /// 相位配置
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum PhaseConfig {
     SinglePhase,        // 单相
-    ThreePhase,         // 三相
+    #[default]
+    ThreePhase,         // 三相（默认）
     ThreePhaseFourWire, // 三相四线
 }
 
-impl Default for PhaseConfig {
-    fn default() -> Self {
-        PhaseConfig::ThreePhase // 默认三相
+/// 负荷等级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LoadLevel {
+    First,  // 一级负荷
+    Second, // 二级负荷
+    #[default]
+    Third,  // 三级负荷（默认）
+}
+
+impl LoadLevel {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            LoadLevel::First => "一级负荷",
+            LoadLevel::Second => "二级负荷",
+            LoadLevel::Third => "三级负荷",
+        }
+    }
+}
+
+/// 负荷分级 - 负荷等级与是否为消防负荷
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct LoadClass {
+    pub level: LoadLevel, // 负荷等级
+    pub is_fire: bool,    // 是否为消防负荷
+}
+
+impl LoadClass {
+    /// 创建消防负荷分级
+    pub fn fire(level: LoadLevel) -> Self {
+        Self { level, is_fire: true }
+    }
+
+    /// 创建非消防负荷分级
+    pub fn non_fire(level: LoadLevel) -> Self {
+        Self { level, is_fire: false }
+    }
+
+    /// 转换为显示文本，如"一级负荷(消防)"
+    pub fn to_display_string(&self) -> String {
+        if self.is_fire {
+            format!("{}(消防)", self.level.to_str())
+        } else {
+            format!("{}(非消防)", self.level.to_str())
+        }
     }
 }
 
@@ -529,6 +658,28 @@ pub struct EnergyMeter {
     pub phase_config: Option<PhaseConfig>,      // 相位配置
 }
 
+/// 浪涌保护器参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SurgeProtector {
+    pub model: String,                          // 浪涌保护器型号
+    pub test_class: String,                     // 试验类别(T1/T2/T3)
+    pub max_discharge_current: f64,             // 最大放电电流Imax(kA)
+    pub protection_level: f64,                  // 电压保护水平Up(kV)
+    pub pole: Pole,                             // 极数
+}
+
+impl Default for SurgeProtector {
+    fn default() -> Self {
+        Self {
+            model: "SPD-T2-40".to_string(),
+            test_class: "T2".to_string(),
+            max_discharge_current: 40.0,
+            protection_level: 1.5,
+            pole: Pole::P4,
+        }
+    }
+}
+
 /// 线缆参数
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CableInfo {
@@ -538,6 +689,8 @@ pub struct CableInfo {
     pub laying_method: String, // 敷设方式
     pub pipe_diameter: String, // 穿管管径
     pub is_three_phase: bool,  // 单三相判断
+    #[serde(default)]
+    pub length: f64,           // 线缆长度(m)，0表示尚未统计
 }
 
 impl Default for CableInfo {
@@ -549,6 +702,7 @@ impl Default for CableInfo {
             laying_method: "SC20".to_string(),
             pipe_diameter: "20".to_string(),
             is_three_phase: false,
+            length: 0.0,
         }
     }
 }
@@ -561,6 +715,7 @@ pub enum ElectricComponent {
     DualPowerSwitch(DualPowerSwitch), // 双电源开关
     Contactor(Contactor),           // 接触器
     EnergyMeter(EnergyMeter),       // 电能表
+    SurgeProtector(SurgeProtector), // 浪涌保护器
 }
 
 impl ElectricComponent {
@@ -572,6 +727,7 @@ impl ElectricComponent {
             ElectricComponent::DualPowerSwitch(dps) => dps.rated_current.to_f64(),
             ElectricComponent::Contactor(contactor) => contactor.rated_current.to_f64(),
             ElectricComponent::EnergyMeter(meter) => meter.rated_current.to_f64(),
+            ElectricComponent::SurgeProtector(_) => 0.0, // 浪涌保护器无额定工作电流
         }
     }
 
@@ -583,6 +739,7 @@ impl ElectricComponent {
             ElectricComponent::DualPowerSwitch(dps) => &dps.model,
             ElectricComponent::Contactor(contactor) => &contactor.model,
             ElectricComponent::EnergyMeter(meter) => &meter.model,
+            ElectricComponent::SurgeProtector(spd) => &spd.model,
         }
     }
 
@@ -600,6 +757,7 @@ impl ElectricComponent {
                     PhaseConfig::ThreePhaseFourWire => 4,
                 })
             },
+            ElectricComponent::SurgeProtector(spd) => spd.pole.to_u32(),
         }
     }
 }
@@ -674,6 +832,7 @@ impl CircuitData {
                 ElectricComponent::DualPowerSwitch(_) => "dual_power_switch",
                 ElectricComponent::Contactor(_) => "contactor",
                 ElectricComponent::EnergyMeter(_) => "energy_meter",
+                ElectricComponent::SurgeProtector(_) => "surge_protector",
             };
             comp_type_str != component_type.to_lowercase()
         });
@@ -764,7 +923,9 @@ pub struct PhaseBalanceInfo {
 }
 
 // 定义电气系统参数值类型
+// 参数值按值存放在节点输入中，不对大变体装箱
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
 pub enum ElectricValueType {
     Float(f64),              // 用于电流、功率等数值
     Integer(i64),            // 用于整数标识
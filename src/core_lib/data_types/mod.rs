@@ -1,5 +1,5 @@
-/// 核心数据类型模块
-/// 定义电气系统的基础数据类型和节点数据结构
+//! 核心数据类型模块
+//! 定义电气系统的基础数据类型和节点数据结构
 
 pub mod electric_data;
 pub mod node_data;
@@ -7,14 +7,14 @@ pub mod circuit_node;
 
 // 重新导出常用类型 - 基础参数类型
 pub use electric_data::{
-    VoltageLevel, LayingMethod, PipeSpecification, LayingArea, PhaseSequence, Phase,
+    VoltageLevel, LayingMethod, PipeSpecification, LayingArea, Phase,
     CircuitNumber, BreakerType, FrameCurrent, BreakingCapacity, DeductionMethod,
-    Pole, Curve, SettingValue, PhaseConfig
+    Pole, Curve, SettingValue, PhaseConfig, LoadLevel, LoadClass
 };
 
 // 重新导出常用类型 - 组件参数类型
 pub use electric_data::{
-    Breaker, Isolator, DualPowerSwitch, Contactor, EnergyMeter, CableInfo,
+    Breaker, Isolator, DualPowerSwitch, Contactor, EnergyMeter, SurgeProtector, CableInfo,
     ElectricComponent
 };
 
@@ -1,9 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-use std::process::id as process_id;
 
-use super::electric_data::{Breaker, CableInfo, CircuitData, CircuitNumber, Phase, PhaseBalanceInfo};
+use crate::core_lib::utils::IdUtils;
+
+use super::electric_data::{Breaker, CircuitData, CircuitNumber, LoadClass, PhaseBalanceInfo};
 
 /// 物理尺寸
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -46,7 +46,7 @@ pub struct DistributionBoxData {
 impl Default for DistributionBoxData {
     fn default() -> Self {
         Self {
-            id: generate_unique_id(),
+            id: IdUtils::new_id(),
             name: "配电箱".to_string(),
             number: CircuitNumber::new(1),
             incoming_circuit: None,
@@ -97,7 +97,7 @@ impl DistributionBoxData {
     /// 获取下一个可用的回路编号
     pub fn get_next_circuit_number(&self) -> CircuitNumber {
         let last_number = self.outgoing_circuits.iter()
-            .filter_map(|id| CircuitNumber::from_str(id))
+            .filter_map(|id| CircuitNumber::parse(id))
             .max_by(|a, b| a.compare(b))
             .unwrap_or_else(|| CircuitNumber::new(0));
         
@@ -110,17 +110,6 @@ impl DistributionBoxData {
     }
 }
 
-/// 生成唯一标识符
-fn generate_unique_id() -> String {
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("Time went backwards")
-        .as_millis();
-    let pid = process_id();
-    let thread_id = std::thread::current().id();
-    format!("{}_{}_{:?}", timestamp, pid, thread_id)
-}
-
 /// 配电回路节点数据结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CircuitNodeData {
@@ -164,6 +153,10 @@ pub struct DistributionBoxNodeData {
     // 进线参数
     pub incoming_current: f64,    // 进线电流(A)
     pub incoming_wire_size: String, // 进线规格
+    
+    // 负荷分级
+    #[serde(default)]
+    pub load_class: LoadClass,    // 负荷等级及消防属性
 }
 
 /// 干线系统图节点数据结构
@@ -273,6 +266,7 @@ impl Default for DistributionBoxNodeData {
             phase_c_load: 0.0,
             incoming_current: 0.0,
             incoming_wire_size: "BV-25mm²".to_string(),
+            load_class: LoadClass::default(),
         }
     }
 }
@@ -1,5 +1,5 @@
-/// 核心库模块
-/// 提供电气系统图的基础数据类型、算法和工具函数
+//! 核心库模块
+//! 提供电气系统图的基础数据类型、算法和工具函数
 
 pub mod data_types;
 pub mod traits;
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use crate::core_lib::data_types::{ElectricNodeData, ElectricValueType};
+use crate::core_lib::data_types::ElectricNodeData;
 
 /// 电气计算接口
 /// 定义所有电气计算功能必须实现的方法
@@ -97,13 +97,11 @@ impl ElectricalCalculation for DefaultElectricalCalculation {
         // 三相计算: P = √3 * U * I * cosφ
         // 单相计算: P = U * I * cosφ
         let sqrt_3 = 3.0f64.sqrt();
-        let power_w: f64;
-        
-        if phase_count == 3 {
-            power_w = sqrt_3 * voltage * current * power_factor
+        let power_w = if phase_count == 3 {
+            sqrt_3 * voltage * current * power_factor
         } else {
-            power_w = voltage * current * power_factor
-        }
+            voltage * current * power_factor
+        };
         
         // 转换为kW
         power_w / 1000.0
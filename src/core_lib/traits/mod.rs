@@ -1,5 +1,5 @@
-/// 核心trait模块
-/// 定义电气计算和数据处理的核心接口
+//! 核心trait模块
+//! 定义电气计算和数据处理的核心接口
 
 pub mod calculation;
 pub mod widgets;
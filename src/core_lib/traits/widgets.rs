@@ -1,5 +1,5 @@
-/// UI控件相关的trait定义
-/// 实现电气参数的UI控件显示和交互功能
+//! UI控件相关的trait定义
+//! 实现电气参数的UI控件显示和交互功能
 
 use egui_node_graph::*;
 use crate::core_lib::data_types::electric_data::ElectricValueType;
@@ -32,7 +32,7 @@ impl WidgetValueTrait for ElectricValueType {
             ElectricValueType::Float(val) => {
                 ui.horizontal(|ui| {
                     ui.label(param_name);
-                    ui.add(egui::DragValue::new(val).clamp_range(0.0..=f64::MAX));
+                    ui.add(egui::DragValue::new(val).range(0.0..=f64::MAX));
                 });
             },
             
@@ -40,7 +40,7 @@ impl WidgetValueTrait for ElectricValueType {
             ElectricValueType::Integer(val) => {
                 ui.horizontal(|ui| {
                     ui.label(param_name);
-                    ui.add(egui::DragValue::new(val).clamp_range(0..=i64::MAX));
+                    ui.add(egui::DragValue::new(val).range(0..=i64::MAX));
                 });
             },
             
@@ -52,66 +52,58 @@ impl WidgetValueTrait for ElectricValueType {
                 });
             },
             
-            // 处理布尔类型
-            ElectricValueType::Boolean(val) => {
-                ui.horizontal(|ui| {
-                    ui.label(param_name);
-                    ui.checkbox(val, "");
-                });
-            },
-            
             // 简化处理复杂类型 - 只显示基本信息
             ElectricValueType::CircuitData(circuit) => {
                 ui.label(format!("{}: 回路-{} (功率: {:.2}kW)", 
                     param_name, 
-                    circuit.name, 
+                    circuit.circuit_id, 
                     circuit.rated_power
                 ));
                 
                 // 显示回路的简要信息
                 ui.collapsing("查看详情", |ui| {
-                    ui.label(format!("回路编号: {}", circuit.number.get_full_number()));
+                    ui.label(format!("回路编号: {}", circuit.circuit_id));
                     ui.label(format!("计算电流: {:.2}A", circuit.calculated_current));
                     ui.label(format!("功率因数: {:.2}", circuit.power_factor));
                     ui.label(format!("需用系数: {:.2}", circuit.demand_coefficient));
                     
                     // 显示线缆信息
-                    if let Some(cable) = &circuit.cable_info {
-                        ui.label(format!("线缆型号: {}", cable.model));
+                    if !circuit.cable.model.is_empty() {
+                        ui.label(format!("线缆型号: {}", circuit.cable.model));
                     }
                 });
             },
             
             ElectricValueType::DistributionBoxData(box_data) => {
-                ui.label(format!("{}: 配电箱-{} (总功率: {:.2}kW)", 
+                ui.label(format!("{}: 配电箱 (总功率: {:.2}kW)", 
                     param_name, 
-                    box_data.name, 
-                    box_data.total_power
+                    box_data.get("total_power").copied().unwrap_or(0.0)
                 ));
                 
                 // 显示配电箱简要信息
                 ui.collapsing("查看详情", |ui| {
-                    ui.label(format!("总电流: {:.2}A", box_data.total_current));
-                    ui.label(format!("电压等级: {:.1}kV", box_data.voltage_level));
-                    ui.label(format!("相数: {}", box_data.phase_count));
-                    ui.label(format!("出线回路数: {}", box_data.outgoing_circuits.len()));
+                    let mut keys: Vec<&String> = box_data.keys().collect();
+                    keys.sort();
+                    for key in keys {
+                        ui.label(format!("{}: {:.2}", key, box_data[key]));
+                    }
                 });
             },
             
-            ElectricValueType::PhaseBalanceInfo(balance_info) => {
+            ElectricValueType::ThreePhaseData(balance_info) => {
                 ui.label(format!("{}: 三相平衡信息", param_name));
                 
                 ui.collapsing("查看详情", |ui| {
-                    ui.label(format!("A相负载: {:.2}kW", balance_info.phase_a_load));
-                    ui.label(format!("B相负载: {:.2}kW", balance_info.phase_b_load));
-                    ui.label(format!("C相负载: {:.2}kW", balance_info.phase_c_load));
-                    ui.label(format!("不平衡度: {:.2}%", balance_info.unbalance_degree));
+                    ui.label(format!("A相负载: {:.2}kW", balance_info.phase_a_power));
+                    ui.label(format!("B相负载: {:.2}kW", balance_info.phase_b_power));
+                    ui.label(format!("C相负载: {:.2}kW", balance_info.phase_c_power));
+                    ui.label(format!("不平衡度: {:.2}%", balance_info.balance_degree));
                 });
             },
             
             // 其他类型简单显示
             _ => {
-                ui.label(format!("{}: {}", param_name, self));
+                ui.label(format!("{}: {}", param_name, self.value_to_text()));
             }
         }
         
@@ -136,18 +128,19 @@ impl WidgetValueTrait for ElectricValueType {
         });
         Vec::new()
     }
-    
+}
+
+impl ElectricValueType {
     /// 获取值的简短描述，用于在连接线上显示
-    fn value_to_text(&self) -> String {
+    pub fn value_to_text(&self) -> String {
         match self {
             ElectricValueType::Float(val) => format!("{:.2}", val),
             ElectricValueType::Integer(val) => format!("{}", val),
             ElectricValueType::String(val) => val.clone(),
-            ElectricValueType::Boolean(val) => format!("{}", val),
-            ElectricValueType::CircuitData(circuit) => format!("回路{}", circuit.name),
-            ElectricValueType::DistributionBoxData(box_data) => format!("配电箱{}", box_data.name),
-            ElectricValueType::PhaseBalanceInfo(_) => "三相平衡".to_string(),
-            _ => "".to_string(),
+            ElectricValueType::CircuitData(circuit) => format!("回路{}", circuit.circuit_id),
+            ElectricValueType::DistributionBoxData(_) => "配电箱".to_string(),
+            ElectricValueType::ThreePhaseData(_) => "三相平衡".to_string(),
+            ElectricValueType::SystemDiagramData(_) => "系统图".to_string(),
         }
     }
 }
@@ -162,7 +155,7 @@ impl Default for ElectricValueType {
 
 /// 提供UI控件的辅助函数
 pub mod widget_utils {
-    use egui::{Ui, Label, RichText};
+    use egui::{Ui, RichText};
     
     /// 显示电气参数的标签，支持带单位的显示
     pub fn electric_param_label(ui: &mut Ui, label: &str, value: f64, unit: &str) {
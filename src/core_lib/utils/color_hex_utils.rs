@@ -1,7 +1,7 @@
-use std::str::FromStr;
 
 /// 颜色十六进制工具类
 /// 提供颜色转换和处理功能
+#[derive(Default)]
 pub struct ColorHexUtils;
 
 impl ColorHexUtils {
@@ -74,9 +74,9 @@ impl ColorHexUtils {
         let (r, g, b) = Self::hex_to_rgb(hex)?;
         
         // 将RGB值转换为0-1范围并调整亮度
-        let r = (r as f64 * factor).max(0.0).min(255.0) as u8;
-        let g = (g as f64 * factor).max(0.0).min(255.0) as u8;
-        let b = (b as f64 * factor).max(0.0).min(255.0) as u8;
+        let r = (r as f64 * factor).clamp(0.0, 255.0) as u8;
+        let g = (g as f64 * factor).clamp(0.0, 255.0) as u8;
+        let b = (b as f64 * factor).clamp(0.0, 255.0) as u8;
         
         Some(Self::rgb_to_hex(r, g, b))
     }
@@ -1,5 +1,6 @@
 /// 转换工具类
 /// 提供各种单位转换和数据格式转换功能
+#[derive(Default)]
 pub struct ConversionUtils;
 
 impl ConversionUtils {
@@ -60,7 +61,7 @@ impl ConversionUtils {
     /// - power_factor: 功率因数
     pub fn power_factor_to_degrees(power_factor: f64) -> f64 {
         // 确保功率因数在有效范围内
-        let power_factor = power_factor.max(-1.0).min(1.0);
+        let power_factor = power_factor.clamp(-1.0, 1.0);
         power_factor.acos().to_degrees()
     }
     
@@ -195,17 +196,15 @@ impl ConversionUtils {
             // 对于一般数值，添加千位分隔符
             let s = format!("{:.2}", value);
             let parts: Vec<&str> = s.split('.').collect();
-            let mut integer_part = parts[0].to_string();
+            let integer_part = parts[0].to_string();
             
             // 添加千位分隔符
             let mut result = String::new();
-            let mut count = 0;
-            for c in integer_part.chars().rev() {
+            for (count, c) in integer_part.chars().rev().enumerate() {
                 if count > 0 && count % 3 == 0 {
                     result.push(',');
                 }
                 result.push(c);
-                count += 1;
             }
             
             result = result.chars().rev().collect();
@@ -0,0 +1,60 @@
+//! 对象标识工具
+//!
+//! 配电箱、回路和图节点统一使用UUID（v4）字符串作为持久标识。标识随项目文件保存，
+//! 加载、导入及剪切移动时保持不变；复制得到的新对象另行分配，保证项目内不重复。
+
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+/// 标识工具类
+pub struct IdUtils;
+
+impl IdUtils {
+    /// 生成新的对象标识
+    pub fn new_id() -> String {
+        Uuid::new_v4().to_string()
+    }
+
+    /// 判断是否为UUID格式的标识（早期版本生成的标识不是）
+    pub fn is_uuid(id: &str) -> bool {
+        Uuid::parse_str(id).is_ok()
+    }
+
+    /// 为空白或与已用标识重复的标识分配新标识
+    ///
+    /// # 参数
+    /// * `id` - 待检查的标识
+    /// * `used` - 已用标识集合，检查后加入该标识
+    ///
+    /// # 返回值
+    /// 重新分配了标识时返回true
+    pub fn ensure_unique(id: &mut String, used: &mut HashSet<String>) -> bool {
+        let reassigned = id.trim().is_empty() || used.contains(id.as_str());
+        if reassigned {
+            *id = Self::new_id();
+        }
+        used.insert(id.clone());
+        reassigned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ensure_unique() {
+        let mut used = HashSet::new();
+        let mut legacy = "circuit_1a2b".to_string();
+        let mut duplicate = legacy.clone();
+        let mut empty = String::new();
+        assert!(!IdUtils::ensure_unique(&mut legacy, &mut used));
+        assert_eq!(legacy, "circuit_1a2b");
+        assert!(IdUtils::ensure_unique(&mut duplicate, &mut used));
+        assert!(IdUtils::ensure_unique(&mut empty, &mut used));
+        assert!(IdUtils::is_uuid(&duplicate) && IdUtils::is_uuid(&empty));
+        assert_ne!(duplicate, empty);
+        assert!(!IdUtils::is_uuid(&legacy));
+    }
+}
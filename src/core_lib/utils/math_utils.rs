@@ -1,5 +1,6 @@
 /// 数学工具类
 /// 提供各种数学计算和辅助功能
+#[derive(Default)]
 pub struct MathUtils;
 
 impl MathUtils {
@@ -170,19 +171,8 @@ impl MathUtils {
             return None;
         }
         
-        // 使用fold避免unwrap，手动计算最大值
-        values.iter().fold(Some(values[0]), |current_max, &value| {
-            match current_max {
-                Some(max_val) => {
-                    if value > max_val {
-                        Some(value)
-                    } else {
-                        Some(max_val)
-                    }
-                },
-                None => Some(value)
-            }
-        })
+        // 使用fold避免unwrap，逐个比较得到最大值
+        Some(values.iter().copied().fold(values[0], f64::max))
     }
     
     /// 计算最小值
@@ -192,19 +182,8 @@ impl MathUtils {
             return None;
         }
         
-        // 使用fold避免unwrap，手动计算最小值
-        values.iter().fold(Some(values[0]), |current_min, &value| {
-            match current_min {
-                Some(min_val) => {
-                    if value < min_val {
-                        Some(value)
-                    } else {
-                        Some(min_val)
-                    }
-                },
-                None => Some(value)
-            }
-        })
+        // 使用fold避免unwrap，逐个比较得到最小值
+        Some(values.iter().copied().fold(values[0], f64::min))
     }
     
     /// 计算平均值
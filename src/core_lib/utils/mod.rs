@@ -1,13 +1,15 @@
-/// 工具模块
-/// 提供各种通用工具函数
+//! 工具模块
+//! 提供各种通用工具函数
 
 pub mod color_hex_utils;
 pub mod validation_utils;
 pub mod conversion_utils;
 pub mod math_utils;
+pub mod id_utils;
 
 // 重新导出常用组件
 pub use color_hex_utils::ColorHexUtils;
 pub use validation_utils::ValidationUtils;
 pub use conversion_utils::ConversionUtils;
-pub use math_utils::MathUtils;
\ No newline at end of file
+pub use math_utils::MathUtils;
+pub use id_utils::IdUtils;
\ No newline at end of file
@@ -1,5 +1,6 @@
 /// 验证工具类
 /// 提供数据验证和检查功能
+#[derive(Default)]
 pub struct ValidationUtils;
 
 impl ValidationUtils {
@@ -159,8 +160,8 @@ impl ValidationUtils {
         
         // 直接检查字符是否为数字，避免unwrap_or
         // 由于前面已经检查了长度为4，这里可以安全访问
-        ip_rating.chars().nth(2).map_or(false, |c| c.is_digit(10)) && 
-        ip_rating.chars().nth(3).map_or(false, |c| c.is_digit(10))
+        ip_rating.chars().nth(2).is_some_and(|c| c.is_ascii_digit()) && 
+        ip_rating.chars().nth(3).is_some_and(|c| c.is_ascii_digit())
     }
     
     /// 验证UUID格式
@@ -1,14 +1,9 @@
-use std::collections::{VecDeque, HashMap};
-use crate::editor::business::{DistributionBoxNodeData, MainSystemNodeData, SystemDiagram, CircuitNodeData};
-
-/// 配电箱进线类型枚举
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum IncomingType {
-    /// 单电源进线
-    SinglePower,
-    /// 双电源进线
-    DualPower,
-}
+use std::collections::{BTreeMap, HashMap};
+use crate::editor::business::{BoxData, CircuitInfo, MainSystemNodeData, SystemDiagram};
+pub use crate::editor::business::distribution_box_parameters::IncomingType;
+use crate::editor::business::diagram_layout::riser_key;
+use crate::editor::business::main_system_node::{ConnectionType as DiagramConnectionType, DiagramConnection};
+use crate::editor::business::diagram_routing::{DiagramRouter, RoutingConfig};
 
 /// 连接类型枚举
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -25,6 +20,21 @@ pub enum ConnectionType {
     FeederConnection,
 }
 
+impl ConnectionType {
+    /// 转换为系统图中的连接类型
+    ///
+    /// # 返回值
+    /// 双电源及备用电源连接对应系统图中的双电源连线，其余均为单电源连线
+    pub fn to_diagram_type(&self) -> DiagramConnectionType {
+        match self {
+            ConnectionType::DualPower | ConnectionType::BackupPower => DiagramConnectionType::DualPower,
+            ConnectionType::SinglePower | ConnectionType::BusConnection | ConnectionType::FeederConnection => {
+                DiagramConnectionType::SinglePower
+            },
+        }
+    }
+}
+
 /// 连接信息结构体
 #[derive(Debug, Clone)]
 pub struct ConnectionInfo {
@@ -36,76 +46,59 @@ pub struct ConnectionInfo {
     pub connection_type: ConnectionType,
 }
 
+/// 竖向干线名称（如"AL竖向干线"），同一竖井内各层配电箱均由其供电
+pub fn riser_trunk_label(riser: &str) -> String {
+    format!("{}竖向干线", riser)
+}
+
 /// 自动连接生成器 trait
 pub trait AutoConnectionGenerator {
     /// 自动生成连接信息
-    fn auto_generate_connections(&self, boxes: &[DistributionBoxNodeData]) -> Vec<ConnectionInfo>;
-}
-
-/// 扩展配电箱节点数据以支持进线类型判断
-impl DistributionBoxNodeData {
-    /// 判断配电箱的进线类型
-    pub fn determine_incoming_type(&self) -> IncomingType {
-        // 根据模块配置判断是否为双电源切换配电箱
-        if self.modules.iter().any(|module| module.contains("双电源切换") || module.contains("双电源")) {
-            IncomingType::DualPower
-        } else {
-            IncomingType::SinglePower
-        }
-    }
-    
-    /// 检查是否为消防负荷配电箱
-    pub fn is_fire_load(&self) -> bool {
-        // 根据命名规则或标记判断是否为消防负荷
-        self.name.contains("消防") || self.name.contains("XF")
-    }
+    fn auto_generate_connections(&self, boxes: &[BoxData]) -> Vec<ConnectionInfo>;
 }
 
 /// 扩展干线系统图节点以支持自动连线生成
 impl AutoConnectionGenerator for MainSystemNodeData {
     /// 根据进线类型自动生成连线
-    fn auto_generate_connections(&self, boxes: &[DistributionBoxNodeData]) -> Vec<ConnectionInfo> {
+    fn auto_generate_connections(&self, boxes: &[BoxData]) -> Vec<ConnectionInfo> {
         let mut connections = Vec::new();
 
-        // 1. 按楼层排序配电箱
-        let mut sorted_boxes = boxes.to_vec();
-        sorted_boxes.sort_by(|a, b| a.floor.cmp(&b.floor));
-
-        // 2. 为每层建立配电箱组
-        let mut floor_boxes: HashMap<i32, Vec<&DistributionBoxNodeData>> = HashMap::new();
-        for box_node in &sorted_boxes {
-            floor_boxes.entry(box_node.floor).or_default().push(box_node);
+        // 1. 按竖向干线分组：指定了竖井的按竖井，否则按配电箱名称推导
+        let mut riser_boxes: BTreeMap<String, Vec<&BoxData>> = BTreeMap::new();
+        for box_node in boxes {
+            let riser = box_node.shaft.clone().unwrap_or_else(|| riser_key(&box_node.name));
+            riser_boxes.entry(riser).or_default().push(box_node);
         }
 
-        // 3. 按楼层层级关系生成连线
-        for (current_floor, current_boxes) in &floor_boxes {
-            // 找到上一层的配电箱（如果存在）
-            if let Some(prev_boxes) = floor_boxes.get(&(current_floor - 1)) {
-                // 简单策略：当前层的每个配电箱都连接到上一层的主配电箱
-                // 实际应用中可以实现更复杂的连接逻辑
-                if let Some(prev_main_box) = prev_boxes.first() {
-                    for box_node in current_boxes {
-                        // 根据进线类型生成连线
-                        let incoming_type = box_node.determine_incoming_type();
-
-                        connections.push(ConnectionInfo {
-                            from: prev_main_box.name.clone(),
-                            to: box_node.name.clone(),
-                            connection_type: match incoming_type {
-                                IncomingType::SinglePower => ConnectionType::SinglePower,
-                                IncomingType::DualPower => ConnectionType::DualPower,
-                            },
-                        });
-
-                        // 如果是双电源，还需要连接备用电源
-                        if let IncomingType::DualPower = incoming_type {
-                            connections.push(ConnectionInfo {
-                                from: "备用电源".to_string(),
-                                to: box_node.name.clone(),
-                                connection_type: ConnectionType::BackupPower,
-                            });
-                        }
-                    }
+        // 2. 每条竖向干线由主母线引出，同一竖井内各层配电箱均由该竖向干线T接供电，不经其他配电箱转供
+        for (riser, risers) in riser_boxes.iter_mut() {
+            risers.sort_by_key(|box_node| box_node.floor);
+            let trunk = riser_trunk_label(riser);
+            connections.push(ConnectionInfo {
+                from: "主母线".to_string(),
+                to: trunk.clone(),
+                connection_type: ConnectionType::FeederConnection,
+            });
+            for box_node in risers.iter() {
+                // 根据进线类型生成连线
+                let incoming_type = box_node.determine_incoming_type();
+
+                connections.push(ConnectionInfo {
+                    from: trunk.clone(),
+                    to: box_node.name.clone(),
+                    connection_type: match incoming_type {
+                        IncomingType::SinglePower => ConnectionType::SinglePower,
+                        IncomingType::DualPower => ConnectionType::DualPower,
+                    },
+                });
+
+                // 如果是双电源，还需要连接备用电源
+                if let IncomingType::DualPower = incoming_type {
+                    connections.push(ConnectionInfo {
+                        from: "备用电源".to_string(),
+                        to: box_node.name.clone(),
+                        connection_type: ConnectionType::BackupPower,
+                    });
                 }
             }
         }
@@ -121,25 +114,26 @@ impl SystemDiagram {
         // 将连接信息添加到系统图中
         for connection in connections {
             // 查找源和目标组件
-            let from_index = self.components.iter().position(|c| c.name == connection.from);
-            let to_index = self.components.iter().position(|c| c.name == connection.to);
+            let from_index = self.components.iter().position(|c| c.label == connection.from);
+            let to_index = self.components.iter().position(|c| c.label == connection.to);
             
             if let (Some(from_idx), Some(to_idx)) = (from_index, to_index) {
                 // 添加连接
-                self.connections.push((from_idx, to_idx));
+                self.connections.push(DiagramConnection {
+                    from: self.components[from_idx].id,
+                    to: self.components[to_idx].id,
+                    connection_type: connection.connection_type.to_diagram_type(),
+                    route: Vec::new(),
+                });
             }
         }
     }
     
     /// 基于拓扑结构进行智能连线优化
-    pub fn optimize_connections(&mut self) {
-        // 实现连线优化逻辑，避免交叉和过长的连线
-        // 简化实现：使用BFS来确定最短路径连接
-        let mut optimized_connections = Vec::new();        
-        // 这里可以实现更复杂的连线优化算法
-        // 例如基于网格的布线、最短路径算法等        
-        // 暂时保留原始连接
-        self.connections = optimized_connections;
+    ///
+    /// 保留全部连接，按当前布局重新正交布线（见DiagramRouter），返回布线后的交叉数
+    pub fn optimize_connections(&mut self) -> usize {
+        DiagramRouter::route(self, &RoutingConfig::default())
     }
 }
 
@@ -162,6 +156,12 @@ pub struct ConnectionRule {
     pub priority: u32,
 }
 
+impl Default for AutoConnectionManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AutoConnectionManager {
     /// 创建新的自动连接管理器
     pub fn new() -> Self {
@@ -190,15 +190,20 @@ impl AutoConnectionManager {
     }
     
     /// 为干线系统图执行自动连接生成
-    pub fn auto_connect_for_system(&self, main_system: &mut MainSystemNodeData, distribution_boxes: &[DistributionBoxNodeData]) -> Vec<ConnectionInfo> {
+    pub fn auto_connect_for_system(&self, main_system: &mut MainSystemNodeData, distribution_boxes: &[BoxData]) -> Vec<ConnectionInfo> {
         main_system.auto_generate_connections(distribution_boxes)
     }
 }
 
 /// 辅助函数：从配电回路推导出设备类型
-pub fn derive_equipment_type(circuit: &CircuitNodeData) -> String {
-    // 根据回路用途、功率和其他参数推导设备类型
-    if let Some(purpose) = &circuit.purpose {
+pub fn derive_equipment_type(circuit: &CircuitInfo) -> String {
+    equipment_type_for_purpose(circuit.purpose.as_deref())
+}
+
+/// 辅助函数：从回路用途推导出设备类型
+pub fn equipment_type_for_purpose(purpose: Option<&str>) -> String {
+    // 根据回路用途推导设备类型
+    if let Some(purpose) = purpose {
         if purpose.contains("照明") {
             return "照明设备".to_string();
         } else if purpose.contains("插座") {
@@ -1,7 +1,7 @@
 // 自动识别与连线生成功能示例
 // 本文件展示了如何使用DistributionBoxNode的determine_incoming_type方法和MainSystemNodeUI的auto_generate_connections方法
 
-use crate::editor::business::{DistributionBoxNode, MainSystemNodeUI, MainSystemResponse};
+use crate::editor::business::{DistributionBoxNode, MainSystemNodeUI, BoxModule, ModuleKind};
 
 pub fn demonstrate_auto_connections() {
     // 创建一些配电箱示例
@@ -14,22 +14,22 @@ pub fn demonstrate_auto_connections() {
     dual_power_box.total_power = 30.0;    // 30kW
     dual_power_box.total_current = 45.6;  // 45.6A
     // 双电源配电箱添加双电源切换模块
-    dual_power_box.modules.push("双电源切换".to_string());
+    dual_power_box.add_module(BoxModule::default_for(ModuleKind::DualPowerSwitch));
     
     let mut energy_monitoring_box = DistributionBoxNode::new("能耗监测配电箱".to_string(), 3);
     energy_monitoring_box.total_power = 20.0;  // 20kW
     energy_monitoring_box.total_current = 30.4; // 30.4A
-    energy_monitoring_box.modules.push("能耗监测".to_string());
+    energy_monitoring_box.add_module(BoxModule::default_for(ModuleKind::EnergyMonitoring));
     
     let mut fire_monitoring_box = DistributionBoxNode::new("电气火灾监控配电箱".to_string(), 4);
     fire_monitoring_box.total_power = 10.0;  // 10kW
     fire_monitoring_box.total_current = 15.2; // 15.2A
-    fire_monitoring_box.modules.push("电气火灾监控".to_string());
+    fire_monitoring_box.add_module(BoxModule::default_for(ModuleKind::ElectricalFireMonitoring));
     
     let mut fire_power_box = DistributionBoxNode::new("消防电源监测配电箱".to_string(), 5);
     fire_power_box.total_power = 25.0;  // 25kW
     fire_power_box.total_current = 38.0; // 38.0A
-    fire_power_box.modules.push("消防电源监测".to_string());
+    fire_power_box.add_module(BoxModule::default_for(ModuleKind::FirePowerMonitoring));
     
     // 演示determine_incoming_type方法
     println!("配电箱进线类型判断:");
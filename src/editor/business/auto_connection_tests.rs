@@ -2,20 +2,21 @@
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-    use crate::editor::business::{DistributionBoxNode, MainSystemNodeUI, IncomingType};
+    use crate::editor::business::{DistributionBoxNode, MainSystemNodeData, MainSystemNodeUI, IncomingType, BoxModule, ModuleKind};
+    use crate::editor::business::auto_connection::{riser_trunk_label, AutoConnectionGenerator, ConnectionType as LinkType};
+    use crate::editor::business::diagram_layout::riser_key;
     use crate::editor::business::main_system_node::{ConnectionType, ComponentType};
     
     #[test]
     fn test_determine_incoming_type() {
         // 创建单电源配电箱
-        let mut single_power_box = DistributionBoxNode::new("单电源配电箱".to_string(), 1);
+        let single_power_box = DistributionBoxNode::new("单电源配电箱".to_string(), 1);
         // 验证识别结果为单电源
         assert_eq!(single_power_box.determine_incoming_type(), IncomingType::SinglePower);
         
         // 创建双电源配电箱
         let mut dual_power_box = DistributionBoxNode::new("双电源配电箱".to_string(), 2);
-        dual_power_box.modules.push("双电源切换".to_string());
+        dual_power_box.add_module(BoxModule::default_for(ModuleKind::DualPowerSwitch));
         // 验证识别结果为双电源
         assert_eq!(dual_power_box.determine_incoming_type(), IncomingType::DualPower);
     }
@@ -30,7 +31,7 @@ mod tests {
         let mut dual_power_box = DistributionBoxNode::new("双电源配电箱".to_string(), 2);
         dual_power_box.total_power = 20.0;
         dual_power_box.total_current = 30.4;
-        dual_power_box.modules.push("双电源切换".to_string());
+        dual_power_box.add_module(BoxModule::default_for(ModuleKind::DualPowerSwitch));
         
         // 创建配电箱向量
         let boxes = vec![&single_power_box, &dual_power_box];
@@ -51,7 +52,7 @@ mod tests {
         assert!(diagram.connections.len() >= 3);
         
         // 找到配电箱组件
-        let mut box_components = diagram.components
+        let box_components = diagram.components
             .iter()
             .filter(|comp| comp.component_type == ComponentType::DistributionBox)
             .collect::<Vec<_>>();
@@ -82,20 +83,118 @@ mod tests {
         // 验证连接类型设置正确
         let single_power_conn = diagram.connections
             .iter()
-            .find(|conn| conn.source_id == main_busbar.id && conn.target_id == single_power_component.id)
+            .find(|conn| conn.from == main_busbar.id && conn.to == single_power_component.id)
             .unwrap();
         assert_eq!(single_power_conn.connection_type, ConnectionType::SinglePower);
         
         let dual_power_main_conn = diagram.connections
             .iter()
-            .find(|conn| conn.source_id == main_busbar.id && conn.target_id == dual_power_component.id)
+            .find(|conn| conn.from == main_busbar.id && conn.to == dual_power_component.id)
             .unwrap();
         assert_eq!(dual_power_main_conn.connection_type, ConnectionType::DualPower);
         
         let dual_power_backup_conn = diagram.connections
             .iter()
-            .find(|conn| conn.source_id == backup_power.id && conn.target_id == dual_power_component.id)
+            .find(|conn| conn.from == backup_power.id && conn.to == dual_power_component.id)
             .unwrap();
         assert_eq!(dual_power_backup_conn.connection_type, ConnectionType::DualPower);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_riser_trunk_feeds_each_floor() {
+        // 同一竖井内各层配电箱均由竖向干线供电，不由下层配电箱转供
+        let mut boxes: Vec<DistributionBoxNode> = [("B1AL1", -1), ("1AL1", 1), ("2AL1", 2), ("3AL1", 3), ("1AP1", 1)]
+            .iter()
+            .map(|(name, floor)| DistributionBoxNode::new(name.to_string(), *floor))
+            .collect();
+        boxes[2].add_module(BoxModule::default_for(ModuleKind::DualPowerSwitch));
+
+        let connections = MainSystemNodeData::default().auto_generate_connections(&boxes);
+        let trunks: Vec<&str> = connections.iter().filter(|c| c.from == "主母线").map(|c| c.to.as_str()).collect();
+        assert_eq!(trunks, vec![riser_trunk_label("AL1"), riser_trunk_label("AP1")]);
+        assert!(connections.iter().filter(|c| c.from == "主母线").all(|c| c.connection_type == LinkType::FeederConnection));
+
+        for box_data in &boxes {
+            let feeders: Vec<&str> = connections
+                .iter()
+                .filter(|c| c.to == box_data.name && c.from != "备用电源")
+                .map(|c| c.from.as_str())
+                .collect();
+            assert_eq!(feeders, vec![riser_trunk_label(&riser_key(&box_data.name))], "{}", box_data.name);
+            assert!(!connections.iter().any(|c| c.from == box_data.name));
+        }
+        assert!(connections.iter().any(|c| c.from == "备用电源" && c.to == "2AL1"));
+    }
+
+    #[test]
+    fn test_fire_power_trunk_assigns_addresses() {
+        use crate::core_lib::data_types::{LoadClass, LoadLevel};
+        use crate::editor::business::{CircuitInfo, FireModuleGenerator, MainSystemType};
+
+        // 尚未编址的配电箱在生成消防电源监测干线图时按楼层、名称编址
+        let mut fire_box = DistributionBoxNode::new("2APE1".to_string(), 2);
+        let mut fan = CircuitInfo::new("c1".to_string(), "消防风机".to_string(), 11.0, 22.0);
+        fan.load_class = LoadClass::fire(LoadLevel::First);
+        fire_box.circuits.push(fan);
+        let mut lighting_box = DistributionBoxNode::new("1AL1".to_string(), 1);
+        lighting_box.circuits.push(CircuitInfo::new("c1".to_string(), "照明".to_string(), 2.0, 10.0));
+        for box_data in [&mut fire_box, &mut lighting_box] {
+            FireModuleGenerator::auto_insert(box_data);
+        }
+
+        let mut system_node = MainSystemNodeUI::default();
+        system_node.data.systems = vec![MainSystemType::FirePowerMonitoring];
+        let diagrams = system_node.auto_map_distribution_boxes(&[&fire_box, &lighting_box]);
+        let labels: Vec<&str> = diagrams[0]
+            .components
+            .iter()
+            .filter(|c| c.component_type == ComponentType::MonitoringModule)
+            .map(|c| c.label.as_str())
+            .collect();
+        assert_eq!(labels.len(), 2);
+        assert!(labels[0].starts_with("消防切断\n地址:1\n"));
+        assert!(labels[1].starts_with("消防电源监测\n地址:2\n"));
+    }
+
+    #[test]
+    fn test_electrical_fire_trunk_assigns_addresses() {
+        use crate::editor::business::{CircuitInfo, ElectricalFireMonitoringConfig, ElectricalFirePlanner, MainSystemType};
+
+        // 探测器在生成电气火灾监控干线图时按楼层、名称编址，总线标注探测器总数
+        let config = ElectricalFireMonitoringConfig::default();
+        let mut upper = DistributionBoxNode::new("2AP1".to_string(), 2);
+        upper.incoming_current = 100.0;
+        upper.circuits.push(CircuitInfo::new("c1".to_string(), "空调".to_string(), 22.0, 40.0));
+        let mut lower = DistributionBoxNode::new("1AP1".to_string(), 1);
+        lower.incoming_current = 80.0;
+        for box_data in [&mut upper, &mut lower] {
+            ElectricalFirePlanner::plan_box(box_data, &config);
+        }
+
+        let mut system_node = MainSystemNodeUI::default();
+        system_node.data.systems = vec![MainSystemType::ElectricalFireMonitoring];
+        let diagrams = system_node.auto_map_distribution_boxes(&[&upper, &lower]);
+        let busbar = diagrams[0].components.iter().find(|c| c.component_type == ComponentType::Busbar).unwrap();
+        assert_eq!(busbar.label, "监控总线\n探测器总数:3");
+        let labels: Vec<&str> = diagrams[0]
+            .components
+            .iter()
+            .filter(|c| c.component_type == ComponentType::DistributionBox)
+            .map(|c| c.label.as_str())
+            .collect();
+        assert_eq!(labels.len(), 2);
+        assert!(labels[0].starts_with("1AP1\n") && labels[0].ends_with("地址:1-1"));
+        assert!(labels[1].starts_with("2AP1\n") && labels[1].ends_with("地址:2-3"));
+    }
+
+    #[test]
+    fn test_trunk_current_beyond_feeder_ratings_is_reported() {
+        // 干线计算电流超出最大保护电器额定电流时记录错误，不静默跳过馈线选择
+        let mut huge = DistributionBoxNode::new("B1AP1".to_string(), -1);
+        huge.total_current = 6000.0;
+
+        let mut system_node = MainSystemNodeUI::default();
+        system_node.auto_generate_connections(vec![&huge]);
+        assert!(system_node.data.errors.iter().any(|e| e.contains("干线计算电流6000.0A超出")));
+    }
+}
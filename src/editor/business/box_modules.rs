@@ -0,0 +1,529 @@
+//! 配电箱功能模块
+//!
+//! 本模块定义配电箱内可配置的功能模块（双电源切换、能耗监测、电气火灾监控、消防电源监测、
+//! 消防切断、浪涌保护器、PE端子排、电能计量及自定义模块）。每个模块携带自身参数，
+//! 向配电箱系统图提供绘制元件，并声明参与的干线系统图类型。
+//! 模块类型通过`ModuleRegistry`登记，可在内置模块之外注册自定义模块；配电箱属性面板从注册表中
+//! 选择要添加的模块。
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::core_lib::data_types::{
+    DualPowerSwitch, ElectricComponent, EnergyMeter, PhaseConfig, SettingValue, SurgeProtector,
+};
+use crate::editor::business::DistributionBoxError;
+use crate::editor::business::electrical_fire_monitoring::{DetectorKind, ElectricalFireMonitoringModule};
+use crate::editor::business::fire_power_monitoring::{FireCutOffModule, FirePowerMonitoringModule};
+use crate::editor::business::main_system_node::MainSystemType;
+
+/// 模块类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ModuleKind {
+    DualPowerSwitch,          // 双电源切换
+    EnergyMonitoring,         // 能耗监测
+    ElectricalFireMonitoring, // 电气火灾监控
+    FirePowerMonitoring,      // 消防电源监测
+    FireCutOff,               // 消防切断
+    Spd,                      // 浪涌保护器
+    PeTerminalBar,            // PE端子排
+    Meter,                    // 电能计量
+    Custom,                   // 自定义模块
+}
+
+impl ModuleKind {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            ModuleKind::DualPowerSwitch => "双电源切换",
+            ModuleKind::EnergyMonitoring => "能耗监测",
+            ModuleKind::ElectricalFireMonitoring => "电气火灾监控",
+            ModuleKind::FirePowerMonitoring => "消防电源监测",
+            ModuleKind::FireCutOff => "消防切断",
+            ModuleKind::Spd => "浪涌保护器",
+            ModuleKind::PeTerminalBar => "PE端子排",
+            ModuleKind::Meter => "电能计量",
+            ModuleKind::Custom => "自定义模块",
+        }
+    }
+
+    /// 根据旧版本的模块名称字符串识别模块类型
+    ///
+    /// 兼容早期以字符串保存的模块列表，如"双电源切换模块"、"dual power transfer switch"
+    pub fn from_legacy_name(name: &str) -> Option<Self> {
+        let lower = name.to_lowercase();
+        if lower.contains("双电源") || lower.contains("dual power") || lower.contains("duplex power") {
+            Some(ModuleKind::DualPowerSwitch)
+        } else if lower.contains("能耗") {
+            Some(ModuleKind::EnergyMonitoring)
+        } else if lower.contains("电气火灾") {
+            Some(ModuleKind::ElectricalFireMonitoring)
+        } else if lower.contains("消防电源") {
+            Some(ModuleKind::FirePowerMonitoring)
+        } else if lower.contains("消防切断") || lower.contains("切非") {
+            Some(ModuleKind::FireCutOff)
+        } else if lower.contains("浪涌") || lower.contains("spd") {
+            Some(ModuleKind::Spd)
+        } else if lower.contains("端子排") || lower.contains("pe bar") {
+            Some(ModuleKind::PeTerminalBar)
+        } else if lower.contains("电能表") || lower.contains("计量") || lower.contains("meter") {
+            Some(ModuleKind::Meter)
+        } else {
+            None
+        }
+    }
+}
+
+/// 模块绘制符号
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModuleSymbol {
+    DualPowerSwitch,            // 双电源自动转换开关
+    EnergyMeter,                // 电能表
+    ResidualCurrentTransformer, // 剩余电流互感器
+    TemperatureSensor,          // 测温探测器
+    VoltageCurrentSensor,       // 电压/电流传感器
+    ShuntTrip,                  // 分励脱扣器
+    SurgeProtector,             // 浪涌保护器
+    PeTerminalBar,              // PE端子排
+    Generic,                    // 通用模块框
+}
+
+impl ModuleSymbol {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            ModuleSymbol::DualPowerSwitch => "ATSE",
+            ModuleSymbol::EnergyMeter => "电能表",
+            ModuleSymbol::ResidualCurrentTransformer => "剩余电流互感器",
+            ModuleSymbol::TemperatureSensor => "测温探测器",
+            ModuleSymbol::VoltageCurrentSensor => "电压/电流传感器",
+            ModuleSymbol::ShuntTrip => "分励脱扣器",
+            ModuleSymbol::SurgeProtector => "SPD",
+            ModuleSymbol::PeTerminalBar => "PE端子排",
+            ModuleSymbol::Generic => "模块",
+        }
+    }
+}
+
+/// 模块向配电箱系统图提供的绘制元件
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleComponent {
+    /// 绘制符号
+    pub symbol: ModuleSymbol,
+    /// 标注文字
+    pub label: String,
+    /// 关联的回路ID，None表示位于进线或箱体
+    pub circuit_id: Option<String>,
+    /// 对应的电气元器件（用于材料统计），无对应元器件时为None
+    pub component: Option<ElectricComponent>,
+}
+
+impl ModuleComponent {
+    fn new(symbol: ModuleSymbol, label: String) -> Self {
+        Self { symbol, label, circuit_id: None, component: None }
+    }
+}
+
+/// 双电源切换模块
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct DualPowerSwitchModule {
+    /// 双电源自动转换开关参数
+    pub switch: DualPowerSwitch,
+}
+
+/// 能耗监测模块
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnergyMonitoringModule {
+    /// 总线地址，None表示尚未分配
+    pub address: Option<u32>,
+    /// 远传电能表参数
+    pub meter: EnergyMeter,
+}
+
+impl Default for EnergyMonitoringModule {
+    fn default() -> Self {
+        Self {
+            address: None,
+            meter: EnergyMeter {
+                model: "DTSD1352".to_string(),
+                rated_current: SettingValue::A10,
+                max_current: SettingValue::A80,
+                accuracy_class: 1.0,
+                communication: "RS485".to_string(),
+                phase_config: Some(PhaseConfig::ThreePhaseFourWire),
+            },
+        }
+    }
+}
+
+/// 浪涌保护器模块
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SpdModule {
+    /// 浪涌保护器参数
+    pub spd: SurgeProtector,
+}
+
+/// PE端子排模块
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeTerminalBarModule {
+    /// 端子数量
+    pub terminal_count: u32,
+    /// 端子排截面（mm²）
+    pub section: f64,
+}
+
+impl Default for PeTerminalBarModule {
+    fn default() -> Self {
+        Self { terminal_count: 12, section: 100.0 }
+    }
+}
+
+/// 电能计量模块
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MeterModule {
+    /// 电能表参数
+    pub meter: EnergyMeter,
+}
+
+impl Default for MeterModule {
+    fn default() -> Self {
+        Self {
+            meter: EnergyMeter {
+                model: "DTS1352".to_string(),
+                rated_current: SettingValue::A10,
+                max_current: SettingValue::A40,
+                accuracy_class: 1.0,
+                communication: "无".to_string(),
+                phase_config: Some(PhaseConfig::ThreePhaseFourWire),
+            },
+        }
+    }
+}
+
+/// 自定义模块
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct CustomModule {
+    /// 模块名称
+    pub name: String,
+    /// 模块参数（名称-值）
+    pub params: BTreeMap<String, String>,
+    /// 参与的干线系统图
+    pub trunk_diagrams: Vec<MainSystemType>,
+}
+
+/// 配电箱功能模块
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BoxModule {
+    DualPowerSwitch(DualPowerSwitchModule),
+    EnergyMonitoring(EnergyMonitoringModule),
+    ElectricalFireMonitoring(ElectricalFireMonitoringModule),
+    FirePowerMonitoring(FirePowerMonitoringModule),
+    FireCutOff(FireCutOffModule),
+    Spd(SpdModule),
+    PeTerminalBar(PeTerminalBarModule),
+    Meter(MeterModule),
+    Custom(CustomModule),
+}
+
+impl BoxModule {
+    /// 创建指定类型的默认模块
+    ///
+    /// 自定义模块以类型名称作为模块名称
+    pub fn default_for(kind: ModuleKind) -> Self {
+        match kind {
+            ModuleKind::DualPowerSwitch => BoxModule::DualPowerSwitch(DualPowerSwitchModule::default()),
+            ModuleKind::EnergyMonitoring => BoxModule::EnergyMonitoring(EnergyMonitoringModule::default()),
+            ModuleKind::ElectricalFireMonitoring => {
+                BoxModule::ElectricalFireMonitoring(ElectricalFireMonitoringModule::default())
+            }
+            ModuleKind::FirePowerMonitoring => BoxModule::FirePowerMonitoring(FirePowerMonitoringModule {
+                address: None,
+                incoming_lines: Vec::new(),
+            }),
+            ModuleKind::FireCutOff => BoxModule::FireCutOff(FireCutOffModule {
+                address: None,
+                shunt_trip_circuits: Vec::new(),
+            }),
+            ModuleKind::Spd => BoxModule::Spd(SpdModule::default()),
+            ModuleKind::PeTerminalBar => BoxModule::PeTerminalBar(PeTerminalBarModule::default()),
+            ModuleKind::Meter => BoxModule::Meter(MeterModule::default()),
+            ModuleKind::Custom => BoxModule::Custom(CustomModule {
+                name: ModuleKind::Custom.to_str().to_string(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// 根据旧版本的模块名称字符串创建模块
+    pub fn from_legacy_name(name: &str) -> Option<Self> {
+        ModuleKind::from_legacy_name(name).map(Self::default_for)
+    }
+
+    /// 模块类型
+    pub fn kind(&self) -> ModuleKind {
+        match self {
+            BoxModule::DualPowerSwitch(_) => ModuleKind::DualPowerSwitch,
+            BoxModule::EnergyMonitoring(_) => ModuleKind::EnergyMonitoring,
+            BoxModule::ElectricalFireMonitoring(_) => ModuleKind::ElectricalFireMonitoring,
+            BoxModule::FirePowerMonitoring(_) => ModuleKind::FirePowerMonitoring,
+            BoxModule::FireCutOff(_) => ModuleKind::FireCutOff,
+            BoxModule::Spd(_) => ModuleKind::Spd,
+            BoxModule::PeTerminalBar(_) => ModuleKind::PeTerminalBar,
+            BoxModule::Meter(_) => ModuleKind::Meter,
+            BoxModule::Custom(_) => ModuleKind::Custom,
+        }
+    }
+
+    /// 模块显示名称
+    pub fn name(&self) -> &str {
+        match self {
+            BoxModule::Custom(module) => &module.name,
+            _ => self.kind().to_str(),
+        }
+    }
+
+    /// 模块参与的干线系统图
+    pub fn trunk_diagrams(&self) -> Vec<MainSystemType> {
+        match self {
+            BoxModule::DualPowerSwitch(_) => vec![MainSystemType::PowerDistribution],
+            BoxModule::EnergyMonitoring(_) => vec![MainSystemType::EnergyMonitoring],
+            BoxModule::ElectricalFireMonitoring(_) => vec![MainSystemType::ElectricalFireMonitoring],
+            BoxModule::FirePowerMonitoring(_) | BoxModule::FireCutOff(_) => {
+                vec![MainSystemType::FirePowerMonitoring]
+            }
+            BoxModule::Spd(_) | BoxModule::PeTerminalBar(_) | BoxModule::Meter(_) => Vec::new(),
+            BoxModule::Custom(module) => module.trunk_diagrams.clone(),
+        }
+    }
+
+    /// 模块向配电箱系统图提供的绘制元件
+    pub fn components(&self) -> Vec<ModuleComponent> {
+        match self {
+            BoxModule::DualPowerSwitch(module) => vec![ModuleComponent {
+                component: Some(ElectricComponent::DualPowerSwitch(module.switch.clone())),
+                ..ModuleComponent::new(
+                    ModuleSymbol::DualPowerSwitch,
+                    format!("{} {}A/{}", module.switch.model, module.switch.rated_current.to_str(), module.switch.pole.to_str()),
+                )
+            }],
+            BoxModule::EnergyMonitoring(module) => vec![ModuleComponent {
+                component: Some(ElectricComponent::EnergyMeter(module.meter.clone())),
+                ..ModuleComponent::new(
+                    ModuleSymbol::EnergyMeter,
+                    format!("{} {}", module.meter.model, module.meter.communication),
+                )
+            }],
+            BoxModule::Meter(module) => vec![ModuleComponent {
+                component: Some(ElectricComponent::EnergyMeter(module.meter.clone())),
+                ..ModuleComponent::new(
+                    ModuleSymbol::EnergyMeter,
+                    format!("{} {}({})A", module.meter.model, module.meter.rated_current.to_str(), module.meter.max_current.to_str()),
+                )
+            }],
+            BoxModule::ElectricalFireMonitoring(module) => module
+                .detectors
+                .iter()
+                .map(|detector| {
+                    let symbol = match detector.kind {
+                        DetectorKind::ResidualCurrent => ModuleSymbol::ResidualCurrentTransformer,
+                        DetectorKind::Temperature => ModuleSymbol::TemperatureSensor,
+                    };
+                    ModuleComponent {
+                        circuit_id: detector.circuit_id.clone(),
+                        ..ModuleComponent::new(symbol, detector.kind.to_str().to_string())
+                    }
+                })
+                .collect(),
+            BoxModule::FirePowerMonitoring(module) => module
+                .incoming_lines
+                .iter()
+                .map(|line| ModuleComponent::new(
+                    ModuleSymbol::VoltageCurrentSensor,
+                    format!("{}电压/电流传感器", line.line_label),
+                ))
+                .collect(),
+            BoxModule::FireCutOff(module) => module
+                .shunt_trip_circuits
+                .iter()
+                .map(|circuit_id| ModuleComponent {
+                    circuit_id: Some(circuit_id.clone()),
+                    ..ModuleComponent::new(ModuleSymbol::ShuntTrip, "分励脱扣".to_string())
+                })
+                .collect(),
+            BoxModule::Spd(module) => vec![ModuleComponent {
+                component: Some(ElectricComponent::SurgeProtector(module.spd.clone())),
+                ..ModuleComponent::new(
+                    ModuleSymbol::SurgeProtector,
+                    format!("{} Imax={}kA Up≤{}kV", module.spd.test_class, module.spd.max_discharge_current, module.spd.protection_level),
+                )
+            }],
+            BoxModule::PeTerminalBar(module) => vec![ModuleComponent::new(
+                ModuleSymbol::PeTerminalBar,
+                format!("PE端子排 {}位", module.terminal_count),
+            )],
+            BoxModule::Custom(module) => vec![ModuleComponent::new(ModuleSymbol::Generic, module.name.clone())],
+        }
+    }
+}
+
+/// 反序列化模块列表，兼容旧版本以字符串保存的模块名称
+///
+/// 无法识别的旧名称保留为同名自定义模块
+pub fn deserialize_modules<'de, D>(deserializer: D) -> Result<Vec<BoxModule>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ModuleRepr {
+        Typed(BoxModule),
+        Legacy(String),
+    }
+
+    let items = Vec::<ModuleRepr>::deserialize(deserializer)?;
+    Ok(items
+        .into_iter()
+        .map(|item| match item {
+            ModuleRepr::Typed(module) => module,
+            ModuleRepr::Legacy(name) => BoxModule::from_legacy_name(&name).unwrap_or_else(|| {
+                BoxModule::Custom(CustomModule { name, ..Default::default() })
+            }),
+        })
+        .collect())
+}
+
+/// 模块定义
+#[derive(Debug, Clone)]
+pub struct ModuleDefinition {
+    /// 模块类型
+    pub kind: ModuleKind,
+    /// 模块名称（在注册表中唯一）
+    pub name: String,
+    /// 创建默认模块实例的函数
+    pub create: fn() -> BoxModule,
+}
+
+/// 模块注册表
+///
+/// 内置模块在创建时登记，项目可通过`register`追加自定义模块定义
+#[derive(Debug, Clone)]
+pub struct ModuleRegistry {
+    definitions: Vec<ModuleDefinition>,
+}
+
+impl Default for ModuleRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl ModuleRegistry {
+    /// 创建包含全部内置模块的注册表
+    pub fn builtin() -> Self {
+        fn def(kind: ModuleKind, create: fn() -> BoxModule) -> ModuleDefinition {
+            ModuleDefinition { kind, name: kind.to_str().to_string(), create }
+        }
+
+        Self {
+            definitions: vec![
+                def(ModuleKind::DualPowerSwitch, || BoxModule::default_for(ModuleKind::DualPowerSwitch)),
+                def(ModuleKind::EnergyMonitoring, || BoxModule::default_for(ModuleKind::EnergyMonitoring)),
+                def(ModuleKind::ElectricalFireMonitoring, || BoxModule::default_for(ModuleKind::ElectricalFireMonitoring)),
+                def(ModuleKind::FirePowerMonitoring, || BoxModule::default_for(ModuleKind::FirePowerMonitoring)),
+                def(ModuleKind::FireCutOff, || BoxModule::default_for(ModuleKind::FireCutOff)),
+                def(ModuleKind::Spd, || BoxModule::default_for(ModuleKind::Spd)),
+                def(ModuleKind::PeTerminalBar, || BoxModule::default_for(ModuleKind::PeTerminalBar)),
+                def(ModuleKind::Meter, || BoxModule::default_for(ModuleKind::Meter)),
+            ],
+        }
+    }
+
+    /// 注册模块定义
+    ///
+    /// # 返回值
+    /// * `Ok(())` - 注册成功
+    /// * `Err(DistributionBoxError)` - 同名模块已存在
+    pub fn register(&mut self, definition: ModuleDefinition) -> Result<(), DistributionBoxError> {
+        if self.find(&definition.name).is_some() {
+            return Err(DistributionBoxError::InvalidParameter(
+                format!("模块'{}'已注册", definition.name)
+            ));
+        }
+        self.definitions.push(definition);
+        Ok(())
+    }
+
+    /// 全部模块定义
+    pub fn definitions(&self) -> &[ModuleDefinition] {
+        &self.definitions
+    }
+
+    /// 按名称查找模块定义
+    pub fn find(&self, name: &str) -> Option<&ModuleDefinition> {
+        self.definitions.iter().find(|d| d.name == name)
+    }
+
+    /// 按名称创建模块实例
+    pub fn create(&self, name: &str) -> Option<BoxModule> {
+        self.find(name).map(|d| (d.create)())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::business::BoxData;
+    use crate::editor::business::distribution_box_parameters::IncomingType;
+
+    #[test]
+    fn test_legacy_names() {
+        assert_eq!(ModuleKind::from_legacy_name("双电源切换模块"), Some(ModuleKind::DualPowerSwitch));
+        assert_eq!(ModuleKind::from_legacy_name("dual power transfer switch"), Some(ModuleKind::DualPowerSwitch));
+        assert_eq!(ModuleKind::from_legacy_name("能耗监测"), Some(ModuleKind::EnergyMonitoring));
+        assert_eq!(ModuleKind::from_legacy_name("断路器"), None);
+    }
+
+    #[test]
+    fn test_deserialize_legacy_string_modules() {
+        let json = r#"{"name":"AL1","total_power":0.0,"total_current":0.0,"incoming_current":0.0,
+            "floor":1,"modules":["双电源切换","普通配电模块"],"phase_loads":[0.0,0.0,0.0],"circuits":[]}"#;
+        let box_data: BoxData = serde_json::from_str(json).unwrap();
+
+        assert_eq!(box_data.determine_incoming_type(), IncomingType::DualPower);
+        assert_eq!(box_data.modules[1].name(), "普通配电模块");
+
+        let round_trip: BoxData = serde_json::from_str(&serde_json::to_string(&box_data).unwrap()).unwrap();
+        assert_eq!(round_trip, box_data);
+    }
+
+    #[test]
+    fn test_module_diagrams_and_components() {
+        let mut box_data = BoxData::new("AL1".to_string(), 1);
+        box_data.add_module(BoxModule::default_for(ModuleKind::EnergyMonitoring));
+        box_data.add_module(BoxModule::default_for(ModuleKind::Spd));
+        box_data.add_module(BoxModule::default_for(ModuleKind::EnergyMonitoring));
+
+        assert_eq!(box_data.modules.len(), 2);
+        assert!(box_data.participates_in(&MainSystemType::EnergyMonitoring));
+        assert!(!box_data.participates_in(&MainSystemType::FirePowerMonitoring));
+
+        let spd = box_data.module(ModuleKind::Spd).unwrap().components();
+        assert!(matches!(spd[0].component, Some(ElectricComponent::SurgeProtector(_))));
+    }
+
+    #[test]
+    fn test_registry_custom_module() {
+        let mut registry = ModuleRegistry::builtin();
+        let definition = ModuleDefinition {
+            kind: ModuleKind::Custom,
+            name: "智能照明控制".to_string(),
+            create: || BoxModule::Custom(CustomModule { name: "智能照明控制".to_string(), ..Default::default() }),
+        };
+        registry.register(definition.clone()).unwrap();
+        assert!(registry.register(definition).is_err());
+
+        let module = registry.create("智能照明控制").unwrap();
+        assert_eq!(module.kind(), ModuleKind::Custom);
+        assert_eq!(module.name(), "智能照明控制");
+        assert!(registry.create("双电源切换").is_some());
+    }
+}
@@ -0,0 +1,331 @@
+//! 建筑楼层与竖井模型
+//!
+//! 楼层按楼层号标识：地上为1、2…，地下室为负数（B1为-1、B2为-2），不使用0。各楼层带名称、
+//! 楼面标高和层高；电气竖井记录平面位置和贯通的楼层范围。配电箱通过楼层号和竖井标识关联到
+//! 建筑，干线系统图的分层与竖向干线、干线竖向长度和配电箱编号均由此推导。
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::core_lib::utils::IdUtils;
+use crate::editor::business::BoxData;
+
+/// 建筑模型错误类型
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum BuildingError {
+    /// 楼层号为0
+    #[error("楼层号不能为0（地下室使用负数，如B1为-1）")]
+    ZeroLevel,
+
+    /// 楼层号重复
+    #[error("楼层{0}重复")]
+    DuplicateFloor(String),
+
+    /// 层高无效
+    #[error("楼层{0}的层高必须大于0")]
+    InvalidHeight(String),
+
+    /// 标高与楼层顺序不符
+    #[error("楼层{0}的标高不高于下一层")]
+    ElevationOrder(String),
+
+    /// 楼层未定义
+    #[error("楼层{0}未在建筑中定义")]
+    UnknownFloor(String),
+
+    /// 竖井未定义
+    #[error("竖井不存在: {0}")]
+    UnknownShaft(String),
+
+    /// 竖井未贯通该楼层
+    #[error("竖井{shaft}未贯通楼层{floor}")]
+    ShaftNotOnFloor { shaft: String, floor: String },
+}
+
+/// 楼层号的默认名称：地上为数字，地下室为"B"加层数（-1为B1）
+pub fn level_name(level: i32) -> String {
+    if level < 0 {
+        format!("B{}", -level)
+    } else {
+        level.to_string()
+    }
+}
+
+/// 解析楼层文字为楼层号
+///
+/// 支持"3"、"3F"、"3层"、"B1"、"B1F"、"-1"等写法
+///
+/// # 返回值
+/// 返回楼层号，无法识别或为0时返回None
+pub fn parse_level(text: &str) -> Option<i32> {
+    let text = text.trim();
+    let text = text.strip_suffix(['F', 'f', '层']).unwrap_or(text).trim();
+    let level = match text.strip_prefix(['B', 'b']) {
+        Some(depth) => -depth.trim().parse::<i32>().ok().filter(|d| *d > 0)?,
+        None => text.parse::<i32>().ok()?,
+    };
+    (level != 0).then_some(level)
+}
+
+/// 按"楼层前缀+类别+序号"规则生成下一个配电箱编号（如1AL1、B1AP2）
+///
+/// # 参数
+/// * `names` - 已有的配电箱名称
+/// * `level` - 楼层号
+/// * `stem` - 配电箱类别（如AL、AP、AT）
+///
+/// # 返回值
+/// 返回该楼层该类别未使用的最小后续编号
+pub fn next_box_name<'a>(names: impl IntoIterator<Item = &'a str>, level: i32, stem: &str) -> String {
+    let prefix = format!("{}{}", level_name(level), stem);
+    let last = names
+        .into_iter()
+        .filter_map(|name| name.trim().strip_prefix(prefix.as_str()))
+        .filter_map(|rest| rest.parse::<u32>().ok())
+        .max()
+        .unwrap_or(0);
+    format!("{}{}", prefix, last + 1)
+}
+
+/// 楼层
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Floor {
+    /// 楼层号（地下室为负数，不使用0）
+    pub level: i32,
+    /// 楼层名称（如"B1"、"1F"、"屋面层"）
+    pub name: String,
+    /// 楼面标高（m）
+    pub elevation: f64,
+    /// 层高（m）
+    pub height: f64,
+}
+
+/// 电气竖井
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Shaft {
+    /// 竖井唯一标识（UUID，随项目保存）
+    #[serde(default = "IdUtils::new_id")]
+    pub id: String,
+    /// 竖井名称（如"强电井1"）
+    pub name: String,
+    /// 竖井在楼层平面中的位置（m）
+    #[serde(default)]
+    pub position: (f64, f64),
+    /// 贯通的最低、最高楼层号
+    pub levels: (i32, i32),
+}
+
+impl Shaft {
+    /// 竖井是否贯通指定楼层
+    pub fn serves(&self, level: i32) -> bool {
+        self.levels.0 <= level && level <= self.levels.1
+    }
+}
+
+/// 建筑模型
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Building {
+    /// 楼层列表（按楼层号自下而上排列）
+    #[serde(default)]
+    pub floors: Vec<Floor>,
+    /// 电气竖井列表
+    #[serde(default)]
+    pub shafts: Vec<Shaft>,
+    /// 变配电所低压出线位置（楼层号、平面位置），干线电缆由此引出
+    #[serde(default)]
+    pub source: Option<(i32, (f64, f64))>,
+}
+
+impl Building {
+    /// 按统一层高创建建筑，首层楼面标高为±0.000
+    ///
+    /// # 参数
+    /// * `basements` - 地下层数
+    /// * `storeys` - 地上层数
+    /// * `height` - 层高（m）
+    pub fn uniform(basements: u32, storeys: u32, height: f64) -> Self {
+        let levels = (1..=basements as i32).rev().map(|d| -d).chain(1..=storeys as i32);
+        let floors = levels
+            .map(|level| Floor {
+                level,
+                name: format!("{}F", level_name(level)),
+                elevation: if level < 0 { level as f64 * height } else { (level - 1) as f64 * height },
+                height,
+            })
+            .collect();
+        Self { floors, ..Self::default() }
+    }
+
+    /// 是否尚未定义楼层（未定义时按楼层号推导，不做校验）
+    pub fn is_empty(&self) -> bool {
+        self.floors.is_empty()
+    }
+
+    /// 添加或替换楼层，保持楼层按楼层号排列
+    ///
+    /// # 返回值
+    /// * `Ok(())` - 添加成功
+    /// * `Err(BuildingError)` - 楼层号为0或层高无效
+    pub fn set_floor(&mut self, floor: Floor) -> Result<(), BuildingError> {
+        if floor.level == 0 {
+            return Err(BuildingError::ZeroLevel);
+        }
+        if floor.height <= 0.0 {
+            return Err(BuildingError::InvalidHeight(floor.name));
+        }
+        match self.floors.binary_search_by_key(&floor.level, |f| f.level) {
+            Ok(i) => self.floors[i] = floor,
+            Err(i) => self.floors.insert(i, floor),
+        }
+        Ok(())
+    }
+
+    /// 添加竖井
+    ///
+    /// # 返回值
+    /// 返回新竖井的标识
+    pub fn add_shaft(&mut self, name: &str, position: (f64, f64), levels: (i32, i32)) -> String {
+        let id = IdUtils::new_id();
+        self.shafts.push(Shaft {
+            id: id.clone(),
+            name: name.to_string(),
+            position,
+            levels: (levels.0.min(levels.1), levels.0.max(levels.1)),
+        });
+        id
+    }
+
+    /// 查找楼层
+    pub fn floor(&self, level: i32) -> Option<&Floor> {
+        self.floors.iter().find(|f| f.level == level)
+    }
+
+    /// 楼层名称，楼层未定义时按楼层号给出默认名称
+    pub fn floor_name(&self, level: i32) -> String {
+        self.floor(level).map_or_else(|| level_name(level), |f| f.name.clone())
+    }
+
+    /// 查找竖井
+    pub fn shaft(&self, id: &str) -> Option<&Shaft> {
+        self.shafts.iter().find(|s| s.id == id)
+    }
+
+    /// 两楼层楼面之间的竖向距离（m）
+    ///
+    /// # 返回值
+    /// 返回标高差的绝对值，任一楼层未定义时返回None
+    pub fn riser_length(&self, from: i32, to: i32) -> Option<f64> {
+        Some((self.floor(to)?.elevation - self.floor(from)?.elevation).abs())
+    }
+
+    /// 校验楼层和竖井定义
+    ///
+    /// # 返回值
+    /// 返回发现的问题，为空表示通过
+    pub fn validate(&self) -> Vec<BuildingError> {
+        let mut errors = Vec::new();
+        for (i, floor) in self.floors.iter().enumerate() {
+            if floor.level == 0 {
+                errors.push(BuildingError::ZeroLevel);
+            }
+            if floor.height <= 0.0 {
+                errors.push(BuildingError::InvalidHeight(floor.name.clone()));
+            }
+            if let Some(below) = i.checked_sub(1).map(|j| &self.floors[j]) {
+                if below.level == floor.level {
+                    errors.push(BuildingError::DuplicateFloor(floor.name.clone()));
+                } else if floor.elevation <= below.elevation {
+                    errors.push(BuildingError::ElevationOrder(floor.name.clone()));
+                }
+            }
+        }
+        for shaft in &self.shafts {
+            for level in [shaft.levels.0, shaft.levels.1] {
+                if self.floor(level).is_none() {
+                    errors.push(BuildingError::UnknownFloor(level_name(level)));
+                }
+            }
+        }
+        if let Some((level, _)) = self.source.filter(|(level, _)| self.floor(*level).is_none()) {
+            errors.push(BuildingError::UnknownFloor(level_name(level)));
+        }
+        errors
+    }
+
+    /// 校验配电箱的楼层和竖井归属
+    ///
+    /// # 返回值
+    /// * `Ok(())` - 归属有效（建筑未定义楼层时不校验）
+    /// * `Err(BuildingError)` - 楼层未定义、竖井不存在或竖井未贯通该楼层
+    pub fn check_box(&self, box_data: &BoxData) -> Result<(), BuildingError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        if self.floor(box_data.floor).is_none() {
+            return Err(BuildingError::UnknownFloor(level_name(box_data.floor)));
+        }
+        if let Some(id) = &box_data.shaft {
+            let shaft = self.shaft(id).ok_or_else(|| BuildingError::UnknownShaft(id.clone()))?;
+            if !shaft.serves(box_data.floor) {
+                return Err(BuildingError::ShaftNotOnFloor {
+                    shaft: shaft.name.clone(),
+                    floor: self.floor_name(box_data.floor),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_names_and_numbering() {
+        assert_eq!(level_name(-2), "B2");
+        assert_eq!(level_name(3), "3");
+        assert_eq!(parse_level("B1"), Some(-1));
+        assert_eq!(parse_level("b2F"), Some(-2));
+        assert_eq!(parse_level("3层"), Some(3));
+        assert_eq!(parse_level("-1"), Some(-1));
+        assert_eq!(parse_level("0"), None);
+        assert_eq!(parse_level("屋面"), None);
+        assert_eq!(next_box_name(["B1AL1", "B1AL3", "1AL7", "B1AP1"], -1, "AL"), "B1AL4");
+        assert_eq!(next_box_name([], 2, "AT"), "2AT1");
+    }
+
+    #[test]
+    fn test_uniform_building_riser_lengths() {
+        let building = Building::uniform(2, 3, 4.5);
+        let levels: Vec<i32> = building.floors.iter().map(|f| f.level).collect();
+        assert_eq!(levels, vec![-2, -1, 1, 2, 3]);
+        assert_eq!(building.floor_name(-1), "B1F");
+        assert_eq!(building.floor(1).unwrap().elevation, 0.0);
+        assert_eq!(building.riser_length(-2, 3), Some(18.0));
+        assert_eq!(building.riser_length(3, 1), Some(9.0));
+        assert_eq!(building.riser_length(1, 9), None);
+        assert!(building.validate().is_empty());
+    }
+
+    #[test]
+    fn test_box_assignment_checked_against_shafts() {
+        let mut building = Building::uniform(1, 4, 3.6);
+        let shaft = building.add_shaft("强电井1", (12.0, 30.0), (-1, 2));
+        let mut box_data = BoxData::new("B1AL1".to_string(), -1);
+        box_data.shaft = Some(shaft.clone());
+        assert_eq!(building.check_box(&box_data), Ok(()));
+
+        box_data.floor = 4;
+        assert_eq!(
+            building.check_box(&box_data),
+            Err(BuildingError::ShaftNotOnFloor { shaft: "强电井1".to_string(), floor: "4F".to_string() })
+        );
+        box_data.floor = 6;
+        assert_eq!(building.check_box(&box_data), Err(BuildingError::UnknownFloor("6".to_string())));
+        box_data.floor = 1;
+        box_data.shaft = Some("missing".to_string());
+        assert!(matches!(building.check_box(&box_data), Err(BuildingError::UnknownShaft(_))));
+        assert!(Building::default().check_box(&box_data).is_ok());
+    }
+}
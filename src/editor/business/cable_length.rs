@@ -0,0 +1,213 @@
+//! 电缆长度估算模块
+//!
+//! 本模块按建筑几何估算配电箱进线电缆长度：水平敷设长度 + 竖井内竖向长度 + 端头预留。
+//! 水平段沿桥架按直角路径计（两点坐标差的绝对值之和），由变配电所低压出线位置至竖井、
+//! 再由竖井至配电箱；未指定竖井或与出线位置同层时直接由出线位置至配电箱。竖向长度取
+//! 两楼层楼面标高差。手动输入的长度优先于估算值，长度均标注来源，供校审时区分核对。
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::editor::business::building::{level_name, Building, BuildingError};
+use crate::editor::business::BoxData;
+
+/// 电缆长度估算错误类型
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum CableLengthError {
+    /// 未设置变配电所位置
+    #[error("建筑未设置变配电所位置")]
+    NoSource,
+
+    /// 配电箱未指定平面位置
+    #[error("配电箱未指定平面位置")]
+    NoPosition,
+
+    /// 楼层或竖井无效
+    #[error(transparent)]
+    Building(#[from] BuildingError),
+}
+
+/// 长度来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LengthSource {
+    /// 手动输入
+    Entered,
+    /// 按建筑几何估算
+    Estimated,
+}
+
+impl LengthSource {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            LengthSource::Entered => "输入",
+            LengthSource::Estimated => "估算",
+        }
+    }
+}
+
+/// 电缆长度估算配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct CableLengthConfig {
+    /// 每端端头预留长度（m），含进箱、做头和检修余量
+    pub termination_allowance: f64,
+}
+
+impl Default for CableLengthConfig {
+    fn default() -> Self {
+        Self { termination_allowance: 1.5 }
+    }
+}
+
+/// 电缆长度估算结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CableLengthEstimate {
+    /// 水平敷设长度（m）
+    pub horizontal: f64,
+    /// 竖井内竖向长度（m）
+    pub riser: f64,
+    /// 两端端头预留长度（m）
+    pub allowance: f64,
+}
+
+impl CableLengthEstimate {
+    /// 估算总长度（m）
+    pub fn total(&self) -> f64 {
+        self.horizontal + self.riser + self.allowance
+    }
+}
+
+/// 两点间沿桥架直角路径的长度
+fn manhattan(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - b.0).abs() + (a.1 - b.1).abs()
+}
+
+/// 电缆长度估算器
+pub struct CableLengthEstimator;
+
+impl CableLengthEstimator {
+    /// 估算配电箱进线电缆长度
+    ///
+    /// # 参数
+    /// * `building` - 建筑模型（楼层标高、竖井位置及变配电所位置）
+    /// * `box_data` - 配电箱（楼层、竖井及平面位置）
+    /// * `config` - 估算配置
+    ///
+    /// # 返回值
+    /// * `Ok(CableLengthEstimate)` - 各段长度
+    /// * `Err(CableLengthError)` - 缺少变配电所或配电箱位置，或楼层、竖井未定义
+    pub fn estimate(
+        building: &Building,
+        box_data: &BoxData,
+        config: &CableLengthConfig,
+    ) -> Result<CableLengthEstimate, CableLengthError> {
+        let (source_level, source_position) = building.source.ok_or(CableLengthError::NoSource)?;
+        let position = box_data.position.ok_or(CableLengthError::NoPosition)?;
+        for level in [source_level, box_data.floor] {
+            if building.floor(level).is_none() {
+                return Err(BuildingError::UnknownFloor(level_name(level)).into());
+            }
+        }
+        let riser = building.riser_length(source_level, box_data.floor).unwrap_or(0.0);
+
+        let horizontal = match &box_data.shaft {
+            Some(id) if box_data.floor != source_level => {
+                let shaft = building.shaft(id).ok_or_else(|| BuildingError::UnknownShaft(id.clone()))?;
+                manhattan(source_position, shaft.position) + manhattan(shaft.position, position)
+            }
+            _ => manhattan(source_position, position),
+        };
+
+        Ok(CableLengthEstimate { horizontal, riser, allowance: 2.0 * config.termination_allowance })
+    }
+
+    /// 估算各配电箱进线长度并写入进线电缆
+    ///
+    /// 只估算指定了平面位置的配电箱；进线电缆长度取手动输入值，未输入时取估算值。
+    /// 建筑未定义楼层时不估算
+    ///
+    /// # 返回值
+    /// 返回无法估算的配电箱名称及原因
+    pub fn apply(building: &Building, boxes: &mut [BoxData], config: &CableLengthConfig) -> Vec<(String, CableLengthError)> {
+        let mut errors = Vec::new();
+        if building.is_empty() {
+            return errors;
+        }
+        for box_data in boxes.iter_mut() {
+            box_data.feeder_estimate = None;
+            if box_data.position.is_some() {
+                match Self::estimate(building, box_data, config) {
+                    Ok(estimate) => box_data.feeder_estimate = Some(estimate),
+                    Err(err) => errors.push((box_data.name.clone(), err)),
+                }
+            }
+            if let (Some((length, _)), Some(cable)) = (box_data.incoming_length(), box_data.incoming_cable.as_mut()) {
+                cable.length = length;
+            }
+        }
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_lib::data_types::CableInfo;
+
+    fn sample_building() -> (Building, String) {
+        let mut building = Building::uniform(1, 5, 4.0);
+        building.source = Some((-1, (0.0, 0.0)));
+        let shaft = building.add_shaft("强电井1", (20.0, 10.0), (-1, 5));
+        (building, shaft)
+    }
+
+    #[test]
+    fn test_estimate_through_shaft() {
+        let (building, shaft) = sample_building();
+        let mut box_data = BoxData::new("3AL1".to_string(), 3);
+        box_data.shaft = Some(shaft);
+        box_data.position = Some((26.0, 4.0));
+        let estimate = CableLengthEstimator::estimate(&building, &box_data, &CableLengthConfig::default()).unwrap();
+        // 水平：(0,0)→(20,10)为30m，(20,10)→(26,4)为12m；竖向：B1至3层为12m
+        assert_eq!(estimate.horizontal, 42.0);
+        assert_eq!(estimate.riser, 12.0);
+        assert_eq!(estimate.total(), 57.0);
+
+        // 与变配电所同层的配电箱不经竖井
+        box_data.floor = -1;
+        let estimate = CableLengthEstimator::estimate(&building, &box_data, &CableLengthConfig::default()).unwrap();
+        assert_eq!((estimate.horizontal, estimate.riser), (30.0, 0.0));
+
+        box_data.position = None;
+        assert_eq!(
+            CableLengthEstimator::estimate(&building, &box_data, &CableLengthConfig::default()),
+            Err(CableLengthError::NoPosition)
+        );
+    }
+
+    #[test]
+    fn test_entered_length_overrides_estimate() {
+        let (building, shaft) = sample_building();
+        let mut boxes = vec![BoxData::new("1AL1".to_string(), 1), BoxData::new("2AL1".to_string(), 2)];
+        for box_data in &mut boxes {
+            box_data.shaft = Some(shaft.clone());
+            box_data.position = Some((20.0, 10.0));
+            box_data.incoming_cable = Some(CableInfo::default());
+        }
+        boxes[1].feeder_length = Some(80.0);
+
+        let errors = CableLengthEstimator::apply(&building, &mut boxes, &CableLengthConfig::default());
+        assert!(errors.is_empty());
+        assert_eq!(boxes[0].incoming_length(), Some((37.0, LengthSource::Estimated)));
+        assert_eq!(boxes[0].incoming_cable.as_ref().unwrap().length, 37.0);
+        assert_eq!(boxes[1].incoming_length(), Some((80.0, LengthSource::Entered)));
+        assert_eq!(boxes[1].incoming_cable.as_ref().unwrap().length, 80.0);
+        assert!(boxes[1].feeder_estimate.is_some());
+
+        boxes[0].position = None;
+        let mut no_source = building.clone();
+        no_source.source = None;
+        let errors = CableLengthEstimator::apply(&no_source, &mut boxes, &CableLengthConfig::default());
+        assert_eq!(errors, vec![("2AL1".to_string(), CableLengthError::NoSource)]);
+        assert_eq!(boxes[0].incoming_length(), None);
+    }
+}
@@ -1,4 +1,4 @@
-/// 配电回路计算集成模块
+//! 配电回路计算集成模块
 
 use crate::core_lib::algorithm::current_calculation::CurrentCalculator;
 use crate::editor::business::circuit_parameters::{CircuitParameters, CircuitResult, VoltageType};
@@ -28,6 +28,7 @@ impl std::fmt::Display for CircuitCalculationError {
 impl std::error::Error for CircuitCalculationError {}
 
 /// 回路计算器
+#[derive(Default)]
 pub struct CircuitCalculator;
 
 impl CircuitCalculator {
@@ -53,20 +54,17 @@ impl CircuitCalculator {
         let voltage_type = parameters.voltage_type;
         let voltage = voltage_type.voltage_value();
         
-        // 创建电流计算器
-        let calculator = CurrentCalculator::new();
-        
         // 根据电压类型选择计算方法
         let ijs = match voltage_type {
             VoltageType::SinglePhase => {
-                calculator.single_phase_current_calculation(
+                CurrentCalculator::single_phase_current_calculation(
                     parameters.pe,
                     parameters.kx,
                     parameters.cos
                 )
             },
             VoltageType::ThreePhase => {
-                calculator.three_phase_current_calculation(
+                CurrentCalculator::three_phase_current_calculation(
                     parameters.pe,
                     parameters.kx,
                     parameters.cos
@@ -92,7 +90,7 @@ impl CircuitCalculator {
         circuits: &[CircuitParameters]
     ) -> Vec<Result<CircuitResult, CircuitCalculationError>> {
         circuits.iter()
-            .map(|params| Self::calculate_circuit_current(params))
+            .map(Self::calculate_circuit_current)
             .collect()
     }
     
@@ -106,14 +104,12 @@ impl CircuitCalculator {
         const DEFAULT_KX: f32 = 0.8;
         const DEFAULT_COS: f32 = 0.85;
         
-        let calculator = CurrentCalculator::new();
-        
         match voltage_type {
             VoltageType::SinglePhase => {
-                calculator.single_phase_current_calculation(pe, DEFAULT_KX, DEFAULT_COS)
+                CurrentCalculator::single_phase_current_calculation(pe, DEFAULT_KX, DEFAULT_COS)
             },
             VoltageType::ThreePhase => {
-                calculator.three_phase_current_calculation(pe, DEFAULT_KX, DEFAULT_COS)
+                CurrentCalculator::three_phase_current_calculation(pe, DEFAULT_KX, DEFAULT_COS)
             },
         }
     }
@@ -146,8 +142,8 @@ mod tests {
         
         let result = CircuitCalculator::calculate_circuit_current(&params).unwrap();
         
-        // 验证计算结果（1kW * 0.8 / 0.22kV / 0.85 ≈ 5.24A）
-        assert!(result.ijs > 5.2 && result.ijs < 5.3);
+        // 验证计算结果（1kW * 0.8 / 0.22kV / 0.85 ≈ 4.28A）
+        assert!(result.ijs > 4.2 && result.ijs < 4.3);
         assert_eq!(result.voltage, 0.22);
     }
     
@@ -164,8 +160,8 @@ mod tests {
         
         let result = CircuitCalculator::calculate_circuit_current(&params).unwrap();
         
-        // 验证计算结果（10kW * 0.8 / 0.38kV / 0.85 / √3 ≈ 17.86A）
-        assert!(result.ijs > 17.8 && result.ijs < 18.0);
+        // 验证计算结果（10kW * 0.8 / 0.38kV / 0.85 / √3 ≈ 14.30A）
+        assert!(result.ijs > 14.2 && result.ijs < 14.4);
         assert_eq!(result.voltage, 0.38);
     }
     
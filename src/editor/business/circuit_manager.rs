@@ -76,7 +76,7 @@ impl CircuitManager {
     /// # 返回值
     /// * `Ok(())` - 更新成功
     /// * `Err(DistributionBoxError)` - 更新失败，包含错误信息
-    pub fn update_circuit(circuits: &mut Vec<CircuitInfo>, circuit: CircuitInfo) -> Result<(), DistributionBoxError> {
+    pub fn update_circuit(circuits: &mut [CircuitInfo], circuit: CircuitInfo) -> Result<(), DistributionBoxError> {
         // 验证回路信息
         circuit.validate()?;
         
@@ -170,19 +170,18 @@ impl CircuitManager {
         }
         
         // 验证回路编号（如果已分配）
-        if circuit.number > 0 && circuit.number > 99 {
+        if circuit.number > 99 {
             return Err(DistributionBoxError::InvalidParameter(
                 format!("回路编号必须在1-99之间，当前值：{}", circuit.number)
             ));
         }
         
         // 验证相位（如果已分配）
-        if let Some(phase) = circuit.phase {
-            if phase != 'L' && phase != '1' && phase != '2' && phase != '3' {
-                return Err(DistributionBoxError::InvalidParameter(
-                    format!("无效的相位标识：{}，有效标识为 L, 1, 2, 3", phase)
-                ));
-            }
+        if let Some(phase) = circuit.phase
+            && phase != 'L' && phase != '1' && phase != '2' && phase != '3' {
+            return Err(DistributionBoxError::InvalidParameter(
+                format!("无效的相位标识：{}，有效标识为 L, 1, 2, 3", phase)
+            ));
         }
         
         Ok(())
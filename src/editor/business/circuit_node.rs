@@ -1,19 +1,14 @@
-/// 配电回路节点实现
-
-use std::time::Instant;
 use std::collections::HashMap;
 use std::borrow::Cow;
 use std::fmt;
-use rand;
-use uuid::Uuid;
 
 use egui::Color32;
-use slotmap::{SlotMap, SecondaryMap};
 
-use egui_node_graph::{Graph, NodeId, InputId, OutputId, AnyParameterId, NodeResponse};
+use egui_node_graph::{Graph, NodeId, NodeResponse};
 use egui_node_graph::traits::{NodeDataTrait, UserResponseTrait, NodeTemplateTrait};
 
-use crate::core_lib::data_types::{ElectricDataType, ElectricValueType, CircuitNodeProperties, CircuitType, CircuitPurpose};
+use crate::core_lib::data_types::{ElectricDataType, ElectricValueType, CircuitNodeProperties, CircuitType};
+use crate::core_lib::utils::IdUtils;
 use crate::editor::business::{CircuitParameters, CircuitResult, VoltageType};
 use crate::editor::business::circuit_calculator::CircuitCalculator;
 
@@ -50,7 +45,7 @@ pub struct CircuitNode {
 impl Default for CircuitNode {
     fn default() -> Self {
         Self {
-            id: format!("circuit_{:x}", rand::random::<u32>()),
+            id: IdUtils::new_id(),
             parameters: CircuitParameters::default(),
             properties: CircuitNodeProperties::default(),
             result: None,
@@ -79,14 +74,14 @@ impl CircuitNode {
     pub fn new_single_phase(name: &str, power: f64) -> Self {
         let params = CircuitParameters {
             name: name.to_string(),
-            pe: power,
+            pe: power as f32,
             kx: 1.0,
             cos: 0.85,
             voltage_type: VoltageType::SinglePhase,
         };
         
         let mut node = Self {
-            id: format!("circuit_{:x}", rand::random::<u32>()),
+            id: IdUtils::new_id(),
             parameters: params,
             properties: CircuitNodeProperties::default(),
             result: None,
@@ -107,14 +102,14 @@ impl CircuitNode {
     pub fn new_three_phase(name: &str, power: f64) -> Self {
         let params = CircuitParameters {
             name: name.to_string(),
-            pe: power,
+            pe: power as f32,
             kx: 0.8,
             cos: 0.85,
             voltage_type: VoltageType::ThreePhase,
         };
         
         let mut node = Self {
-            id: format!("circuit_{:x}", rand::random::<u32>()),
+            id: IdUtils::new_id(),
             parameters: params,
             properties: CircuitNodeProperties::default(),
             result: None,
@@ -134,9 +129,9 @@ impl CircuitNode {
     
     /// 将CircuitParameters同步到CircuitNodeProperties
     pub fn sync_parameters_to_properties(&mut self) {
-        self.properties.power = self.parameters.pe;
-        self.properties.demand_factor = self.parameters.kx;
-        self.properties.power_factor = self.parameters.cos;
+        self.properties.power = self.parameters.pe as f64;
+        self.properties.demand_factor = self.parameters.kx as f64;
+        self.properties.power_factor = self.parameters.cos as f64;
         
         // 根据电压类型设置回路类型
         if self.parameters.voltage_type == VoltageType::SinglePhase {
@@ -292,7 +287,7 @@ impl NodeDataTrait for CircuitNode {
         _graph: &Graph<Self, Self::DataType, Self::ValueType>,
         _user_state: &mut Self::UserState,
     ) -> Vec<NodeResponse<Self::Response, Self>> {
-        let mut responses = Vec::new();
+        let responses = Vec::new();
         
         ui.separator();
         
@@ -345,6 +340,14 @@ impl NodeDataTrait for CircuitNode {
             });
         }
         
+        // 显示保护配合校验记录
+        if !self.properties.coordination_log.is_empty() {
+            ui.label("保护配合校验:");
+            for line in &self.properties.coordination_log {
+                ui.label(egui::RichText::new(format!("- {}", line)).small());
+            }
+        }
+
         // 显示原有的计算结果
         if let Some(result) = &self.result {
             ui.label("详细数据:");
@@ -378,7 +381,7 @@ impl NodeDataTrait for CircuitNode {
         _graph: &Graph<Self, Self::DataType, Self::ValueType>,
         _user_state: &mut Self::UserState,
     ) -> Vec<NodeResponse<Self::Response, Self>> {
-        let mut responses = Vec::new();
+        let responses = Vec::new();
         
         ui.label(self.get_title());
         
@@ -405,15 +408,14 @@ impl NodeDataTrait for CircuitNode {
         _user_state: &mut Self::UserState,
         param_name: &str,
     ) -> Vec<NodeResponse<Self::Response, Self>> {
-        let mut responses = Vec::new();
+        let responses = Vec::new();
         
         ui.horizontal(|ui| {
             ui.label(param_name);
             
             // 如果是电流输出，显示当前计算值
-            if param_name == "计算电流" && self.result.is_some() {
-                ui.label(format!("({})
-", self.result.as_ref().unwrap().formatted_ijs()));
+            if param_name == "计算电流" && let Some(result) = &self.result {
+                ui.label(format!("({})", result.formatted_ijs()));
             } else if param_name == "回路数据" {
                 // 对于回路数据，可以显示功率信息
                 ui.label(format!("({:.2}kW)", self.parameters.pe));
@@ -457,7 +459,7 @@ impl CircuitNodeResponse {
         self,
         node_id: NodeId,
         graph: &mut Graph<CircuitNode, ElectricDataType, ElectricValueType>,
-        user_state: &mut EditorState,
+        _user_state: &mut EditorState,
     ) {
         match self {
             CircuitNodeResponse::ParameterUpdated(params) => {
@@ -484,7 +486,7 @@ pub struct CircuitNodeTemplate;
 impl CircuitNodeTemplate {
     /// 创建新的回路节点模板实例
     pub fn new() -> Self {
-        Self::default()
+        Self
     }
 }
 
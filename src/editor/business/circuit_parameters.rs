@@ -1,22 +1,20 @@
-/// 配电回路参数与结果数据结构定义
+//! 配电回路参数与结果数据结构定义
 
 use std::time::Instant;
 use serde::{Deserialize, Serialize};
 
+use crate::core_lib::utils::IdUtils;
+
 /// 电压类型枚举
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum VoltageType {
     /// 单相电压 (220V)
+    #[default]
     SinglePhase,
     /// 三相电压 (380V)
     ThreePhase,
 }
 
-impl Default for VoltageType {
-    fn default() -> Self {
-        VoltageType::SinglePhase
-    }
-}
 
 impl VoltageType {
     /// 获取电压值（单位：kV）
@@ -114,7 +112,8 @@ pub struct CircuitResult {
     pub ijs: f32,
     /// 电压值 (kV)
     pub voltage: f32,
-    /// 计算时间戳
+    /// 计算时间戳（仅用于运行期判断结果新旧，不参与序列化）
+    #[serde(skip, default = "Instant::now")]
     pub calculation_time: Instant,
 }
 
@@ -153,7 +152,7 @@ pub struct CircuitData {
 impl Default for CircuitData {
     fn default() -> Self {
         Self {
-            id: format!("circuit_{:x}", rand::random::<u32>()),
+            id: IdUtils::new_id(),
             parameters: CircuitParameters::default(),
             result: None,
         }
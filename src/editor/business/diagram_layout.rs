@@ -0,0 +1,252 @@
+//! 干线系统图分层布局模块
+//!
+//! 本模块按楼层分层布置干线系统图：楼层自下而上排列，母线、电源等不属于楼层的组件位于
+//! 最底部（变配电所）。同一竖向干线的组件在各楼层竖直对齐成列，列的左右顺序和底部电源的
+//! 位置按重心法交替调整，使电源到各列的连线尽量少交叉。
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::editor::business::main_system_node::SystemDiagram;
+
+/// 布局参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutConfig {
+    /// 相邻楼层（行）的竖向间距
+    pub floor_spacing: f64,
+    /// 同一行相邻组件的水平间距
+    pub column_spacing: f64,
+    /// 图纸边距
+    pub margin: f64,
+    /// 重心法排序的迭代次数
+    pub sweeps: usize,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            floor_spacing: 150.0,
+            column_spacing: 200.0,
+            margin: 100.0,
+            sweeps: 4,
+        }
+    }
+}
+
+/// 按配电箱名称推断所属竖向干线
+///
+/// 各层同一位置的配电箱通常以楼层号为前缀命名（如1AL1、2AL1、B1AL1），去掉楼层前缀后
+/// 相同的配电箱视为同一竖向干线
+///
+/// # 参数
+/// * `box_name` - 配电箱名称
+///
+/// # 返回值
+/// 竖向干线标识，名称不带楼层前缀时返回名称本身
+pub fn riser_key(box_name: &str) -> String {
+    let name = box_name.trim();
+    let rest = name.strip_prefix(['B', 'b', '-']).unwrap_or(name);
+    let stem = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+    if stem.len() < rest.len() && stem.starts_with(|c: char| c.is_alphabetic()) {
+        stem.to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// 干线系统图布局器
+pub struct DiagramLayout;
+
+impl DiagramLayout {
+    /// 按楼层分行、按竖向干线分列布置系统图组件
+    ///
+    /// # 参数
+    /// * `diagram` - 系统图，布局结果写入各组件的position
+    /// * `config` - 布局参数
+    pub fn layout(diagram: &mut SystemDiagram, config: &LayoutConfig) {
+        let count = diagram.components.len();
+        if count == 0 {
+            return;
+        }
+
+        // 分列：同一竖向干线成一列，未指定干线的楼层组件单独成列，其余组件置于底部
+        let mut columns: Vec<Vec<usize>> = Vec::new();
+        let mut riser_columns: BTreeMap<String, usize> = BTreeMap::new();
+        let mut column_of = vec![None; count];
+        let mut bottom = Vec::new();
+        let mut bottom_of = vec![None; count];
+        for (index, component) in diagram.components.iter().enumerate() {
+            let column = match (&component.riser, component.floor) {
+                (Some(riser), _) => *riser_columns.entry(riser.clone()).or_insert_with(|| {
+                    columns.push(Vec::new());
+                    columns.len() - 1
+                }),
+                (None, Some(_)) => {
+                    columns.push(Vec::new());
+                    columns.len() - 1
+                }
+                (None, None) => {
+                    bottom_of[index] = Some(bottom.len());
+                    bottom.push(index);
+                    continue;
+                }
+            };
+            columns[column].push(index);
+            column_of[index] = Some(column);
+        }
+
+        // 列与底部组件之间的连线（同一列多次连接只计一次）
+        let links: BTreeSet<(usize, usize)> = diagram
+            .connections
+            .iter()
+            .filter(|c| c.from < count && c.to < count)
+            .filter_map(|c| match (column_of[c.from], bottom_of[c.to], bottom_of[c.from], column_of[c.to]) {
+                (Some(column), Some(b), _, _) | (_, _, Some(b), Some(column)) => Some((column, b)),
+                _ => None,
+            })
+            .collect();
+
+        let mut column_order: Vec<usize> = (0..columns.len()).collect();
+        let mut bottom_order: Vec<usize> = (0..bottom.len()).collect();
+        for _ in 0..config.sweeps {
+            let column_rank = ranks(&column_order);
+            sort_by_barycenter(&mut bottom_order, |b| {
+                links.iter().filter(|l| l.1 == b).map(|l| column_rank[l.0]).collect()
+            });
+            let bottom_rank = ranks(&bottom_order);
+            sort_by_barycenter(&mut column_order, |c| {
+                links.iter().filter(|l| l.0 == c).map(|l| bottom_rank[l.1]).collect()
+            });
+        }
+
+        // 行：楼层自上而下由高到低，不属于楼层的干线组件（如母线槽）在楼层之下，底部组件在最下
+        let floors: BTreeSet<i32> = diagram.components.iter().filter_map(|c| c.floor).collect();
+        let riser_base = column_of.iter().zip(&diagram.components).any(|(c, d)| c.is_some() && d.floor.is_none());
+        let row_y = |row: usize| config.margin + row as f64 * config.floor_spacing;
+        let floor_row = |floor: Option<i32>| match floor {
+            Some(floor) => floors.len() - 1 - floors.iter().position(|&f| f == floor).unwrap_or(0),
+            None => floors.len(),
+        };
+        let bottom_row = floors.len() + riser_base as usize;
+
+        // 列宽取各行中该列组件数的最大值
+        let mut offset = 0usize;
+        let mut centers = vec![0.0; columns.len()];
+        for &column in &column_order {
+            let mut cells: BTreeMap<Option<i32>, usize> = BTreeMap::new();
+            for &index in &columns[column] {
+                let slot = cells.entry(diagram.components[index].floor).or_insert(0);
+                let x = config.margin + (offset + *slot) as f64 * config.column_spacing;
+                diagram.components[index].position = (x, row_y(floor_row(diagram.components[index].floor)));
+                *slot += 1;
+            }
+            let slots = cells.values().copied().max().unwrap_or(1);
+            centers[column] = config.margin + (offset as f64 + (slots - 1) as f64 / 2.0) * config.column_spacing;
+            offset += slots;
+        }
+
+        // 底部组件尽量位于所连各列的中间，彼此保持间距
+        let mut previous: Option<f64> = None;
+        for &b in &bottom_order {
+            let linked: Vec<f64> = links.iter().filter(|l| l.1 == b).map(|l| centers[l.0]).collect();
+            let desired = if linked.is_empty() {
+                previous.map_or(config.margin, |x| x + config.column_spacing)
+            } else {
+                linked.iter().sum::<f64>() / linked.len() as f64
+            };
+            let x = previous.map_or(desired, |p| desired.max(p + config.column_spacing));
+            diagram.components[bottom[b]].position = (x, row_y(bottom_row));
+            previous = Some(x);
+        }
+    }
+}
+
+/// 各项在排列中的位置
+fn ranks(order: &[usize]) -> Vec<f64> {
+    let mut ranks = vec![0.0; order.len()];
+    for (position, &item) in order.iter().enumerate() {
+        ranks[item] = position as f64;
+    }
+    ranks
+}
+
+/// 按相邻项位置的平均值（重心）稳定排序，无相邻项的保持原位置
+fn sort_by_barycenter(order: &mut [usize], neighbors: impl Fn(usize) -> Vec<f64>) {
+    let keys: BTreeMap<usize, f64> = order
+        .iter()
+        .enumerate()
+        .map(|(position, &item)| {
+            let ranks = neighbors(item);
+            let key = if ranks.is_empty() { position as f64 } else { ranks.iter().sum::<f64>() / ranks.len() as f64 };
+            (item, key)
+        })
+        .collect();
+    order.sort_by(|a, b| keys[a].total_cmp(&keys[b]));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::business::main_system_node::ComponentType;
+
+    fn add_box(diagram: &mut SystemDiagram, name: &str, floor: i32) -> usize {
+        let id = diagram.add_component(ComponentType::DistributionBox, name.to_string());
+        diagram.place_component(id, Some(floor), Some(riser_key(name)));
+        id
+    }
+
+    #[test]
+    fn test_riser_key() {
+        assert_eq!(riser_key("1AL1"), "AL1");
+        assert_eq!(riser_key("12AL1"), "AL1");
+        assert_eq!(riser_key("B1AP2"), "AP2");
+        assert_eq!(riser_key("AL1"), "AL1");
+        assert_eq!(riser_key("101"), "101");
+    }
+
+    #[test]
+    fn test_floors_stacked_and_risers_aligned() {
+        let mut diagram = SystemDiagram::new("配电干线图".to_string());
+        let busbar = diagram.add_component(ComponentType::Busbar, "主母线".to_string());
+        let al1 = add_box(&mut diagram, "1AL1", 1);
+        let ap1 = add_box(&mut diagram, "1AP1", 1);
+        let al2 = add_box(&mut diagram, "2AL1", 2);
+        let b1 = add_box(&mut diagram, "B1AL1", -1);
+        for target in [al1, ap1, al2, b1] {
+            diagram.add_connection(busbar, target);
+        }
+        DiagramLayout::layout(&mut diagram, &LayoutConfig::default());
+
+        let position = |id: usize| diagram.components[id].position;
+        assert!(position(al2).1 < position(al1).1);
+        assert!(position(al1).1 < position(b1).1);
+        assert!(position(b1).1 < position(busbar).1);
+        assert_eq!(position(al1).0, position(al2).0);
+        assert_eq!(position(b1).0, position(al1).0);
+        assert_eq!(position(al1).1, position(ap1).1);
+        assert_ne!(position(al1).0, position(ap1).0);
+        // 母线位于两列之间
+        assert_eq!(position(busbar).0, (position(al1).0 + position(ap1).0) / 2.0);
+    }
+
+    #[test]
+    fn test_dual_power_boxes_grouped_near_backup() {
+        let mut diagram = SystemDiagram::new("配电干线图".to_string());
+        let busbar = diagram.add_component(ComponentType::Busbar, "主母线".to_string());
+        let backup = diagram.add_component(ComponentType::PowerSource, "备用电源".to_string());
+        let boxes: Vec<usize> = ["1AL1", "1AT1", "1AL2", "1AT2"].iter().map(|name| add_box(&mut diagram, name, 1)).collect();
+        for (i, &target) in boxes.iter().enumerate() {
+            diagram.add_connection(busbar, target);
+            if i % 2 == 1 {
+                diagram.add_connection(backup, target);
+            }
+        }
+        DiagramLayout::layout(&mut diagram, &LayoutConfig::default());
+
+        let x = |id: usize| diagram.components[id].position.0;
+        assert!(x(boxes[0]) < x(boxes[2]));
+        assert!(x(boxes[2]) < x(boxes[1]));
+        assert!(x(boxes[1]) < x(boxes[3]));
+        assert!(x(busbar) < x(backup));
+        assert_eq!(x(backup), (x(boxes[1]) + x(boxes[3])) / 2.0);
+    }
+}
@@ -0,0 +1,400 @@
+//! 干线系统图布线模块
+//!
+//! 本模块为布局后的干线系统图生成正交连线：连线自下方的供电设备向上引出，沿设备上方的
+//! 水平通道走到目标设备靠近供电设备一侧的竖向干线，再沿干线上行至目标楼层水平接入。同一供电设备
+//! 送往同一列的馈线共用一根竖向干线，分支处生成连接点；不同供电设备使用错开的通道和
+//! 干线位置，通道次序按交叉数最少选取。布线结果保存在连线上，界面和导出按同一几何绘制。
+
+use std::collections::BTreeSet;
+
+use crate::editor::business::main_system_node::{ComponentType, DiagramComponent, SystemDiagram};
+
+/// 浮点坐标比较容差
+const EPSILON: f64 = 1e-6;
+
+/// 参与交叉数比较的最大供电设备数，超过时按水平位置分配通道
+const MAX_PERMUTED_FEEDERS: usize = 6;
+
+/// 布线参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoutingConfig {
+    /// 设备框尺寸（宽，高），与导出的设备框一致
+    pub component_size: (f64, f64),
+    /// 连线与设备的最小间距
+    pub clearance: f64,
+    /// 相邻通道（干线）的间距
+    pub lane_spacing: f64,
+}
+
+impl Default for RoutingConfig {
+    fn default() -> Self {
+        Self {
+            component_size: (160.0, 80.0),
+            clearance: 10.0,
+            lane_spacing: 8.0,
+        }
+    }
+}
+
+/// 正交线段
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Segment {
+    start: (f64, f64),
+    end: (f64, f64),
+}
+
+impl Segment {
+    fn is_horizontal(&self) -> bool {
+        (self.start.1 - self.end.1).abs() < EPSILON
+    }
+
+    fn x_range(&self) -> (f64, f64) {
+        (self.start.0.min(self.end.0), self.start.0.max(self.end.0))
+    }
+
+    fn y_range(&self) -> (f64, f64) {
+        (self.start.1.min(self.end.1), self.start.1.max(self.end.1))
+    }
+
+    /// 两线段是否在各自内部正交相交
+    fn crosses(&self, other: &Segment) -> bool {
+        let (h, v) = match (self.is_horizontal(), other.is_horizontal()) {
+            (true, false) => (self, other),
+            (false, true) => (other, self),
+            _ => return false,
+        };
+        let (x0, x1) = h.x_range();
+        let (y0, y1) = v.y_range();
+        let (x, y) = (v.start.0, h.start.1);
+        x0 + EPSILON < x && x < x1 - EPSILON && y0 + EPSILON < y && y < y1 - EPSILON
+    }
+}
+
+fn segments(route: &[(f64, f64)]) -> impl Iterator<Item = Segment> + '_ {
+    route.windows(2).map(|w| Segment { start: w[0], end: w[1] })
+}
+
+/// 干线系统图布线器
+pub struct DiagramRouter;
+
+impl DiagramRouter {
+    /// 为系统图的全部连线布线，并生成干线分支连接点
+    ///
+    /// # 参数
+    /// * `diagram` - 已完成布局的系统图，布线结果写入各连线的route及系统图的junctions
+    /// * `config` - 布线参数
+    ///
+    /// # 返回值
+    /// 返回布线后的连线交叉数
+    pub fn route(diagram: &mut SystemDiagram, config: &RoutingConfig) -> usize {
+        let mut feeders: Vec<usize> = diagram.connections.iter().map(|c| Self::feeder(diagram, c.from, c.to)).collect();
+        feeders.sort_by(|a, b| diagram.components[*a].position.0.total_cmp(&diagram.components[*b].position.0));
+        feeders.dedup();
+
+        // 通道次序：供电设备较少时逐一比较各种次序，取交叉数最少者
+        let mut lanes = feeders.clone();
+        let mut best = (usize::MAX, Vec::new());
+        loop {
+            let routes = Self::routes(diagram, config, &lanes);
+            let crossings = Self::count_crossings(diagram, &routes);
+            if crossings < best.0 {
+                best = (crossings, routes);
+            }
+            if crossings == 0 || feeders.len() > MAX_PERMUTED_FEEDERS || !next_permutation(&mut lanes) {
+                break;
+            }
+        }
+
+        let (crossings, routes) = best;
+        for (connection, route) in diagram.connections.iter_mut().zip(routes) {
+            connection.route = route;
+        }
+        diagram.junctions = Self::junctions(diagram);
+        crossings
+    }
+
+    /// 按已保存的布线计算连线交叉数（同一供电设备的馈线分支不计）
+    pub fn crossing_count(diagram: &SystemDiagram) -> usize {
+        let routes: Vec<Vec<(f64, f64)>> = diagram.connections.iter().map(|c| c.route.clone()).collect();
+        Self::count_crossings(diagram, &routes)
+    }
+
+    /// 连线的供电端：位置较低的一端（同一行时为from）
+    fn feeder(diagram: &SystemDiagram, from: usize, to: usize) -> usize {
+        match (diagram.components.get(from), diagram.components.get(to)) {
+            (Some(a), Some(b)) if b.position.1 > a.position.1 + EPSILON => to,
+            _ => from,
+        }
+    }
+
+    fn routes(diagram: &SystemDiagram, config: &RoutingConfig, lanes: &[usize]) -> Vec<Vec<(f64, f64)>> {
+        diagram
+            .connections
+            .iter()
+            .map(|connection| {
+                let (Some(from), Some(to)) = (diagram.components.get(connection.from), diagram.components.get(connection.to)) else {
+                    return Vec::new();
+                };
+                let feeder = Self::feeder(diagram, connection.from, connection.to);
+                let lane = lanes.iter().position(|&f| f == feeder).unwrap_or(0) as f64;
+                let mut route = if feeder == connection.from {
+                    Self::route_between(diagram, config, from, to, lane)
+                } else {
+                    Self::route_between(diagram, config, to, from, lane)
+                };
+                if feeder != connection.from {
+                    route.reverse();
+                }
+                route
+            })
+            .collect()
+    }
+
+    /// 自供电设备至受电设备的折线
+    fn route_between(
+        diagram: &SystemDiagram,
+        config: &RoutingConfig,
+        source: &DiagramComponent,
+        target: &DiagramComponent,
+        lane: f64,
+    ) -> Vec<(f64, f64)> {
+        let half_width = config.component_size.0 / 2.0;
+        let (sx, sy) = source.position;
+        let (tx, ty) = target.position;
+        let offset = config.clearance + lane * config.lane_spacing;
+        let excluded = [source.id, target.id];
+
+        // 同一行：水平直连，中间有设备时从下方绕行
+        if (sy - ty).abs() < EPSILON {
+            let dir = if tx >= sx { 1.0 } else { -1.0 };
+            let (x0, x1) = (sx + dir * half_width, tx - dir * half_width);
+            if !Self::blocked(diagram, config, &excluded, Segment { start: (x0, sy), end: (x1, ty) }) {
+                return vec![(x0, sy), (x1, ty)];
+            }
+            let y = sy + Self::half_height(config, source).max(Self::half_height(config, target)) + offset;
+            return simplify(vec![
+                (sx, sy + Self::half_height(config, source)),
+                (sx, y),
+                (tx, y),
+                (tx, ty + Self::half_height(config, target)),
+            ]);
+        }
+
+        let start = (sx, sy - Self::half_height(config, source));
+        let channel = start.1 - offset;
+        let end = (tx, ty + Self::half_height(config, target));
+
+        // 正上方、中间无设备且该供电设备在这一列没有其他馈线时竖直直连
+        let direct = Segment { start, end };
+        let column_targets = diagram
+            .connections
+            .iter()
+            .filter(|c| c.from == source.id || c.to == source.id)
+            .map(|c| if c.from == source.id { c.to } else { c.from })
+            .filter(|&other| diagram.components.get(other).is_some_and(|c| (c.position.0 - tx).abs() < EPSILON))
+            .count();
+        if (sx - tx).abs() < EPSILON && column_targets == 1 && !Self::blocked(diagram, config, &excluded, direct) {
+            return vec![start, end];
+        }
+
+        // 经通道至目标靠近供电设备一侧的干线（正下方时取左侧），上行后水平接入；
+        // 接入段被同行设备挡住时改为从下方接入
+        let side = if sx > tx + EPSILON { 1.0 } else { -1.0 };
+        let riser = tx + side * (half_width + offset);
+        let tap = Segment { start: (riser, ty), end: (tx + side * half_width, ty) };
+        if !Self::blocked(diagram, config, &excluded, tap) {
+            simplify(vec![start, (sx, channel), (riser, channel), (riser, ty), (tx + side * half_width, ty)])
+        } else {
+            let below = end.1 + offset;
+            simplify(vec![start, (sx, channel), (riser, channel), (riser, below), (tx, below), end])
+        }
+    }
+
+    /// 母线、母线槽绘为水平线，没有高度
+    fn half_height(config: &RoutingConfig, component: &DiagramComponent) -> f64 {
+        match component.component_type {
+            ComponentType::Busbar | ComponentType::Busway => 0.0,
+            _ => config.component_size.1 / 2.0,
+        }
+    }
+
+    /// 线段是否穿过其他设备
+    fn blocked(diagram: &SystemDiagram, config: &RoutingConfig, excluded: &[usize], segment: Segment) -> bool {
+        let half_width = config.component_size.0 / 2.0;
+        let (x0, x1) = segment.x_range();
+        let (y0, y1) = segment.y_range();
+        diagram.components.iter().filter(|c| !excluded.contains(&c.id)).any(|c| {
+            let half_height = Self::half_height(config, c);
+            let (cx, cy) = c.position;
+            x0 < cx + half_width && x1 > cx - half_width && y0 <= cy + half_height && y1 >= cy - half_height
+        })
+    }
+
+    fn count_crossings(diagram: &SystemDiagram, routes: &[Vec<(f64, f64)>]) -> usize {
+        let feeders: Vec<usize> = diagram.connections.iter().map(|c| Self::feeder(diagram, c.from, c.to)).collect();
+        let mut count = 0;
+        for i in 0..routes.len() {
+            for j in i + 1..routes.len() {
+                if feeders[i] == feeders[j] {
+                    continue;
+                }
+                count += segments(&routes[i])
+                    .map(|a| segments(&routes[j]).filter(|b| a.crosses(b)).count())
+                    .sum::<usize>();
+            }
+        }
+        count
+    }
+
+    /// 同一供电设备的馈线在三个及以上方向交汇的点
+    fn junctions(diagram: &SystemDiagram) -> Vec<(f64, f64)> {
+        let key = |p: (f64, f64)| ((p.0 * 1000.0).round() as i64, (p.1 * 1000.0).round() as i64);
+        let sign = |d: f64| if d.abs() < EPSILON { 0 } else if d > 0.0 { 1 } else { -1 };
+        let mut junctions = BTreeSet::new();
+        let feeders: BTreeSet<usize> = diagram.connections.iter().map(|c| Self::feeder(diagram, c.from, c.to)).collect();
+        for feeder in feeders {
+            let group: Vec<Segment> = diagram
+                .connections
+                .iter()
+                .filter(|c| Self::feeder(diagram, c.from, c.to) == feeder)
+                .flat_map(|c| segments(&c.route).collect::<Vec<_>>())
+                .collect();
+            for point in group.iter().flat_map(|s| [s.start, s.end]) {
+                let mut directions = BTreeSet::new();
+                for segment in &group {
+                    for (from, to) in [(segment.start, segment.end), (segment.end, segment.start)] {
+                        let on_segment = key(from) == key(point)
+                            || (segment.is_horizontal() && (point.1 - from.1).abs() < EPSILON && {
+                                let (x0, x1) = segment.x_range();
+                                x0 + EPSILON < point.0 && point.0 < x1 - EPSILON
+                            })
+                            || (!segment.is_horizontal() && (point.0 - from.0).abs() < EPSILON && {
+                                let (y0, y1) = segment.y_range();
+                                y0 + EPSILON < point.1 && point.1 < y1 - EPSILON
+                            });
+                        if on_segment && key(to) != key(point) {
+                            directions.insert((sign(to.0 - point.0), sign(to.1 - point.1)));
+                        }
+                    }
+                }
+                if directions.len() >= 3 {
+                    junctions.insert(key(point));
+                }
+            }
+        }
+        junctions.into_iter().map(|(x, y)| (x as f64 / 1000.0, y as f64 / 1000.0)).collect()
+    }
+}
+
+/// 去掉重复点和共线的中间点
+fn simplify(points: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    let mut result: Vec<(f64, f64)> = Vec::new();
+    for point in points {
+        if result.last().is_some_and(|last| (last.0 - point.0).abs() < EPSILON && (last.1 - point.1).abs() < EPSILON) {
+            continue;
+        }
+        if result.len() >= 2 {
+            let (a, b) = (result[result.len() - 2], result[result.len() - 1]);
+            let collinear = ((a.0 - b.0).abs() < EPSILON && (b.0 - point.0).abs() < EPSILON)
+                || ((a.1 - b.1).abs() < EPSILON && (b.1 - point.1).abs() < EPSILON);
+            if collinear {
+                result.pop();
+            }
+        }
+        result.push(point);
+    }
+    result
+}
+
+/// 字典序的下一个排列，已是最后一个排列时返回false
+fn next_permutation(items: &mut [usize]) -> bool {
+    let Some(i) = (1..items.len()).rev().find(|&i| items[i - 1] < items[i]) else {
+        return false;
+    };
+    let j = (i..items.len()).rev().find(|&j| items[j] > items[i - 1]).unwrap_or(i);
+    items.swap(i - 1, j);
+    items[i..].reverse();
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::business::diagram_layout::{riser_key, DiagramLayout, LayoutConfig};
+
+    fn add_box(diagram: &mut SystemDiagram, name: &str, floor: i32) -> usize {
+        let id = diagram.add_component(ComponentType::DistributionBox, name.to_string());
+        diagram.place_component(id, Some(floor), Some(riser_key(name)));
+        id
+    }
+
+    fn is_orthogonal(route: &[(f64, f64)]) -> bool {
+        route.len() >= 2 && segments(route).all(|s| (s.start.0 - s.end.0).abs() < EPSILON || s.is_horizontal())
+    }
+
+    #[test]
+    fn test_riser_bundles_feeders_with_junctions() {
+        let mut diagram = SystemDiagram::new("配电干线图".to_string());
+        let busbar = diagram.add_component(ComponentType::Busbar, "主母线".to_string());
+        let boxes: Vec<usize> = (1..=3).map(|floor| add_box(&mut diagram, &format!("{}AL1", floor), floor)).collect();
+        for &target in &boxes {
+            diagram.add_connection(busbar, target);
+        }
+        DiagramLayout::layout(&mut diagram, &LayoutConfig::default());
+        assert_eq!(DiagramRouter::route(&mut diagram, &RoutingConfig::default()), 0);
+
+        let config = RoutingConfig::default();
+        for connection in &diagram.connections {
+            assert!(is_orthogonal(&connection.route));
+            let target = &diagram.components[connection.to];
+            assert_eq!(connection.route[0].1, diagram.components[busbar].position.1);
+            assert_eq!(*connection.route.last().unwrap(), (target.position.0 - config.component_size.0 / 2.0, target.position.1));
+            let excluded = [connection.from, connection.to];
+            assert!(segments(&connection.route).all(|s| !DiagramRouter::blocked(&diagram, &config, &excluded, s)));
+        }
+        // 三条馈线共用一根竖向干线，1、2层的分支处各有一个连接点
+        let riser_x = diagram.connections[0].route[2].0;
+        assert!(diagram.connections.iter().all(|c| c.route[2].0 == riser_x));
+        let mut junction_floors: Vec<f64> = diagram.junctions.iter().filter(|j| j.0 == riser_x).map(|j| j.1).collect();
+        junction_floors.sort_by(f64::total_cmp);
+        let floor_y = |i: usize| diagram.components[boxes[i]].position.1;
+        assert_eq!(junction_floors, vec![floor_y(1), floor_y(0)]);
+    }
+
+    #[test]
+    fn test_dual_power_routes_do_not_cross() {
+        let mut diagram = SystemDiagram::new("配电干线图".to_string());
+        let busbar = diagram.add_component(ComponentType::Busbar, "主母线".to_string());
+        let backup = diagram.add_component(ComponentType::PowerSource, "备用电源".to_string());
+        for (i, name) in ["1AL1", "1AT1", "2AL1", "2AT1"].iter().enumerate() {
+            let target = add_box(&mut diagram, name, 1 + i as i32 / 2);
+            diagram.add_connection(busbar, target);
+            if name.contains("AT") {
+                diagram.add_connection(backup, target);
+            }
+        }
+        DiagramLayout::layout(&mut diagram, &LayoutConfig::default());
+        let crossings = DiagramRouter::route(&mut diagram, &RoutingConfig::default());
+        assert_eq!(crossings, DiagramRouter::crossing_count(&diagram));
+        assert_eq!(crossings, 0);
+        assert!(diagram.connections.iter().all(|c| is_orthogonal(&c.route)));
+        // 主母线与备用电源的通道错开，不共线
+        let channel = |source: usize| diagram.connections.iter().find(|c| c.from == source).unwrap().route[1].1;
+        assert_ne!(channel(busbar), channel(backup));
+    }
+
+    #[test]
+    fn test_crossing_count() {
+        let mut diagram = SystemDiagram::new("交叉".to_string());
+        let a = diagram.add_component(ComponentType::PowerSource, "A".to_string());
+        let b = diagram.add_component(ComponentType::PowerSource, "B".to_string());
+        let c = diagram.add_component(ComponentType::DistributionBox, "C".to_string());
+        diagram.add_connection(a, c);
+        diagram.add_connection(b, c);
+        diagram.connections[0].route = vec![(0.0, 0.0), (100.0, 0.0)];
+        diagram.connections[1].route = vec![(50.0, -50.0), (50.0, 50.0)];
+        assert_eq!(DiagramRouter::crossing_count(&diagram), 1);
+        diagram.connections[1].route = vec![(100.0, -50.0), (100.0, 50.0)];
+        assert_eq!(DiagramRouter::crossing_count(&diagram), 0);
+    }
+}
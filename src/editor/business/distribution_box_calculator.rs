@@ -1,7 +1,12 @@
 //! 配电箱计算模块
 //! 
-//! 本模块提供配电箱相关的电气计算功能，包括总功率计算、总电流计算、进线保护设备选型以及三相平衡算法。
+//! 本模块提供配电箱相关的电气计算功能，包括总功率计算、总电流计算、进线保护设备选型、线缆保护配合校验、穿管管径选择以及三相平衡算法。
 
+use crate::core_lib::algorithm::conduit_sizing::{ConductorSpec, ConduitSizing};
+use crate::core_lib::algorithm::protection_coordination::{
+    ConductorInsulation, CoordinationInput, FaultWithstand, ProtectionCoordinator, STANDARD_SECTIONS,
+};
+use crate::core_lib::data_types::{Breaker, BreakerType, CableInfo, ElectricComponent, FrameCurrent, LayingMethod, Pole, SettingValue};
 use crate::editor::business::{CircuitInfo, DistributionBoxError};
 
 /// 配电箱计算器
@@ -20,6 +25,37 @@ impl DistributionBoxCalculator {
     pub fn calculate_total_power(circuits: &[CircuitInfo]) -> f64 {
         circuits.iter().map(|c| c.power).sum()
     }
+
+    /// 计算总计算功率
+    ///
+    /// # 参数
+    /// * `circuits` - 回路信息集合
+    ///
+    /// # 返回值
+    /// 返回各回路计算功率Pjs = Kx·Pe的总和（kW）
+    pub fn calculate_demand_power(circuits: &[CircuitInfo]) -> f64 {
+        circuits.iter().map(|c| c.calculated_power()).sum()
+    }
+
+    /// 计算综合功率因数
+    ///
+    /// 按各回路计算功率的有功、无功合成：cosφ = ΣPjs / √(ΣPjs² + ΣQjs²)，其中Qjs = Pjs·tanφ
+    ///
+    /// # 参数
+    /// * `circuits` - 回路信息集合
+    ///
+    /// # 返回值
+    /// 返回综合功率因数，没有有功负荷时返回None
+    pub fn combined_power_factor(circuits: &[CircuitInfo]) -> Option<f64> {
+        let active = Self::calculate_demand_power(circuits);
+        let reactive: f64 = circuits
+            .iter()
+            .filter(|c| c.power_factor > 0.0 && c.power_factor <= 1.0)
+            .map(|c| c.calculated_power() * c.power_factor.acos().tan())
+            .sum();
+        let apparent = active.hypot(reactive);
+        (apparent > 0.0).then(|| active / apparent)
+    }
     
     /// 计算总电流
     /// 
@@ -65,7 +101,8 @@ impl DistributionBoxCalculator {
     /// 计算进线保护设备电流整定值
     /// 
     /// 基于总电流的1.2倍选择标准电流等级
-    /// 标准电流等级序列：6, 10, 16, 20, 25, 32, 40, 50, 63, 80, 100, 125, 160, 200, 250, 315, 400, 500, 630
+    /// 标准电流等级序列：6, 10, 16, 20, 25, 32, 40, 50, 63, 80, 100, 125, 160, 200, 250, 315, 400, 500, 630,
+    /// 800, 1000, 1250, 1600, 2000, 2500, 3200
     /// 
     /// # 参数
     /// * `total_current` - 总电流（A）
@@ -94,7 +131,8 @@ impl DistributionBoxCalculator {
         // 标准电流等级序列
         let standard_currents = [
             6.0, 10.0, 16.0, 20.0, 25.0, 32.0, 40.0, 50.0, 63.0,
-            80.0, 100.0, 125.0, 160.0, 200.0, 250.0, 315.0, 400.0, 500.0, 630.0
+            80.0, 100.0, 125.0, 160.0, 200.0, 250.0, 315.0, 400.0, 500.0, 630.0,
+            800.0, 1000.0, 1250.0, 1600.0, 2000.0, 2500.0, 3200.0
         ];
         
         // 查找第一个大于等于所需电流的标准等级
@@ -107,7 +145,264 @@ impl DistributionBoxCalculator {
         // 如果超出最大标准等级，返回最大等级
         Ok(standard_currents[standard_currents.len() - 1])
     }
-    
+
+    /// 选择进线电缆并校验保护配合
+    ///
+    /// 按载流量初选交联电缆截面，再校验 Ib ≤ In ≤ Iz、I2 ≤ 1.45Iz 及短路热稳定 k²S² ≥ I²t，
+    /// 不满足时自动加大截面。消防负荷配电箱采用NH-YJV耐火电缆。
+    ///
+    /// # 参数
+    /// * `total_current` - 总电流，即设计电流Ib（A），谐波含量较高时取中性线电流
+    /// * `incoming_current` - 进线保护设备电流整定值In（A）
+    /// * `derating_factor` - 载流量降低系数（四芯电缆谐波降低系数）
+    /// * `fire_load` - 是否为消防负荷配电箱
+    /// * `fault` - 短路热稳定校验参数
+    ///
+    /// # 返回值
+    /// * `Ok((CableInfo, Vec<String>))` - 选定的进线电缆及校验记录
+    /// * `Err(DistributionBoxError)` - 设计电流大于进线保护设备整定电流，或最大截面仍不满足要求
+    pub fn select_incoming_cable(
+        total_current: f64,
+        incoming_current: f64,
+        derating_factor: f64,
+        fire_load: bool,
+        fault: &FaultWithstand,
+    ) -> Result<(CableInfo, Vec<String>), DistributionBoxError> {
+        let insulation = ConductorInsulation::Xlpe;
+        let initial_section = STANDARD_SECTIONS
+            .iter()
+            .copied()
+            .find(|&s| ProtectionCoordinator::ampacity(s, insulation).unwrap_or(0.0) * derating_factor >= total_current)
+            .unwrap_or(STANDARD_SECTIONS[STANDARD_SECTIONS.len() - 1]);
+
+        let input = CoordinationInput {
+            design_current: total_current,
+            rated_current: incoming_current,
+            tripping_factor: if incoming_current <= 63.0 { 1.45 } else { 1.30 },
+            section: initial_section,
+            insulation,
+            derating_factor,
+            fault: Some(*fault),
+        };
+        let result = ProtectionCoordinator::coordinate(&input)
+            .map_err(|e| DistributionBoxError::CalculationError(e.to_string()))?;
+        if !result.design_current_ok {
+            return Err(DistributionBoxError::CalculationError(format!(
+                "设计电流Ib={:.1}A大于进线保护设备整定电流In={:.0}A，应加大保护电器规格",
+                total_current, incoming_current
+            )));
+        }
+
+        let cable = CableInfo {
+            model: if fire_load { "NH-YJV".to_string() } else { "YJV".to_string() },
+            voltage_level: 1.0,
+            size: format!("{}mm²", result.section),
+            is_three_phase: true,
+            ..Default::default()
+        };
+        Ok((cable, result.log))
+    }
+
+    /// 计算三相进线电缆的电压损失
+    ///
+    /// 按 ΔU% = √3·Ib·L·(r·cosφ + x·sinφ)/Un×100 计算，其中r取铜导体工作温度下的电阻，
+    /// x取低压电缆单位长度电抗0.08mΩ/m
+    ///
+    /// # 参数
+    /// * `current` - 设计电流Ib（A）
+    /// * `section` - 导体截面（mm²）
+    /// * `length` - 电缆长度（m）
+    /// * `power_factor` - 功率因数
+    ///
+    /// # 返回值
+    /// 返回电压损失百分比（%）
+    pub fn feeder_voltage_drop(current: f64, section: f64, length: f64, power_factor: f64) -> f64 {
+        // 铜导体70℃时的电阻率（Ω·mm²/m）
+        const COPPER_RESISTIVITY: f64 = 0.0219;
+        // 低压电缆单位长度电抗（Ω/m）
+        const REACTANCE_PER_METER: f64 = 0.08e-3;
+        const VOLTAGE: f64 = 380.0;
+
+        if section <= 0.0 {
+            return 0.0;
+        }
+        let resistance = COPPER_RESISTIVITY / section;
+        let sin_phi = (1.0 - power_factor * power_factor).max(0.0).sqrt();
+        3f64.sqrt() * current * length * (resistance * power_factor + REACTANCE_PER_METER * sin_phi) / VOLTAGE * 100.0
+    }
+
+    /// 按整定电流选择断路器
+    ///
+    /// 整定电流取不小于`current`的标准值，63A及以下选微型断路器、以上选塑壳断路器
+    ///
+    /// # 参数
+    /// * `current` - 要求的最小整定电流（A）
+    /// * `pole` - 极数
+    ///
+    /// # 返回值
+    /// 超出断路器整定范围时返回None
+    pub fn select_breaker(current: f64, pole: Pole) -> Option<Breaker> {
+        let setting = SettingValue::from_current(current)?;
+        let frame = FrameCurrent::for_setting(setting).unwrap_or(FrameCurrent::A800);
+        let miniature = setting.to_f64() <= 63.0;
+        Some(Breaker {
+            breaker_type: Some(if miniature { BreakerType::MCB } else { BreakerType::MCCB }),
+            model: if miniature { "iC65N".to_string() } else { format!("NSX{}N", frame.to_str()) },
+            frame_current: frame,
+            rated_current: setting,
+            pole: Some(pole),
+            ..Default::default()
+        })
+    }
+
+    /// 为出线回路选择断路器和线缆
+    ///
+    /// 断路器整定电流取不小于1.1倍计算电流的标准值，63A及以下选微型断路器、以上选塑壳断路器；
+    /// 线缆按载流量初选交联电缆截面，消防负荷回路采用NH-YJV耐火电缆，截面不足时由随后的保护配合校验加大。
+    /// 未手动指定的回路每次按当前计算电流重新选型；手动指定的断路器和线缆保持不变，仅按回路长度更新线缆长度
+    ///
+    /// # 参数
+    /// * `circuits` - 回路集合的可变引用
+    ///
+    /// # 返回值
+    /// 返回无法选型的回路记录，每条记录以回路名称开头
+    pub fn select_circuit_equipment(circuits: &mut [CircuitInfo]) -> Vec<String> {
+        let mut log = Vec::new();
+
+        for circuit in circuits.iter_mut() {
+            if circuit.equipment_pinned {
+                if let (Some(cable), Some(length)) = (circuit.cable.as_mut(), circuit.length) {
+                    cable.length = length;
+                }
+                continue;
+            }
+            circuit.components.retain(|comp| !matches!(comp, ElectricComponent::Breaker(_)));
+            circuit.cable = None;
+            if circuit.current <= 0.0 {
+                continue;
+            }
+
+            let pole = if circuit.three_phase { Pole::P3 } else { Pole::P1N };
+            let Some(breaker) = Self::select_breaker(circuit.current * 1.1, pole) else {
+                log.push(format!("{}: 计算电流{:.1}A超出断路器整定范围，未选择断路器", circuit.name, circuit.current));
+                continue;
+            };
+            circuit.components.push(ElectricComponent::Breaker(breaker));
+
+            let section = STANDARD_SECTIONS
+                .iter()
+                .copied()
+                .filter(|&s| s >= 2.5)
+                .find(|&s| ProtectionCoordinator::ampacity(s, ConductorInsulation::Xlpe).unwrap_or(0.0) >= circuit.current)
+                .unwrap_or(STANDARD_SECTIONS[STANDARD_SECTIONS.len() - 1]);
+            circuit.cable = Some(CableInfo {
+                model: if circuit.is_fire_load() { "NH-YJV".to_string() } else { "YJV".to_string() },
+                voltage_level: 1.0,
+                size: format!("{}mm²", section),
+                is_three_phase: circuit.three_phase,
+                length: circuit.length.unwrap_or(0.0),
+                ..Default::default()
+            });
+        }
+
+        log
+    }
+
+    /// 校验出线回路的保护配合
+    ///
+    /// 对已选定断路器和线缆的回路校验 Ib ≤ In ≤ Iz、I2 ≤ 1.45Iz，不满足时自动加大线缆截面；
+    /// 手动指定线缆的回路不改截面，只记录所需的最小截面
+    ///
+    /// # 参数
+    /// * `circuits` - 回路集合的可变引用
+    ///
+    /// # 返回值
+    /// 返回(校验记录, 错误)，计算电流大于断路器整定电流（Ib > In）的回路记为错误，每条记录以回路名称开头
+    pub fn coordinate_circuit_cables(circuits: &mut [CircuitInfo]) -> (Vec<String>, Vec<String>) {
+        let mut log = Vec::new();
+        let mut errors = Vec::new();
+
+        for circuit in circuits.iter_mut() {
+            if circuit.current <= 0.0 {
+                continue;
+            }
+            let Some(breaker) = circuit.find_breaker() else { continue };
+            let rated_current = breaker.rated_current.to_f64();
+            let tripping_factor = match breaker.breaker_type {
+                Some(BreakerType::MCB) | Some(BreakerType::RCBO) => 1.45,
+                _ => 1.30,
+            };
+            let Some(cable) = circuit.cable.as_mut() else { continue };
+            let Ok(section) = cable.size.trim_end_matches("mm²").parse::<f64>() else {
+                log.push(format!("{}: 无法识别线缆规格{}，未进行保护配合校验", circuit.name, cable.size));
+                continue;
+            };
+
+            let input = CoordinationInput {
+                design_current: circuit.current,
+                rated_current,
+                tripping_factor,
+                section,
+                insulation: ConductorInsulation::from_model(&cable.model),
+                derating_factor: 1.0,
+                fault: None,
+            };
+            match ProtectionCoordinator::coordinate(&input) {
+                Ok(result) if !result.design_current_ok => {
+                    errors.push(format!(
+                        "{}: 计算电流Ib={:.1}A大于断路器整定电流In={:.0}A，应加大保护电器规格",
+                        circuit.name, circuit.current, rated_current
+                    ));
+                }
+                Ok(result) if circuit.equipment_pinned && result.section > section => {
+                    log.push(format!(
+                        "{}: 手动指定的线缆{}不满足保护配合，截面应不小于{}mm²",
+                        circuit.name, cable.size, result.section
+                    ));
+                }
+                Ok(result) => {
+                    cable.size = format!("{}mm²", result.section);
+                    log.extend(result.log.into_iter().map(|l| format!("{}: {}", circuit.name, l)));
+                }
+                Err(e) => log.push(format!("{}: {}", circuit.name, e)),
+            }
+        }
+
+        (log, errors)
+    }
+
+    /// 按线缆规格为出线回路选择穿管管径
+    ///
+    /// 单相回路按三芯、三相回路按五芯计算，敷设方式取回路设定的敷设方式，未设定时取线缆原标注（无法识别时按SC），
+    /// 选定的管径写回线缆的敷设方式和穿管管径
+    ///
+    /// # 参数
+    /// * `circuits` - 回路集合的可变引用
+    ///
+    /// # 返回值
+    /// 返回无法选管的回路记录，每条记录以回路名称开头
+    pub fn size_circuit_conduits(circuits: &mut [CircuitInfo]) -> Vec<String> {
+        let mut log = Vec::new();
+
+        for circuit in circuits.iter_mut() {
+            let Some(cable) = circuit.cable.as_mut() else { continue };
+            let Ok(section) = cable.size.trim_end_matches("mm²").parse::<f64>() else { continue };
+            let cores = if cable.is_three_phase { 5 } else { 3 };
+            let spec = ConductorSpec::new(&cable.model, cores, section, None);
+            let method = circuit
+                .laying_method
+                .or_else(|| LayingMethod::from_annotation(&cable.laying_method))
+                .unwrap_or(LayingMethod::SC);
+
+            match ConduitSizing::size(&spec, method, None) {
+                Ok(selection) => ConduitSizing::apply_to_cable(cable, method, &selection),
+                Err(e) => log.push(format!("{}: {}", circuit.name, e)),
+            }
+        }
+
+        log
+    }
+
     /// 执行三相平衡
     /// 
     /// 使用贪心算法将回路分配到L1、L2、L3三相，实现负载平衡
@@ -124,20 +419,31 @@ impl DistributionBoxCalculator {
             return Ok([0.0, 0.0, 0.0]);
         }
         
-        // 重置所有回路的相位分配
+        // 重置相位分配，手动指定相的单相回路保持不变
         for circuit in circuits.iter_mut() {
-            circuit.phase = None;
+            if circuit.three_phase || !circuit.phase_pinned || Self::phase_index(circuit.phase).is_none() {
+                circuit.phase = None;
+                circuit.phase_pinned = false;
+            }
         }
         
-        // 按功率降序排序回路
-        let mut indices: Vec<usize> = (0..circuits.len()).collect();
+        // 按功率降序排序待分配的单相回路，三相回路及已指定相的回路不参与分配
+        let mut indices: Vec<usize> = (0..circuits.len())
+            .filter(|&i| !circuits[i].three_phase && !circuits[i].phase_pinned)
+            .collect();
         indices.sort_by(|&i, &j| {
             circuits[j].power.partial_cmp(&circuits[i].power)
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
         
-        // 初始化各相负载
-        let mut phase_loads = [0.0, 0.0, 0.0];
+        // 初始化各相负载，三相回路负荷均分到各相，已指定相的回路计入所在相
+        let three_phase_load: f64 = circuits.iter().filter(|c| c.three_phase).map(|c| c.power / 3.0).sum();
+        let mut phase_loads = [three_phase_load; 3];
+        for circuit in circuits.iter().filter(|c| c.phase_pinned) {
+            if let Some(index) = Self::phase_index(circuit.phase) {
+                phase_loads[index] += circuit.power;
+            }
+        }
         
         // 初始分配：按功率大小依次分配到负载最小的相
         for &index in &indices {
@@ -193,7 +499,7 @@ impl DistributionBoxCalculator {
                 let circuit_power = circuits[index].power;
                 
                 // 计算转移后的负载
-                let mut new_phase_loads = phase_loads.clone();
+                let mut new_phase_loads = phase_loads;
                 new_phase_loads[max_phase_index] -= circuit_power;
                 new_phase_loads[min_phase_index] += circuit_power;
                 
@@ -234,9 +540,19 @@ impl DistributionBoxCalculator {
         // 确保返回的相位负载是正确的
         Ok(phase_loads)
     }
-    
+
+    /// 相标识转换为相索引（'1'=L1, '2'=L2, '3'=L3），未分配或无效时返回None
+    fn phase_index(phase: Option<char>) -> Option<usize> {
+        match phase {
+            Some('1') => Some(0),
+            Some('2') => Some(1),
+            Some('3') => Some(2),
+            _ => None,
+        }
+    }
+
     /// 查找负载最小的相索引
-    /// 
+    ///
     /// # 参数
     /// * `phase_loads` - 各相负载数组
     /// 
@@ -3,23 +3,26 @@
 //! 本模块实现了配电箱节点的核心功能，包括数据管理、UI展示、参数计算等。
 
 use std::collections::HashMap;
-use std::borrow::Cow;
 
 use egui::Color32;
 
 use egui_node_graph::{Graph, NodeId, NodeResponse};
-use egui_node_graph::traits::{NodeDataTrait, UserResponseTrait, NodeTemplateTrait};
+use egui_node_graph::traits::{NodeDataTrait, UserResponseTrait};
 
 use crate::core_lib::data_types::{ElectricDataType, ElectricValueType};
-use crate::editor::business::{BoxData, CircuitInfo, DistributionBoxError, DistributionBoxResponse};
-use crate::editor::business::{CircuitManager, DistributionBoxCalculator, EditorState};
+use crate::core_lib::algorithm::protection_coordination::FaultWithstand;
+use crate::editor::business::{BoxData, CircuitInfo, DistributionBoxResponse};
+use crate::editor::business::building::level_name;
+use crate::editor::business::{CircuitManager, DistributionBoxCalculator, EditorState, FireModuleGenerator};
+use crate::editor::business::{ElectricalFireMonitoringConfig, ElectricalFirePlanner};
+use crate::editor::business::{HarmonicAnalyzer, HarmonicConfig};
 
 /// 配电箱节点UI实现
 /// 
 /// 包装了DistributionBoxNodeUI数据，实现了NodeDataTrait接口，提供UI交互功能
 #[derive(Debug, Clone)]
 pub struct DistributionBoxNodeUI {
-    /// 节点ID
+    /// 节点ID（与配电箱标识一致）
     pub id: String,
     /// 配电箱数据
     pub data: BoxData,
@@ -29,9 +32,10 @@ pub struct DistributionBoxNodeUI {
 
 impl Default for DistributionBoxNodeUI {
     fn default() -> Self {
+        let data = BoxData::default();
         Self {
-            id: format!("distribution_box_{:x}", rand::random::<u32>()),
-            data: BoxData::default(),
+            id: data.id.clone(),
+            data,
             errors: Vec::new(),
         }
     }
@@ -40,15 +44,16 @@ impl Default for DistributionBoxNodeUI {
 impl DistributionBoxNodeUI {
     /// 创建新的配电箱节点
     /// 
+    /// 节点标识取配电箱的持久标识
+    /// 
     /// # 参数
-    /// * `id` - 节点唯一标识符
     /// * `data` - 配电箱数据
     /// 
     /// # 返回值
     /// 返回新创建的配电箱节点实例
-    pub fn new(id: String, data: BoxData) -> Self {
+    pub fn new(data: BoxData) -> Self {
         Self {
-            id,
+            id: data.id.clone(),
             data,
             errors: Vec::new(),
         }
@@ -65,8 +70,8 @@ impl DistributionBoxNodeUI {
     /// 更新配电箱楼层
     /// 
     /// # 参数
-    /// * `floor` - 新的楼层号
-    pub fn update_floor(&mut self, floor: u32) {
+    /// * `floor` - 新的楼层号（地下室为负数）
+    pub fn update_floor(&mut self, floor: i32) {
         self.data.floor = floor;
     }
     
@@ -163,11 +168,26 @@ impl DistributionBoxNodeUI {
                 // 更新回路数据
                 self.data.circuits = circuits;
                 
+                // 为出线回路选择断路器和线缆
+                let log = DistributionBoxCalculator::select_circuit_equipment(&mut self.data.circuits);
+                self.data.coordination_log.extend(log);
+                
+                // 校验出线回路保护配合
+                let (log, errors) = DistributionBoxCalculator::coordinate_circuit_cables(&mut self.data.circuits);
+                self.data.coordination_log.extend(log);
+                self.errors.extend(errors);
+                
+                // 按校验后的线缆规格选择穿管管径
+                let log = DistributionBoxCalculator::size_circuit_conduits(&mut self.data.circuits);
+                self.data.coordination_log.extend(log);
+                
                 // 计算总功率
                 self.data.total_power = DistributionBoxCalculator::calculate_total_power(&self.data.circuits);
                 
-                // 计算总电流（默认功率因数0.85）
-                match DistributionBoxCalculator::calculate_total_current(self.data.total_power, 0.85) {
+                // 按总计算功率ΣKx·Pe及综合功率因数计算总电流（无有功负荷时电流为0，功率因数取1不影响结果）
+                let demand_power = DistributionBoxCalculator::calculate_demand_power(&self.data.circuits);
+                let power_factor = DistributionBoxCalculator::combined_power_factor(&self.data.circuits).unwrap_or(1.0);
+                match DistributionBoxCalculator::calculate_total_current(demand_power, power_factor) {
                     Ok(total_current) => {
                         self.data.total_current = total_current;
                         
@@ -175,6 +195,29 @@ impl DistributionBoxNodeUI {
                         match DistributionBoxCalculator::calculate_incoming_current(total_current) {
                             Ok(incoming_current) => {
                                 self.data.incoming_current = incoming_current;
+                                
+                                // 估算谐波电流，进线四芯电缆按谐波计算电流选择截面
+                                let harmonics = HarmonicAnalyzer::analyze_box(&self.data, &HarmonicConfig::default());
+                                self.data.coordination_log.push(format!("谐波: {}", harmonics.summary()));
+                                
+                                // 选择进线电缆并校验保护配合
+                                if incoming_current > 0.0 {
+                                    match DistributionBoxCalculator::select_incoming_cable(
+                                        harmonics.design_current(),
+                                        incoming_current,
+                                        harmonics.derating_factor,
+                                        self.data.is_fire_load(),
+                                        &FaultWithstand::default(),
+                                    ) {
+                                        Ok((cable, log)) => {
+                                            self.data.incoming_cable = Some(cable);
+                                            self.data.coordination_log.extend(log.into_iter().map(|l| format!("进线: {}", l)));
+                                        },
+                                        Err(err) => {
+                                            self.errors.push(format!("进线电缆选择错误: {}", err));
+                                        }
+                                    }
+                                }
                             },
                             Err(err) => {
                                 self.errors.push(format!("进线电流计算错误: {}", err));
@@ -190,6 +233,12 @@ impl DistributionBoxNodeUI {
                 self.errors.push(format!("三相平衡失败: {}", err));
             }
         }
+        
+        // 根据负荷分级插入消防电源监测/消防切断模块
+        FireModuleGenerator::auto_insert(&mut self.data);
+        
+        // 按进出线电流规划电气火灾监控探测器
+        ElectricalFirePlanner::plan_box(&mut self.data, &ElectricalFireMonitoringConfig::default());
     }
     
     /// 获取节点标题
@@ -232,13 +281,13 @@ impl NodeDataTrait for DistributionBoxNodeUI {
         _graph: &Graph<Self, Self::DataType, Self::ValueType>,
         _user_state: &mut Self::UserState,
     ) -> Vec<NodeResponse<Self::Response, Self>> {
-        let mut responses = Vec::new();
+        let responses = Vec::new();
         
         // 显示基本信息
         ui.separator();
         ui.horizontal(|ui| {
             ui.label("楼层: ");
-            ui.label(format!("{}", self.data.floor));
+            ui.label(level_name(self.data.floor));
         });
         
         // 显示计算结果
@@ -310,7 +359,8 @@ impl NodeDataTrait for DistributionBoxNodeUI {
                     ui.label(format!("{} ", circuit.name));
                     ui.label(format!("{:.2}kW ", circuit.power));
                     if let Some(phase) = circuit.phase {
-                        ui.label(format!("(相: L{})", phase));
+                        let pinned = if circuit.phase_pinned { ", 指定" } else { "" };
+                        ui.label(format!("(相: L{}{})", phase, pinned));
                     }
                 });
             }
@@ -328,193 +378,6 @@ impl NodeDataTrait for DistributionBoxNodeUI {
                 ui.label(egui::RichText::new(format!("- {}", error)).color(Color32::RED));
             }
         }
-    }
-}
-
-// 实现UserResponseTrait接口
-impl UserResponseTrait for DistributionBoxResponse {}
-
-impl DistributionBoxResponse {
-    /// 应用响应到节点图
-    pub fn apply(
-        self,
-        node_id: NodeId,
-        graph: &mut Graph<DistributionBoxNodeUI, ElectricDataType, ElectricValueType>,
-        user_state: &mut EditorState,
-    ) {
-        match self {
-            DistributionBoxResponse::NodeUpdated => {
-                // 节点已更新，可能需要刷新UI
-            },
-            DistributionBoxResponse::ParameterChanged(name, value) => {
-                // 参数变更处理
-            },
-            DistributionBoxResponse::CircuitAdded(circuit) => {
-                // 添加回路
-                if let Some(node) = graph.nodes.get_mut(node_id) {
-                    node.user_data.add_circuit(circuit);
-                }
-            },
-            DistributionBoxResponse::CircuitRemoved(circuit_id) => {
-                // 移除回路
-                if let Some(node) = graph.nodes.get_mut(node_id) {
-                    node.user_data.remove_circuit(&circuit_id);
-                }
-            },
-            DistributionBoxResponse::CircuitUpdated(circuit) => {
-                // 更新回路
-                if let Some(node) = graph.nodes.get_mut(node_id) {
-                    node.user_data.update_circuit(circuit);
-                }
-            },
-            DistributionBoxResponse::CalculationCompleted => {
-                // 计算完成
-            },
-            DistributionBoxResponse::Error(err) => {
-                // 错误处理
-                if let Some(node) = graph.nodes.get_mut(node_id) {
-                    node.user_data.errors.push(err.to_string());
-                }
-            },
-        }
-    }
-}
-
-// 单元测试
-#[cfg(test)]
-mod tests {
-    use super::*;
-    
-    #[test]
-    fn test_distribution_box_node_creation() {
-        let node = DistributionBoxNodeUI::default();
-        assert_eq!(node.data.name, "新建配电箱");
-        assert_eq!(node.data.floor, 1);
-        assert_eq!(node.data.circuits.len(), 0);
-        assert_eq!(node.data.total_power, 0.0);
-        assert_eq!(node.data.total_current, 0.0);
-        assert_eq!(node.data.incoming_current, 0.0);
-        assert!(node.errors.is_empty());
-    }
-    
-    #[test]
-    fn test_add_circuit() {
-        let mut node = DistributionBoxNodeUI::default();
-        let circuit = CircuitInfo::new(
-            "circuit_1".to_string(),
-            "测试回路1".to_string(),
-            5.0,
-            10.0
-        );
-        
-        let result = node.add_circuit(circuit);
-        
-        assert!(result);
-        assert_eq!(node.data.circuits.len(), 1);
-        assert_eq!(node.data.circuits[0].name, "测试回路1");
-        assert_eq!(node.data.circuits[0].power, 5.0);
-        assert!(node.errors.is_empty());
-    }
-    
-    #[test]
-    fn test_update_name() {
-        let mut node = DistributionBoxNodeUI::default();
-        node.update_name("测试配电箱".to_string());
-        
-        assert_eq!(node.data.name, "测试配电箱");
-        assert_eq!(node.get_title(), "配电箱: 测试配电箱");
-    }
-    
-    #[test]
-    fn test_recalculate() {
-        let mut node = DistributionBoxNodeUI::default();
-        
-        // 添加几个回路
-        let circuit1 = CircuitInfo::new(
-            "circuit_1".to_string(),
-            "回路1".to_string(),
-            3.0,
-            6.0
-        );
-        let circuit2 = CircuitInfo::new(
-            "circuit_2".to_string(),
-            "回路2".to_string(),
-            4.0,
-            8.0
-        );
-        let circuit3 = CircuitInfo::new(
-            "circuit_3".to_string(),
-            "回路3".to_string(),
-            5.0,
-            10.0
-        );
-        
-        node.add_circuit(circuit1);
-        node.add_circuit(circuit2);
-        node.add_circuit(circuit3);
-        
-        // 手动触发重新计算
-        node.recalculate();
-        
-        // 验证计算结果
-        assert_eq!(node.data.circuits.len(), 3);
-        assert_eq!(node.data.total_power, 12.0); // 3+4+5=12kW
-        assert!(node.data.total_current > 0.0);
-        assert!(node.data.incoming_current >= node.data.total_current);
-        assert!(node.errors.is_empty());
-        
-        // 验证编号
-        let mut numbers: Vec<u32> = node.data.circuits.iter().map(|c| c.number).collect();
-        numbers.sort();
-        assert_eq!(numbers, vec![1, 2, 3]);
-    }
-}
-
-// 继续实现NodeDataTrait接口的方法
-impl NodeDataTrait for DistributionBoxNodeUI {
-    type Response = DistributionBoxResponse;
-    type UserState = EditorState;
-    type DataType = ElectricDataType;
-    type ValueType = ElectricValueType;
-    
-    /// 节点底部UI，显示计算结果和错误信息
-    fn bottom_ui(
-        &self,
-        ui: &mut egui::Ui,
-        _node_id: NodeId,
-        _graph: &Graph<Self, Self::DataType, Self::ValueType>,
-        _user_state: &mut Self::UserState,
-    ) -> Vec<NodeResponse<Self::Response, Self>> {
-        let mut responses = Vec::new();
-        
-        // 显示基本信息
-        ui.separator();
-        ui.horizontal(|ui| {
-            ui.label("楼层: ");
-            ui.label(format!("{}", self.data.floor));
-        });
-        
-        // 显示计算结果
-        if !self.data.circuits.is_empty() {
-            ui.separator();
-            ui.label("计算结果:");
-            ui.horizontal(|ui| {
-                ui.label("回路数量: ");
-                ui.label(format!("{}", self.data.circuits.len()));
-            });
-            ui.horizontal(|ui| {
-                ui.label("总功率: ");
-                ui.label(egui::RichText::new(format!("{:.2} kW", self.data.total_power)).color(Color32::GREEN));
-            });
-            ui.horizontal(|ui| {
-                ui.label("总电流: ");
-                ui.label(egui::RichText::new(format!("{:.2} A", self.data.total_current)).color(Color32::GREEN));
-            });
-            ui.horizontal(|ui| {
-                ui.label("进线电流: ");
-                ui.label(egui::RichText::new(format!("{:.0} A", self.data.incoming_current)).color(Color32::BLUE));
-            });
-        }
         
         responses
     }
@@ -527,13 +390,12 @@ impl NodeDataTrait for DistributionBoxNodeUI {
         _graph: &Graph<Self, Self::DataType, Self::ValueType>,
         _user_state: &mut Self::UserState,
     ) -> Vec<NodeResponse<Self::Response, Self>> {
-        let mut responses = Vec::new();
+        let responses = Vec::new();
         
         ui.label(self.get_title());
         
         responses
     }
-}
     
     /// 标题栏颜色
     fn titlebar_color(
@@ -555,7 +417,7 @@ impl NodeDataTrait for DistributionBoxNodeUI {
         _user_state: &mut Self::UserState,
         param_name: &str,
     ) -> Vec<NodeResponse<Self::Response, Self>> {
-        let mut responses = Vec::new();
+        let responses = Vec::new();
         
         ui.horizontal(|ui| {
             ui.label(param_name);
@@ -580,6 +442,7 @@ impl NodeDataTrait for DistributionBoxNodeUI {
         
         responses
     }
+}
 
 // 实现UserResponseTrait接口
 impl UserResponseTrait for DistributionBoxResponse {}
@@ -590,13 +453,13 @@ impl DistributionBoxResponse {
         self,
         node_id: NodeId,
         graph: &mut Graph<DistributionBoxNodeUI, ElectricDataType, ElectricValueType>,
-        user_state: &mut EditorState,
+        _user_state: &mut EditorState,
     ) {
         match self {
             DistributionBoxResponse::NodeUpdated => {
                 // 节点已更新，可能需要刷新UI
             },
-            DistributionBoxResponse::ParameterChanged(name, value) => {
+            DistributionBoxResponse::ParameterChanged(_name, _value) => {
                 // 参数变更处理
             },
             DistributionBoxResponse::CircuitAdded(circuit) => {
@@ -645,6 +508,10 @@ mod tests {
         assert_eq!(node.data.total_current, 0.0);
         assert_eq!(node.data.incoming_current, 0.0);
         assert!(node.errors.is_empty());
+
+        let data = BoxData::new("AL1".to_string(), 1);
+        let node = DistributionBoxNodeUI::new(data.clone());
+        assert_eq!(node.id, data.id);
     }
     
     #[test]
@@ -717,5 +584,43 @@ mod tests {
         let mut numbers: Vec<u32> = node.data.circuits.iter().map(|c| c.number).collect();
         numbers.sort();
         assert_eq!(numbers, vec![1, 2, 3]);
+        
+        // 验证断路器和线缆选型
+        for circuit in &node.data.circuits {
+            let breaker = circuit.find_breaker().unwrap();
+            assert!(breaker.rated_current.to_f64() >= circuit.current);
+            assert_eq!(circuit.cable.as_ref().unwrap().model, "YJV");
+        }
+        assert!(node.data.coordination_log.iter().any(|l| l.starts_with("回路3: ")));
+    }
+    
+    #[test]
+    fn test_recalculate_harmonic_derating() {
+        // 插座负荷三次谐波含量高，进线按中性线电流及降低系数选择截面，大于同功率水泵配电箱
+        let incoming_section = |purpose: &str| {
+            let mut node = DistributionBoxNodeUI::default();
+            let mut circuit = CircuitInfo::new("circuit_1".to_string(), purpose.to_string(), 40.0, 72.0);
+            circuit.three_phase = true;
+            node.add_circuit(circuit);
+            node.recalculate();
+            assert!(node.data.coordination_log.iter().any(|l| l.starts_with("谐波: ")));
+            node.data.incoming_cable.unwrap().size.trim_end_matches("mm²").parse::<f64>().unwrap()
+        };
+        assert!(incoming_section("插座") > incoming_section("水泵"));
+    }
+    
+    #[test]
+    fn test_recalculate_sizes_conduits() {
+        let mut node = DistributionBoxNodeUI::default();
+        let mut circuit = CircuitInfo::new("circuit_1".to_string(), "空调".to_string(), 30.0, 54.0);
+        circuit.three_phase = true;
+        node.add_circuit(circuit);
+        node.add_circuit(CircuitInfo::new("circuit_2".to_string(), "照明".to_string(), 1.0, 5.0));
+        node.recalculate();
+        
+        // 选型得到的线缆随即选管：In=63A时YJV-5×16穿SC40，照明YJV-3×2.5穿SC20
+        let cables: Vec<_> = node.data.circuits.iter().map(|c| c.cable.as_ref().unwrap()).collect();
+        assert_eq!((cables[0].size.as_str(), cables[0].laying_method.as_str()), ("16mm²", "SC40"));
+        assert_eq!((cables[1].size.as_str(), cables[1].laying_method.as_str()), ("2.5mm²", "SC20"));
     }
-}
\ No newline at end of file
+}
@@ -2,11 +2,21 @@
 //! 
 //! 本模块定义了配电箱节点相关的数据结构，包括配电箱节点数据、回路信息和错误类型等。
 
+use std::collections::HashMap;
+
 use serde::{Serialize, Deserialize};
 use thiserror::Error;
 
+use crate::core_lib::data_types::{Breaker, BreakerType, CableInfo, Dimensions, ElectricComponent, LayingMethod, LoadClass};
+use crate::core_lib::utils::IdUtils;
+use crate::editor::business::box_modules::{self, BoxModule, ModuleKind};
+use crate::editor::business::cable_length::{CableLengthEstimate, LengthSource};
+use crate::editor::business::fire_power_monitoring::{FirePowerMonitoringModule, FireCutOffModule};
+use crate::editor::business::electrical_fire_monitoring::ElectricalFireMonitoringModule;
+use crate::editor::business::main_system_node::MainSystemType;
+
 /// 配电箱错误类型
-#[derive(Debug, Error, Clone, PartialEq)]
+#[derive(Debug, Error, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DistributionBoxError {
     /// 回路数量超出限制
     #[error("回路数量超出限制: {0}个回路，最大允许99个")]
@@ -42,14 +52,58 @@ pub struct CircuitInfo {
     pub circuit_id: String,
     /// 回路名称
     pub name: String,
-    /// 回路功率（kW）
+    /// 回路功率（kW），即设备容量Pe
     pub power: f64,
     /// 回路电流（A）
     pub current: f64,
+    /// 需要系数Kx
+    #[serde(default = "default_demand_coefficient")]
+    pub demand_coefficient: f64,
+    /// 功率因数cosφ
+    #[serde(default = "default_power_factor")]
+    pub power_factor: f64,
     /// 自动分配的编号
     pub number: u32,
     /// 分配的相（L1/L2/L3），None表示未分配
     pub phase: Option<char>,
+    /// 相是否为手动指定（指定后三相平衡不再调整该回路）
+    #[serde(default)]
+    pub phase_pinned: bool,
+    /// 是否为三相回路（三相回路不参与单相分配，负荷均分到三相）
+    #[serde(default)]
+    pub three_phase: bool,
+    /// 负荷分级（负荷等级及是否为消防负荷）
+    #[serde(default)]
+    pub load_class: LoadClass,
+    /// 回路元器件列表（断路器、接触器等）
+    #[serde(default)]
+    pub components: Vec<ElectricComponent>,
+    /// 回路线缆选型，None表示尚未选型
+    #[serde(default)]
+    pub cable: Option<CableInfo>,
+    /// 断路器和线缆是否为手动选定（指定后重新计算及重新导入不再替换）
+    #[serde(default)]
+    pub equipment_pinned: bool,
+    /// 回路用途（如"照明"、"风机"），None表示按回路名称判断
+    #[serde(default)]
+    pub purpose: Option<String>,
+    /// 回路线缆长度（m），由负荷清单导入或手动填写，None表示未填写
+    #[serde(default)]
+    pub length: Option<f64>,
+    /// 回路敷设方式（如SC、JDG、CT），由负荷清单导入或手动填写，None表示按线缆原标注（无标注时按SC）
+    #[serde(default)]
+    pub laying_method: Option<LayingMethod>,
+    /// 外部标识（如负荷清单中的设备编号），重新导入时用于匹配回路
+    #[serde(default)]
+    pub source_id: Option<String>,
+}
+
+fn default_demand_coefficient() -> f64 {
+    1.0
+}
+
+fn default_power_factor() -> f64 {
+    0.85
 }
 
 impl CircuitInfo {
@@ -69,11 +123,76 @@ impl CircuitInfo {
             name,
             power,
             current,
+            demand_coefficient: default_demand_coefficient(),
+            power_factor: default_power_factor(),
             number: 0, // 初始编号为0，将在自动编号时设置
             phase: None, // 初始未分配相位
+            phase_pinned: false,
+            three_phase: false,
+            load_class: LoadClass::default(),
+            components: Vec::new(),
+            cable: None,
+            equipment_pinned: false,
+            purpose: None,
+            length: None,
+            laying_method: None,
+            source_id: None,
         }
     }
     
+    /// 手动指定回路所在的相，None表示取消指定、交由三相平衡分配
+    ///
+    /// # 参数
+    /// * `phase` - 相（'1'、'2'、'3'对应L1、L2、L3）
+    pub fn pin_phase(&mut self, phase: Option<char>) {
+        self.phase_pinned = phase.is_some();
+        if phase.is_some() {
+            self.phase = phase;
+        }
+    }
+
+    /// 手动指定回路的断路器和线缆，指定后不再按负荷自动选型
+    ///
+    /// # 参数
+    /// * `breaker` - 选定的断路器
+    /// * `cable` - 选定的线缆
+    pub fn pin_equipment(&mut self, breaker: Breaker, cable: CableInfo) {
+        self.components.retain(|comp| !matches!(comp, ElectricComponent::Breaker(_)));
+        self.components.push(ElectricComponent::Breaker(breaker));
+        self.cable = Some(cable);
+        self.equipment_pinned = true;
+    }
+
+    /// 计算功率Pjs = Kx·Pe（kW）
+    pub fn calculated_power(&self) -> f64 {
+        self.power * self.demand_coefficient
+    }
+
+    /// 判断是否为消防负荷回路
+    pub fn is_fire_load(&self) -> bool {
+        self.load_class.is_fire
+    }
+    
+    /// 回路用途，未设置时取回路名称
+    pub fn purpose_or_name(&self) -> &str {
+        self.purpose.as_deref().unwrap_or(&self.name)
+    }
+    
+    /// 查找回路中的断路器
+    pub fn find_breaker(&self) -> Option<&Breaker> {
+        self.components.iter().find_map(|comp| match comp {
+            ElectricComponent::Breaker(breaker) => Some(breaker),
+            _ => None,
+        })
+    }
+    
+    /// 判断回路保护是否带剩余电流（漏电）脱扣
+    pub fn has_rcd_trip(&self) -> bool {
+        self.find_breaker()
+            .and_then(|breaker| breaker.breaker_type)
+            .is_some_and(|t| matches!(t, BreakerType::RCBO | BreakerType::MCCBRCBO))
+    }
+    
     /// 验证回路信息是否有效
     /// 
     /// # 返回值
@@ -110,25 +229,6 @@ impl CircuitInfo {
         
         Ok(())
     }
-
-    /// 自动识别配电箱进线类型
-    /// 
-    /// # 返回值
-    /// 返回识别出的进线类型
-    pub fn determine_incoming_type(&self) -> IncomingType {
-        // 检查modules列表中是否包含"双电源切换"模块
-        // 为了提高鲁棒性，使用模糊匹配
-        for module in &self.modules {
-            if module.contains("双电源切换") || 
-               module.contains("双电源") || 
-               module.contains("duplex power") || 
-               module.contains("dual power") {
-                return IncomingType::DualPower;
-            }
-        }
-        // 默认返回单电源
-        IncomingType::SinglePower
-    }
 }
 
 /// 配电箱节点数据结构体
@@ -136,6 +236,9 @@ impl CircuitInfo {
 /// 存储配电箱节点的所有相关信息和状态
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DistributionBoxNode {
+    /// 配电箱唯一标识符（UUID，随项目保存，改名后不变）
+    #[serde(default = "IdUtils::new_id")]
+    pub id: String,
     /// 配电箱名称
     pub name: String,
     /// 总功率（kW）
@@ -144,18 +247,43 @@ pub struct DistributionBoxNode {
     pub total_current: f64,
     /// 进线保护设备电流整定值（A）
     pub incoming_current: f64,
-    /// 所在楼层
-    pub floor: u32,
-    /// 包含的模块列表
-    pub modules: Vec<String>,
+    /// 所在楼层号（地下室为负数，B1为-1，见building::level_name）
+    pub floor: i32,
+    /// 所属电气竖井标识（见Building::shafts），None表示未指定
+    #[serde(default)]
+    pub shaft: Option<String>,
+    /// 箱体在所在楼层平面中的位置（m），用于估算进线电缆长度
+    #[serde(default)]
+    pub position: Option<(f64, f64)>,
+    /// 包含的功能模块列表
+    #[serde(default, deserialize_with = "box_modules::deserialize_modules")]
+    pub modules: Vec<BoxModule>,
     /// L1, L2, L3各相负载（kW）
     pub phase_loads: [f64; 3],
     /// 管理的回路信息列表
     pub circuits: Vec<CircuitInfo>,
+    /// 配电箱负荷分级
+    #[serde(default)]
+    pub load_class: LoadClass,
+    /// 进线电缆（经保护配合校验后选定）
+    #[serde(default)]
+    pub incoming_cable: Option<CableInfo>,
+    /// 手动输入的进线电缆长度（m），优先于估算值
+    #[serde(default)]
+    pub feeder_length: Option<f64>,
+    /// 按建筑几何估算的进线电缆长度
+    #[serde(default)]
+    pub feeder_estimate: Option<CableLengthEstimate>,
+    /// 进线及出线回路保护配合校验记录
+    #[serde(default)]
+    pub coordination_log: Vec<String>,
+    /// 箱体外形尺寸（宽×高×深，mm）
+    #[serde(default)]
+    pub dimensions: Dimensions,
 }
 
 /// 进线类型枚举
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum IncomingType {
     SinglePower, // 单电源
     DualPower,   // 双电源
@@ -165,14 +293,23 @@ impl Default for DistributionBoxNode {
     /// 创建默认的配电箱节点数据
     fn default() -> Self {
         Self {
+            id: IdUtils::new_id(),
             name: "新建配电箱".to_string(),
             total_power: 0.0,
             total_current: 0.0,
             incoming_current: 0.0,
             floor: 1,
+            shaft: None,
+            position: None,
             modules: Vec::new(),
             phase_loads: [0.0; 3],
             circuits: Vec::new(),
+            load_class: LoadClass::default(),
+            incoming_cable: None,
+            feeder_length: None,
+            feeder_estimate: None,
+            coordination_log: Vec::new(),
+            dimensions: Dimensions::default(),
         }
     }
 }
@@ -182,11 +319,11 @@ impl DistributionBoxNode {
     /// 
     /// # 参数
     /// * `name` - 配电箱名称
-    /// * `floor` - 所在楼层
+    /// * `floor` - 所在楼层号（地下室为负数）
     /// 
     /// # 返回值
     /// 返回新创建的DistributionBoxNode实例
-    pub fn new(name: String, floor: u32) -> Self {
+    pub fn new(name: String, floor: i32) -> Self {
         Self {
             name,
             floor,
@@ -201,13 +338,126 @@ impl DistributionBoxNode {
     /// # 返回值
     /// 返回判断的进线类型
     pub fn determine_incoming_type(&self) -> IncomingType {
-        if self.modules.contains(&"双电源切换".to_string()) {
+        if self.has_module(ModuleKind::DualPowerSwitch) {
             IncomingType::DualPower
         } else {
             IncomingType::SinglePower
         }
     }
     
+    /// 获取指定类型的模块
+    /// 
+    /// 自定义模块可能有多个，返回第一个
+    pub fn module(&self, kind: ModuleKind) -> Option<&BoxModule> {
+        self.modules.iter().find(|m| m.kind() == kind)
+    }
+    
+    /// 获取指定类型的模块（可变引用）
+    pub fn module_mut(&mut self, kind: ModuleKind) -> Option<&mut BoxModule> {
+        self.modules.iter_mut().find(|m| m.kind() == kind)
+    }
+    
+    /// 判断是否包含指定类型的模块
+    pub fn has_module(&self, kind: ModuleKind) -> bool {
+        self.module(kind).is_some()
+    }
+    
+    /// 添加模块
+    /// 
+    /// 内置模块每种类型只保留一个，已存在时替换；自定义模块按名称替换
+    pub fn add_module(&mut self, module: BoxModule) {
+        let existing = self.modules.iter().position(|m| {
+            m.kind() == module.kind() && (m.kind() != ModuleKind::Custom || m.name() == module.name())
+        });
+        match existing {
+            Some(index) => self.modules[index] = module,
+            None => self.modules.push(module),
+        }
+    }
+    
+    /// 移除指定类型的模块
+    /// 
+    /// # 返回值
+    /// 返回被移除的模块，不存在时返回None
+    pub fn remove_module(&mut self, kind: ModuleKind) -> Option<BoxModule> {
+        let index = self.modules.iter().position(|m| m.kind() == kind)?;
+        Some(self.modules.remove(index))
+    }
+    
+    /// 判断配电箱是否参与指定的干线系统图
+    pub fn participates_in(&self, diagram: &MainSystemType) -> bool {
+        self.modules.iter().any(|m| m.trunk_diagrams().contains(diagram))
+    }
+    
+    /// 消防电源监测模块
+    pub fn fire_power_monitoring(&self) -> Option<&FirePowerMonitoringModule> {
+        self.modules.iter().find_map(|m| match m {
+            BoxModule::FirePowerMonitoring(module) => Some(module),
+            _ => None,
+        })
+    }
+    
+    /// 消防电源监测模块（可变引用）
+    pub fn fire_power_monitoring_mut(&mut self) -> Option<&mut FirePowerMonitoringModule> {
+        self.modules.iter_mut().find_map(|m| match m {
+            BoxModule::FirePowerMonitoring(module) => Some(module),
+            _ => None,
+        })
+    }
+    
+    /// 消防切断模块
+    pub fn fire_cut_off(&self) -> Option<&FireCutOffModule> {
+        self.modules.iter().find_map(|m| match m {
+            BoxModule::FireCutOff(module) => Some(module),
+            _ => None,
+        })
+    }
+    
+    /// 消防切断模块（可变引用）
+    pub fn fire_cut_off_mut(&mut self) -> Option<&mut FireCutOffModule> {
+        self.modules.iter_mut().find_map(|m| match m {
+            BoxModule::FireCutOff(module) => Some(module),
+            _ => None,
+        })
+    }
+    
+    /// 电气火灾监控模块
+    pub fn electrical_fire_monitoring(&self) -> Option<&ElectricalFireMonitoringModule> {
+        self.modules.iter().find_map(|m| match m {
+            BoxModule::ElectricalFireMonitoring(module) => Some(module),
+            _ => None,
+        })
+    }
+    
+    /// 电气火灾监控模块（可变引用）
+    pub fn electrical_fire_monitoring_mut(&mut self) -> Option<&mut ElectricalFireMonitoringModule> {
+        self.modules.iter_mut().find_map(|m| match m {
+            BoxModule::ElectricalFireMonitoring(module) => Some(module),
+            _ => None,
+        })
+    }
+    
+    /// 判断是否为消防负荷配电箱
+    /// 
+    /// 配电箱本身标记为消防负荷，或包含任一消防负荷回路时返回true
+    pub fn is_fire_load(&self) -> bool {
+        self.load_class.is_fire || self.circuits.iter().any(|c| c.is_fire_load())
+    }
+    
+    /// 进线电缆长度（m）及其来源：手动输入的长度优先，否则采用估算值
+    pub fn incoming_length(&self) -> Option<(f64, LengthSource)> {
+        match (self.feeder_length, &self.feeder_estimate) {
+            (Some(length), _) => Some((length, LengthSource::Entered)),
+            (None, Some(estimate)) => Some((estimate.total(), LengthSource::Estimated)),
+            (None, None) => None,
+        }
+    }
+    
+    /// 判断是否包含非消防负荷回路
+    pub fn has_non_fire_loads(&self) -> bool {
+        self.circuits.iter().any(|c| !c.is_fire_load())
+    }
+    
     /// 添加回路到配电箱
     /// 
     /// # 参数
@@ -276,32 +526,20 @@ impl DistributionBoxNode {
         updated_circuit.validate()?;
         
         // 查找并更新回路
-        let mut found = false;
+        let Some(i) = self.circuits.iter().position(|circuit| circuit.circuit_id == updated_circuit.circuit_id) else {
+            return Err(DistributionBoxError::CircuitNotFound(updated_circuit.circuit_id.to_string()));
+        };
         
-        for (i, circuit) in self.circuits.iter_mut().enumerate() {
-            if circuit.circuit_id == updated_circuit.circuit_id {
-                // 保留原始编号和相位信息
-                let original_number = circuit.number;
-                let original_phase = circuit.phase;
-                
-                // 更新回路信息
-                self.circuits[i] = updated_circuit;
-                
-                // 恢复编号和相位
-                self.circuits[i].number = original_number;
-                self.circuits[i].phase = original_phase;
-                
-                found = true;
-                break;
-            }
-        }
+        // 保留原始编号和相位信息
+        let original_number = self.circuits[i].number;
+        let original_phase = self.circuits[i].phase;
         
-        // 检查是否找到回路
-        if !found {
-            return Err(DistributionBoxError::CircuitNotFound(
-                updated_circuit.circuit_id.to_string()
-            ));
-        }
+        // 更新回路信息
+        self.circuits[i] = updated_circuit;
+        
+        // 恢复编号和相位
+        self.circuits[i].number = original_number;
+        self.circuits[i].phase = original_phase;
         
         Ok(())
     }
@@ -325,6 +563,42 @@ impl DistributionBoxNode {
         self.circuits.iter().any(|c| c.circuit_id == circuit_id)
     }
     
+    /// 复制配电箱（用于复制粘贴）
+    /// 
+    /// 复制品及其回路分配新的标识，功能模块中对回路的引用随之更新
+    /// 
+    /// # 返回值
+    /// 返回复制得到的配电箱
+    pub fn duplicate(&self) -> Self {
+        let mut copy = self.clone();
+        copy.id = IdUtils::new_id();
+        let mut renamed = HashMap::new();
+        for circuit in &mut copy.circuits {
+            let id = IdUtils::new_id();
+            renamed.insert(std::mem::replace(&mut circuit.circuit_id, id.clone()), id);
+        }
+        for module in &mut copy.modules {
+            match module {
+                BoxModule::ElectricalFireMonitoring(module) => {
+                    for detector in &mut module.detectors {
+                        if let Some(new) = detector.circuit_id.as_ref().and_then(|id| renamed.get(id)) {
+                            detector.circuit_id = Some(new.clone());
+                        }
+                    }
+                }
+                BoxModule::FireCutOff(module) => {
+                    for id in &mut module.shunt_trip_circuits {
+                        if let Some(new) = renamed.get(id.as_str()) {
+                            *id = new.clone();
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        copy
+    }
+    
     /// 重置计算结果
     /// 
     /// 将总功率、总电流、进线电流和相负载重置为零
@@ -333,6 +607,8 @@ impl DistributionBoxNode {
         self.total_current = 0.0;
         self.incoming_current = 0.0;
         self.phase_loads = [0.0; 3];
+        self.incoming_cable = None;
+        self.coordination_log.clear();
     }
 }
 
@@ -8,7 +8,7 @@ use egui_node_graph::{Graph, NodeId};
 use egui_node_graph::traits::{NodeTemplateTrait};
 
 use crate::core_lib::data_types::{ElectricDataType, ElectricValueType};
-use crate::editor::business::{DistributionBoxNodeUI, BoxData, EditorState};
+use crate::editor::business::{DistributionBoxNodeUI, EditorState};
 
 /// 配电箱节点模板
 /// 
@@ -19,7 +19,7 @@ pub struct DistributionBoxTemplate;
 impl DistributionBoxTemplate {
     /// 创建新的配电箱节点模板实例
     pub fn new() -> Self {
-        Self::default()
+        Self
     }
 }
 
@@ -56,14 +56,14 @@ impl NodeTemplateTrait for DistributionBoxTemplate {
     fn build_node(
         &self,
         graph: &mut Graph<Self::NodeData, Self::DataType, Self::ValueType>,
-        user_state: &mut Self::UserState,
+        _user_state: &mut Self::UserState,
         node_id: NodeId,
     ) {
         // 添加输入参数
         // 配电箱名称
         graph.add_input_param(
             node_id,
-            "配电箱名称",
+            "配电箱名称".to_string(),
             ElectricDataType::String,
             ElectricValueType::String("新建配电箱".to_string()),
             egui_node_graph::InputParamKind::ConstantOnly,
@@ -73,22 +73,22 @@ impl NodeTemplateTrait for DistributionBoxTemplate {
         // 所在楼层
         graph.add_input_param(
             node_id,
-            "所在楼层",
+            "所在楼层".to_string(),
             ElectricDataType::Integer,
             ElectricValueType::Float(1.0),
             egui_node_graph::InputParamKind::ConstantOnly,
             true,
         );
         
-        // 回路数据输入端口（动态，最多99个）
+        // 回路数据输入端口（最多99个）
         // 这里先添加3个输入端口，实际使用时可以根据需要添加更多
         for i in 1..=3 {
             graph.add_input_param(
                 node_id,
-                &format!("回路输入{}", i),
+                format!("回路输入{}", i),
                 ElectricDataType::CircuitData,
                 ElectricValueType::Float(0.0),
-                egui_node_graph::InputParamKind::Dynamic,
+                egui_node_graph::InputParamKind::ConnectionOnly,
                 false,
             );
         }
@@ -97,28 +97,28 @@ impl NodeTemplateTrait for DistributionBoxTemplate {
         // 配电箱数据（完整信息）
         graph.add_output_param(
             node_id,
-            "配电箱数据",
+            "配电箱数据".to_string(),
             ElectricDataType::DistributionBoxData,
         );
         
         // 总电流
         graph.add_output_param(
             node_id,
-            "总电流",
+            "总电流".to_string(),
             ElectricDataType::Current,
         );
         
         // 总功率
         graph.add_output_param(
             node_id,
-            "总功率",
+            "总功率".to_string(),
             ElectricDataType::Power,
         );
         
         // 进线电流（保护设备电流整定值）
         graph.add_output_param(
             node_id,
-            "进线电流",
+            "进线电流".to_string(),
             ElectricDataType::Current,
         );
     }
@@ -3,7 +3,10 @@
 //! 本文件包含对配电箱相关功能的全面单元测试
 
 use crate::editor::business::{BoxData, CircuitInfo, CircuitManager, DistributionBoxCalculator, DistributionBoxNodeUI, DistributionBoxTemplate};
-use crate::editor::business::{DistributionBoxError};
+use crate::editor::business::{DistributionBoxError, EditorState};
+use crate::core_lib::data_types::{Breaker, CableInfo, ElectricComponent, SettingValue};
+use egui_node_graph::Graph;
+use egui_node_graph::traits::NodeTemplateTrait;
 
 #[test]
 fn test_box_data_basic_operations() {
@@ -61,8 +64,10 @@ fn test_box_data_basic_operations() {
     
     let result = box_data.add_circuit(duplicate_circuit);
     assert!(result.is_err());
-    if let Err(DistributionBoxError::CircuitIdExists(id)) = result {
-        assert_eq!(id, "circuit_2");
+    if let Err(DistributionBoxError::InvalidParameter(msg)) = result {
+        assert!(msg.contains("circuit_2"));
+    } else {
+        panic!("重复ID应返回参数无效错误");
     }
     
     // 测试错误处理：更新不存在的回路
@@ -126,12 +131,12 @@ fn test_distribution_box_calculator() {
     
     // 测试总电流计算（三相380V，功率因数0.85）
     let total_current = DistributionBoxCalculator::calculate_total_current(total_power, 0.85).unwrap();
-    // 计算公式：I = 12000 / (1.732 * 380 * 0.85) ≈ 21.08A
-    assert!((total_current - 21.08).abs() < 0.01);
+    // 计算公式：I = 12000 / (1.732 * 380 * 0.85) ≈ 21.45A
+    assert!((total_current - 21.45).abs() < 0.01);
     
     // 测试进线电流计算（标准电流等级）
     let incoming_current = DistributionBoxCalculator::calculate_incoming_current(total_current).unwrap();
-    // 21.08 * 1.2 = 25.3A，应该选择32A
+    // 21.45 * 1.2 = 25.74A，应该选择32A
     assert_eq!(incoming_current, 32.0);
     
     // 测试错误处理：负值总功率
@@ -169,9 +174,9 @@ fn test_three_phase_balancing() {
         assert!(circuit.phase.is_some());
     }
     
-    // 计算各相负载总和（应该等于总功率30kW）
+    // 计算各相负载总和（应该等于总功率40kW）
     let total_phase_loads: f64 = phase_loads.iter().sum();
-    assert_eq!(total_phase_loads, 30.0);
+    assert_eq!(total_phase_loads, 40.0);
     
     // 计算平衡度（应该小于0.2，即20%）
     let balance_degree = DistributionBoxCalculator::calculate_balance_degree(&phase_loads);
@@ -182,6 +187,100 @@ fn test_three_phase_balancing() {
     assert_eq!(phase_counts.iter().sum::<usize>(), 6); // 总共6个回路
 }
 
+#[test]
+fn test_three_phase_circuits_spread_evenly() {
+    // 三相回路不分配相位，负荷均分到三相
+    let mut motor = CircuitInfo::new("circuit_1".to_string(), "水泵".to_string(), 15.0, 28.0);
+    motor.three_phase = true;
+    let mut circuits = vec![
+        motor,
+        CircuitInfo::new("circuit_2".to_string(), "照明".to_string(), 3.0, 14.0),
+    ];
+
+    let phase_loads = DistributionBoxCalculator::balance_three_phases(&mut circuits).unwrap();
+
+    assert_eq!(circuits[0].phase, None);
+    assert!(circuits[1].phase.is_some());
+    assert_eq!(phase_loads.iter().sum::<f64>(), 18.0);
+    assert_eq!(phase_loads.iter().filter(|&&load| load == 5.0).count(), 2);
+}
+
+#[test]
+fn test_pinned_phase_kept_by_balancing() {
+    // 手动指定相的回路保持原相，其负荷计入该相
+    let mut pinned = CircuitInfo::new("circuit_1".to_string(), "照明".to_string(), 4.0, 18.0);
+    pinned.pin_phase(Some('3'));
+    let mut circuits = vec![
+        pinned,
+        CircuitInfo::new("circuit_2".to_string(), "插座1".to_string(), 4.0, 18.0),
+        CircuitInfo::new("circuit_3".to_string(), "插座2".to_string(), 4.0, 18.0),
+    ];
+
+    let phase_loads = DistributionBoxCalculator::balance_three_phases(&mut circuits).unwrap();
+
+    assert_eq!(circuits[0].phase, Some('3'));
+    assert!(circuits[0].phase_pinned);
+    assert!(circuits[1..].iter().all(|c| c.phase != Some('3') && !c.phase_pinned));
+    assert_eq!(phase_loads, [4.0, 4.0, 4.0]);
+}
+
+#[test]
+fn test_pinned_equipment_kept_by_selection() {
+    // 未指定的回路按当前电流重新选型，手动指定的断路器和线缆保持不变，仅更新线缆长度
+    let mut pinned = CircuitInfo::new("circuit_1".to_string(), "照明".to_string(), 4.0, 40.0);
+    pinned.length = Some(25.0);
+    pinned.pin_equipment(Breaker::default(), CableInfo { size: "2.5mm²".to_string(), ..Default::default() });
+    let mut auto = CircuitInfo::new("circuit_2".to_string(), "插座".to_string(), 2.0, 9.0);
+    auto.components.push(ElectricComponent::Breaker(Breaker::default()));
+    auto.cable = Some(CableInfo { size: "2.5mm²".to_string(), ..Default::default() });
+    auto.current = 40.0;
+    let mut circuits = vec![pinned, auto];
+
+    DistributionBoxCalculator::select_circuit_equipment(&mut circuits);
+    let (log, errors) = DistributionBoxCalculator::coordinate_circuit_cables(&mut circuits);
+
+    let cable = circuits[0].cable.as_ref().unwrap();
+    assert_eq!((cable.size.as_str(), cable.length), ("2.5mm²", 25.0));
+    assert!(errors.is_empty());
+    assert!(log.iter().any(|l| l.starts_with("照明: 手动指定的线缆")));
+    assert!(circuits[1].find_breaker().unwrap().rated_current.to_f64() >= 44.0);
+    assert_ne!(circuits[1].cable.as_ref().unwrap().size, "2.5mm²");
+}
+
+#[test]
+fn test_undersized_pinned_breaker_is_box_error() {
+    // 手动指定的断路器整定电流小于计算电流（Ib > In）时记为配电箱错误
+    let mut box_node = DistributionBoxNodeUI::default();
+    let mut circuit = CircuitInfo::new("circuit_1".to_string(), "空调".to_string(), 8.0, 40.0);
+    circuit.pin_equipment(
+        Breaker { rated_current: SettingValue::A16, ..Default::default() },
+        CableInfo { size: "10mm²".to_string(), ..Default::default() },
+    );
+    assert!(box_node.add_circuit(circuit));
+
+    assert!(box_node.errors.iter().any(|e| e.starts_with("空调: 计算电流Ib=") && e.contains("In=16A")));
+}
+
+#[test]
+fn test_total_current_from_demand_power() {
+    // 总电流按ΣKx·Pe及综合功率因数计算：Pjs = 0.8×10 + 5 = 13kW，Qjs = 8×0.75 + 0 = 6kvar
+    let mut box_node = DistributionBoxNodeUI::default();
+    let mut motor = CircuitInfo::new("circuit_1".to_string(), "风机".to_string(), 10.0, 19.0);
+    motor.demand_coefficient = 0.8;
+    motor.power_factor = 0.8;
+    let mut heater = CircuitInfo::new("circuit_2".to_string(), "电加热".to_string(), 5.0, 7.6);
+    heater.power_factor = 1.0;
+    heater.three_phase = true;
+    assert!(box_node.add_circuit(motor));
+    assert!(box_node.add_circuit(heater));
+
+    let power_factor = DistributionBoxCalculator::combined_power_factor(&box_node.data.circuits).unwrap();
+    assert!((power_factor - 13.0 / 13f64.hypot(6.0)).abs() < 1e-9);
+    let expected = DistributionBoxCalculator::calculate_total_current(13.0, power_factor).unwrap();
+    assert!((box_node.data.total_current - expected).abs() < 1e-9);
+    assert_eq!(box_node.data.total_power, 15.0);
+}
+
 #[test]
 fn test_distribution_box_node_recalculation() {
     // 测试配电箱节点的完整计算流程
@@ -208,11 +307,11 @@ fn test_distribution_box_node_recalculation() {
     assert_eq!(box_node.data.total_power, 21.0); // 5+3+7+4+2=21kW
     
     // 验证总电流（基于21kW，功率因数0.85）
-    // 计算值应为：21000 / (1.732 * 380 * 0.85) ≈ 36.89A
-    assert!((box_node.data.total_current - 36.89).abs() < 0.01);
+    // 计算值应为：21000 / (1.732 * 380 * 0.85) ≈ 37.54A
+    assert!((box_node.data.total_current - 37.54).abs() < 0.01);
     
     // 验证进线电流（1.2倍总电流，向上取标准值）
-    // 36.89 * 1.2 = 44.27A，应选择50A
+    // 37.54 * 1.2 = 45.05A，应选择50A
     assert_eq!(box_node.data.incoming_current, 50.0);
     
     // 验证所有回路都有编号
@@ -242,28 +341,34 @@ fn test_distribution_box_template() {
     // 测试配电箱节点模板
     let template = DistributionBoxTemplate;
     
+    let mut user_state = EditorState;
+
     // 验证模板属性
-    assert_eq!(template.node_finder_label(), "配电箱");
-    assert_eq!(template.node_category(), "电气节点");
-    assert_eq!(template.node_name(), "配电箱");
-    
+    assert_eq!(template.node_finder_label(&mut user_state), "配电箱");
+    assert_eq!(template.node_graph_label(&mut user_state), "配电箱");
+
     // 验证类别
-    let categories = template.node_finder_categories();
+    let categories = template.node_finder_categories(&mut user_state);
     assert!(categories.contains(&"电气节点"));
-    
+
     // 验证输入输出端口
-    let input_types = template.input_types();
-    assert_eq!(input_types.len(), 5);
-    assert!(input_types.iter().any(|(name, _)| *name == "配电箱名称"));
-    assert!(input_types.iter().any(|(name, _)| *name == "所在楼层"));
-    assert!(input_types.iter().any(|(name, _)| *name == "回路输入1"));
-    
-    let output_types = template.output_types();
-    assert_eq!(output_types.len(), 4);
-    assert!(output_types.iter().any(|(name, _)| *name == "配电箱数据"));
-    assert!(output_types.iter().any(|(name, _)| *name == "总电流"));
-    assert!(output_types.iter().any(|(name, _)| *name == "总功率"));
-    assert!(output_types.iter().any(|(name, _)| *name == "进线电流"));
+    let mut graph = Graph::default();
+    let node_id = graph.add_node("配电箱".to_string(), DistributionBoxNodeUI::default(), |_graph, _node_id| {});
+    template.build_node(&mut graph, &mut user_state, node_id);
+    let node = &graph[node_id];
+
+    let input_names: Vec<&str> = node.inputs.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(input_names.len(), 5);
+    assert!(input_names.contains(&"配电箱名称"));
+    assert!(input_names.contains(&"所在楼层"));
+    assert!(input_names.contains(&"回路输入1"));
+
+    let output_names: Vec<&str> = node.outputs.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(output_names.len(), 4);
+    assert!(output_names.contains(&"配电箱数据"));
+    assert!(output_names.contains(&"总电流"));
+    assert!(output_names.contains(&"总功率"));
+    assert!(output_names.contains(&"进线电流"));
 }
 
 #[test]
@@ -277,15 +382,15 @@ fn test_edge_cases() {
     assert_eq!(box_node.data.total_current, 0.0);
     assert_eq!(box_node.data.incoming_current, 0.0);
     
-    // 2. 添加零功率回路
+    // 2. 零功率回路不通过校验，不会加入配电箱
     let zero_power_circuit = CircuitInfo::new(
         "circuit_zero".to_string(),
         "零功率回路".to_string(),
         0.0,
         0.0
     );
-    assert!(box_node.add_circuit(zero_power_circuit));
-    assert_eq!(box_node.data.circuits.len(), 1);
+    assert!(!box_node.add_circuit(zero_power_circuit));
+    assert!(box_node.data.circuits.is_empty());
     assert_eq!(box_node.data.total_power, 0.0);
     
     // 3. 测试极端大功率
@@ -0,0 +1,315 @@
+//! 电气火灾监控点位规划模块
+//!
+//! 本模块根据配电箱进线及出线回路的电流，确定需要设置剩余电流互感器和测温探测器的位置，
+//! 统计每个配电箱的探测器数量，分配总线地址，并按楼层生成探测器点位表。
+
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+
+use crate::editor::business::building::level_name;
+use crate::editor::business::BoxData;
+use crate::editor::business::box_modules::{BoxModule, ModuleKind};
+
+/// 探测器类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DetectorKind {
+    ResidualCurrent, // 剩余电流互感器
+    Temperature,     // 测温探测器
+}
+
+impl DetectorKind {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            DetectorKind::ResidualCurrent => "剩余电流式",
+            DetectorKind::Temperature => "测温式",
+        }
+    }
+}
+
+/// 单个电气火灾探测器
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElectricalFireDetector {
+    /// 监测的回路ID，None表示监测配电箱进线
+    pub circuit_id: Option<String>,
+    /// 探测器类型
+    pub kind: DetectorKind,
+    /// 总线地址，None表示尚未分配
+    pub address: Option<u32>,
+}
+
+/// 电气火灾监控点位规划参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElectricalFireMonitoringConfig {
+    /// 进线设置剩余电流互感器的电流阈值（A）
+    pub incoming_residual_threshold: f64,
+    /// 出线回路设置剩余电流互感器的电流阈值（A）
+    pub outgoing_residual_threshold: f64,
+    /// 设置测温探测器的电流阈值（A）
+    pub temperature_threshold: f64,
+}
+
+impl Default for ElectricalFireMonitoringConfig {
+    fn default() -> Self {
+        Self {
+            incoming_residual_threshold: 63.0,
+            outgoing_residual_threshold: 32.0,
+            temperature_threshold: 250.0,
+        }
+    }
+}
+
+/// 电气火灾监控模块
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ElectricalFireMonitoringModule {
+    /// 探测器列表
+    pub detectors: Vec<ElectricalFireDetector>,
+}
+
+impl ElectricalFireMonitoringModule {
+    /// 根据规划参数为配电箱生成探测器
+    ///
+    /// # 参数
+    /// * `box_data` - 配电箱数据
+    /// * `config` - 规划参数
+    ///
+    /// # 返回值
+    /// 无需设置探测器时返回None
+    pub fn plan(box_data: &BoxData, config: &ElectricalFireMonitoringConfig) -> Option<Self> {
+        let mut detectors = Vec::new();
+
+        // 进线：按进线保护整定值判断
+        if box_data.incoming_current >= config.incoming_residual_threshold {
+            detectors.push(ElectricalFireDetector { circuit_id: None, kind: DetectorKind::ResidualCurrent, address: None });
+        }
+        if box_data.incoming_current >= config.temperature_threshold {
+            detectors.push(ElectricalFireDetector { circuit_id: None, kind: DetectorKind::Temperature, address: None });
+        }
+
+        // 出线回路：按回路计算电流判断
+        for circuit in &box_data.circuits {
+            if circuit.current >= config.outgoing_residual_threshold {
+                detectors.push(ElectricalFireDetector {
+                    circuit_id: Some(circuit.circuit_id.clone()),
+                    kind: DetectorKind::ResidualCurrent,
+                    address: None,
+                });
+            }
+            if circuit.current >= config.temperature_threshold {
+                detectors.push(ElectricalFireDetector {
+                    circuit_id: Some(circuit.circuit_id.clone()),
+                    kind: DetectorKind::Temperature,
+                    address: None,
+                });
+            }
+        }
+
+        if detectors.is_empty() {
+            None
+        } else {
+            Some(Self { detectors })
+        }
+    }
+
+    /// 探测器总数
+    pub fn detector_count(&self) -> usize {
+        self.detectors.len()
+    }
+
+    /// 指定类型的探测器数量
+    pub fn count_of(&self, kind: DetectorKind) -> usize {
+        self.detectors.iter().filter(|d| d.kind == kind).count()
+    }
+
+    /// 已分配的地址范围
+    pub fn address_range(&self) -> Option<(u32, u32)> {
+        let addresses = self.detectors.iter().filter_map(|d| d.address);
+        let min = addresses.clone().min()?;
+        let max = addresses.max()?;
+        Some((min, max))
+    }
+}
+
+/// 配电箱探测器汇总
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxDetectorSummary {
+    pub box_name: String,
+    pub residual_current_count: usize,
+    pub temperature_count: usize,
+    pub address_range: Option<(u32, u32)>,
+}
+
+/// 楼层探测器点位表
+#[derive(Debug, Clone, PartialEq)]
+pub struct FloorDetectorSchedule {
+    pub floor: i32,
+    pub boxes: Vec<BoxDetectorSummary>,
+}
+
+impl FloorDetectorSchedule {
+    /// 该楼层探测器总数
+    pub fn detector_count(&self) -> usize {
+        self.boxes.iter().map(|b| b.residual_current_count + b.temperature_count).sum()
+    }
+}
+
+/// 电气火灾监控点位规划器
+pub struct ElectricalFirePlanner;
+
+impl ElectricalFirePlanner {
+    /// 为配电箱规划电气火灾监控模块
+    ///
+    /// 重新规划时保留相同位置探测器已分配的地址
+    ///
+    /// # 参数
+    /// * `box_data` - 配电箱数据
+    /// * `config` - 规划参数
+    pub fn plan_box(box_data: &mut BoxData, config: &ElectricalFireMonitoringConfig) {
+        let previous = box_data.electrical_fire_monitoring().cloned();
+        let mut module = ElectricalFireMonitoringModule::plan(box_data, config);
+
+        if let (Some(module), Some(previous)) = (module.as_mut(), previous.as_ref()) {
+            for detector in &mut module.detectors {
+                detector.address = previous
+                    .detectors
+                    .iter()
+                    .find(|d| d.circuit_id == detector.circuit_id && d.kind == detector.kind)
+                    .and_then(|d| d.address);
+            }
+        }
+
+        match module {
+            Some(module) => box_data.add_module(BoxModule::ElectricalFireMonitoring(module)),
+            None => {
+                box_data.remove_module(ModuleKind::ElectricalFireMonitoring);
+            }
+        }
+    }
+
+    /// 为所有探测器分配总线地址
+    ///
+    /// 按楼层、配电箱名称排序后从`start_address`起依次编址，每个探测器占一个地址
+    ///
+    /// # 返回值
+    /// 返回下一个可用地址
+    pub fn assign_addresses(boxes: &mut [&mut BoxData], start_address: u32) -> u32 {
+        boxes.sort_by(|a, b| a.floor.cmp(&b.floor).then_with(|| a.name.cmp(&b.name)));
+
+        let mut address = start_address;
+        for box_data in boxes.iter_mut() {
+            if let Some(module) = box_data.electrical_fire_monitoring_mut() {
+                for detector in &mut module.detectors {
+                    detector.address = Some(address);
+                    address += 1;
+                }
+            }
+        }
+        address
+    }
+
+    /// 按楼层生成探测器点位表
+    ///
+    /// # 参数
+    /// * `boxes` - 配电箱数据集合
+    ///
+    /// # 返回值
+    /// 按楼层升序排列的点位表
+    pub fn detector_schedule(boxes: &[&BoxData]) -> Vec<FloorDetectorSchedule> {
+        let mut floors: BTreeMap<i32, Vec<BoxDetectorSummary>> = BTreeMap::new();
+
+        for box_data in boxes {
+            if let Some(module) = box_data.electrical_fire_monitoring() {
+                floors.entry(box_data.floor).or_default().push(BoxDetectorSummary {
+                    box_name: box_data.name.clone(),
+                    residual_current_count: module.count_of(DetectorKind::ResidualCurrent),
+                    temperature_count: module.count_of(DetectorKind::Temperature),
+                    address_range: module.address_range(),
+                });
+            }
+        }
+
+        floors
+            .into_iter()
+            .map(|(floor, mut boxes)| {
+                boxes.sort_by(|a, b| a.box_name.cmp(&b.box_name));
+                FloorDetectorSchedule { floor, boxes }
+            })
+            .collect()
+    }
+
+    /// 生成文本格式的点位表
+    pub fn schedule_to_text(schedule: &[FloorDetectorSchedule]) -> String {
+        let mut text = String::from("电气火灾监控探测器点位表\n");
+        for floor in schedule {
+            text.push_str(&format!("楼层{}：共{}个探测器\n", level_name(floor.floor), floor.detector_count()));
+            for summary in &floor.boxes {
+                let range = summary
+                    .address_range
+                    .map_or("未分配".to_string(), |(min, max)| format!("{}-{}", min, max));
+                text.push_str(&format!(
+                    "  {}：剩余电流式{}个，测温式{}个，地址{}\n",
+                    summary.box_name, summary.residual_current_count, summary.temperature_count, range
+                ));
+            }
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::business::CircuitInfo;
+
+    fn sample_box(name: &str, floor: i32, incoming_current: f64, circuit_currents: &[f64]) -> BoxData {
+        let mut box_data = BoxData::new(name.to_string(), floor);
+        box_data.incoming_current = incoming_current;
+        for (i, current) in circuit_currents.iter().enumerate() {
+            box_data.circuits.push(CircuitInfo::new(format!("c{}", i + 1), format!("回路{}", i + 1), 5.0, *current));
+        }
+        box_data
+    }
+
+    #[test]
+    fn test_plan_by_current_threshold() {
+        let config = ElectricalFireMonitoringConfig::default();
+        let box_data = sample_box("AP1", 1, 400.0, &[10.0, 40.0, 260.0]);
+
+        let module = ElectricalFireMonitoringModule::plan(&box_data, &config).unwrap();
+        // 进线：剩余电流+测温；c2：剩余电流；c3：剩余电流+测温
+        assert_eq!(module.count_of(DetectorKind::ResidualCurrent), 3);
+        assert_eq!(module.count_of(DetectorKind::Temperature), 2);
+    }
+
+    #[test]
+    fn test_small_box_needs_no_module() {
+        let config = ElectricalFireMonitoringConfig::default();
+        let mut box_data = sample_box("AL1", 1, 32.0, &[10.0, 16.0]);
+
+        ElectricalFirePlanner::plan_box(&mut box_data, &config);
+        assert!(box_data.electrical_fire_monitoring().is_none());
+        assert!(!box_data.has_module(ModuleKind::ElectricalFireMonitoring));
+    }
+
+    #[test]
+    fn test_addresses_and_floor_schedule() {
+        let config = ElectricalFireMonitoringConfig::default();
+        let mut upper = sample_box("AP2", 2, 100.0, &[40.0]);
+        let mut lower = sample_box("AP1", 1, 100.0, &[]);
+        ElectricalFirePlanner::plan_box(&mut upper, &config);
+        ElectricalFirePlanner::plan_box(&mut lower, &config);
+
+        let next = ElectricalFirePlanner::assign_addresses(&mut [&mut upper, &mut lower], 1);
+        assert_eq!(next, 4);
+
+        let schedule = ElectricalFirePlanner::detector_schedule(&[&upper, &lower]);
+        assert_eq!(schedule.len(), 2);
+        assert_eq!(schedule[0].floor, 1);
+        assert_eq!(schedule[0].boxes[0].address_range, Some((1, 1)));
+        assert_eq!(schedule[1].boxes[0].address_range, Some((2, 3)));
+
+        // 重新规划保留已分配地址
+        ElectricalFirePlanner::plan_box(&mut upper, &config);
+        assert_eq!(upper.electrical_fire_monitoring().unwrap().address_range(), Some((2, 3)));
+    }
+}
@@ -0,0 +1,404 @@
+//! 干线馈线选择模块
+//!
+//! 本模块为配电干线选择馈线形式：小电流干线采用单根电缆，大电流干线在多根电缆并联
+//! （考虑并列敷设降低系数）与封闭式母线槽之间自动选择，并为母线槽按楼层配置插接箱。
+
+use std::collections::BTreeMap;
+
+use crate::core_lib::algorithm::protection_coordination::{
+    ConductorInsulation, CoordinationInput, ProtectionCoordinator,
+};
+use crate::editor::business::building::level_name;
+use crate::editor::business::text_circuit::{CoresNum, NeutralSizing, ParallelCables, PeSizing, PhaseLine};
+use crate::editor::business::{BoxData, DistributionBoxError};
+
+/// 干线保护电器额定电流等级(A)
+pub const FEEDER_RATINGS: [f64; 20] = [
+    63.0, 80.0, 100.0, 125.0, 160.0, 200.0, 250.0, 315.0, 400.0, 500.0, 630.0, 800.0, 1000.0, 1250.0, 1600.0,
+    2000.0, 2500.0, 3200.0, 4000.0, 5000.0,
+];
+
+/// 母线槽额定电流等级(A)
+pub const BUSWAY_RATINGS: [f64; 11] = [
+    400.0, 630.0, 800.0, 1000.0, 1250.0, 1600.0, 2000.0, 2500.0, 3150.0, 4000.0, 5000.0,
+];
+
+/// 插接箱额定电流等级(A)
+pub const TAP_OFF_RATINGS: [f64; 9] = [63.0, 100.0, 125.0, 160.0, 200.0, 250.0, 400.0, 500.0, 630.0];
+
+/// 电缆桥架单层紧靠敷设时的并列降低系数
+pub fn parallel_derating(runs: u32) -> f64 {
+    match runs {
+        0 | 1 => 1.0,
+        2 => 0.88,
+        3 => 0.82,
+        _ => 0.79,
+    }
+}
+
+/// 封闭式母线槽
+#[derive(Debug, Clone, PartialEq)]
+pub struct Busway {
+    /// 型号（如CMC）
+    pub model: String,
+    /// 额定电流(A)
+    pub rated_current: f64,
+}
+
+impl Busway {
+    /// 生成规格字符串，如"CMC-1600A"
+    pub fn to_spec_str(&self) -> String {
+        format!("{}-{}A", self.model, self.rated_current)
+    }
+}
+
+/// 馈线形式
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeederType {
+    /// 单根电缆
+    Cable(CoresNum),
+    /// 多根电缆并联
+    ParallelCables(ParallelCables),
+    /// 封闭式母线槽
+    Busway(Busway),
+}
+
+impl FeederType {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            FeederType::Cable(_) => "电缆",
+            FeederType::ParallelCables(_) => "并联电缆",
+            FeederType::Busway(_) => "母线槽",
+        }
+    }
+}
+
+/// 馈线选择参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeederSelectionConfig {
+    /// 超过该整定电流(A)时采用并联电缆或母线槽
+    pub parallel_threshold: f64,
+    /// 超过该整定电流(A)时直接采用母线槽
+    pub busway_threshold: f64,
+    /// 最大并联根数
+    pub max_parallel_runs: u32,
+    /// 并联电缆的最小单根截面
+    pub min_parallel_section: PhaseLine,
+    /// 电缆型号
+    pub cable_model: String,
+    /// 母线槽型号
+    pub busway_model: String,
+    /// 单根电缆PE线截面选择方法
+    pub pe_sizing: PeSizing,
+    /// 干线三次谐波含量，给定时单根电缆按谐波含量选择中性线截面，否则为4+1芯
+    pub third_harmonic: Option<f64>,
+}
+
+impl Default for FeederSelectionConfig {
+    fn default() -> Self {
+        Self {
+            parallel_threshold: 400.0,
+            busway_threshold: 1250.0,
+            max_parallel_runs: 4,
+            min_parallel_section: PhaseLine::M70,
+            cable_model: "YJV".to_string(),
+            busway_model: "CMC".to_string(),
+            pe_sizing: PeSizing::Table,
+            third_harmonic: None,
+        }
+    }
+}
+
+/// 馈线选择结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeederSelection {
+    /// 馈线形式
+    pub feeder: FeederType,
+    /// 馈线总载流量(A)，已计入并列降低系数
+    pub ampacity: f64,
+    /// 完整标注，如"YJV-2x(4x185+PE95)"、"CMC-1600A"
+    pub annotation: String,
+    /// 选择过程记录
+    pub log: Vec<String>,
+}
+
+/// 母线槽插接箱
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuswayTapOff {
+    /// 所在楼层号
+    pub floor: i32,
+    /// 插接箱额定电流(A)
+    pub rated_current: f64,
+    /// 由该插接箱供电的配电箱名称
+    pub boxes: Vec<String>,
+}
+
+impl BuswayTapOff {
+    /// 系统图标注
+    pub fn label(&self) -> String {
+        format!("{}F插接箱\n{}A", level_name(self.floor), self.rated_current)
+    }
+}
+
+/// 干线馈线选择器
+pub struct FeederSelector;
+
+impl FeederSelector {
+    /// 按计算电流选择干线保护电器额定电流
+    pub fn feeder_rating(current: f64) -> Option<f64> {
+        FEEDER_RATINGS.iter().copied().find(|&r| r >= current)
+    }
+
+    /// 按整定电流选择母线槽额定电流
+    pub fn busway_rating(current: f64) -> Option<f64> {
+        BUSWAY_RATINGS.iter().copied().find(|&r| r >= current)
+    }
+
+    /// 选择干线馈线形式
+    ///
+    /// 整定电流不超过`parallel_threshold`时采用单根电缆；超过时依次尝试2~4根电缆并联，
+    /// 每根电缆载流量乘并列降低系数；整定电流超过`busway_threshold`或并联电缆无法满足时采用母线槽。
+    ///
+    /// # 参数
+    /// * `design_current` - 干线计算电流Ib(A)
+    /// * `rated_current` - 干线保护电器额定电流In(A)
+    /// * `config` - 选择参数
+    ///
+    /// # 返回值
+    /// * `Ok(FeederSelection)` - 选择结果
+    /// * `Err(DistributionBoxError)` - 超出最大母线槽规格
+    pub fn select(
+        design_current: f64,
+        rated_current: f64,
+        config: &FeederSelectionConfig,
+    ) -> Result<FeederSelection, DistributionBoxError> {
+        if design_current <= 0.0 || rated_current < design_current {
+            return Err(DistributionBoxError::InvalidParameter(format!(
+                "干线计算电流{:.1}A与整定电流{:.0}A无效",
+                design_current, rated_current
+            )));
+        }
+
+        let mut log = Vec::new();
+
+        if rated_current <= config.parallel_threshold {
+            if let Some(selection) = Self::single_cable(design_current, rated_current, config, &mut log) {
+                return Ok(selection);
+            }
+        } else if rated_current <= config.busway_threshold {
+            if let Some(selection) = Self::parallel_cables(rated_current, config, &mut log) {
+                return Ok(selection);
+            }
+        } else {
+            log.push(format!("In={:.0}A超过{:.0}A，采用母线槽", rated_current, config.busway_threshold));
+        }
+
+        let rating = Self::busway_rating(rated_current).ok_or_else(|| {
+            DistributionBoxError::CalculationError(format!("In={:.0}A超出最大母线槽规格", rated_current))
+        })?;
+        let busway = Busway { model: config.busway_model.clone(), rated_current: rating };
+        log.push(format!("母线槽{}满足In={:.0}A", busway.to_spec_str(), rated_current));
+        Ok(FeederSelection {
+            annotation: busway.to_spec_str(),
+            ampacity: rating,
+            feeder: FeederType::Busway(busway),
+            log,
+        })
+    }
+
+    /// 单根电缆：按保护配合校验选择截面
+    fn single_cable(
+        design_current: f64,
+        rated_current: f64,
+        config: &FeederSelectionConfig,
+        log: &mut Vec<String>,
+    ) -> Option<FeederSelection> {
+        let input = CoordinationInput {
+            design_current,
+            rated_current,
+            tripping_factor: if rated_current <= 63.0 { 1.45 } else { 1.30 },
+            section: 1.5,
+            insulation: ConductorInsulation::Xlpe,
+            derating_factor: 1.0,
+            fault: None,
+        };
+        let result = match ProtectionCoordinator::coordinate(&input) {
+            Ok(result) => result,
+            Err(e) => {
+                log.push(format!("单根电缆不满足：{}", e));
+                return None;
+            }
+        };
+        let Some(phase) = PhaseLine::from_section(result.section) else {
+            log.push(format!("单根电缆截面{}mm²超出最大规格", result.section));
+            return None;
+        };
+
+        let cores = match Self::single_cable_cores(phase, design_current, config) {
+            Ok(cores) => cores,
+            Err(e) => {
+                log.push(format!("单根电缆{}mm²：{}", phase.to_str(), e));
+                return None;
+            }
+        };
+        log.push(format!("单根电缆{}-{}，Iz={:.0}A", config.cable_model, cores.to_spec_str(), result.iz));
+        Some(FeederSelection {
+            annotation: format!("{}-{}", config.cable_model, cores.to_spec_str()),
+            ampacity: result.iz,
+            feeder: FeederType::Cable(cores),
+            log: std::mem::take(log),
+        })
+    }
+
+    /// 单根电缆芯数：按三次谐波含量确定中性线截面，按PE线选择方法确定PE线截面，
+    /// PE线截面超过相线时逐级加大相线
+    fn single_cable_cores(phase: PhaseLine, design_current: f64, config: &FeederSelectionConfig) -> Result<CoresNum, &'static str> {
+        let mut last_error = "PE线截面超出最大规格";
+        for candidate in PhaseLine::all().into_iter().filter(|p| p.to_f64() >= phase.to_f64()) {
+            let neutral = match config.third_harmonic {
+                Some(third_harmonic) => Some(NeutralSizing::select(candidate, design_current, third_harmonic)?),
+                None => None,
+            };
+            let sized = neutral.map_or(candidate, |n| n.phase);
+            match config.pe_sizing.select(sized) {
+                Ok(pe) => return Ok(neutral.map_or(CoresNum::C4_1(sized, None, pe), |n| n.cores(pe))),
+                Err(e) => last_error = e,
+            }
+        }
+        Err(last_error)
+    }
+
+    /// 多根电缆并联：按并联根数由少到多、截面由小到大选择
+    fn parallel_cables(rated_current: f64, config: &FeederSelectionConfig, log: &mut Vec<String>) -> Option<FeederSelection> {
+        let sections = PhaseLine::all()
+            .into_iter()
+            .filter(|p| p.to_f64() >= config.min_parallel_section.to_f64());
+
+        for runs in 2..=config.max_parallel_runs.min(4) {
+            let derating = parallel_derating(runs);
+            for phase in sections.clone() {
+                let per_run = ProtectionCoordinator::ampacity(phase.to_f64(), ConductorInsulation::Xlpe).unwrap_or(0.0);
+                let total = per_run * derating * runs as f64;
+                if rated_current <= total {
+                    let cables = ParallelCables { runs, phase };
+                    log.push(format!(
+                        "{}根并联，单根Iz={:.0}A×降低系数{:.2}，总载流量{:.0}A ≥ In={:.0}A",
+                        runs, per_run, derating, total, rated_current
+                    ));
+                    return Some(FeederSelection {
+                        annotation: format!("{}-{}", config.cable_model, cables.to_spec_str()),
+                        ampacity: total,
+                        feeder: FeederType::ParallelCables(cables),
+                        log: std::mem::take(log),
+                    });
+                }
+            }
+            log.push(format!("{}根并联最大截面仍不满足In={:.0}A", runs, rated_current));
+        }
+        None
+    }
+
+    /// 为母线槽按楼层配置插接箱
+    ///
+    /// 同一楼层的配电箱由一个插接箱供电，插接箱额定电流按该楼层配电箱进线整定值之和选择
+    ///
+    /// # 参数
+    /// * `boxes` - 由母线槽供电的配电箱
+    ///
+    /// # 返回值
+    /// * `Ok(Vec<BuswayTapOff>)` - 按楼层升序排列的插接箱
+    /// * `Err(DistributionBoxError)` - 某楼层电流超出最大插接箱规格
+    pub fn tap_offs(boxes: &[&BoxData]) -> Result<Vec<BuswayTapOff>, DistributionBoxError> {
+        let mut floors: BTreeMap<i32, Vec<&BoxData>> = BTreeMap::new();
+        for box_data in boxes {
+            floors.entry(box_data.floor).or_default().push(box_data);
+        }
+
+        floors
+            .into_iter()
+            .map(|(floor, boxes)| {
+                let current: f64 = boxes.iter().map(|b| b.incoming_current.max(b.total_current)).sum();
+                let rated_current = TAP_OFF_RATINGS.iter().copied().find(|&r| r >= current).ok_or_else(|| {
+                    DistributionBoxError::InvalidParameter(format!(
+                        "{}层插接箱电流{:.0}A超出最大插接箱规格{:.0}A",
+                        level_name(floor),
+                        current,
+                        TAP_OFF_RATINGS[TAP_OFF_RATINGS.len() - 1]
+                    ))
+                })?;
+                Ok(BuswayTapOff {
+                    floor,
+                    rated_current,
+                    boxes: boxes.iter().map(|b| b.name.clone()).collect(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_cable_feeder() {
+        let selection = FeederSelector::select(150.0, 200.0, &FeederSelectionConfig::default()).unwrap();
+        assert!(matches!(selection.feeder, FeederType::Cable(_)));
+        assert_eq!(selection.annotation, "YJV-4x70+PE35");
+    }
+
+    #[test]
+    fn test_single_cable_pe_and_neutral_sizing() {
+        // 绝热方程：S = 8000×√0.4/143 ≈ 35.4mm²，PE取50
+        let mut config = FeederSelectionConfig {
+            pe_sizing: PeSizing::Adiabatic { fault_current: 8000.0, disconnection_time: 0.4, k: 143.0 },
+            ..FeederSelectionConfig::default()
+        };
+        let selection = FeederSelector::select(150.0, 200.0, &config).unwrap();
+        assert_eq!(selection.annotation, "YJV-4x70+PE50");
+
+        // 三次谐波含量低时中性线减小截面，高时按中性线电流加大截面
+        config.pe_sizing = PeSizing::Table;
+        config.third_harmonic = Some(0.1);
+        let selection = FeederSelector::select(150.0, 200.0, &config).unwrap();
+        assert_eq!(selection.annotation, "YJV-3x70+2x35");
+        config.third_harmonic = Some(0.5);
+        let selection = FeederSelector::select(150.0, 200.0, &config).unwrap();
+        assert!(matches!(selection.feeder, FeederType::Cable(CoresNum::C4_1(phase, None, _)) if phase.to_f64() > 70.0));
+    }
+
+    #[test]
+    fn test_parallel_cable_feeder() {
+        // 630A：2根并联 2×362×0.88=637A（185mm²）
+        let selection = FeederSelector::select(520.0, 630.0, &FeederSelectionConfig::default()).unwrap();
+        assert_eq!(selection.annotation, "YJV-2x(4x185+PE95)");
+
+        // 1000A：2根240mm²仅746A，3根并联 3×424×0.82=1043A
+        let selection = FeederSelector::select(900.0, 1000.0, &FeederSelectionConfig::default()).unwrap();
+        assert_eq!(selection.annotation, "YJV-3x(4x240+PE120)");
+    }
+
+    #[test]
+    fn test_busway_feeder_and_tap_offs() {
+        let selection = FeederSelector::select(1300.0, 1600.0, &FeederSelectionConfig::default()).unwrap();
+        assert_eq!(selection.annotation, "CMC-1600A");
+
+        let mut a = BoxData::new("1AL1".to_string(), 1);
+        a.incoming_current = 100.0;
+        let mut b = BoxData::new("1AP1".to_string(), 1);
+        b.incoming_current = 63.0;
+        let mut c = BoxData::new("2AL1".to_string(), 2);
+        c.incoming_current = 80.0;
+
+        let tap_offs = FeederSelector::tap_offs(&[&c, &a, &b]).unwrap();
+        assert_eq!(tap_offs.len(), 2);
+        assert_eq!(tap_offs[0].floor, 1);
+        assert_eq!(tap_offs[0].rated_current, 200.0);
+        assert_eq!(tap_offs[1].boxes, vec!["2AL1".to_string()]);
+
+        // 单层电流超出最大插接箱规格时报错，不按630A截断
+        c.incoming_current = 800.0;
+        let err = FeederSelector::tap_offs(&[&c, &a, &b]).unwrap_err();
+        assert!(err.to_string().contains("2层插接箱电流800A"));
+    }
+}
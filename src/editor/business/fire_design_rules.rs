@@ -0,0 +1,272 @@
+//! 消防负荷设计规则校验模块
+//!
+//! 本模块根据配电箱及回路的负荷分级，校验消防配电设计规则：
+//! 消防负荷应采用双电源供电并在末级配电箱设置双电源自动切换装置，
+//! 消防回路应采用耐火（NH/N型）电缆，消防回路不得设置剩余电流动作脱扣，
+//! 非消防负荷应设置消防切断模块。违反的规则汇总为校验报告。
+
+use crate::editor::business::{BoxData, CircuitInfo};
+use crate::editor::business::distribution_box_parameters::IncomingType;
+
+/// 消防设计规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FireRule {
+    /// 消防负荷采用双电源供电，末级配电箱设置双电源自动切换装置
+    DualPowerWithAts,
+    /// 消防回路采用耐火电缆
+    FireResistantCable,
+    /// 消防回路不设剩余电流动作脱扣
+    NoRcdOnFireCircuit,
+    /// 非消防负荷设置消防切断
+    FireCutOffForNonFire,
+}
+
+impl FireRule {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            FireRule::DualPowerWithAts => "消防负荷双电源末端自动切换",
+            FireRule::FireResistantCable => "消防回路耐火电缆",
+            FireRule::NoRcdOnFireCircuit => "消防回路不设漏电脱扣",
+            FireRule::FireCutOffForNonFire => "非消防负荷消防切断",
+        }
+    }
+}
+
+/// 单条规则违反记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct FireRuleViolation {
+    /// 配电箱名称
+    pub box_name: String,
+    /// 回路ID，配电箱级别的违反为None
+    pub circuit_id: Option<String>,
+    /// 违反的规则
+    pub rule: FireRule,
+    /// 说明信息
+    pub message: String,
+}
+
+/// 消防设计校验报告
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FireComplianceReport {
+    /// 违反记录列表
+    pub violations: Vec<FireRuleViolation>,
+}
+
+impl FireComplianceReport {
+    /// 是否全部符合规则
+    pub fn is_compliant(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// 获取指定配电箱的违反记录
+    pub fn violations_for_box(&self, box_name: &str) -> Vec<&FireRuleViolation> {
+        self.violations.iter().filter(|v| v.box_name == box_name).collect()
+    }
+
+    /// 生成文本格式的报告
+    pub fn to_text(&self) -> String {
+        if self.is_compliant() {
+            return "消防设计规则校验通过".to_string();
+        }
+
+        let mut text = format!("消防设计规则校验发现{}项问题：\n", self.violations.len());
+        for (i, v) in self.violations.iter().enumerate() {
+            let location = match &v.circuit_id {
+                Some(id) => format!("{} / {}", v.box_name, id),
+                None => v.box_name.clone(),
+            };
+            text.push_str(&format!("{}. [{}] {}：{}\n", i + 1, v.rule.to_str(), location, v.message));
+        }
+        text
+    }
+}
+
+/// 判断线缆型号是否为耐火电缆（NH型或N型）
+///
+/// 依次去除阻燃/低烟无卤前缀（WDZ、WDZA~WDZC、ZR、ZA~ZC，可叠加并以"-"连接）后，以"N"开头
+/// （含"NH"）即认为是耐火电缆，如"NH-YJV"、"WDZN-YJY"、"ZRN-BV"、"WDZ-ZR-NH-YJV"
+///
+/// # 参数
+/// * `model` - 线缆型号
+///
+/// # 返回值
+/// 是耐火电缆返回true
+pub fn is_fire_resistant_cable(model: &str) -> bool {
+    let upper = model.trim().to_uppercase();
+    let mut rest = upper.as_str();
+    while let Some(stripped) = ["WDZA", "WDZB", "WDZC", "WDZ", "ZR", "ZA", "ZB", "ZC"].iter().find_map(|prefix| rest.strip_prefix(prefix)) {
+        rest = stripped.trim_start_matches('-');
+    }
+    rest.starts_with('N')
+}
+
+/// 消防设计规则校验器
+pub struct FireDesignRuleChecker;
+
+impl FireDesignRuleChecker {
+    /// 校验单个配电箱
+    ///
+    /// # 参数
+    /// * `box_data` - 配电箱数据
+    ///
+    /// # 返回值
+    /// 返回该配电箱的规则违反记录
+    pub fn check_box(box_data: &BoxData) -> Vec<FireRuleViolation> {
+        let mut violations = Vec::new();
+
+        // 消防负荷：双电源供电，末级配电箱双电源自动切换
+        if box_data.is_fire_load() && box_data.determine_incoming_type() != IncomingType::DualPower {
+            violations.push(FireRuleViolation {
+                box_name: box_data.name.clone(),
+                circuit_id: None,
+                rule: FireRule::DualPowerWithAts,
+                message: "消防负荷配电箱应采用双电源供电，并在末级配电箱设置双电源自动切换装置".to_string(),
+            });
+        }
+
+        // 非消防负荷：设置消防切断模块，且每个非消防回路设置分励脱扣
+        match box_data.fire_cut_off() {
+            None if box_data.has_non_fire_loads() => violations.push(FireRuleViolation {
+                box_name: box_data.name.clone(),
+                circuit_id: None,
+                rule: FireRule::FireCutOffForNonFire,
+                message: "配电箱含非消防负荷回路，应设置消防切断模块".to_string(),
+            }),
+            Some(module) => {
+                for circuit in box_data.circuits.iter().filter(|c| !c.is_fire_load() && !module.covers(&c.circuit_id)) {
+                    violations.push(FireRuleViolation {
+                        box_name: box_data.name.clone(),
+                        circuit_id: Some(circuit.circuit_id.clone()),
+                        rule: FireRule::FireCutOffForNonFire,
+                        message: format!("非消防回路'{}'未设置分励脱扣", circuit.name),
+                    });
+                }
+            }
+            None => {}
+        }
+
+        for circuit in box_data.circuits.iter().filter(|c| c.is_fire_load()) {
+            violations.extend(Self::check_fire_circuit(&box_data.name, circuit));
+        }
+
+        violations
+    }
+
+    /// 校验单个消防回路
+    fn check_fire_circuit(box_name: &str, circuit: &CircuitInfo) -> Vec<FireRuleViolation> {
+        let mut violations = Vec::new();
+
+        match &circuit.cable {
+            Some(cable) if is_fire_resistant_cable(&cable.model) => {}
+            Some(cable) => violations.push(FireRuleViolation {
+                box_name: box_name.to_string(),
+                circuit_id: Some(circuit.circuit_id.clone()),
+                rule: FireRule::FireResistantCable,
+                message: format!("回路'{}'线缆型号{}不是耐火电缆，应采用NH/N型", circuit.name, cable.model),
+            }),
+            None => violations.push(FireRuleViolation {
+                box_name: box_name.to_string(),
+                circuit_id: Some(circuit.circuit_id.clone()),
+                rule: FireRule::FireResistantCable,
+                message: format!("回路'{}'尚未选型线缆，消防回路应采用NH/N型耐火电缆", circuit.name),
+            }),
+        }
+
+        if circuit.has_rcd_trip() {
+            violations.push(FireRuleViolation {
+                box_name: box_name.to_string(),
+                circuit_id: Some(circuit.circuit_id.clone()),
+                rule: FireRule::NoRcdOnFireCircuit,
+                message: format!("回路'{}'为消防回路，剩余电流保护只应报警不应脱扣", circuit.name),
+            });
+        }
+
+        violations
+    }
+
+    /// 校验多个配电箱并生成报告
+    ///
+    /// # 参数
+    /// * `boxes` - 配电箱数据集合
+    ///
+    /// # 返回值
+    /// 返回消防设计校验报告
+    pub fn check_boxes(boxes: &[&BoxData]) -> FireComplianceReport {
+        FireComplianceReport {
+            violations: boxes.iter().flat_map(|b| Self::check_box(b)).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_lib::data_types::{Breaker, BreakerType, CableInfo, ElectricComponent, LoadClass, LoadLevel};
+    use crate::editor::business::box_modules::{BoxModule, ModuleKind};
+    use crate::editor::business::fire_power_monitoring::FireModuleGenerator;
+
+    fn fire_circuit(id: &str, cable_model: &str) -> CircuitInfo {
+        let mut circuit = CircuitInfo::new(id.to_string(), "消防风机".to_string(), 11.0, 22.0);
+        circuit.load_class = LoadClass::fire(LoadLevel::First);
+        circuit.cable = Some(CableInfo { model: cable_model.to_string(), ..Default::default() });
+        circuit
+    }
+
+    #[test]
+    fn test_fire_resistant_cable_models() {
+        assert!(is_fire_resistant_cable("NH-YJV"));
+        assert!(is_fire_resistant_cable("WDZN-YJY"));
+        assert!(is_fire_resistant_cable("ZRN-BV"));
+        assert!(!is_fire_resistant_cable("YJV"));
+        assert!(!is_fire_resistant_cable("WDZ-YJY"));
+        assert!(is_fire_resistant_cable("WDZ-ZR-NH-YJV"));
+        assert!(is_fire_resistant_cable("zr-wdzn-bv"));
+        assert!(is_fire_resistant_cable("WDZB-N-YJY"));
+        assert!(!is_fire_resistant_cable("WDZ-ZR-YJV"));
+    }
+
+    #[test]
+    fn test_compliant_fire_box() {
+        let mut box_data = BoxData::new("APE1".to_string(), 1);
+        box_data.add_module(BoxModule::default_for(ModuleKind::DualPowerSwitch));
+        box_data.circuits.push(fire_circuit("c1", "NH-YJV"));
+
+        assert!(FireDesignRuleChecker::check_box(&box_data).is_empty());
+    }
+
+    #[test]
+    fn test_fire_box_violations() {
+        let mut box_data = BoxData::new("APE2".to_string(), 1);
+        let mut circuit = fire_circuit("c1", "YJV");
+        circuit.components.push(ElectricComponent::Breaker(Breaker {
+            breaker_type: Some(BreakerType::RCBO),
+            ..Default::default()
+        }));
+        box_data.circuits.push(circuit);
+
+        let rules: Vec<FireRule> = FireDesignRuleChecker::check_box(&box_data)
+            .into_iter()
+            .map(|v| v.rule)
+            .collect();
+        assert!(rules.contains(&FireRule::DualPowerWithAts));
+        assert!(rules.contains(&FireRule::FireResistantCable));
+        assert!(rules.contains(&FireRule::NoRcdOnFireCircuit));
+    }
+
+    #[test]
+    fn test_non_fire_box_requires_cut_off() {
+        let mut box_data = BoxData::new("AL1".to_string(), 2);
+        box_data.circuits.push(CircuitInfo::new("c1".to_string(), "照明".to_string(), 2.0, 10.0));
+
+        let report = FireDesignRuleChecker::check_boxes(&[&box_data]);
+        assert_eq!(report.violations.len(), 1);
+        assert_eq!(report.violations[0].rule, FireRule::FireCutOffForNonFire);
+
+        FireModuleGenerator::auto_insert(&mut box_data);
+        assert!(FireDesignRuleChecker::check_boxes(&[&box_data]).is_compliant());
+
+        box_data.circuits.push(CircuitInfo::new("c2".to_string(), "插座".to_string(), 2.0, 10.0));
+        let report = FireDesignRuleChecker::check_boxes(&[&box_data]);
+        assert_eq!(report.violations[0].circuit_id.as_deref(), Some("c2"));
+    }
+}
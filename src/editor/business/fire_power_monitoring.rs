@@ -0,0 +1,227 @@
+//! 消防电源监测与消防切断模块
+//!
+//! 本模块定义配电箱内的消防电源监测模块（每路进线设置电压/电流传感器）和
+//! 消防切断模块（非消防回路设置分励脱扣），并根据负荷分级自动为配电箱插入模块、
+//! 分配总线地址，供消防电源监测干线图使用。
+
+use serde::{Serialize, Deserialize};
+
+use crate::editor::business::BoxData;
+use crate::editor::business::box_modules::{BoxModule, ModuleKind};
+use crate::editor::business::distribution_box_parameters::IncomingType;
+
+/// 进线传感器配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IncomingLineSensor {
+    /// 进线名称（如"主用进线"、"备用进线"）
+    pub line_label: String,
+    /// 是否设置电压传感器
+    pub voltage_sensor: bool,
+    /// 是否设置电流传感器
+    pub current_sensor: bool,
+}
+
+impl IncomingLineSensor {
+    /// 创建同时设置电压、电流传感器的进线配置
+    pub fn new(line_label: &str) -> Self {
+        Self {
+            line_label: line_label.to_string(),
+            voltage_sensor: true,
+            current_sensor: true,
+        }
+    }
+
+    /// 该进线的监测点数
+    pub fn point_count(&self) -> u32 {
+        self.voltage_sensor as u32 + self.current_sensor as u32
+    }
+}
+
+/// 消防电源监测模块
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FirePowerMonitoringModule {
+    /// 总线地址，None表示尚未分配
+    pub address: Option<u32>,
+    /// 各进线的传感器配置
+    pub incoming_lines: Vec<IncomingLineSensor>,
+}
+
+impl FirePowerMonitoringModule {
+    /// 根据配电箱进线类型生成监测模块
+    ///
+    /// 单电源进线设置一路传感器，双电源进线主用、备用各设置一路
+    pub fn for_box(box_data: &BoxData) -> Self {
+        let incoming_lines = match box_data.determine_incoming_type() {
+            IncomingType::SinglePower => vec![IncomingLineSensor::new("进线")],
+            IncomingType::DualPower => vec![
+                IncomingLineSensor::new("主用进线"),
+                IncomingLineSensor::new("备用进线"),
+            ],
+        };
+        Self { address: None, incoming_lines }
+    }
+
+    /// 模块监测点数
+    pub fn point_count(&self) -> u32 {
+        self.incoming_lines.iter().map(|l| l.point_count()).sum()
+    }
+}
+
+/// 消防切断模块
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FireCutOffModule {
+    /// 总线地址，None表示尚未分配
+    pub address: Option<u32>,
+    /// 设置分励脱扣的回路ID列表
+    pub shunt_trip_circuits: Vec<String>,
+}
+
+impl FireCutOffModule {
+    /// 为配电箱中所有非消防回路生成分励脱扣配置
+    pub fn for_box(box_data: &BoxData) -> Self {
+        Self {
+            address: None,
+            shunt_trip_circuits: box_data
+                .circuits
+                .iter()
+                .filter(|c| !c.is_fire_load())
+                .map(|c| c.circuit_id.clone())
+                .collect(),
+        }
+    }
+
+    /// 模块控制点数（每个分励脱扣回路一个控制点）
+    pub fn point_count(&self) -> u32 {
+        self.shunt_trip_circuits.len() as u32
+    }
+
+    /// 判断回路是否已设置分励脱扣
+    pub fn covers(&self, circuit_id: &str) -> bool {
+        self.shunt_trip_circuits.iter().any(|id| id == circuit_id)
+    }
+}
+
+/// 消防模块生成器
+pub struct FireModuleGenerator;
+
+impl FireModuleGenerator {
+    /// 根据负荷分级为配电箱自动插入或移除消防模块
+    ///
+    /// 消防负荷配电箱插入消防电源监测模块；含非消防回路的配电箱插入消防切断模块。
+    /// 已分配的地址在重新生成时保留。
+    ///
+    /// # 参数
+    /// * `box_data` - 配电箱数据
+    pub fn auto_insert(box_data: &mut BoxData) {
+        if box_data.is_fire_load() {
+            let mut module = FirePowerMonitoringModule::for_box(box_data);
+            module.address = box_data.fire_power_monitoring().and_then(|m| m.address);
+            box_data.add_module(BoxModule::FirePowerMonitoring(module));
+        } else {
+            box_data.remove_module(ModuleKind::FirePowerMonitoring);
+        }
+
+        if box_data.has_non_fire_loads() {
+            let mut module = FireCutOffModule::for_box(box_data);
+            module.address = box_data.fire_cut_off().and_then(|m| m.address);
+            box_data.add_module(BoxModule::FireCutOff(module));
+        } else {
+            box_data.remove_module(ModuleKind::FireCutOff);
+        }
+    }
+
+    /// 为消防模块分配总线地址
+    ///
+    /// 按楼层、配电箱名称排序后从`start_address`起依次编址，消防电源监测模块与
+    /// 消防切断模块各占一个地址
+    ///
+    /// # 参数
+    /// * `boxes` - 配电箱数据集合
+    /// * `start_address` - 起始地址
+    ///
+    /// # 返回值
+    /// 返回下一个可用地址
+    pub fn assign_addresses(boxes: &mut [&mut BoxData], start_address: u32) -> u32 {
+        boxes.sort_by(|a, b| a.floor.cmp(&b.floor).then_with(|| a.name.cmp(&b.name)));
+
+        let mut address = start_address;
+        for box_data in boxes.iter_mut() {
+            if let Some(module) = box_data.fire_power_monitoring_mut() {
+                module.address = Some(address);
+                address += 1;
+            }
+            if let Some(module) = box_data.fire_cut_off_mut() {
+                module.address = Some(address);
+                address += 1;
+            }
+        }
+        address
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_lib::data_types::{LoadClass, LoadLevel};
+    use crate::editor::business::CircuitInfo;
+    use crate::editor::business::main_system_node::MainSystemType;
+
+    fn circuit(id: &str, fire: bool) -> CircuitInfo {
+        let mut circuit = CircuitInfo::new(id.to_string(), format!("回路{}", id), 5.0, 10.0);
+        if fire {
+            circuit.load_class = LoadClass::fire(LoadLevel::First);
+        }
+        circuit
+    }
+
+    #[test]
+    fn test_dual_power_fire_box_gets_two_sensor_lines() {
+        let mut box_data = BoxData::new("APE1".to_string(), 1);
+        box_data.add_module(BoxModule::default_for(ModuleKind::DualPowerSwitch));
+        box_data.circuits.push(circuit("c1", true));
+
+        FireModuleGenerator::auto_insert(&mut box_data);
+
+        let module = box_data.fire_power_monitoring().unwrap();
+        assert_eq!(module.incoming_lines.len(), 2);
+        assert_eq!(module.point_count(), 4);
+        assert!(box_data.fire_cut_off().is_none());
+        assert!(box_data.participates_in(&MainSystemType::FirePowerMonitoring));
+    }
+
+    #[test]
+    fn test_shunt_trip_on_non_fire_circuits_only() {
+        let mut box_data = BoxData::new("AL1".to_string(), 1);
+        box_data.circuits.push(circuit("c1", false));
+        box_data.circuits.push(circuit("c2", false));
+
+        FireModuleGenerator::auto_insert(&mut box_data);
+
+        let module = box_data.fire_cut_off().unwrap();
+        assert_eq!(module.point_count(), 2);
+        assert!(module.covers("c1") && module.covers("c2"));
+        assert!(box_data.fire_power_monitoring().is_none());
+    }
+
+    #[test]
+    fn test_assign_addresses_and_keep_on_regenerate() {
+        let mut upper = BoxData::new("AL2".to_string(), 2);
+        upper.circuits.push(circuit("c1", false));
+        let mut lower = BoxData::new("APE1".to_string(), 1);
+        lower.add_module(BoxModule::default_for(ModuleKind::DualPowerSwitch));
+        lower.circuits.push(circuit("c2", true));
+        FireModuleGenerator::auto_insert(&mut upper);
+        FireModuleGenerator::auto_insert(&mut lower);
+
+        let next = FireModuleGenerator::assign_addresses(&mut [&mut upper, &mut lower], 1);
+        assert_eq!(next, 3);
+        assert_eq!(lower.fire_power_monitoring().unwrap().address, Some(1));
+        assert_eq!(upper.fire_cut_off().unwrap().address, Some(2));
+
+        upper.circuits.push(circuit("c3", false));
+        FireModuleGenerator::auto_insert(&mut upper);
+        let module = upper.fire_cut_off().unwrap();
+        assert_eq!(module.address, Some(2));
+        assert_eq!(module.point_count(), 2);
+    }
+}
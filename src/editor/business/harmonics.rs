@@ -0,0 +1,254 @@
+//! 谐波电流估算模块
+//!
+//! 本模块按设备类型（由回路用途推导）给出典型谐波电流含量，估算配电箱进线的
+//! 三次谐波含量、总谐波畸变率THDi和中性线电流，给出四芯电缆的载流量降低系数，
+//! 并标记需要加大中性线或设置谐波治理装置的配电箱。
+
+use crate::editor::business::auto_connection::equipment_type_for_purpose;
+use crate::editor::business::BoxData;
+
+/// 单类负荷的谐波电流含量（各次谐波电流与基波电流之比）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HarmonicProfile {
+    pub third: f64,   // 3次
+    pub fifth: f64,   // 5次
+    pub seventh: f64, // 7次
+}
+
+impl HarmonicProfile {
+    /// 设备类型对应的典型谐波含量
+    ///
+    /// 照明设备按LED驱动电源，空调设备与动力设备按六脉波变频器。
+    /// 插座设备为计算机、充电器等单相整流负荷与电热、电机等线性负荷的混合，
+    /// 无功率因数校正的整流负荷三次谐波可达60%以上，但混合后在配电箱进线处
+    /// 明显降低，按整流负荷约占一半取35%
+    pub fn for_equipment_type(equipment_type: &str) -> Self {
+        match equipment_type {
+            "照明设备" => Self { third: 0.30, fifth: 0.15, seventh: 0.08 },
+            "插座设备" => Self { third: 0.35, fifth: 0.25, seventh: 0.10 },
+            "空调设备" => Self { third: 0.05, fifth: 0.35, seventh: 0.20 },
+            "动力设备" => Self { third: 0.02, fifth: 0.30, seventh: 0.12 },
+            _ => Self { third: 0.05, fifth: 0.05, seventh: 0.03 },
+        }
+    }
+
+    /// 总谐波畸变率THDi
+    pub fn thd_i(&self) -> f64 {
+        (self.third.powi(2) + self.fifth.powi(2) + self.seventh.powi(2)).sqrt()
+    }
+}
+
+/// 谐波治理建议
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HarmonicRecommendation {
+    /// 中性线与相线等截面
+    FullNeutral,
+    /// 按中性线电流加大中性线截面
+    OversizedNeutral,
+    /// 设置有源滤波等谐波治理装置
+    HarmonicFilter,
+}
+
+impl HarmonicRecommendation {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            HarmonicRecommendation::FullNeutral => "中性线与相线等截面",
+            HarmonicRecommendation::OversizedNeutral => "加大中性线截面",
+            HarmonicRecommendation::HarmonicFilter => "设置谐波治理装置",
+        }
+    }
+}
+
+/// 谐波分析参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct HarmonicConfig {
+    /// 建议设置谐波治理装置的THDi阈值
+    pub filter_thd_threshold: f64,
+}
+
+impl Default for HarmonicConfig {
+    fn default() -> Self {
+        Self { filter_thd_threshold: 0.40 }
+    }
+}
+
+/// 配电箱谐波分析结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxHarmonicReport {
+    pub box_name: String,
+    /// 进线相电流(A)
+    pub phase_current: f64,
+    /// 三次谐波含量（按回路电流加权）
+    pub third_harmonic: f64,
+    /// 总谐波畸变率（按回路电流加权）
+    pub thd_i: f64,
+    /// 中性线电流(A)
+    pub neutral_current: f64,
+    /// 四芯电缆载流量降低系数
+    pub derating_factor: f64,
+    /// 治理建议
+    pub recommendations: Vec<HarmonicRecommendation>,
+}
+
+impl BoxHarmonicReport {
+    /// 是否需要关注（加大中性线或设置谐波治理装置）
+    pub fn is_flagged(&self) -> bool {
+        self.recommendations.iter().any(|r| {
+            matches!(r, HarmonicRecommendation::OversizedNeutral | HarmonicRecommendation::HarmonicFilter)
+        })
+    }
+
+    /// 四芯电缆选择截面用的计算电流(A)
+    ///
+    /// 三次谐波含量超过33%时按中性线电流选择，否则按相电流选择，均再乘以载流量降低系数校验
+    pub fn design_current(&self) -> f64 {
+        if self.third_harmonic > 0.33 {
+            self.neutral_current.max(self.phase_current)
+        } else {
+            self.phase_current
+        }
+    }
+
+    /// 单行文本摘要
+    pub fn summary(&self) -> String {
+        let advice: Vec<&str> = self.recommendations.iter().map(|a| a.to_str()).collect();
+        format!(
+            "THDi={:.0}%，三次谐波{:.0}%，中性线电流{:.1}A，降低系数{:.2}，建议：{}",
+            self.thd_i * 100.0,
+            self.third_harmonic * 100.0,
+            self.neutral_current,
+            self.derating_factor,
+            if advice.is_empty() { "无".to_string() } else { advice.join("、") }
+        )
+    }
+}
+
+/// 谐波分析器
+pub struct HarmonicAnalyzer;
+
+impl HarmonicAnalyzer {
+    /// 四芯电缆（中性线载流）的载流量降低系数
+    ///
+    /// 三次谐波含量 ≤ 15%取1.0；15% ~ 45%取0.86（超过33%时按中性线电流选择截面）；大于45%按中性线电流选择截面，取1.0
+    pub fn four_core_derating(third_harmonic: f64) -> f64 {
+        if third_harmonic <= 0.15 || third_harmonic > 0.45 {
+            1.0
+        } else {
+            0.86
+        }
+    }
+
+    /// 分析单个配电箱
+    ///
+    /// # 参数
+    /// * `box_data` - 配电箱数据
+    /// * `config` - 分析参数
+    ///
+    /// # 返回值
+    /// 返回配电箱谐波分析结果
+    pub fn analyze_box(box_data: &BoxData, config: &HarmonicConfig) -> BoxHarmonicReport {
+        let total: f64 = box_data.circuits.iter().map(|c| c.current).sum();
+        let (third_harmonic, thd_i) = if total > 0.0 {
+            box_data.circuits.iter().fold((0.0, 0.0), |(third, thd), c| {
+                let profile = HarmonicProfile::for_equipment_type(&equipment_type_for_purpose(Some(c.purpose_or_name())));
+                let weight = c.current / total;
+                (third + profile.third * weight, thd + profile.thd_i() * weight)
+            })
+        } else {
+            (0.0, 0.0)
+        };
+
+        // 三相平衡时三次谐波电流在中性线中叠加
+        let phase_current = box_data.total_current;
+        let neutral_current = 3.0 * third_harmonic * phase_current;
+
+        let mut recommendations = Vec::new();
+        if third_harmonic > 0.33 {
+            recommendations.push(HarmonicRecommendation::OversizedNeutral);
+        } else if third_harmonic > 0.15 {
+            recommendations.push(HarmonicRecommendation::FullNeutral);
+        }
+        if thd_i > config.filter_thd_threshold {
+            recommendations.push(HarmonicRecommendation::HarmonicFilter);
+        }
+
+        BoxHarmonicReport {
+            box_name: box_data.name.clone(),
+            phase_current,
+            third_harmonic,
+            thd_i,
+            neutral_current,
+            derating_factor: Self::four_core_derating(third_harmonic),
+            recommendations,
+        }
+    }
+
+    /// 分析多个配电箱，返回需要关注的配电箱
+    pub fn flagged_boxes(boxes: &[&BoxData], config: &HarmonicConfig) -> Vec<BoxHarmonicReport> {
+        boxes
+            .iter()
+            .map(|b| Self::analyze_box(b, config))
+            .filter(|r| r.is_flagged())
+            .collect()
+    }
+
+    /// 生成文本格式的谐波分析报告
+    pub fn reports_to_text(reports: &[BoxHarmonicReport]) -> String {
+        let mut text = String::from("谐波电流估算\n");
+        for r in reports {
+            text.push_str(&format!("{}：{}\n", r.box_name, r.summary()));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::business::CircuitInfo;
+
+    fn sample_box(name: &str, circuits: &[(&str, f64)]) -> BoxData {
+        let mut box_data = BoxData::new(name.to_string(), 1);
+        for (i, (purpose, current)) in circuits.iter().enumerate() {
+            let mut circuit = CircuitInfo::new(format!("c{}", i + 1), format!("回路{}", i + 1), 1.0, *current);
+            circuit.purpose = Some(purpose.to_string());
+            box_data.circuits.push(circuit);
+        }
+        box_data.total_current = circuits.iter().map(|(_, c)| c).sum();
+        box_data
+    }
+
+    #[test]
+    fn test_profiles() {
+        let led = HarmonicProfile::for_equipment_type("照明设备");
+        assert!(led.third > HarmonicProfile::for_equipment_type("动力设备").third);
+        assert!((led.thd_i() - 0.345).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_it_box_needs_oversized_neutral_and_filter() {
+        let box_data = sample_box("AL-IT", &[("插座", 40.0), ("照明", 10.0)]);
+        let report = HarmonicAnalyzer::analyze_box(&box_data, &HarmonicConfig::default());
+
+        // 三次谐波 0.35×0.8 + 0.3×0.2 = 0.34，按中性线电流选择截面
+        assert!((report.third_harmonic - 0.34).abs() < 1e-9);
+        assert!((report.neutral_current - 3.0 * 0.34 * 50.0).abs() < 1e-9);
+        assert_eq!(report.derating_factor, 0.86);
+        assert!((report.design_current() - 3.0 * 0.34 * 50.0).abs() < 1e-9);
+        assert!(report.recommendations.contains(&HarmonicRecommendation::OversizedNeutral));
+        assert!(report.recommendations.contains(&HarmonicRecommendation::HarmonicFilter));
+    }
+
+    #[test]
+    fn test_lighting_and_motor_boxes() {
+        let lighting = sample_box("AL1", &[("照明", 20.0)]);
+        let report = HarmonicAnalyzer::analyze_box(&lighting, &HarmonicConfig::default());
+        assert_eq!(report.derating_factor, 0.86);
+        assert_eq!(report.recommendations, vec![HarmonicRecommendation::FullNeutral]);
+        assert!(!report.is_flagged());
+        assert_eq!(report.design_current(), 20.0);
+
+        let pumps = sample_box("AP1", &[("水泵", 60.0)]);
+        assert!(HarmonicAnalyzer::flagged_boxes(&[&lighting, &pumps], &HarmonicConfig::default()).is_empty());
+    }
+}
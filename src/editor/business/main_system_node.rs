@@ -4,19 +4,26 @@
 
 use std::collections::BTreeMap;
 use std::fmt::Display;
-use std::borrow::Cow;
 
-use egui::{Ui, Widget};
-use egui_node_graph::{Graph, NodeId, NodeTemplateTrait};
-use crate::editor::ui::debug_tools::log_info;
-use crate::editor::business::{BoxData as DistributionBoxNode};
+use serde::{Serialize, Deserialize};
+
+use egui::Ui;
+use egui_node_graph::{Graph, NodeId};
+use crate::log_info;
 
 use crate::core_lib::data_types::{ElectricDataType, ElectricValueType};
 use crate::editor::business::EditorState;
 use crate::editor::business::distribution_box_parameters::{DistributionBoxNode, IncomingType};
+use crate::editor::business::feeder_selection::{FeederSelectionConfig, FeederSelector, FeederType, FEEDER_RATINGS};
+use crate::editor::business::electrical_fire_monitoring::ElectricalFirePlanner;
+use crate::editor::business::fire_power_monitoring::FireModuleGenerator;
+use crate::editor::business::harmonics::{HarmonicAnalyzer, HarmonicConfig};
+use crate::editor::business::building::Building;
+use crate::editor::business::diagram_layout::{riser_key, DiagramLayout, LayoutConfig};
+use crate::editor::business::diagram_routing::{DiagramRouter, RoutingConfig};
 
 /// 干线系统图类型
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MainSystemType {
     PowerDistribution,     // 配电干线图
     EnergyMonitoring,      // 能耗监测干线图
@@ -36,45 +43,95 @@ impl Display for MainSystemType {
 }
 
 /// 系统图组件类型
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ComponentType {
     Busbar,           // 母线
     DistributionBox,  // 配电箱
     MonitoringModule, // 监测模块
     PowerSource,      // 电源
+    Busway,           // 母线槽
+    TapOffBox,        // 插接箱
+}
+
+impl ComponentType {
+    /// 获取组件类型名称
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            ComponentType::Busbar => "母线",
+            ComponentType::DistributionBox => "配电箱",
+            ComponentType::MonitoringModule => "监测模块",
+            ComponentType::PowerSource => "电源",
+            ComponentType::Busway => "母线槽",
+            ComponentType::TapOffBox => "插接箱",
+        }
+    }
 }
 
 /// 系统图连接类型
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ConnectionType {
     SinglePower, // 单电源
     DualPower,   // 双电源
     Monitoring,  // 监测
+    FireMonitoring,      // 电气火灾监控
+    FirePowerMonitoring, // 消防电源监测
 }
 
 /// 系统图组件
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DiagramComponent {
     pub id: usize,
     pub component_type: ComponentType,
     pub label: String,
     pub position: (f64, f64),
+    /// 所在楼层，None表示不属于某一楼层（母线、电源等位于底部）
+    #[serde(default)]
+    pub floor: Option<i32>,
+    /// 所属竖向干线（同一干线的组件在各楼层竖直对齐），None表示单独成列
+    #[serde(default)]
+    pub riser: Option<String>,
+    /// 对应配电箱的持久标识（配电箱及其监测模块），用于跳转到配电箱节点
+    #[serde(default)]
+    pub box_id: Option<String>,
+}
+
+impl DiagramComponent {
+    /// 组件在重新生成前后不变的标识，用于保存手动调整的位置
+    ///
+    /// 配电箱按配电箱标识，其监测模块按配电箱标识和模块名称，其余组件按类型和标签首行
+    pub fn key(&self) -> String {
+        let title = self.label.lines().next().unwrap_or("");
+        match (&self.box_id, &self.component_type) {
+            (Some(box_id), ComponentType::DistributionBox) => format!("{}:{}", self.component_type.to_str(), box_id),
+            (Some(box_id), _) => format!("{}:{}:{}", self.component_type.to_str(), box_id, title),
+            (None, _) => format!("{}:{}", self.component_type.to_str(), title),
+        }
+    }
 }
 
 /// 系统图连接
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DiagramConnection {
     pub from: usize,
     pub to: usize,
     pub connection_type: ConnectionType,
+    /// 布线后的正交折线（自from至to），为空表示尚未布线
+    #[serde(default)]
+    pub route: Vec<(f64, f64)>,
 }
 
 /// 系统图数据结构
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SystemDiagram {
     pub name: String,
     pub components: Vec<DiagramComponent>,
     pub connections: Vec<DiagramConnection>,
+    /// 干线分支处的连接点（布线时生成）
+    #[serde(default)]
+    pub junctions: Vec<(f64, f64)>,
+    /// 手动调整的组件位置（按组件标识保存，重新生成系统图后保留）
+    #[serde(default)]
+    pub overrides: BTreeMap<String, (f64, f64)>,
 }
 
 impl SystemDiagram {
@@ -84,6 +141,8 @@ impl SystemDiagram {
             name,
             components: Vec::new(),
             connections: Vec::new(),
+            junctions: Vec::new(),
+            overrides: BTreeMap::new(),
         }
     }
 
@@ -95,16 +154,79 @@ impl SystemDiagram {
             component_type,
             label,
             position: (0.0, 0.0), // 初始位置，自动布局时会更新
+            floor: None,
+            riser: None,
+            box_id: None,
         });
         id
     }
 
+    /// 设置组件所在楼层及所属竖向干线（供分层布局使用）
+    pub fn place_component(&mut self, id: usize, floor: Option<i32>, riser: Option<String>) {
+        if let Some(component) = self.components.get_mut(id) {
+            component.floor = floor;
+            component.riser = riser;
+        }
+    }
+
+    /// 关联组件对应的配电箱
+    pub fn link_box(&mut self, id: usize, box_id: &str) {
+        if let Some(component) = self.components.get_mut(id) {
+            component.box_id = Some(box_id.to_string());
+        }
+    }
+
+    /// 手动移动组件，位置记入手动调整
+    ///
+    /// # 参数
+    /// * `id` - 组件ID
+    /// * `position` - 新位置（组件中心）
+    pub fn move_component(&mut self, id: usize, position: (f64, f64)) {
+        if let Some(component) = self.components.get_mut(id) {
+            component.position = position;
+            self.overrides.insert(component.key(), position);
+        }
+    }
+
+    /// 按手动调整覆盖组件位置
+    ///
+    /// # 返回值
+    /// 返回被覆盖位置的组件数
+    pub fn apply_overrides(&mut self) -> usize {
+        let mut count = 0;
+        for component in &mut self.components {
+            if let Some(&position) = self.overrides.get(&component.key()) {
+                component.position = position;
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// 沿用重新生成前同名系统图的手动调整
+    ///
+    /// # 参数
+    /// * `previous` - 重新生成前的系统图
+    ///
+    /// # 返回值
+    /// 返回被覆盖位置的组件数
+    pub fn restore_overrides(&mut self, previous: &[SystemDiagram]) -> usize {
+        match previous.iter().find(|d| d.name == self.name) {
+            Some(old) if !old.overrides.is_empty() => {
+                self.overrides = old.overrides.clone();
+                self.apply_overrides()
+            }
+            _ => 0,
+        }
+    }
+
     /// 添加连接
     pub fn add_connection(&mut self, from: usize, to: usize) {
         self.connections.push(DiagramConnection {
             from,
             to,
             connection_type: ConnectionType::SinglePower, // 默认连接类型
+            route: Vec::new(),
         });
     }
 
@@ -127,6 +249,7 @@ pub struct MainSystemNodeData {
     pub auto_layout: bool,            // 是否自动布局
     pub diagrams: Vec<SystemDiagram>, // 生成的系统图
     pub errors: Vec<String>,          // 错误信息
+    pub building: Building,           // 建筑楼层与竖井
 }
 
 impl Default for MainSystemNodeData {
@@ -137,10 +260,18 @@ impl Default for MainSystemNodeData {
             auto_layout: true,
             diagrams: Vec::new(),
             errors: Vec::new(),
+            building: Building::default(),
         }
     }
 }
 
+impl MainSystemNodeData {
+    /// 配电箱所属的竖向干线：指定了竖井时按竖井，否则按配电箱名称推导
+    fn riser_of(&self, box_data: &DistributionBoxNode) -> String {
+        box_data.shaft.clone().unwrap_or_else(|| riser_key(&box_data.name))
+    }
+}
+
 /// 干线系统图节点UI
 #[derive(Debug, Clone, Default)]
 pub struct MainSystemNodeUI {
@@ -166,16 +297,71 @@ impl MainSystemNodeUI {
         
         // 按照楼层对配电箱进行排序
         let mut sorted_boxes = distribution_boxes.to_vec();
-        sorted_boxes.sort_by(|a, b| a.floor.cmp(&b.floor));
+        sorted_boxes.sort_by_key(|a| a.floor);
+        
+        // 选择干线馈线形式，母线槽干线按楼层设置插接箱
+        let mut floor_sources: BTreeMap<i32, usize> = BTreeMap::new();
+        let trunk_current: f64 = sorted_boxes.iter().map(|b| b.total_current).sum();
+        let feeder_rating = FeederSelector::feeder_rating(trunk_current);
+        if feeder_rating.is_none() {
+            self.data.errors.push(format!(
+                "干线计算电流{:.1}A超出干线保护电器最大额定电流{:.0}A，未选择干线馈线",
+                trunk_current,
+                FEEDER_RATINGS[FEEDER_RATINGS.len() - 1]
+            ));
+        }
+        if let Some(rating) = feeder_rating.filter(|_| trunk_current > 0.0) {
+            // 干线三次谐波含量按各配电箱进线电流加权，用于选择中性线截面
+            let third_harmonic = sorted_boxes
+                .iter()
+                .map(|b| HarmonicAnalyzer::analyze_box(b, &HarmonicConfig::default()))
+                .map(|r| r.third_harmonic * r.phase_current)
+                .sum::<f64>()
+                / trunk_current;
+            let config = FeederSelectionConfig { third_harmonic: Some(third_harmonic), ..FeederSelectionConfig::default() };
+            match FeederSelector::select(trunk_current, rating, &config) {
+                Ok(selection) => {
+                    if let FeederType::Busway(_) = selection.feeder {
+                        let busway = diagram.add_component(ComponentType::Busway, selection.annotation.clone());
+                        diagram.place_component(busway, None, Some(selection.annotation.clone()));
+                        diagram.add_connection(main_busbar, busway);
+                        match FeederSelector::tap_offs(&sorted_boxes) {
+                            Ok(tap_offs) => {
+                                for tap_off in tap_offs {
+                                    let tap_off_component = diagram.add_component(ComponentType::TapOffBox, tap_off.label());
+                                    diagram.place_component(tap_off_component, Some(tap_off.floor), Some(selection.annotation.clone()));
+                                    diagram.add_connection(busway, tap_off_component);
+                                    floor_sources.insert(tap_off.floor, tap_off_component);
+                                }
+                            },
+                            Err(err) => {
+                                self.data.errors.push(format!("插接箱选择错误: {}", err));
+                            }
+                        }
+                    } else {
+                        diagram.components[main_busbar].label = format!("主母线\n{}", selection.annotation);
+                    }
+                    log_info!("干线馈线采用{}: {}", selection.feeder.to_str(), selection.annotation);
+                },
+                Err(err) => {
+                    self.data.errors.push(format!("干线馈线选择错误: {}", err));
+                }
+            }
+        }
         
         // 为每个配电箱生成连线
         for box_data in &sorted_boxes {
+            // 母线槽干线由所在楼层的插接箱供电
+            let main_source = floor_sources.get(&box_data.floor).copied().unwrap_or(main_busbar);
+            
             // 添加配电箱组件
             let box_component = diagram.add_component(
                 ComponentType::DistributionBox,
                 format!("{}\n楼层:{}\n功率:{:.2}kW\n电流:{:.2}A", 
-                        box_data.name, box_data.floor, box_data.total_power, box_data.total_current)
+                        box_data.name, self.data.building.floor_name(box_data.floor), box_data.total_power, box_data.total_current)
             );
+            diagram.place_component(box_component, Some(box_data.floor), Some(self.data.riser_of(box_data)));
+            diagram.link_box(box_component, &box_data.id);
             
             // 判断配电箱进线类型
             let incoming_type = box_data.determine_incoming_type();
@@ -184,14 +370,14 @@ impl MainSystemNodeUI {
             match incoming_type {
                 IncomingType::SinglePower => {
                     // 单电源直接连接到主母线
-                    diagram.add_connection(main_busbar, box_component);
-                    diagram.set_connection_type(main_busbar, box_component, ConnectionType::SinglePower);
+                    diagram.add_connection(main_source, box_component);
+                    diagram.set_connection_type(main_source, box_component, ConnectionType::SinglePower);
                     log_info!("为配电箱'{}'添加单电源连接", box_data.name);
                 },
                 IncomingType::DualPower => {
                     // 双电源连接到主母线和备用电源
-                    diagram.add_connection(main_busbar, box_component);
-                    diagram.set_connection_type(main_busbar, box_component, ConnectionType::DualPower);
+                    diagram.add_connection(main_source, box_component);
+                    diagram.set_connection_type(main_source, box_component, ConnectionType::DualPower);
                     
                     diagram.add_connection(backup_power, box_component);
                     diagram.set_connection_type(backup_power, box_component, ConnectionType::DualPower);
@@ -213,13 +399,13 @@ impl MainSystemNodeUI {
         let mut diagrams = Vec::new();
 
         // 生成各类系统图
-        for system_type in &self.data.systems {
+        for system_type in self.data.systems.clone() {
             match system_type {
                 MainSystemType::PowerDistribution => {
                     // 对于配电干线图，使用自动连线生成功能
                     log_info!("正在生成配电干线系统图，包含{}个配电箱", distribution_boxes.len());
                     let diagram = self.auto_generate_connections(
-                        distribution_boxes.iter().copied().collect()
+                        distribution_boxes.to_vec()
                     );
                     diagrams.push(diagram);
                     log_info!("配电干线系统图生成完成");
@@ -230,12 +416,12 @@ impl MainSystemNodeUI {
                     // 过滤出包含能耗监测模块的配电箱
                     let energy_boxes: Vec<_> = distribution_boxes
                         .iter()
-                        .filter(|box_node| box_node.modules.contains(&"能耗监测".to_string()))
+                        .filter(|box_node| box_node.participates_in(&MainSystemType::EnergyMonitoring))
                         .copied()
                         .collect();
                     
                     log_info!("找到{}个包含能耗监测模块的配电箱", energy_boxes.len());
-                    let diagram = if !energy_boxes.is_empty() {
+                    let mut diagram = if !energy_boxes.is_empty() {
                         // 如果有带能耗监测模块的配电箱，为它们生成专用连接
                         let mut diagram = SystemDiagram::new("能耗监测干线系统图".to_string());
                         let busbar = diagram.add_component(ComponentType::Busbar, "监测总线".to_string());
@@ -245,8 +431,10 @@ impl MainSystemNodeUI {
                             let box_component = diagram.add_component(
                                 ComponentType::DistributionBox,
                                 format!("{}\n楼层:{}\n功率:{:.2}kW", 
-                                        box_data.name, box_data.floor, box_data.total_power)
+                                        box_data.name, self.data.building.floor_name(box_data.floor), box_data.total_power)
                             );
+                            diagram.place_component(box_component, Some(box_data.floor), Some(self.data.riser_of(box_data)));
+                            diagram.link_box(box_component, &box_data.id);
                             diagram.add_connection(busbar, box_component);
                             diagram.set_connection_type(busbar, box_component, ConnectionType::Monitoring);
                             log_info!("为配电箱'{}'添加能耗监测连接", box_data.name);
@@ -268,26 +456,42 @@ impl MainSystemNodeUI {
                 MainSystemType::ElectricalFireMonitoring => {
                     // 为电气火灾监控干线图添加自动连线逻辑
                     log_info!("正在生成电气火灾监控干线图");
-                    // 过滤出包含电气火灾监控模块的配电箱
-                    let fire_boxes: Vec<_> = distribution_boxes
+                    // 过滤出规划了电气火灾监控探测器的配电箱
+                    let mut fire_boxes: Vec<DistributionBoxNode> = distribution_boxes
                         .iter()
-                        .filter(|box_node| box_node.modules.contains(&"电气火灾监控".to_string()))
-                        .copied()
+                        .filter(|box_node| box_node.participates_in(&MainSystemType::ElectricalFireMonitoring))
+                        .map(|box_node| (*box_node).clone())
                         .collect();
+                    // 按楼层、配电箱名称排序并为探测器编址
+                    let mut box_refs: Vec<&mut DistributionBoxNode> = fire_boxes.iter_mut().collect();
+                    ElectricalFirePlanner::assign_addresses(&mut box_refs, 1);
+                    fire_boxes.sort_by(|a, b| a.floor.cmp(&b.floor).then_with(|| a.name.cmp(&b.name)));
                     
                     log_info!("找到{}个包含电气火灾监控模块的配电箱", fire_boxes.len());
-                    let diagram = if !fire_boxes.is_empty() {
+                    let mut diagram = if !fire_boxes.is_empty() {
                         // 如果有带电气火灾监控模块的配电箱，为它们生成专用连接
                         let mut diagram = SystemDiagram::new("电气火灾监控干线系统图".to_string());
-                        let busbar = diagram.add_component(ComponentType::Busbar, "监控总线".to_string());
+                        let schedule_refs: Vec<&DistributionBoxNode> = fire_boxes.iter().collect();
+                        let total: usize = ElectricalFirePlanner::detector_schedule(&schedule_refs)
+                            .iter()
+                            .map(|floor| floor.detector_count())
+                            .sum();
+                        let busbar = diagram.add_component(ComponentType::Busbar, format!("监控总线\n探测器总数:{}", total));
                         
-                        // 为每个带电气火灾监控模块的配电箱创建连接
+                        // 为每个带电气火灾监控模块的配电箱创建连接，标注探测器数量和地址
                         for box_data in &fire_boxes {
+                            let (detectors, addresses) = box_data.electrical_fire_monitoring()
+                                .map(|m| (m.detector_count(), m.address_range()))
+                                .unwrap_or((0, None));
+                            let address_text = addresses
+                                .map_or("未分配".to_string(), |(min, max)| format!("{}-{}", min, max));
                             let box_component = diagram.add_component(
                                 ComponentType::DistributionBox,
-                                format!("{}\n楼层:{}\n功率:{:.2}kW", 
-                                        box_data.name, box_data.floor, box_data.total_power)
+                                format!("{}\n楼层:{}\n探测器:{}个\n地址:{}", 
+                                        box_data.name, self.data.building.floor_name(box_data.floor), detectors, address_text)
                             );
+                            diagram.place_component(box_component, Some(box_data.floor), Some(self.data.riser_of(box_data)));
+                            diagram.link_box(box_component, &box_data.id);
                             diagram.add_connection(busbar, box_component);
                             diagram.set_connection_type(busbar, box_component, ConnectionType::FireMonitoring);
                             log_info!("为配电箱'{}'添加电气火灾监控连接", box_data.name);
@@ -309,30 +513,57 @@ impl MainSystemNodeUI {
                 MainSystemType::FirePowerMonitoring => {
                     // 为消防电源监测干线图添加自动连线逻辑
                     log_info!("正在生成消防电源监测干线图");
-                    // 过滤出包含消防电源监测模块的配电箱
-                    let fire_power_boxes: Vec<_> = distribution_boxes
+                    // 过滤出参与消防电源监测干线图的配电箱（消防电源监测、消防切断模块）
+                    let mut fire_power_boxes: Vec<DistributionBoxNode> = distribution_boxes
                         .iter()
-                        .filter(|box_node| box_node.modules.contains(&"消防电源监测".to_string()))
-                        .copied()
+                        .filter(|box_node| box_node.participates_in(&MainSystemType::FirePowerMonitoring))
+                        .map(|box_node| (*box_node).clone())
                         .collect();
+                    // 按楼层、配电箱名称排序并为消防模块编址
+                    let mut box_refs: Vec<&mut DistributionBoxNode> = fire_power_boxes.iter_mut().collect();
+                    FireModuleGenerator::assign_addresses(&mut box_refs, 1);
+                    fire_power_boxes.sort_by(|a, b| a.floor.cmp(&b.floor).then_with(|| a.name.cmp(&b.name)));
                     
                     log_info!("找到{}个包含消防电源监测模块的配电箱", fire_power_boxes.len());
-                    let diagram = if !fire_power_boxes.is_empty() {
+                    let mut diagram = if !fire_power_boxes.is_empty() {
                         // 如果有带消防电源监测模块的配电箱，为它们生成专用连接
                         let mut diagram = SystemDiagram::new("消防电源监测干线系统图".to_string());
                         let busbar = diagram.add_component(ComponentType::Busbar, "消防总线".to_string());
                         
-                        // 为每个带消防电源监测模块的配电箱创建连接
+                        // 为每个配电箱的消防模块创建连接，标注模块地址和点数
+                        let mut total_points = 0;
                         for box_data in &fire_power_boxes {
                             let box_component = diagram.add_component(
                                 ComponentType::DistributionBox,
                                 format!("{}\n楼层:{}\n功率:{:.2}kW", 
-                                        box_data.name, box_data.floor, box_data.total_power)
+                                        box_data.name, self.data.building.floor_name(box_data.floor), box_data.total_power)
                             );
-                            diagram.add_connection(busbar, box_component);
-                            diagram.set_connection_type(busbar, box_component, ConnectionType::FirePowerMonitoring);
+                            diagram.place_component(box_component, Some(box_data.floor), Some(self.data.riser_of(box_data)));
+                            diagram.link_box(box_component, &box_data.id);
+                            
+                            let modules = [
+                                box_data.fire_power_monitoring()
+                                    .map(|m| ("消防电源监测", m.address, m.point_count())),
+                                box_data.fire_cut_off()
+                                    .map(|m| ("消防切断", m.address, m.point_count())),
+                            ];
+                            for (module_name, address, points) in modules.into_iter().flatten() {
+                                let address_text = address.map_or("未分配".to_string(), |a| a.to_string());
+                                let module_component = diagram.add_component(
+                                    ComponentType::MonitoringModule,
+                                    format!("{}\n地址:{}\n点数:{}", module_name, address_text, points)
+                                );
+                                diagram.place_component(module_component, Some(box_data.floor), Some(self.data.riser_of(box_data)));
+                                diagram.link_box(module_component, &box_data.id);
+                                diagram.add_connection(busbar, module_component);
+                                diagram.set_connection_type(busbar, module_component, ConnectionType::FirePowerMonitoring);
+                                diagram.add_connection(module_component, box_component);
+                                diagram.set_connection_type(module_component, box_component, ConnectionType::Monitoring);
+                                total_points += points;
+                            }
                             log_info!("为配电箱'{}'添加消防电源监测连接", box_data.name);
                         }
+                        diagram.components[busbar].label = format!("消防总线\n总点数:{}", total_points);
                         
                         diagram
                     } else {
@@ -350,51 +581,20 @@ impl MainSystemNodeUI {
             }
         }
 
+        // 沿用上次生成时的手动调整，调整后的位置重新布线
+        for diagram in &mut diagrams {
+            let restored = diagram.restore_overrides(&self.data.diagrams);
+            if restored > 0 {
+                DiagramRouter::route(diagram, &RoutingConfig::default());
+                log_info!("系统图'{}'沿用{}处手动调整", diagram.name, restored);
+            }
+        }
+
         // 更新节点数据中的系统图
         self.data.diagrams = diagrams.clone();
         diagrams
     }
 
-    /// 生成配电干线系统图
-    fn generate_power_distribution_diagram(&self, distribution_boxes: Vec<&DistributionBoxNode>) -> SystemDiagram {
-        // 如果提供了配电箱数据，使用自动连线生成功能
-        if !distribution_boxes.is_empty() {
-            log_info!("使用自动连线生成功能创建配电干线系统图");
-            let mut diagram = self.auto_generate_connections(distribution_boxes);
-            
-            if self.data.auto_layout {
-                self.auto_layout_diagram(&mut diagram);
-            }
-            
-            diagram
-        } else {
-            // 如果没有提供配电箱数据，创建示例系统图
-            log_info!("创建示例配电干线系统图");
-            let mut diagram = SystemDiagram::new("配电干线系统图".to_string());
-
-            // 创建母线
-            let busbar = diagram.add_component(ComponentType::Busbar, "主母线".to_string());
-
-            // 创建一个示例配电箱
-            let box_component = diagram.add_component(
-                ComponentType::DistributionBox,
-                "示例配电箱\n楼层:1\n功率:10.00kW\n电流:15.20A".to_string()
-            );
-
-            // 添加连线
-            diagram.add_connection(busbar, box_component);
-
-            // 设置连接类型为单电源
-            diagram.set_connection_type(busbar, box_component, ConnectionType::SinglePower);
-
-            if self.data.auto_layout {
-                self.auto_layout_diagram(&mut diagram);
-            }
-
-            diagram
-        }
-    }
-
     /// 生成能耗监测干线系统图
     fn generate_energy_monitoring_diagram(&self) -> SystemDiagram {
         let mut diagram = SystemDiagram::new("能耗监测干线系统图".to_string());
@@ -467,17 +667,11 @@ impl MainSystemNodeUI {
         diagram
     }
 
-    /// 自动布局算法
+    /// 自动布局算法：楼层分层、竖向干线对齐（见DiagramLayout），随后正交布线（见DiagramRouter）
     fn auto_layout_diagram(&self, diagram: &mut SystemDiagram) {
-        // 简化的自动布局实现
-        // 实际应用中应根据图论算法进行优化布局
-        let mut x_pos = 100.0;
-        let y_pos = 100.0;
-        
-        for component in &mut diagram.components {
-            component.position = (x_pos, y_pos);
-            x_pos += 200.0; // 组件之间水平间距
-        }
+        DiagramLayout::layout(diagram, &LayoutConfig::default());
+        let crossings = DiagramRouter::route(diagram, &RoutingConfig::default());
+        log_info!("系统图'{}'布线完成，交叉{}处", diagram.name, crossings);
     }
 
     /// 渲染节点UI
@@ -550,7 +744,7 @@ pub enum MainSystemResponse {
     Error(String),        // 错误信息
 }
 
-impl crate::editor::business::UserResponseTrait for MainSystemResponse {}
+impl egui_node_graph::UserResponseTrait for MainSystemResponse {}
 
 // 为MainSystemResponse实现apply方法
 impl MainSystemResponse {
@@ -577,20 +771,21 @@ impl MainSystemResponse {
     }
 }
 
-// 实现NodeDataTrait，处理节点数据的输入输出
-impl crate::editor::business::NodeDataTrait for MainSystemNodeUI {
+// 实现NodeDataTrait，在节点底部显示生成结果
+impl egui_node_graph::NodeDataTrait for MainSystemNodeUI {
     type DataType = ElectricDataType;
     type ValueType = ElectricValueType;
     type UserState = EditorState;
     type Response = MainSystemResponse;
     
-    /// 渲染节点主体UI
-    fn node_ui(&mut self, ui: &mut Ui) -> Option<Self::Response> {
-        self.ui(ui)
-    }
-    
     /// 渲染节点底部UI（用于显示输出结果）
-    fn bottom_ui(&self, ui: &mut Ui) {
+    fn bottom_ui(
+        &self,
+        ui: &mut Ui,
+        _node_id: NodeId,
+        _graph: &Graph<Self, Self::DataType, Self::ValueType>,
+        _user_state: &mut Self::UserState,
+    ) -> Vec<egui_node_graph::NodeResponse<Self::Response, Self>> {
         // 显示已生成的系统图信息
         if !self.data.diagrams.is_empty() {
             ui.label("已生成系统图：");
@@ -598,62 +793,6 @@ impl crate::editor::business::NodeDataTrait for MainSystemNodeUI {
                 ui.label(format!("- {}", diagram.name));
             }
         }
+        Vec::new()
     }
-    
-    /// 参数更新处理
-    fn update_params(
-        &mut self,
-        params: Self::ValueType,
-        _user_state: &mut Self::UserState,
-    ) -> Result<(), Self::DataType> {
-        match params {
-            ElectricValueType::String(value) => {
-                self.data.name = value;
-            },
-            ElectricValueType::Bool(value) => {
-                self.data.auto_layout = value;
-            },
-            _ => {},
-        }
-        
-        Ok(())
-    }
-    
-    /// 输入数据处理
-    fn input_data(
-        &mut self,
-        input_idx: usize,
-        data: &Self::DataType,
-        _user_state: &mut Self::UserState,
-    ) -> Result<(), Self::DataType> {
-        // 处理来自配电箱节点的输入数据
-        if let ElectricDataType::DistributionBoxData = data {
-            // 实际应用中，这里应该解析配电箱数据并用于生成系统图
-            // 现在简化处理
-        }
-        
-        Ok(())
-    }
-    
-    /// 输出数据获取
-    fn output_data(
-        &self,
-        output_idx: usize,
-        _user_state: &mut Self::UserState,
-    ) -> Result<Self::DataType, Self::DataType> {
-        match output_idx {
-            0 => {
-                // 输出系统图数据（简化处理，输出名称列表）
-                let diagram_names: Vec<String> = self.data.diagrams.iter().map(|d| d.name.clone()).collect();
-                Ok(ElectricDataType::String(format!("系统图列表: {:?}", diagram_names)))
-            },
-            1 => {
-                // 输出系统图数量
-                Ok(ElectricDataType::Integer(self.data.diagrams.len() as i64))
-            },
-            _ => {
-                Err(ElectricDataType::String("无效的输出端口索引".to_string()))
-            }
-        }
-    }
-}
\ No newline at end of file
+}
@@ -14,7 +14,17 @@ pub struct MainSystemTemplate;
 impl MainSystemTemplate {
     /// 创建新的干线系统图节点模板实例
     pub fn new() -> Self {
-        Self::default()
+        Self
+    }
+    
+    /// 节点描述
+    pub fn node_description(&self) -> &'static str {
+        "用于自动生成配电干线图和各种监测系统图的节点"
+    }
+    
+    /// 节点默认宽度
+    pub fn node_width(&self) -> f32 {
+        250.0
     }
 }
 
@@ -36,13 +46,8 @@ impl NodeTemplateTrait for MainSystemTemplate {
     }
     
     /// 节点图标签
-    fn node_graph_label(&self, _user_state: &mut Self::UserState) -> Cow<'static, str> {
-        Cow::Borrowed("干线系统图节点")
-    }
-    
-    /// 节点描述
-    fn node_description(&self, _user_state: &mut Self::UserState) -> Cow<'static, str> {
-        Cow::Borrowed("用于自动生成配电干线图和各种监测系统图的节点")
+    fn node_graph_label(&self, _user_state: &mut Self::UserState) -> String {
+        "干线系统图节点".to_string()
     }
     
     /// 用户数据创建
@@ -60,34 +65,25 @@ impl NodeTemplateTrait for MainSystemTemplate {
         // 输入参数：接收配电箱数据
         graph.add_input_param(
             node_id,
-            "配电箱数据", 
-            InputParamKind::Connection, 
+            "配电箱数据".to_string(),
             ElectricDataType::DistributionBoxData,
-            None
+            ElectricValueType::default(),
+            InputParamKind::ConnectionOnly,
+            true,
         );
         
         // 输出参数：系统图数据
         graph.add_output_param(
             node_id,
-            "系统图数据",
+            "系统图数据".to_string(),
             ElectricDataType::SystemDiagramData
         );
         
         // 输出参数：系统图数量
         graph.add_output_param(
             node_id,
-            "系统图数量",
+            "系统图数量".to_string(),
             ElectricDataType::Integer
         );
     }
-    
-    /// 节点预览图标（可选）
-    fn node_finder_icon(&self, _user_state: &mut Self::UserState) -> Option<&'static str> {
-        None // 可以在这里指定图标
-    }
-    
-    /// 节点默认宽度
-    fn node_width(&self, _user_state: &mut Self::UserState) -> f32 {
-        250.0
-    }
-}
\ No newline at end of file
+}
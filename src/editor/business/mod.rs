@@ -1,4 +1,4 @@
-/// 编辑器业务逻辑模块
+//! 编辑器业务逻辑模块
 
 pub mod circuit_parameters;
 pub mod circuit_calculator;
@@ -12,8 +12,24 @@ pub mod node_data_transfer;
 pub mod node_templates;
 pub mod main_system_node;
 pub mod main_system_template;
+pub mod diagram_layout;
+pub mod diagram_routing;
 pub mod data_flow;
 pub mod auto_connection;
+pub mod fire_design_rules;
+pub mod fire_power_monitoring;
+pub mod electrical_fire_monitoring;
+pub mod box_modules;
+pub mod text_circuit;
+pub mod feeder_selection;
+pub mod building;
+pub mod cable_length;
+pub mod harmonics;
+pub mod text_switching_components;
+pub mod switching_components;
+pub mod shape_switching_components;
+pub mod shape_circuit;
+pub mod shape_box;
 
 // 条件导出测试模块
 #[cfg(test)]
@@ -23,17 +39,27 @@ pub mod distribution_box_tests;
 pub use circuit_parameters::{CircuitParameters, CircuitResult, VoltageType, CircuitData};
 pub use circuit_calculator::{CircuitCalculator, CircuitCalculationError};
 pub use circuit_node::{CircuitNode, CircuitNodeTemplate, CircuitNodeResponse, EditorState};
-pub use distribution_box_parameters::{DistributionBoxNode as BoxData, CircuitInfo, DistributionBoxError, DistributionBoxResponse};
+pub use distribution_box_parameters::{DistributionBoxNode, DistributionBoxNode as BoxData, CircuitInfo, DistributionBoxError, DistributionBoxResponse};
 pub use circuit_manager::CircuitManager;
 pub use distribution_box_calculator::DistributionBoxCalculator;
 pub use distribution_box_node::DistributionBoxNodeUI;
 pub use distribution_box_template::DistributionBoxTemplate;
 pub use node_data_transfer::{PowerGraphNode, PowerGraphState};
-pub use node_templates::{ElectricNodeTemplate, get_all_node_templates};
-pub use main_system_node::{MainSystemNodeData, MainSystemNodeUI, MainSystemType, SystemDiagram};
+pub use node_templates::{ElectricNodeTemplate, AllElectricTemplates, get_all_node_templates, all_electric_templates};
+pub use main_system_node::{MainSystemNodeData, MainSystemNodeUI, MainSystemResponse, MainSystemType, SystemDiagram};
 pub use main_system_template::MainSystemTemplate;
+pub use diagram_layout::{DiagramLayout, LayoutConfig};
+pub use diagram_routing::{DiagramRouter, RoutingConfig};
 pub use data_flow::{DataFlowManager, UpdatableNode};
-pub use auto_connection::{IncomingType, ConnectionType, ConnectionInfo, AutoConnectionGenerator, AutoConnectionManager, derive_equipment_type};
+pub use auto_connection::{IncomingType, ConnectionType, ConnectionInfo, AutoConnectionGenerator, AutoConnectionManager, derive_equipment_type, equipment_type_for_purpose};
+pub use fire_design_rules::{FireRule, FireRuleViolation, FireComplianceReport, FireDesignRuleChecker};
+pub use fire_power_monitoring::{FirePowerMonitoringModule, FireCutOffModule, IncomingLineSensor, FireModuleGenerator};
+pub use electrical_fire_monitoring::{DetectorKind, ElectricalFireDetector, ElectricalFireMonitoringConfig, ElectricalFireMonitoringModule, ElectricalFirePlanner, FloorDetectorSchedule};
+pub use box_modules::{BoxModule, ModuleKind, ModuleSymbol, ModuleComponent, ModuleDefinition, ModuleRegistry};
+pub use building::{Building, BuildingError, Floor, Shaft};
+pub use cable_length::{CableLengthConfig, CableLengthError, CableLengthEstimate, CableLengthEstimator, LengthSource};
+pub use feeder_selection::{Busway, BuswayTapOff, FeederType, FeederSelectionConfig, FeederSelection, FeederSelector};
+pub use harmonics::{HarmonicProfile, HarmonicRecommendation, HarmonicConfig, BoxHarmonicReport, HarmonicAnalyzer};
 
 // 自动识别与连线生成功能
 pub mod auto_connection_example;
@@ -1,16 +1,14 @@
-/// 节点模板定义
-/// 用于创建和配置不同类型的电气节点
+//! 节点模板定义
+//! 用于创建和配置不同类型的电气节点
 
 use std::borrow::Cow;
-use uuid::Uuid;
-use egui_node_graph::{NodeTemplateTrait, NodeId, Graph, CategoryTrait, InputParamKind};
+use egui_node_graph::{NodeTemplateTrait, NodeTemplateIter, NodeId, Graph, InputParamKind};
 use crate::editor::{DataType, UIValueType, UIUserState};
-use crate::core_lib::data_types::ElectricNodeData as CoreElectricNodeData;
-use crate::editor::business::{PowerGraphNode};
-use crate::core_lib::data_types::{CircuitType, CircuitNodeProperties};
+use crate::editor::business::{BoxData, MainSystemNodeData, PowerGraphNode};
+use crate::core_lib::data_types::{CircuitType, CircuitNodeProperties, ElectricNodeData};
 
 /// 电气节点模板，用于创建不同类型的节点
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ElectricNodeTemplate {
     // 配电回路相关节点
     SinglePhaseCircuitNode,    // 单相回路节点
@@ -36,47 +34,15 @@ pub enum ElectricNodeTemplate {
     PhaseBalanceNode,          // 相平衡节点
 }
 
-// 实现CategoryTrait以支持节点分类
-impl egui_node_graph::CategoryTrait for ElectricNodeTemplate {
-    fn name(&self) -> String {
+impl ElectricNodeTemplate {
+    /// 节点标签
+    pub fn node_label(&self) -> &'static str {
         match self {
-            ElectricNodeTemplate::SinglePhaseCircuitNode |
-            ElectricNodeTemplate::ThreePhaseCircuitNode |
-            ElectricNodeTemplate::CircuitNode |
-            ElectricNodeTemplate::CircuitGroupNode => "配电回路".to_string(),
-
-            ElectricNodeTemplate::DistributionBoxNode |
-            ElectricNodeTemplate::MainDistributionBoxNode |
-            ElectricNodeTemplate::SubDistributionBoxNode => "配电箱".to_string(),
-
-            ElectricNodeTemplate::MainLineNode |
-            ElectricNodeTemplate::FeederLineNode |
-            ElectricNodeTemplate::MainSystemNode => "干线系统".to_string(),
-
-            ElectricNodeTemplate::PowerSourceNode => "电源".to_string(),
-
-            ElectricNodeTemplate::CurrentCalculationNode |
-            ElectricNodeTemplate::PhaseBalanceNode => "计算工具".to_string(),
-        }
-    }
-}
-
-impl NodeTemplateTrait for ElectricNodeTemplate {
-    type NodeData = PowerGraphNode;
-    type DataType = DataType;
-    type ValueType = UIValueType;
-    type UserState = UIUserState;
-    type Category = ElectricNodeTemplate;
-    type CategoryType = ElectricNodeTemplate;
-    
-    /// 节点查找器中显示的标签
-    fn node_finder_label(&self, _user_state: &mut Self::UserState) -> Cow<str> {
-        Cow::Borrowed(match self {
-            ElectricNodeTemplate::SinglePhaseCircuitNode => "单相回路",
-            ElectricNodeTemplate::ThreePhaseCircuitNode => "三相回路",
+            ElectricNodeTemplate::SinglePhaseCircuitNode => "单相配电回路",
+            ElectricNodeTemplate::ThreePhaseCircuitNode => "三相配电回路",
             ElectricNodeTemplate::CircuitNode => "配电回路",
             ElectricNodeTemplate::CircuitGroupNode => "回路组",
-            ElectricNodeTemplate::DistributionBoxNode => "配电箱",
+            ElectricNodeTemplate::DistributionBoxNode => "标准配电箱",
             ElectricNodeTemplate::MainDistributionBoxNode => "总配电箱",
             ElectricNodeTemplate::SubDistributionBoxNode => "分配电箱",
             ElectricNodeTemplate::MainLineNode => "干线",
@@ -85,46 +51,14 @@ impl NodeTemplateTrait for ElectricNodeTemplate {
             ElectricNodeTemplate::PowerSourceNode => "电源",
             ElectricNodeTemplate::CurrentCalculationNode => "电流计算",
             ElectricNodeTemplate::PhaseBalanceNode => "三相平衡",
-        })
-    }
-    
-    /// 节点在查找器中的分类
-    fn node_finder_categories(&self, _user_state: &mut Self::UserState) -> Vec<ElectricNodeTemplate> {
-        vec![*self]  // 返回自身作为分类
-    }
-    
-    /// 节点在图形编辑器中显示的标签
-    fn node_graph_label(&self, _user_state: &mut Self::UserState) -> String {
-        self.node_label()
-    }
-    
-    /// 节点标签
-    fn node_label(&self) -> String {
-        match self {
-            ElectricNodeTemplate::SinglePhaseCircuitNode => "单相回路".to_string(),
-            ElectricNodeTemplate::ThreePhaseCircuitNode => "三相回路".to_string(),
-            ElectricNodeTemplate::CircuitNode => "配电回路".to_string(),
-            ElectricNodeTemplate::CircuitGroupNode => "回路组".to_string(),
-            ElectricNodeTemplate::DistributionBoxNode => "配电箱".to_string(),
-            ElectricNodeTemplate::MainDistributionBoxNode => "总配电箱".to_string(),
-            ElectricNodeTemplate::SubDistributionBoxNode => "分配电箱".to_string(),
-            ElectricNodeTemplate::MainLineNode => "干线".to_string(),
-            ElectricNodeTemplate::FeederLineNode => "馈线".to_string(),
-            ElectricNodeTemplate::MainSystemNode => "干线系统图".to_string(),
-            ElectricNodeTemplate::PowerSourceNode => "电源".to_string(),
-            ElectricNodeTemplate::CurrentCalculationNode => "电流计算".to_string(),
-            ElectricNodeTemplate::PhaseBalanceNode => "三相平衡".to_string(),
         }
     }
     
-    /// 节点分类
-    fn node_category(&self) -> ElectricNodeTemplate {
-        *self
-    }
-    
-    /// 获取分类名称（兼容CategoryTrait的category方法）
-    fn category(&self) -> &str {
+    /// 获取分类名称
+    pub fn category(&self) -> &'static str {
         match self {
+            ElectricNodeTemplate::SinglePhaseCircuitNode |
+            ElectricNodeTemplate::ThreePhaseCircuitNode |
             ElectricNodeTemplate::CircuitNode |
             ElectricNodeTemplate::CircuitGroupNode => "配电回路",
 
@@ -133,7 +67,8 @@ impl NodeTemplateTrait for ElectricNodeTemplate {
             ElectricNodeTemplate::SubDistributionBoxNode => "配电箱",
 
             ElectricNodeTemplate::MainLineNode |
-            ElectricNodeTemplate::FeederLineNode => "干线系统",
+            ElectricNodeTemplate::FeederLineNode |
+            ElectricNodeTemplate::MainSystemNode => "干线系统",
 
             ElectricNodeTemplate::PowerSourceNode => "电源",
 
@@ -142,61 +77,11 @@ impl NodeTemplateTrait for ElectricNodeTemplate {
         }
     }
     
-    /// 创建节点的用户数据（使用UUID生成临时ID）
-    fn user_data(&self, _user_state: &mut Self::UserState) -> Self::NodeData {
-        // 使用UUID::new_v4()生成临时ID
-        let temp_id = Uuid::new_v4();
-        self.node_data(temp_id)
-    }
-    
-    /// 根据ID创建节点数据
-    fn node_data(&self, id: Uuid) -> Self::NodeData {
-        let node_id = format!("{}-{}", self.node_prefix(), id.to_string().split('-').next().unwrap());
-        let node_name = self.node_label();
-        let description = self.node_description();
-        
-        // 创建对应的core_lib::data_types::ElectricNodeData
-        let core_node_data = match self {
-            ElectricNodeTemplate::SinglePhaseCircuitNode => crate::core_lib::data_types::ElectricNodeData::CircuitNode(Default::default()),
-            ElectricNodeTemplate::ThreePhaseCircuitNode => crate::core_lib::data_types::ElectricNodeData::CircuitNode(Default::default()),
-            ElectricNodeTemplate::CircuitNode => crate::core_lib::data_types::ElectricNodeData::CircuitNode(Default::default()),
-            ElectricNodeTemplate::CircuitGroupNode => crate::core_lib::data_types::ElectricNodeData::CircuitNode(Default::default()),
-            ElectricNodeTemplate::DistributionBoxNode |
-            ElectricNodeTemplate::MainDistributionBoxNode |
-            ElectricNodeTemplate::SubDistributionBoxNode => crate::core_lib::data_types::ElectricNodeData::DistributionBoxNode(Default::default()),
-            ElectricNodeTemplate::MainLineNode |
-            ElectricNodeTemplate::FeederLineNode => crate::core_lib::data_types::ElectricNodeData::TrunkLineNode(Default::default()),
-            ElectricNodeTemplate::MainSystemNode => crate::core_lib::data_types::ElectricNodeData::TrunkLineNode(Default::default()),
-            ElectricNodeTemplate::PowerSourceNode => crate::core_lib::data_types::ElectricNodeData::PowerSourceNode(Default::default()),
-            ElectricNodeTemplate::CurrentCalculationNode |
-            ElectricNodeTemplate::PhaseBalanceNode => crate::core_lib::data_types::ElectricNodeData::CalculationNode(Default::default()),
-        };
-        
-        let mut node = PowerGraphNode {
-            id: node_id,
-            data: core_node_data,
-            calculation_cache: std::collections::HashMap::new(),
-        };
-        
-        // 根据节点类型设置特定属性
-        match self {
-            ElectricNodeTemplate::SinglePhaseCircuitNode => {
-                // 可以在这里为单相回路节点设置特定属性
-                // 例如通过某种方式设置回路类型为单相
-            },
-            ElectricNodeTemplate::ThreePhaseCircuitNode => {
-                // 可以在这里为三相回路节点设置特定属性
-                // 例如通过某种方式设置回路类型为三相
-            },
-            _ => {}
-        }
-        
-        node
-    }
-    
     /// 获取节点ID前缀
-    fn node_prefix(&self) -> &str {
+    pub fn node_prefix(&self) -> &'static str {
         match self {
+            ElectricNodeTemplate::SinglePhaseCircuitNode => "SPC",
+            ElectricNodeTemplate::ThreePhaseCircuitNode => "TPC",
             ElectricNodeTemplate::CircuitNode => "CIR",
             ElectricNodeTemplate::CircuitGroupNode => "CIG",
             ElectricNodeTemplate::DistributionBoxNode => "DB",
@@ -212,22 +97,77 @@ impl NodeTemplateTrait for ElectricNodeTemplate {
     }
     
     /// 获取节点描述
-    fn node_description(&self) -> String {
+    pub fn node_description(&self) -> &'static str {
         match self {
-            ElectricNodeTemplate::CircuitNode => "用于表示配电回路的节点".to_string(),
-            ElectricNodeTemplate::CircuitGroupNode => "用于表示配电回路组的节点".to_string(),
-            ElectricNodeTemplate::DistributionBoxNode => "用于表示通用配电箱的节点".to_string(),
-            ElectricNodeTemplate::MainDistributionBoxNode => "用于表示主配电箱的节点".to_string(),
-            ElectricNodeTemplate::SubDistributionBoxNode => "用于表示子配电箱的节点".to_string(),
-            ElectricNodeTemplate::MainLineNode => "用于表示主线路的节点".to_string(),
-            ElectricNodeTemplate::FeederLineNode => "用于表示馈线的节点".to_string(),
-            ElectricNodeTemplate::MainSystemNode => "用于自动生成配电干线图和各种监测系统图的节点".to_string(),
-            ElectricNodeTemplate::PowerSourceNode => "用于表示电源的节点".to_string(),
-            ElectricNodeTemplate::CurrentCalculationNode => "用于进行电流计算的节点".to_string(),
-            ElectricNodeTemplate::PhaseBalanceNode => "用于进行相平衡分析的节点".to_string(),
+            ElectricNodeTemplate::SinglePhaseCircuitNode => "用于表示单相配电回路的节点",
+            ElectricNodeTemplate::ThreePhaseCircuitNode => "用于表示三相配电回路的节点",
+            ElectricNodeTemplate::CircuitNode => "用于表示配电回路的节点",
+            ElectricNodeTemplate::CircuitGroupNode => "用于表示配电回路组的节点",
+            ElectricNodeTemplate::DistributionBoxNode => "用于表示通用配电箱的节点",
+            ElectricNodeTemplate::MainDistributionBoxNode => "用于表示主配电箱的节点",
+            ElectricNodeTemplate::SubDistributionBoxNode => "用于表示子配电箱的节点",
+            ElectricNodeTemplate::MainLineNode => "用于表示主线路的节点",
+            ElectricNodeTemplate::FeederLineNode => "用于表示馈线的节点",
+            ElectricNodeTemplate::MainSystemNode => "用于自动生成配电干线图和各种监测系统图的节点",
+            ElectricNodeTemplate::PowerSourceNode => "用于表示电源的节点",
+            ElectricNodeTemplate::CurrentCalculationNode => "用于进行电流计算的节点",
+            ElectricNodeTemplate::PhaseBalanceNode => "用于进行相平衡分析的节点",
         }
     }
     
+    /// 创建节点数据
+    pub fn node_data(&self) -> PowerGraphNode {
+        match self {
+            ElectricNodeTemplate::SinglePhaseCircuitNode |
+            ElectricNodeTemplate::CircuitNode |
+            ElectricNodeTemplate::CircuitGroupNode => PowerGraphNode::CircuitNode(CircuitNodeProperties::default()),
+            ElectricNodeTemplate::ThreePhaseCircuitNode => PowerGraphNode::CircuitNode(CircuitNodeProperties {
+                circuit_type: CircuitType::ThreePhase,
+                power: 3.0,
+                voltage: 380.0,
+                phase: None,
+                ..CircuitNodeProperties::default()
+            }),
+            ElectricNodeTemplate::DistributionBoxNode |
+            ElectricNodeTemplate::MainDistributionBoxNode |
+            ElectricNodeTemplate::SubDistributionBoxNode => PowerGraphNode::DistributionBoxNode(BoxData::default()),
+            ElectricNodeTemplate::MainLineNode |
+            ElectricNodeTemplate::FeederLineNode |
+            ElectricNodeTemplate::MainSystemNode => PowerGraphNode::TrunkLineNode(MainSystemNodeData::default()),
+            ElectricNodeTemplate::PowerSourceNode => PowerGraphNode::Other(ElectricNodeData::PowerSourceNode(Default::default())),
+            ElectricNodeTemplate::CurrentCalculationNode |
+            ElectricNodeTemplate::PhaseBalanceNode => PowerGraphNode::Other(ElectricNodeData::CalculationNode(Default::default())),
+        }
+    }
+}
+
+impl NodeTemplateTrait for ElectricNodeTemplate {
+    type NodeData = PowerGraphNode;
+    type DataType = DataType;
+    type ValueType = UIValueType;
+    type UserState = UIUserState;
+    type CategoryType = &'static str;
+    
+    /// 节点查找器中显示的标签
+    fn node_finder_label(&self, _user_state: &mut Self::UserState) -> Cow<'_, str> {
+        Cow::Borrowed(self.node_label())
+    }
+    
+    /// 节点在查找器中的分类
+    fn node_finder_categories(&self, _user_state: &mut Self::UserState) -> Vec<&'static str> {
+        vec![self.category()]
+    }
+    
+    /// 节点在图形编辑器中显示的标签
+    fn node_graph_label(&self, _user_state: &mut Self::UserState) -> String {
+        self.node_label().to_string()
+    }
+    
+    /// 创建节点的用户数据
+    fn user_data(&self, _user_state: &mut Self::UserState) -> Self::NodeData {
+        self.node_data()
+    }
+    
     /// 构建节点，添加输入输出参数
     fn build_node(
         &self,
@@ -239,92 +179,109 @@ impl NodeTemplateTrait for ElectricNodeTemplate {
         match self {
             ElectricNodeTemplate::SinglePhaseCircuitNode => {
                 // 单相回路节点：输入功率、电压、功率因数、需用系数，输出计算电流、元器件规格、线缆规格
-                graph.add_input_param(node_id, "功率", InputParamKind::ConnectionOrConstant, DataType::Power, Some(UIValueType::Float(1.0)));
-                graph.add_input_param(node_id, "电压", InputParamKind::ConnectionOrConstant, DataType::Voltage, Some(UIValueType::Float(220.0)));
-                graph.add_input_param(node_id, "功率因数", InputParamKind::ConnectionOrConstant, DataType::PowerFactor, Some(UIValueType::Float(0.85)));
-                graph.add_input_param(node_id, "需用系数", InputParamKind::ConnectionOrConstant, DataType::Coefficient, Some(UIValueType::Float(1.0)));
-                graph.add_output_param(node_id, "计算电流", DataType::Current);
-                graph.add_output_param(node_id, "元器件电流", DataType::Current);
-                graph.add_output_param(node_id, "线缆规格", DataType::String);
+                graph.add_input_param(node_id, "功率".to_string(), DataType::Power, UIValueType::Float(1.0), InputParamKind::ConnectionOrConstant, true);
+                graph.add_input_param(node_id, "电压".to_string(), DataType::Voltage, UIValueType::Float(220.0), InputParamKind::ConnectionOrConstant, true);
+                graph.add_input_param(node_id, "功率因数".to_string(), DataType::PowerFactor, UIValueType::Float(0.85), InputParamKind::ConnectionOrConstant, true);
+                graph.add_input_param(node_id, "需用系数".to_string(), DataType::Coefficient, UIValueType::Float(1.0), InputParamKind::ConnectionOrConstant, true);
+                graph.add_output_param(node_id, "计算电流".to_string(), DataType::Current);
+                graph.add_output_param(node_id, "元器件电流".to_string(), DataType::Current);
+                graph.add_output_param(node_id, "线缆规格".to_string(), DataType::String);
             },
             
             ElectricNodeTemplate::ThreePhaseCircuitNode => {
                 // 三相回路节点：输入功率、电压、功率因数、需用系数，输出计算电流、元器件规格、线缆规格
-                graph.add_input_param(node_id, "功率", InputParamKind::ConnectionOrConstant, DataType::Power, Some(UIValueType::Float(3.0)));
-                graph.add_input_param(node_id, "电压", InputParamKind::ConnectionOrConstant, DataType::Voltage, Some(UIValueType::Float(380.0)));
-                graph.add_input_param(node_id, "功率因数", InputParamKind::ConnectionOrConstant, DataType::PowerFactor, Some(UIValueType::Float(0.85)));
-                graph.add_input_param(node_id, "需用系数", InputParamKind::ConnectionOrConstant, DataType::Coefficient, Some(UIValueType::Float(0.8)));
-                graph.add_output_param(node_id, "计算电流", DataType::Current);
-                graph.add_output_param(node_id, "元器件电流", DataType::Current);
-                graph.add_output_param(node_id, "线缆规格", DataType::String);
+                graph.add_input_param(node_id, "功率".to_string(), DataType::Power, UIValueType::Float(3.0), InputParamKind::ConnectionOrConstant, true);
+                graph.add_input_param(node_id, "电压".to_string(), DataType::Voltage, UIValueType::Float(380.0), InputParamKind::ConnectionOrConstant, true);
+                graph.add_input_param(node_id, "功率因数".to_string(), DataType::PowerFactor, UIValueType::Float(0.85), InputParamKind::ConnectionOrConstant, true);
+                graph.add_input_param(node_id, "需用系数".to_string(), DataType::Coefficient, UIValueType::Float(0.8), InputParamKind::ConnectionOrConstant, true);
+                graph.add_output_param(node_id, "计算电流".to_string(), DataType::Current);
+                graph.add_output_param(node_id, "元器件电流".to_string(), DataType::Current);
+                graph.add_output_param(node_id, "线缆规格".to_string(), DataType::String);
             },
             
             ElectricNodeTemplate::CircuitNode => {
                 // 配电回路节点：一个输入（上级电源），多个输出（功率、电流、回路数据）
-                graph.add_input_param(node_id, "上级电源", InputParamKind::ConnectionOnly, DataType::Voltage, None);
-                graph.add_output_param(node_id, "功率", DataType::Power);
-                graph.add_output_param(node_id, "电流", DataType::Current);
-                graph.add_output_param(node_id, "回路数据", DataType::CircuitInfo);
+                graph.add_input_param(node_id, "上级电源".to_string(), DataType::Voltage, UIValueType::default(), InputParamKind::ConnectionOnly, true);
+                graph.add_output_param(node_id, "功率".to_string(), DataType::Power);
+                graph.add_output_param(node_id, "电流".to_string(), DataType::Current);
+                graph.add_output_param(node_id, "回路数据".to_string(), DataType::CircuitInfo);
             },
 
             ElectricNodeTemplate::CircuitGroupNode => {
                 // 回路组节点：输入各回路信息，输出组总功率和电流
-                graph.add_input_param(node_id, "回路数据1", InputParamKind::ConnectionOrConstant, DataType::CircuitInfo, Some(UIValueType::CircuitInfo(Default::default())));
-                graph.add_input_param(node_id, "回路数据2", InputParamKind::ConnectionOrConstant, DataType::CircuitInfo, Some(UIValueType::CircuitInfo(Default::default())));
-                graph.add_input_param(node_id, "回路数据3", InputParamKind::ConnectionOrConstant, DataType::CircuitInfo, Some(UIValueType::CircuitInfo(Default::default())));
-                graph.add_output_param(node_id, "总功率", DataType::Power);
-                graph.add_output_param(node_id, "总电流", DataType::Current);
-                graph.add_output_param(node_id, "回路组数据", DataType::CircuitGroupInfo);
+                graph.add_input_param(node_id, "回路数据1".to_string(), DataType::CircuitInfo, UIValueType::CircuitInfo(Default::default()), InputParamKind::ConnectionOrConstant, true);
+                graph.add_input_param(node_id, "回路数据2".to_string(), DataType::CircuitInfo, UIValueType::CircuitInfo(Default::default()), InputParamKind::ConnectionOrConstant, true);
+                graph.add_input_param(node_id, "回路数据3".to_string(), DataType::CircuitInfo, UIValueType::CircuitInfo(Default::default()), InputParamKind::ConnectionOrConstant, true);
+                graph.add_output_param(node_id, "总功率".to_string(), DataType::Power);
+                graph.add_output_param(node_id, "总电流".to_string(), DataType::Current);
+                graph.add_output_param(node_id, "回路组数据".to_string(), DataType::CircuitGroupInfo);
             },
 
             ElectricNodeTemplate::DistributionBoxNode |
             ElectricNodeTemplate::MainDistributionBoxNode |
             ElectricNodeTemplate::SubDistributionBoxNode => {
                 // 配电箱节点：一个输入（上级进线），多个输出（出线电流、三相平衡、配电箱数据）
-                graph.add_input_param(node_id, "上级进线", InputParamKind::ConnectionOnly, DataType::Current, None);
-                graph.add_output_param(node_id, "出线电流", DataType::Current);
-                graph.add_output_param(node_id, "三相平衡", DataType::PhaseBalanceInfo);
-                graph.add_output_param(node_id, "配电箱数据", DataType::DistributionBoxInfo);
+                graph.add_input_param(node_id, "上级进线".to_string(), DataType::Current, UIValueType::default(), InputParamKind::ConnectionOnly, true);
+                graph.add_output_param(node_id, "出线电流".to_string(), DataType::Current);
+                graph.add_output_param(node_id, "三相平衡".to_string(), DataType::PhaseBalanceInfo);
+                graph.add_output_param(node_id, "配电箱数据".to_string(), DataType::DistributionBoxInfo);
             },
 
             ElectricNodeTemplate::MainLineNode |
             ElectricNodeTemplate::FeederLineNode => {
                 // 干线节点：一个输入（电源输入），多个输出（线路电流、电压降、干线数据）
-                graph.add_input_param(node_id, "电源输入", InputParamKind::ConnectionOnly, DataType::Voltage, None);
-                graph.add_output_param(node_id, "线路电流", DataType::Current);
-                graph.add_output_param(node_id, "电压降", DataType::Voltage);
-                graph.add_output_param(node_id, "干线数据", DataType::MainLineInfo);
+                graph.add_input_param(node_id, "电源输入".to_string(), DataType::Voltage, UIValueType::default(), InputParamKind::ConnectionOnly, true);
+                graph.add_output_param(node_id, "线路电流".to_string(), DataType::Current);
+                graph.add_output_param(node_id, "电压降".to_string(), DataType::Voltage);
+                graph.add_output_param(node_id, "干线数据".to_string(), DataType::MainLineInfo);
+            },
+
+            ElectricNodeTemplate::MainSystemNode => {
+                // 干线系统图节点：输入配电箱数据，输出系统图数量
+                graph.add_input_param(node_id, "配电箱数据".to_string(), DataType::DistributionBoxInfo, UIValueType::default(), InputParamKind::ConnectionOnly, true);
+                graph.add_output_param(node_id, "系统图数量".to_string(), DataType::Integer);
             },
 
             ElectricNodeTemplate::PowerSourceNode => {
                 // 电源节点：无输入，输出电压和电源信息
-                graph.add_output_param(node_id, "电压", DataType::Voltage);
-                graph.add_output_param(node_id, "电源容量", DataType::Power);
-                graph.add_output_param(node_id, "电源信息", DataType::PowerSourceInfo);
+                graph.add_output_param(node_id, "电压".to_string(), DataType::Voltage);
+                graph.add_output_param(node_id, "电源容量".to_string(), DataType::Power);
+                graph.add_output_param(node_id, "电源信息".to_string(), DataType::PowerSourceInfo);
             },
 
             ElectricNodeTemplate::CurrentCalculationNode => {
                 // 电流计算节点：输入功率、电压、功率因数，输出计算电流
-                graph.add_input_param(node_id, "功率", InputParamKind::ConnectionOrConstant, DataType::Power, Some(UIValueType::Float(0.0)));
-                graph.add_input_param(node_id, "电压", InputParamKind::ConnectionOrConstant, DataType::Voltage, Some(UIValueType::Float(220.0)));
-                graph.add_input_param(node_id, "功率因数", InputParamKind::ConnectionOrConstant, DataType::PowerFactor, Some(UIValueType::Float(0.8)));
-                graph.add_input_param(node_id, "需用系数", InputParamKind::ConnectionOrConstant, DataType::Coefficient, Some(UIValueType::Float(0.8)));
-                graph.add_output_param(node_id, "计算电流", DataType::Current);
+                graph.add_input_param(node_id, "功率".to_string(), DataType::Power, UIValueType::Float(0.0), InputParamKind::ConnectionOrConstant, true);
+                graph.add_input_param(node_id, "电压".to_string(), DataType::Voltage, UIValueType::Float(220.0), InputParamKind::ConnectionOrConstant, true);
+                graph.add_input_param(node_id, "功率因数".to_string(), DataType::PowerFactor, UIValueType::Float(0.8), InputParamKind::ConnectionOrConstant, true);
+                graph.add_input_param(node_id, "需用系数".to_string(), DataType::Coefficient, UIValueType::Float(0.8), InputParamKind::ConnectionOrConstant, true);
+                graph.add_output_param(node_id, "计算电流".to_string(), DataType::Current);
             },
 
             ElectricNodeTemplate::PhaseBalanceNode => {
                 // 三相平衡节点：输入A/B/C相功率，输出不平衡度和优化建议
-                graph.add_input_param(node_id, "A相功率", InputParamKind::ConnectionOrConstant, DataType::Power, Some(UIValueType::Float(0.0)));
-                graph.add_input_param(node_id, "B相功率", InputParamKind::ConnectionOrConstant, DataType::Power, Some(UIValueType::Float(0.0)));
-                graph.add_input_param(node_id, "C相功率", InputParamKind::ConnectionOrConstant, DataType::Power, Some(UIValueType::Float(0.0)));
-                graph.add_output_param(node_id, "不平衡度", DataType::Coefficient);
-                graph.add_output_param(node_id, "平衡建议", DataType::String);
-                graph.add_output_param(node_id, "相平衡结果", DataType::PhaseBalanceInfo);
+                graph.add_input_param(node_id, "A相功率".to_string(), DataType::Power, UIValueType::Float(0.0), InputParamKind::ConnectionOrConstant, true);
+                graph.add_input_param(node_id, "B相功率".to_string(), DataType::Power, UIValueType::Float(0.0), InputParamKind::ConnectionOrConstant, true);
+                graph.add_input_param(node_id, "C相功率".to_string(), DataType::Power, UIValueType::Float(0.0), InputParamKind::ConnectionOrConstant, true);
+                graph.add_output_param(node_id, "不平衡度".to_string(), DataType::Coefficient);
+                graph.add_output_param(node_id, "平衡建议".to_string(), DataType::String);
+                graph.add_output_param(node_id, "相平衡结果".to_string(), DataType::PhaseBalanceInfo);
             },
         }
     }
 }
 
+/// 全部节点模板，供节点查找器枚举
+pub struct AllElectricTemplates;
+
+impl NodeTemplateIter for AllElectricTemplates {
+    type Item = ElectricNodeTemplate;
+
+    fn all_kinds(&self) -> Vec<Self::Item> {
+        all_electric_templates()
+    }
+}
+
 /// 初始化所有节点模板
 pub fn get_all_node_templates() -> Vec<ElectricNodeTemplate> {
     all_electric_templates()
@@ -1,6 +1,8 @@
 /// 回路文字标注库
 use serde::{Deserialize, Serialize};
 
+use crate::core_lib::algorithm::protection_coordination::{ConductorInsulation, ProtectionCoordinator, STANDARD_SECTIONS};
+
 /// 电压等级
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum VoltageLevel {
@@ -181,6 +183,34 @@ impl PhaseLine {
             PhaseLine::M240 => PE::M120,
         }
     }
+    /// 截面数值(mm²)
+    pub fn to_f64(&self) -> f64 {
+        self.to_str().parse().unwrap_or(0.0)
+    }
+    /// 由小到大排列的全部相线规格
+    pub fn all() -> [PhaseLine; 15] {
+        [
+            PhaseLine::M1_5,
+            PhaseLine::M2_5,
+            PhaseLine::M4,
+            PhaseLine::M6,
+            PhaseLine::M10,
+            PhaseLine::M16,
+            PhaseLine::M25,
+            PhaseLine::M35,
+            PhaseLine::M50,
+            PhaseLine::M70,
+            PhaseLine::M95,
+            PhaseLine::M120,
+            PhaseLine::M150,
+            PhaseLine::M185,
+            PhaseLine::M240,
+        ]
+    }
+    /// 按截面向上取整获取相线规格，超出最大规格返回None
+    pub fn from_section(section: f64) -> Option<Self> {
+        Self::all().into_iter().find(|p| p.to_f64() + 1e-6 >= section)
+    }
 }
 
 /// 双拼电缆
@@ -223,6 +253,43 @@ impl TwinParallelCables {
     }
 }
 
+/// 多根并联电缆（2~4根），每根为4+1芯
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParallelCables {
+    pub runs: u32,        // 并联根数
+    pub phase: PhaseLine, // 每根电缆相线截面
+}
+impl ParallelCables {
+    /// 创建并联电缆，并联根数应为2~4
+    pub fn new(runs: u32, phase: PhaseLine) -> Result<Self, &'static str> {
+        if !(2..=4).contains(&runs) {
+            return Err("并联电缆根数应为2~4");
+        }
+        Ok(Self { runs, phase })
+    }
+    /// 每根电缆对应的PE值
+    pub fn corresponding_pe(&self) -> PE {
+        self.phase.corresponding_pe()
+    }
+    /// 生成规格字符串，如"2x(4x185+PE95)"
+    pub fn to_spec_str(&self) -> String {
+        format!("{}x(4x{}+PE{})", self.runs, self.phase.to_str(), self.corresponding_pe().to_str())
+    }
+}
+impl From<TwinParallelCables> for ParallelCables {
+    fn from(twin: TwinParallelCables) -> Self {
+        let phase = match twin {
+            TwinParallelCables::M2x70 => PhaseLine::M70,
+            TwinParallelCables::M2x95 => PhaseLine::M95,
+            TwinParallelCables::M2x120 => PhaseLine::M120,
+            TwinParallelCables::M2x150 => PhaseLine::M150,
+            TwinParallelCables::M2x185 => PhaseLine::M185,
+            TwinParallelCables::M2x240 => PhaseLine::M240,
+        };
+        Self { runs: 2, phase }
+    }
+}
+
 ///PE线
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PE {
@@ -258,6 +325,153 @@ impl PE {
             PE::M120 => "120",
         }
     }
+    /// 截面数值(mm²)
+    pub fn to_f64(&self) -> f64 {
+        self.to_str().parse().unwrap_or(0.0)
+    }
+    /// 由小到大排列的全部PE线规格
+    pub fn all() -> [PE; 12] {
+        [
+            PE::M1_5,
+            PE::M2_5,
+            PE::M4,
+            PE::M6,
+            PE::M10,
+            PE::M16,
+            PE::M25,
+            PE::M35,
+            PE::M50,
+            PE::M70,
+            PE::M95,
+            PE::M120,
+        ]
+    }
+    /// 按截面向上取整获取PE线规格，超出最大规格返回None
+    pub fn from_section(section: f64) -> Option<Self> {
+        Self::all().into_iter().find(|pe| pe.to_f64() + 1e-6 >= section)
+    }
+}
+
+/// PE线截面选择方法
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PeSizing {
+    /// 按相线截面查表
+    Table,
+    /// 按绝热方程 S = I·√t / k 计算
+    Adiabatic {
+        fault_current: f64,      // 接地故障电流(A)
+        disconnection_time: f64, // 保护电器切断时间(s)
+        k: f64,                  // 导体材料及绝缘系数（铜芯PVC 115，XLPE 143）
+    },
+}
+impl PeSizing {
+    /// 选择PE线截面
+    ///
+    /// 绝热方程计算值向上取整到标准截面；计算值超过相线截面时应加大相线，返回错误
+    pub fn select(&self, phase: PhaseLine) -> Result<PE, &'static str> {
+        match self {
+            PeSizing::Table => Ok(phase.corresponding_pe()),
+            PeSizing::Adiabatic {
+                fault_current,
+                disconnection_time,
+                k,
+            } => {
+                if *fault_current <= 0.0 || *disconnection_time <= 0.0 || *k <= 0.0 {
+                    return Err("故障电流、切断时间和k值必须大于0");
+                }
+                let section = fault_current * disconnection_time.sqrt() / k;
+                if section > phase.to_f64() + 1e-6 {
+                    return Err("绝热方程计算的PE截面超过相线截面，应加大相线截面");
+                }
+                PE::from_section(section).ok_or("绝热方程计算的PE截面超出最大规格")
+            }
+        }
+    }
+}
+
+/// 中性线截面选择结果
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NeutralSizing {
+    /// 相线截面（按中性线电流选择时可能加大）
+    pub phase: PhaseLine,
+    /// 中性线截面
+    pub neutral: PhaseLine,
+    /// 中性线电流(A)
+    pub neutral_current: f64,
+    /// 三次谐波载流量降低系数
+    pub reduction_factor: f64,
+}
+impl NeutralSizing {
+    /// 按三次谐波含量选择中性线截面
+    ///
+    /// - 三次谐波含量 ≤ 15%：相线大于16mm²时中性线可按PE线对照表减小截面
+    /// - 15% ~ 33%：中性线与相线等截面，载流量乘0.86
+    /// - 33% ~ 45%：按中性线电流（3 × 谐波含量 × 相电流）选择截面，载流量乘0.86
+    /// - 大于45%：按中性线电流选择截面，全部线芯等截面
+    ///
+    /// # 参数
+    /// * `phase` - 按相电流选定的相线截面
+    /// * `design_current` - 相线设计电流(A)
+    /// * `third_harmonic` - 三次谐波含量（相电流的比例，如0.25）
+    pub fn select(phase: PhaseLine, design_current: f64, third_harmonic: f64) -> Result<Self, &'static str> {
+        if design_current < 0.0 || !(0.0..=1.0).contains(&third_harmonic) {
+            return Err("设计电流不能为负，三次谐波含量应在0~1之间");
+        }
+
+        let harmonic_neutral = 3.0 * third_harmonic * design_current;
+        let (required_current, reduction_factor) = match third_harmonic {
+            h if h <= 0.15 => {
+                let neutral = if phase.to_f64() > 16.0 {
+                    PhaseLine::from_section(phase.corresponding_pe().to_f64()).unwrap_or(phase)
+                } else {
+                    phase
+                };
+                return Ok(Self { phase, neutral, neutral_current: harmonic_neutral, reduction_factor: 1.0 });
+            }
+            h if h <= 0.33 => (design_current / 0.86, 0.86),
+            h if h <= 0.45 => (harmonic_neutral / 0.86, 0.86),
+            _ => (harmonic_neutral, 1.0),
+        };
+
+        let section = STANDARD_SECTIONS
+            .iter()
+            .copied()
+            .find(|&s| {
+                s + 1e-6 >= phase.to_f64()
+                    && ProtectionCoordinator::ampacity(s, ConductorInsulation::Xlpe).unwrap_or(0.0) >= required_current
+            })
+            .ok_or("中性线电流超出最大截面载流量")?;
+        let sized = PhaseLine::from_section(section).ok_or("中性线电流超出最大截面载流量")?;
+
+        Ok(Self { phase: sized, neutral: sized, neutral_current: harmonic_neutral, reduction_factor })
+    }
+
+    /// 负荷类型对应的典型三次谐波含量
+    ///
+    /// 照明（气体放电灯、LED驱动）约25%，计算机、UPS等信息设备约40%，其他负荷按10%
+    pub fn typical_third_harmonic(load: &str) -> f64 {
+        if ["计算机", "电脑", "机房", "UPS", "信息", "IT"].iter().any(|k| load.contains(k)) {
+            0.40
+        } else if load.contains("照明") {
+            0.25
+        } else {
+            0.10
+        }
+    }
+
+    /// 结合PE线截面生成电缆芯数
+    ///
+    /// 中性线减小截面时为3+2芯；中性线与PE线均与相线等截面时为5芯；否则为4+1芯
+    pub fn cores(&self, pe: PE) -> CoresNum {
+        if self.neutral.to_f64() < self.phase.to_f64() {
+            let reduced = PE::from_section(self.neutral.to_f64().max(pe.to_f64())).unwrap_or(pe);
+            CoresNum::C3_2(self.phase, reduced)
+        } else if (pe.to_f64() - self.phase.to_f64()).abs() < 1e-6 {
+            CoresNum::C5(self.phase)
+        } else {
+            CoresNum::C4_1(self.phase, None, pe)
+        }
+    }
 }
 
 /// 电缆芯数
@@ -266,6 +480,8 @@ pub enum CoresNum {
     C2_1(PhaseLine, PE),
     C4_1(PhaseLine, Option<TwinParallelCables>, PE),
     C4(PhaseLine, Option<TwinParallelCables>),
+    C5(PhaseLine),
+    C3_2(PhaseLine, PE),
 }
 impl CoresNum {
     /// 创建C2_1
@@ -295,6 +511,10 @@ impl CoresNum {
         }
         Ok(Self::C4_1(phase, Some(twin), pe))
     }
+    /// 按PE线选择方法创建C4_1（绝热方程计算的PE可与对照表不同）
+    pub fn new_c4_1_sized(phase: PhaseLine, pe_sizing: &PeSizing) -> Result<Self, &'static str> {
+        Ok(Self::C4_1(phase, None, pe_sizing.select(phase)?))
+    }
     /// C4创建逻辑
     pub fn new_c4(phase: PhaseLine, twin: TwinParallelCables) -> Self {
         Self::C4(phase, Some(twin))
@@ -319,6 +539,8 @@ impl CoresNum {
                     format!("2x(4x{})", twin_cable.to_str())
                 }
             },
+            CoresNum::C5(phase) => format!("5x{}", phase.to_str()),
+            CoresNum::C3_2(phase, reduced) => format!("3x{}+2x{}", phase.to_str(), reduced.to_str()),
         }
     }
 }
@@ -381,30 +603,6 @@ impl CircuitNumber {
         }
     }
 
-    // 生成当前编号字符串
-    pub fn to_string(&self) -> String {
-        match self {
-            Self::LetterNumber {
-                letter,
-                current_number,
-            } => {
-                format!("{}{}", letter, current_number)
-            }
-            Self::TwoLettersNumber {
-                prefix,
-                current_number,
-            } => {
-                format!("{}{}", prefix, current_number)
-            }
-            CircuitNumber::DistributionBox {
-                box_id,
-                current_number,
-            } => {
-                format!("{}-{}", box_id, current_number)
-            }
-        }
-    }
-
     // 生成下一个编号
     pub fn next(&self) -> Self {
         match self {
@@ -433,6 +631,26 @@ impl CircuitNumber {
     }
 }
 
+impl std::fmt::Display for CircuitNumber {
+    // 生成当前编号字符串
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LetterNumber {
+                letter,
+                current_number,
+            } => write!(f, "{}{}", letter, current_number),
+            Self::TwoLettersNumber {
+                prefix,
+                current_number,
+            } => write!(f, "{}{}", prefix, current_number),
+            Self::DistributionBox {
+                box_id,
+                current_number,
+            } => write!(f, "{}-{}", box_id, current_number),
+        }
+    }
+}
+
 /// 回路用途,支持手动输入或从外部参数获取
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CircuitPurpose {
@@ -522,21 +740,6 @@ impl CircuitPower {
         })
     }
 
-    /// 获取带单位的功率字符串（自动添加kW）
-    pub fn to_string(&self) -> String {
-        let value = match self {
-            Self::Manual { value } => *value,
-            Self::External { value, .. } => *value,
-        };
-
-        // 整数数值显示为整数形式（如10 -> "10kW"），否则保留小数（如7.5 -> "7.5kW"）
-        if value.fract() == 0.0 {
-            format!("{}kW", value as u32)
-        } else {
-            format!("{}kW", value)
-        }
-    }
-
     /// 更新功率数值（保持kW单位）
     pub fn update(&mut self, new_value: &str) -> Result<(), &'static str> {
         let new_value = new_value.parse().map_err(|_| {
@@ -572,6 +775,23 @@ impl CircuitPower {
     }
 }
 
+impl std::fmt::Display for CircuitPower {
+    /// 获取带单位的功率字符串（自动添加kW）
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            Self::Manual { value } => *value,
+            Self::External { value, .. } => *value,
+        };
+
+        // 整数数值显示为整数形式（如10 -> "10kW"），否则保留小数（如7.5 -> "7.5kW"）
+        if value.fract() == 0.0 {
+            write!(f, "{}kW", value as u32)
+        } else {
+            write!(f, "{}kW", value)
+        }
+    }
+}
+
 // 测试用例
 #[cfg(test)]
 mod tests {
@@ -597,6 +817,41 @@ mod tests {
             CoresNum::new_c4_1_twin(PhaseLine::M120, TwinParallelCables::M2x120, PE::M70).unwrap();
         assert_eq!(c4_1.to_spec_str(), "2x(4x120+PE70)");
     }
+    #[test]
+    fn test_adiabatic_pe_sizing() {
+        // 铜芯XLPE：S = 8000×√0.4/143 ≈ 35.4mm² → 50mm²，大于对照表的PE35
+        let sizing = PeSizing::Adiabatic { fault_current: 8000.0, disconnection_time: 0.4, k: 143.0 };
+        assert_eq!(sizing.select(PhaseLine::M70).unwrap(), PE::M50);
+        assert_eq!(PeSizing::Table.select(PhaseLine::M70).unwrap(), PE::M35);
+
+        let c4_1 = CoresNum::new_c4_1_sized(PhaseLine::M70, &sizing).unwrap();
+        assert_eq!(c4_1.to_spec_str(), "4x70+PE50");
+        assert!(sizing.select(PhaseLine::M25).is_err());
+    }
+
+    #[test]
+    fn test_neutral_sizing_by_third_harmonic() {
+        // 低谐波：中性线减小截面，3+2芯
+        let low = NeutralSizing::select(PhaseLine::M95, 180.0, 0.1).unwrap();
+        assert_eq!(low.neutral, PhaseLine::M50);
+        assert_eq!(low.cores(PE::M50).to_spec_str(), "3x95+2x50");
+
+        // 照明负荷：中性线与相线等截面，4+1芯
+        let lighting = NeutralSizing::select(PhaseLine::M25, 60.0, NeutralSizing::typical_third_harmonic("照明")).unwrap();
+        assert_eq!(lighting.neutral, PhaseLine::M25);
+        assert_eq!(lighting.reduction_factor, 0.86);
+        assert_eq!(lighting.cores(PE::M16).to_spec_str(), "4x25+PE16");
+
+        // 信息设备：按中性线电流 3×0.5×100=150A 加大截面
+        let it = NeutralSizing::select(PhaseLine::M35, 100.0, 0.5).unwrap();
+        assert_eq!(it.neutral, PhaseLine::M50);
+        assert_eq!(it.phase, PhaseLine::M50);
+
+        // 小截面：5芯
+        let small = NeutralSizing::select(PhaseLine::M10, 30.0, 0.25).unwrap();
+        assert_eq!(small.cores(PeSizing::Table.select(small.phase).unwrap()).to_spec_str(), "5x10");
+    }
+
     #[test]
     fn test_two_letters_case() {
         let mut circuit = CircuitNumber::new_two_letters("WL");
@@ -605,7 +860,7 @@ mod tests {
         circuit = circuit.next();
         assert_eq!(circuit.to_string(), "WL2");
 
-        let mut circuit = CircuitNumber::new_two_letters("WX");
+        let circuit = CircuitNumber::new_two_letters("WX");
         assert_eq!(circuit.to_string(), "WX1");
     }
 
@@ -695,7 +950,6 @@ mod tests {
         }
     }
 
-    #[test]
     #[test]
     fn test_manual_power() {
         // 有效手动输入：只传数值（不带单位）
@@ -1,4 +1,4 @@
-/// 开关元器件文字标注库
+//! 开关元器件文字标注库
 use serde::{Deserialize, Serialize};
 
 /// 开关元器件类型
@@ -180,15 +180,11 @@ impl SettingValue {
 }
 
 /// 相位配置
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum Phase {
     SinglePhase, // 单相
-    ThreePhase,  // 三相
-}
-impl Default for Phase {
-    fn default() -> Self {
-        Phase::ThreePhase // 默认三相
-    }
+    #[default]
+    ThreePhase,  // 三相（默认）
 }
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DynamicBreakerText {
@@ -300,6 +296,15 @@ impl DynamicBreakerText {
             self.deduction_method.unwrap_or(DeductionMethod::TM),
         )
     }
+
+    /// 断路器两行标注文字：如"MCB63"和"1P+N-C 20A"
+    pub fn text_lines(&self) -> [String; 2] {
+        let (breaker_type, frame_current, pole, curve, setting_value, _, _) = self.resolved_params();
+        [
+            format!("{}{}", breaker_type.to_str(), frame_current.to_str()),
+            format!("{}-{} {}A", pole.to_str(), curve.to_str(), setting_value.to_str()),
+        ]
+    }
 }
 /// 增加枚举值转浮点数的功能
 impl SettingValue {
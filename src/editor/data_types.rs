@@ -1,11 +1,12 @@
-/// UI层数据结构定义
-/// 用于节点编辑器和UI组件中的数据表示
+//! UI层数据结构定义
+//! 用于节点编辑器和UI组件中的数据表示
 
 use serde::{Deserialize, Serialize};
-use egui_node_graph::{NodeId, NodeResponse, Graph, UserResponseTrait, NodeDataTrait, DataTypeTrait};
+use egui_node_graph::{NodeId, UserResponseTrait, DataTypeTrait, WidgetValueTrait};
 use egui;
 use std::borrow::Cow;
-use crate::core_lib::data_types::electric_data::{Phase, CircuitData, DistributionBoxData};
+use crate::core_lib::data_types::{Phase, CircuitData, DistributionBoxData};
+use crate::editor::business::PowerGraphNode;
 
 /// UI层的值类型枚举，用于节点编辑器中表示不同类型的数据值
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -190,7 +191,7 @@ impl Default for PhaseLoadInfo {
 }
 
 /// 数据类型定义，用于节点编辑器中标识不同的数据类型
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum DataType {
     Float,                  // 浮点数类型
     Integer,                // 整数类型
@@ -282,7 +283,6 @@ impl From<&CircuitData> for CircuitInfo {
                 Phase::L2 => "L2",
                 Phase::L3 => "L3",
                 Phase::ThreePhase => "三相",
-                _ => "L1",
             }.to_string(),
             circuit_type: circuit_data.circuit_type.clone(),
             current: circuit_data.calculated_current,
@@ -300,9 +300,9 @@ impl From<&DistributionBoxData> for DistributionBoxInfo {
             circuit_count: box_data.outgoing_circuits.len(),
             total_power: box_data.total_power,
             calculated_current: box_data.total_current,
-            phase_a_load: box_data.phase_balance.phase_a_load,
-            phase_b_load: box_data.phase_balance.phase_b_load,
-            phase_c_load: box_data.phase_balance.phase_c_load,
+            phase_a_load: box_data.phase_balance.phase_a_power,
+            phase_b_load: box_data.phase_balance.phase_b_power,
+            phase_c_load: box_data.phase_balance.phase_c_power,
         }
     }
 }
@@ -311,10 +311,10 @@ impl From<&DistributionBoxData> for DistributionBoxInfo {
 impl From<&DistributionBoxData> for PhaseBalanceInfo {
     fn from(box_data: &DistributionBoxData) -> Self {
         PhaseBalanceInfo {
-            phase_a: box_data.phase_balance.phase_a_load,
-            phase_b: box_data.phase_balance.phase_b_load,
-            phase_c: box_data.phase_balance.phase_c_load,
-            balance_degree: box_data.phase_balance.unbalance_degree,
+            phase_a: box_data.phase_balance.phase_a_power,
+            phase_b: box_data.phase_balance.phase_b_power,
+            phase_c: box_data.phase_balance.phase_c_power,
+            balance_degree: box_data.phase_balance.balance_degree,
         }
     }
 }
@@ -342,52 +342,91 @@ pub fn update_box_data_from_ui(box_data: &mut DistributionBoxData, box_info: &Di
     // 注意：circuit_count、total_power、calculated_current等应由业务层计算，而不是直接赋值
 }
 
-// TODO: 暂时注释掉部分实现代码
-// 后续将根据需求重新实现
-/*
 /// 为UIValueType实现WidgetValueTrait，提供参数控件的UI渲染支持
 impl WidgetValueTrait for UIValueType {
-    // 使用基本类型作为关联类型
-    type Response = ();
-    type UserState = ();
-    type NodeData = ();
+    type Response = UIResponse;
+    type UserState = UIUserState;
+    type NodeData = PowerGraphNode;
     
     // 实现核心方法 - 处理不同类型的值
     fn value_widget(
         &mut self,
         param_name: &str,
-        _node_id: NodeId,
+        node_id: NodeId,
         ui: &mut egui::Ui,
         _user_state: &mut Self::UserState,
         _node_data: &Self::NodeData,
     ) -> Vec<Self::Response> {
         // 简化版本，只处理基本类型
-        match self {
+        let changed = match self {
             UIValueType::Float(val) => {
                 ui.horizontal(|ui| {
                     ui.label(param_name);
-                    ui.add(egui::DragValue::new(val));
-                });
+                    ui.add(egui::DragValue::new(val)).changed()
+                }).inner
             },
             UIValueType::Integer(val) => {
                 ui.horizontal(|ui| {
                     ui.label(param_name);
-                    ui.add(egui::DragValue::new(val));
-                });
+                    ui.add(egui::DragValue::new(val)).changed()
+                }).inner
             },
             UIValueType::String(val) => {
                 ui.horizontal(|ui| {
                     ui.label(param_name);
-                    ui.text_edit_singleline(val);
-                });
+                    ui.text_edit_singleline(val).changed()
+                }).inner
             },
             _ => {
                 // 其他类型简单显示
                 ui.label(format!("{}: {}", param_name, self));
+                false
             }
-        }
+        };
         
-        Vec::new()
+        if changed {
+            vec![UIResponse::ValueChanged(node_id, param_name.to_string(), self.clone())]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// 为DataType实现DataTypeTrait，提供端口颜色和名称
+impl DataTypeTrait<UIUserState> for DataType {
+    fn data_type_color(&self, _user_state: &mut UIUserState) -> egui::Color32 {
+        match self {
+            DataType::Current => egui::Color32::from_rgb(255, 100, 100),
+            DataType::Power => egui::Color32::from_rgb(100, 200, 100),
+            DataType::Voltage => egui::Color32::from_rgb(100, 100, 255),
+            DataType::PowerFactor => egui::Color32::from_rgb(255, 255, 100),
+            DataType::Coefficient => egui::Color32::from_rgb(255, 100, 255),
+            DataType::CircuitInfo | DataType::CircuitGroupInfo => egui::Color32::from_rgb(200, 150, 100),
+            DataType::DistributionBoxInfo | DataType::MainLineInfo => egui::Color32::from_rgb(100, 200, 200),
+            DataType::PhaseBalanceInfo | DataType::PhaseLoadInfo => egui::Color32::from_rgb(200, 100, 200),
+            DataType::PowerSourceInfo => egui::Color32::from_rgb(255, 180, 60),
+            DataType::Float | DataType::Integer | DataType::String => egui::Color32::from_rgb(180, 180, 180),
+        }
+    }
+
+    fn name(&self) -> Cow<'_, str> {
+        Cow::Borrowed(match self {
+            DataType::Float => "浮点数",
+            DataType::Integer => "整数",
+            DataType::String => "字符串",
+            DataType::CircuitInfo => "回路信息",
+            DataType::CircuitGroupInfo => "回路组信息",
+            DataType::DistributionBoxInfo => "配电箱信息",
+            DataType::PhaseBalanceInfo => "三相平衡",
+            DataType::PowerSourceInfo => "电源信息",
+            DataType::PhaseLoadInfo => "三相负载",
+            DataType::Voltage => "电压",
+            DataType::Current => "电流",
+            DataType::Power => "功率",
+            DataType::PowerFactor => "功率因数",
+            DataType::Coefficient => "系数",
+            DataType::MainLineInfo => "干线信息",
+        })
     }
 }
 
@@ -407,4 +446,3 @@ impl std::fmt::Display for UIValueType {
         }
     }
 }
-*/
\ No newline at end of file
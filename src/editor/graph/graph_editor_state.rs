@@ -1,88 +1,281 @@
-use egui_node_graph::{Graph, GraphEditorState, NodeId, InputId, OutputId};
+use egui_node_graph::{Graph, GraphEditorState, NodeId, InputId, InputParamKind, OutputId, NodeTemplateTrait};
 use crate::editor::{DataType, UIValueType, UIUserState};
-use crate::editor::business::{get_all_node_templates, PowerGraphNode};
-use std::collections::HashMap;
+use crate::editor::business::{get_all_node_templates, ElectricNodeTemplate, PowerGraphNode};
+use crate::editor::business::building::next_box_name;
+
+/// 节点图编辑器UI状态类型
+pub type PowerGraphEditorState = GraphEditorState<PowerGraphNode, DataType, UIValueType, ElectricNodeTemplate, UIUserState>;
 
 /// 电力配电系统图编辑器状态
 pub struct PowerDistributionGraphEditorState {
-    /// 节点图数据
-    pub graph: Graph<PowerGraphNode, DataType, UIValueType>,
-    /// 节点编辑器UI状态
-    pub editor_state: GraphEditorState<PowerGraphNode, DataType, UIValueType, UIUserState>,
+    /// 节点编辑器UI状态（包含节点图数据）
+    pub editor_state: PowerGraphEditorState,
     /// UI状态数据
     pub user_state: UIUserState,
     /// 当前选中的节点ID
     pub selected_node_id: Option<NodeId>,
+    /// 复制的节点，粘贴时据此新建节点
+    pub clipboard: Option<CopiedNode>,
+}
+
+/// 复制的节点：节点数据、端口及原位置
+#[derive(Debug, Clone)]
+pub struct CopiedNode {
+    /// 节点标签
+    pub label: String,
+    /// 节点数据
+    pub user_data: PowerGraphNode,
+    /// 输入端口：名称、数据类型、常量值、输入方式、是否内联显示
+    pub inputs: Vec<(String, DataType, UIValueType, InputParamKind, bool)>,
+    /// 输出端口：名称、数据类型
+    pub outputs: Vec<(String, DataType)>,
+    /// 节点位置，连续粘贴时依次错开
+    pub position: egui::Pos2,
 }
- 
 
 impl Default for PowerDistributionGraphEditorState {
     fn default() -> Self {
-        let mut state = Self {
-            graph: Graph::default(),
-            editor_state: GraphEditorState::default(),
+        Self {
+            editor_state: GraphEditorState::new(1.0),
             user_state: UIUserState::default(),
             selected_node_id: None,
-        };
-        
-        state
+            clipboard: None,
+        }
     }
 }
 
-
 impl PowerDistributionGraphEditorState {
     /// 创建新的图编辑器状态
     pub fn new() -> Self {
         Self::default()
     }
-    
+
+    /// 获取节点图数据
+    pub fn graph(&self) -> &Graph<PowerGraphNode, DataType, UIValueType> {
+        &self.editor_state.graph
+    }
+
+    /// 获取可变节点图数据
+    pub fn graph_mut(&mut self) -> &mut Graph<PowerGraphNode, DataType, UIValueType> {
+        &mut self.editor_state.graph
+    }
+
     /// 获取所有可用的节点模板
-    pub fn get_node_templates(&self) -> Vec<impl egui_node_graph::NodeTemplateTrait<NodeData=PowerGraphNode, DataType=DataType, ValueType=UIValueType, UserState=UIUserState>> {
+    pub fn get_node_templates(&self) -> Vec<ElectricNodeTemplate> {
         get_all_node_templates()
     }
-    
-    /// 添加节点
-    pub fn add_node(&mut self, node_data: PowerGraphNode) -> NodeId {
-        self.graph.add_node(node_data)
+
+    /// 按模板添加节点
+    ///
+    /// # 参数
+    /// * `template` - 节点模板
+    /// * `position` - 节点在画布中的位置
+    ///
+    /// # 返回值
+    /// 新节点的ID
+    pub fn add_template_node(&mut self, template: ElectricNodeTemplate, position: egui::Pos2) -> NodeId {
+        let mut user_data = template.user_data(&mut self.user_state);
+        // 新建的配电箱按"楼层前缀+类别+序号"规则编号
+        if let PowerGraphNode::DistributionBoxNode(box_data) = &mut user_data {
+            box_data.name = next_box_name(self.box_names(), box_data.floor, "AL");
+        }
+        self.add_node(template, user_data, position)
+    }
+
+    /// 画布中已有配电箱的名称
+    fn box_names(&self) -> Vec<&str> {
+        self.editor_state
+            .graph
+            .nodes
+            .values()
+            .filter_map(|node| match &node.user_data {
+                PowerGraphNode::DistributionBoxNode(other) => Some(other.name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 复制选中的节点
+    ///
+    /// # 返回值
+    /// 有选中节点时返回true
+    pub fn copy_selected(&mut self) -> bool {
+        let Some(node) = self.selected_node_id.and_then(|id| self.editor_state.graph.nodes.get(id)) else {
+            return false;
+        };
+        let graph = &self.editor_state.graph;
+        self.clipboard = Some(CopiedNode {
+            label: node.label.clone(),
+            user_data: node.user_data.clone(),
+            inputs: node
+                .inputs
+                .iter()
+                .map(|(name, id)| {
+                    let param = graph.get_input(*id);
+                    (name.clone(), param.typ.clone(), param.value.clone(), param.kind, param.shown_inline)
+                })
+                .collect(),
+            outputs: node.outputs.iter().map(|(name, id)| (name.clone(), graph.get_output(*id).typ.clone())).collect(),
+            position: self.editor_state.node_positions.get(node.id).copied().unwrap_or_default(),
+        });
+        true
+    }
+
+    /// 粘贴复制的节点
+    ///
+    /// 配电箱通过`DistributionBoxNode::duplicate`分配新的标识并按楼层重新编号，
+    /// 新节点相对原位置错开放置并被选中
+    ///
+    /// # 返回值
+    /// 新节点的ID，剪贴板为空时返回None
+    pub fn paste(&mut self) -> Option<NodeId> {
+        let copied = self.clipboard.as_mut()?;
+        copied.position += egui::vec2(40.0, 40.0);
+        let copied = copied.clone();
+
+        let user_data = match &copied.user_data {
+            PowerGraphNode::DistributionBoxNode(box_data) => {
+                let mut data = box_data.duplicate();
+                data.name = next_box_name(self.box_names(), data.floor, "AL");
+                PowerGraphNode::DistributionBoxNode(data)
+            }
+            other => other.clone(),
+        };
+        let label = match &user_data {
+            PowerGraphNode::Other(_) => copied.label,
+            other => other.display_name(),
+        };
+        let node_id = self.editor_state.graph.add_node(label, user_data, |graph, node_id| {
+            for (name, typ, value, kind, shown_inline) in copied.inputs {
+                graph.add_input_param(node_id, name, typ, value, kind, shown_inline);
+            }
+            for (name, typ) in copied.outputs {
+                graph.add_output_param(node_id, name, typ);
+            }
+        });
+        self.editor_state.node_positions.insert(node_id, copied.position);
+        self.editor_state.node_order.push(node_id);
+        self.set_selected_node(Some(node_id));
+        Some(node_id)
     }
-    
+
+    /// 按模板添加节点并指定节点数据
+    ///
+    /// # 参数
+    /// * `template` - 节点模板，决定输入输出端口
+    /// * `node_data` - 节点数据
+    /// * `position` - 节点在画布中的位置
+    ///
+    /// # 返回值
+    /// 新节点的ID
+    pub fn add_node(&mut self, template: ElectricNodeTemplate, node_data: PowerGraphNode, position: egui::Pos2) -> NodeId {
+        let label = match &node_data {
+            PowerGraphNode::Other(_) => template.node_graph_label(&mut self.user_state),
+            other => other.display_name(),
+        };
+        let user_state = &mut self.user_state;
+        let node_id = self.editor_state.graph.add_node(label, node_data, |graph, node_id| {
+            template.build_node(graph, user_state, node_id)
+        });
+        self.editor_state.node_positions.insert(node_id, position);
+        self.editor_state.node_order.push(node_id);
+        node_id
+    }
+
+    /// 删除节点
+    pub fn remove_node(&mut self, node_id: NodeId) {
+        self.editor_state.graph.remove_node(node_id);
+        self.editor_state.node_positions.remove(node_id);
+        self.editor_state.node_order.retain(|id| *id != node_id);
+        self.editor_state.selected_nodes.retain(|id| *id != node_id);
+        if self.selected_node_id == Some(node_id) {
+            self.selected_node_id = None;
+        }
+    }
+
+    /// 清空节点图
+    pub fn clear(&mut self) {
+        self.editor_state = GraphEditorState::new(self.editor_state.pan_zoom.zoom);
+        self.selected_node_id = None;
+    }
+
     /// 创建连接
-    pub fn connect_nodes(&mut self, output_id: OutputId, input_id: InputId) -> bool {
-        self.graph.connect(output_id, input_id)
+    pub fn connect_nodes(&mut self, output_id: OutputId, input_id: InputId) {
+        self.editor_state.graph.add_connection(output_id, input_id)
     }
-    
+
     /// 断开连接
     pub fn disconnect_nodes(&mut self, input_id: InputId) -> bool {
-        self.graph.disconnect(input_id)
+        self.editor_state.graph.remove_connection(input_id).is_some()
     }
-    
+
+    /// 当前选中的节点
+    pub fn selected_node(&self) -> Option<NodeId> {
+        self.selected_node_id
+    }
+
+    /// 设置选中的节点
+    pub fn set_selected_node(&mut self, node_id: Option<NodeId>) {
+        self.selected_node_id = node_id;
+        self.editor_state.selected_nodes = node_id.into_iter().collect();
+    }
+
+    /// 选中节点并平移视图，使节点位于视图中央
+    ///
+    /// # 参数
+    /// * `node_id` - 节点ID
+    /// * `view_size` - 节点图视图尺寸
+    pub fn focus_node(&mut self, node_id: NodeId, view_size: egui::Vec2) {
+        self.set_selected_node(Some(node_id));
+        if let Some(position) = self.editor_state.node_positions.get(node_id) {
+            self.editor_state.pan_zoom.pan = view_size / 2.0 - position.to_vec2();
+        }
+    }
+
     /// 处理节点响应
     pub fn handle_node_responses(&mut self, responses: Vec<egui_node_graph::NodeResponse<crate::editor::UIResponse, PowerGraphNode>>) {
         for response in responses {
             match response {
-                egui_node_graph::NodeResponse::User(user_response) => {
-                    match user_response {
-                        crate::editor::UIResponse::ValueChanged(node_id, param_name, new_value) => {
-                            // 处理值变化事件
-                            println!("Node {} parameter '{}' changed to {:?}", node_id, param_name, new_value);
-                            // 可以在这里触发下游节点的更新
-                            self.mark_downstream_for_update(node_id);
-                        },
-                        crate::editor::UIResponse::NodeSelected(node_id) => {
-                            // 处理节点选择事件
-                            self.selected_node_id = Some(node_id);
-                        },
-                        _ => {}
-                    }
+                egui_node_graph::NodeResponse::SelectNode(node_id) => {
+                    self.selected_node_id = Some(node_id);
+                },
+                egui_node_graph::NodeResponse::DeleteNodeFull { node_id, .. }
+                    if self.selected_node_id == Some(node_id) => {
+                        self.selected_node_id = None;
+                    },
+                egui_node_graph::NodeResponse::User(crate::editor::UIResponse::NodeSelected(node_id)) => {
+                    self.selected_node_id = Some(node_id);
                 },
                 _ => {}
             }
         }
     }
-    
-    /// 标记下游节点为需要更新
-    fn mark_downstream_for_update(&self, _node_id: NodeId) {
-        // 实现拓扑排序和节点更新逻辑
-        // 这部分将在后续实现
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_paste_box() {
+        let mut state = PowerDistributionGraphEditorState::new();
+        let source = state.add_template_node(ElectricNodeTemplate::DistributionBoxNode, egui::pos2(0.0, 0.0));
+        assert!(state.paste().is_none());
+        state.set_selected_node(Some(source));
+        assert!(state.copy_selected());
+
+        let pasted = state.paste().unwrap();
+        assert_eq!(state.selected_node(), Some(pasted));
+        let graph = state.graph();
+        let (PowerGraphNode::DistributionBoxNode(original), PowerGraphNode::DistributionBoxNode(copy)) =
+            (&graph.nodes[source].user_data, &graph.nodes[pasted].user_data)
+        else {
+            panic!("粘贴的节点应为配电箱");
+        };
+        assert_ne!(copy.id, original.id);
+        assert_ne!(copy.name, original.name);
+        assert_eq!(graph.nodes[pasted].inputs.len(), graph.nodes[source].inputs.len());
+        assert_eq!(graph.nodes[pasted].outputs.len(), graph.nodes[source].outputs.len());
+        assert_eq!(state.editor_state.node_positions[pasted], egui::pos2(40.0, 40.0));
     }
-}
\ No newline at end of file
+}
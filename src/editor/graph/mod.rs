@@ -1,6 +1,6 @@
-/// 节点图管理模块
+//! 节点图管理模块
 
 pub mod graph_editor_state;
 
 // 导出主要数据结构和函数
-pub use graph_editor_state::PowerDistributionGraphEditorState;
\ No newline at end of file
+pub use graph_editor_state::{CopiedNode, PowerDistributionGraphEditorState};
\ No newline at end of file
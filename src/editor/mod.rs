@@ -1,5 +1,5 @@
-/// 编辑器模块
-/// 负责节点编辑器和UI界面的实现
+//! 编辑器模块
+//! 负责节点编辑器和UI界面的实现
 
 pub mod business;
 pub mod graph;
@@ -15,7 +15,6 @@ use eframe::egui;
 use crate::application::AppState;
 use crate::editor::ui::{NodeEditor, PropertyPanel, Toolbar, StatusBar};
 use crate::editor::graph::PowerDistributionGraphEditorState;
-use std::sync::{Arc, Mutex};
 
 /// 主编辑器结构体
 pub struct Editor {
@@ -35,6 +34,12 @@ pub struct Editor {
     local_graph_state: Option<PowerDistributionGraphEditorState>,
 }
 
+impl Default for Editor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Editor {
     /// 创建新的编辑器
     pub fn new() -> Self {
@@ -56,9 +61,6 @@ impl Editor {
             self.local_graph_state = Some(PowerDistributionGraphEditorState::new());
         }
         
-        // 从应用状态获取图编辑器状态
-        let mut app_graph_state = state.graph_editor_state.lock().unwrap();
-        
         // 渲染菜单栏
         self.render_menu_bar(ctx, state);
         
@@ -119,7 +121,7 @@ impl Editor {
     /// 渲染菜单栏
     fn render_menu_bar(&mut self, ctx: &egui::Context, state: &mut AppState) {
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
-            egui::menu::bar(ui, |ui| {
+            egui::MenuBar::new().ui(ui, |ui| {
                 // 文件菜单
                 self.render_file_menu(ui, state);
                 
@@ -140,7 +142,7 @@ impl Editor {
     
     /// 渲染文件菜单
     fn render_file_menu(&self, ui: &mut egui::Ui, _state: &mut AppState) {
-        egui::menu::menu_button(ui, "文件", |ui| {
+        ui.menu_button("文件", |ui| {
             if ui.button("新建项目").clicked() {
                 // 实现新建项目功能
                 ui.close();
@@ -184,7 +186,7 @@ impl Editor {
     
     /// 渲染编辑菜单
     fn render_edit_menu(&mut self, ui: &mut egui::Ui, _state: &mut AppState) {
-        egui::menu::menu_button(ui, "编辑", |ui| {
+        ui.menu_button("编辑", |ui| {
             if ui.button("撤销").clicked() {
                 // 实现撤销功能
                 ui.close();
@@ -223,7 +225,7 @@ impl Editor {
     
     /// 渲染视图菜单
     fn render_view_menu(&mut self, ui: &mut egui::Ui, _state: &mut AppState) {
-        egui::menu::menu_button(ui, "视图", |ui| {
+        ui.menu_button("视图", |ui| {
             // 属性面板显示切换
             ui.checkbox(&mut self.show_property_panel.clone(), "显示属性面板");
             
@@ -257,7 +259,7 @@ impl Editor {
     
     /// 渲染工具菜单
     fn render_tools_menu(&self, ui: &mut egui::Ui, _state: &mut AppState) {
-        egui::menu::menu_button(ui, "工具", |ui| {
+        ui.menu_button("工具", |ui| {
             if ui.button("电气计算").clicked() {
                 // 实现电气计算功能
                 ui.close();
@@ -289,7 +291,7 @@ impl Editor {
     
     /// 渲染帮助菜单
     fn render_help_menu(&self, ui: &mut egui::Ui, _state: &mut AppState) {
-        egui::menu::menu_button(ui, "帮助", |ui| {
+        ui.menu_button("帮助", |ui| {
             if ui.button("用户手册").clicked() {
                 // 实现用户手册功能
                 ui.close();
@@ -316,7 +318,7 @@ impl Editor {
     
     /// 设置选中的节点ID
     pub fn set_selected_node_id(&mut self, node_id: Option<String>) {
-        self.selected_node_id = node_id;
         self.status_bar.update_selected_nodes_count(node_id.is_some() as usize);
+        self.selected_node_id = node_id;
     }
 }
\ No newline at end of file
@@ -1,13 +1,16 @@
-/// 调试工具和日志系统
-/// 用于开发过程中的问题诊断和性能监控
+//! 调试工具和日志系统
+//! 用于开发过程中的问题诊断和性能监控
 
-use egui::{Ui, ScrollArea, TextEdit, Button, Checkbox, Separator, CollapsingHeader};
+use egui::{Ui, ScrollArea, Separator, CollapsingHeader};
 use std::collections::VecDeque;
+use std::io::Write;
 use std::sync::Mutex;
 use chrono::{Local, DateTime};
 use serde::{Serialize, Deserialize};
+use once_cell::sync::Lazy;
 
-/// 日志级别\#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+/// 日志级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum LogLevel {
     /// 错误
     Error,
@@ -45,7 +48,8 @@ impl LogLevel {
     }
 }
 
-/// 日志条目\#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 日志条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogEntry {
     /// 时间戳
     pub timestamp: DateTime<Local>,
@@ -191,73 +195,71 @@ impl Logger {
 }
 
 // 创建全局日志实例
-lazy_static::lazy_static! {
-    pub static ref LOGGER: Logger = Logger::new(1000);
-}
+pub static LOGGER: Lazy<Logger> = Lazy::new(|| Logger::new(1000));
 
 /// 日志宏定义
 #[macro_export]
 macro_rules! log_error {
-    ($message:expr $(, $data:expr)?) => {
+    ($($arg:tt)+) => {
         $crate::editor::ui::debug_tools::LOGGER.error(
-            $message,
+            &format!($($arg)+),
             module_path!(),
             file!(),
             line!(),
-            $(Some($data.to_string()))?
-        );
+            None,
+        )
     };
 }
 
 #[macro_export]
 macro_rules! log_warn {
-    ($message:expr $(, $data:expr)?) => {
+    ($($arg:tt)+) => {
         $crate::editor::ui::debug_tools::LOGGER.warn(
-            $message,
+            &format!($($arg)+),
             module_path!(),
             file!(),
             line!(),
-            $(Some($data.to_string()))?
-        );
+            None,
+        )
     };
 }
 
 #[macro_export]
 macro_rules! log_info {
-    ($message:expr $(, $data:expr)?) => {
+    ($($arg:tt)+) => {
         $crate::editor::ui::debug_tools::LOGGER.info(
-            $message,
+            &format!($($arg)+),
             module_path!(),
             file!(),
             line!(),
-            $(Some($data.to_string()))?
-        );
+            None,
+        )
     };
 }
 
 #[macro_export]
 macro_rules! log_debug {
-    ($message:expr $(, $data:expr)?) => {
+    ($($arg:tt)+) => {
         $crate::editor::ui::debug_tools::LOGGER.debug(
-            $message,
+            &format!($($arg)+),
             module_path!(),
             file!(),
             line!(),
-            $(Some($data.to_string()))?
-        );
+            None,
+        )
     };
 }
 
 #[macro_export]
 macro_rules! log_trace {
-    ($message:expr $(, $data:expr)?) => {
+    ($($arg:tt)+) => {
         $crate::editor::ui::debug_tools::LOGGER.trace(
-            $message,
+            &format!($($arg)+),
             module_path!(),
             file!(),
             line!(),
-            $(Some($data.to_string()))?
-        );
+            None,
+        )
     };
 }
 
@@ -306,7 +308,7 @@ pub fn log_panel_ui(ui: &mut Ui, logger: &Logger) {
             }
         });
         
-        Separator::default().ui(ui);
+        ui.add(Separator::default());
         
         // 日志内容显示
         ScrollArea::vertical().show(ui, |ui| {
@@ -334,7 +336,8 @@ pub fn log_panel_ui(ui: &mut Ui, logger: &Logger) {
     });
 }
 
-/// 性能监控工具\#[derive(Debug, Default)]
+/// 性能监控工具
+#[derive(Debug, Default)]
 pub struct PerformanceMonitor {
     /// 帧率记录
     pub fps_history: VecDeque<f32>,
@@ -405,17 +408,16 @@ pub fn performance_monitor_ui(ui: &mut Ui, monitor: &PerformanceMonitor) {
                 ui.label("帧率历史:");
                 let fps_values: Vec<f32> = monitor.fps_history.iter().copied().collect();
                 if !fps_values.is_empty() {
-                    egui::plot::Plot::new(Id::new("fps_plot"))
-                        .view_aspect(2.0)
-                        .height(100.0)
-                        .show(ui, |plot_ui| {
-                            let line = egui::plot::Line::new(
-                                fps_values.into_iter().enumerate()
-                                    .map(|(i, y)| [i as f64, y as f64])
-                                    .collect()
-                            );
-                            plot_ui.line(line);
-                        });
+                    let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 100.0), egui::Sense::hover());
+                    let max = fps_values.iter().copied().fold(1.0_f32, f32::max);
+                    let step = rect.width() / (fps_values.len().max(2) - 1) as f32;
+                    let points: Vec<egui::Pos2> = fps_values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, fps)| egui::pos2(rect.left() + i as f32 * step, rect.bottom() - fps / max * rect.height()))
+                        .collect();
+                    ui.painter().rect_stroke(rect, 0.0, ui.visuals().widgets.noninteractive.bg_stroke, egui::StrokeKind::Inside);
+                    ui.painter().add(egui::Shape::line(points, egui::Stroke::new(1.5, egui::Color32::GREEN)));
                 }
             });
         
@@ -423,7 +425,7 @@ pub fn performance_monitor_ui(ui: &mut Ui, monitor: &PerformanceMonitor) {
         CollapsingHeader::new("操作耗时")
             .default_open(false)
             .show(ui, |ui| {
-                for (operation_name, times) in &monitor.operation_times {
+                for operation_name in monitor.operation_times.keys() {
                     if let Some(avg_time) = monitor.get_average_operation_time(operation_name) {
                         ui.label(format!("{}: 平均 {:.2} ms", operation_name, avg_time));
                     }
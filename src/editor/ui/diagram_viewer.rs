@@ -0,0 +1,295 @@
+//! 干线系统图查看器
+//!
+//! 在画布上绘制干线系统图节点生成的系统图（与导出使用同一图元，见TrunkDiagram），支持
+//! 平移、缩放和拖动组件。拖动后的位置记入系统图的手动调整，重新生成系统图后保留；
+//! 双击配电箱或其监测模块跳转到节点图中对应的配电箱节点。
+
+use egui::{Color32, Rect, Sense, Stroke, Ui, Vec2};
+
+use crate::editor::business::diagram_layout::{DiagramLayout, LayoutConfig};
+use crate::editor::business::diagram_routing::{DiagramRouter, RoutingConfig};
+use crate::editor::business::main_system_node::{ComponentType, SystemDiagram};
+use crate::export::painter::EguiPainter;
+use crate::export::system_diagram::TrunkDiagram;
+
+/// 最小缩放比例
+const MIN_ZOOM: f32 = 0.1;
+/// 最大缩放比例
+const MAX_ZOOM: f32 = 5.0;
+/// 干线图通道间距较小，线宽按图形基准尺寸的0.3倍绘制
+const STROKE_SCALE: f32 = 0.3;
+/// 母线、母线槽绘为水平线，上下各留出的拾取距离
+const BUSBAR_PICK_DISTANCE: f64 = 8.0;
+
+/// 查看器响应
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiagramViewerResponse {
+    /// 系统图中的组件位置已手动调整（系统图名称）
+    ComponentMoved(String),
+    /// 跳转到配电箱节点（配电箱持久标识）
+    OpenBox(String),
+}
+
+/// 干线系统图查看器
+pub struct DiagramViewer {
+    /// 当前显示的系统图序号
+    pub selected: usize,
+    /// 图形原点相对画布左上角的偏移
+    pub pan: Vec2,
+    /// 图形坐标到屏幕坐标的缩放比例
+    pub zoom: f32,
+    /// 正在拖动的组件
+    dragging: Option<usize>,
+    /// 已按窗口适配过视图的系统图名称
+    fitted: Option<String>,
+}
+
+impl Default for DiagramViewer {
+    fn default() -> Self {
+        Self {
+            selected: 0,
+            pan: Vec2::ZERO,
+            zoom: 1.0,
+            dragging: None,
+            fitted: None,
+        }
+    }
+}
+
+impl DiagramViewer {
+    /// 创建查看器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 画布内的相对位置转换为图形坐标
+    pub fn to_diagram(&self, offset: Vec2) -> (f64, f64) {
+        let point = (offset - self.pan) / self.zoom;
+        (point.x as f64, point.y as f64)
+    }
+
+    /// 以画布内指定位置为中心缩放，该位置下的图形保持不动
+    ///
+    /// # 参数
+    /// * `factor` - 缩放倍数
+    /// * `anchor` - 缩放中心（画布内的相对位置）
+    pub fn zoom_at(&mut self, factor: f32, anchor: Vec2) {
+        let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.pan = anchor - (anchor - self.pan) * (zoom / self.zoom);
+        self.zoom = zoom;
+    }
+
+    /// 缩放并平移使整张系统图位于画布中央
+    ///
+    /// # 参数
+    /// * `diagram` - 系统图
+    /// * `size` - 画布尺寸
+    pub fn fit(&mut self, diagram: &SystemDiagram, size: Vec2) {
+        let Some((min, max)) = TrunkDiagram::layout(diagram).bounds() else {
+            return;
+        };
+        let extent = Vec2::new((max.x - min.x) as f32, (max.y - min.y) as f32).max(Vec2::splat(1.0));
+        self.zoom = ((size.x / extent.x).min(size.y / extent.y) * 0.9).clamp(MIN_ZOOM, MAX_ZOOM);
+        let center = Vec2::new((min.x + max.x) as f32, (min.y + max.y) as f32) / 2.0;
+        self.pan = size / 2.0 - center * self.zoom;
+    }
+
+    /// 绘制查看器
+    ///
+    /// # 参数
+    /// * `ui` - 界面
+    /// * `diagrams` - 各干线系统图节点生成的系统图
+    ///
+    /// # 返回值
+    /// 组件被移动或需要跳转到配电箱节点时返回相应响应
+    pub fn ui(&mut self, ui: &mut Ui, diagrams: &mut [&mut SystemDiagram]) -> Option<DiagramViewerResponse> {
+        if diagrams.is_empty() {
+            ui.label("尚未生成干线系统图，请在干线系统图节点中生成");
+            return None;
+        }
+        self.selected = self.selected.min(diagrams.len() - 1);
+
+        let mut relayout = false;
+        let mut fit = false;
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("trunk_diagram_select")
+                .selected_text(diagrams[self.selected].name.clone())
+                .show_ui(ui, |ui| {
+                    for (i, diagram) in diagrams.iter().enumerate() {
+                        ui.selectable_value(&mut self.selected, i, diagram.name.clone());
+                    }
+                });
+            fit = ui.button("适应窗口").clicked();
+            relayout = ui.button("恢复自动布局").on_hover_text("清除手动调整的位置并重新布局").clicked();
+            ui.label(format!("缩放: {:.0}%", self.zoom * 100.0));
+            let overrides = diagrams[self.selected].overrides.len();
+            if overrides > 0 {
+                ui.label(format!("手动调整: {}处", overrides));
+            }
+        });
+
+        let diagram = &mut *diagrams[self.selected];
+        if relayout {
+            diagram.overrides.clear();
+            DiagramLayout::layout(diagram, &LayoutConfig::default());
+            DiagramRouter::route(diagram, &RoutingConfig::default());
+        }
+
+        let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
+        let rect = response.rect;
+        if fit || relayout || self.fitted.as_deref() != Some(diagram.name.as_str()) {
+            self.fit(diagram, rect.size());
+            self.fitted = Some(diagram.name.clone());
+        }
+
+        // 滚轮或触控板缩放
+        if let Some(pointer) = response.hover_pos() {
+            let (scroll, pinch) = ui.input(|i| (i.smooth_scroll_delta.y, i.zoom_delta()));
+            let factor = pinch * (scroll * 0.002).exp();
+            if (factor - 1.0).abs() > f32::EPSILON {
+                self.zoom_at(factor, pointer - rect.min);
+            }
+        }
+
+        let size = RoutingConfig::default().component_size;
+        let pointer = response.interact_pointer_pos().or(response.hover_pos());
+        let picked = pointer.and_then(|p| component_at(diagram, self.to_diagram(p - rect.min), size));
+
+        let mut result = None;
+        if response.drag_started() {
+            let origin = ui.input(|i| i.pointer.press_origin());
+            self.dragging = origin.and_then(|p| component_at(diagram, self.to_diagram(p - rect.min), size));
+        }
+        if response.dragged() {
+            let delta = response.drag_delta();
+            match self.dragging {
+                Some(id) => {
+                    // 拖动中的连线按未布线方式绘制，松开后重新布线
+                    let (x, y) = diagram.components[id].position;
+                    let position = (x + (delta.x / self.zoom) as f64, y + (delta.y / self.zoom) as f64);
+                    diagram.move_component(id, position);
+                    for connection in diagram.connections.iter_mut().filter(|c| c.from == id || c.to == id) {
+                        connection.route.clear();
+                    }
+                    diagram.junctions.clear();
+                }
+                None => self.pan += delta,
+            }
+        }
+        if response.drag_stopped() && self.dragging.take().is_some() {
+            DiagramRouter::route(diagram, &RoutingConfig::default());
+            result = Some(DiagramViewerResponse::ComponentMoved(diagram.name.clone()));
+        }
+        let opened = picked.and_then(|id| diagram.components[id].box_id.clone());
+        if let Some(box_id) = opened.filter(|_| response.double_clicked()) {
+            result = Some(DiagramViewerResponse::OpenBox(box_id));
+        }
+
+        painter.rect_filled(rect, 0.0, Color32::from_gray(24));
+        let mut canvas = EguiPainter::new(&painter, rect.min + self.pan, self.zoom).with_stroke_scale(STROKE_SCALE);
+        TrunkDiagram::layout(diagram).paint(&mut canvas);
+
+        // 高亮指针下的组件，可跳转的组件提示双击
+        if let Some(id) = self.dragging.or(picked) {
+            let component = &diagram.components[id];
+            let center = rect.min + self.pan + Vec2::new(component.position.0 as f32, component.position.1 as f32) * self.zoom;
+            let extent = Vec2::new(size.0 as f32, half_height(&component.component_type, size) as f32 * 2.0);
+            let highlight = Rect::from_center_size(center, extent * self.zoom + Vec2::splat(6.0));
+            painter.rect_stroke(highlight, 2.0, Stroke::new(1.5, Color32::LIGHT_BLUE), egui::StrokeKind::Outside);
+            if component.box_id.is_some() && self.dragging.is_none() {
+                response.on_hover_text(format!("{}\n双击跳转到配电箱节点", component.label.lines().next().unwrap_or("")));
+            }
+        }
+
+        result
+    }
+}
+
+/// 组件的拾取半高，母线、母线槽按拾取距离
+fn half_height(component_type: &ComponentType, size: (f64, f64)) -> f64 {
+    match component_type {
+        ComponentType::Busbar | ComponentType::Busway => BUSBAR_PICK_DISTANCE,
+        _ => size.1 / 2.0,
+    }
+}
+
+/// 图形坐标处的组件（重叠时取后绘制的）
+///
+/// # 参数
+/// * `diagram` - 系统图
+/// * `point` - 图形坐标
+/// * `size` - 设备框尺寸（宽，高）
+///
+/// # 返回值
+/// 返回组件ID，该处没有组件时返回None
+pub fn component_at(diagram: &SystemDiagram, point: (f64, f64), size: (f64, f64)) -> Option<usize> {
+    diagram
+        .components
+        .iter()
+        .rev()
+        .find(|c| {
+            (point.0 - c.position.0).abs() <= size.0 / 2.0
+                && (point.1 - c.position.1).abs() <= half_height(&c.component_type, size)
+        })
+        .map(|c| c.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::business::diagram_layout::riser_key;
+
+    fn sample(name: &str) -> SystemDiagram {
+        let mut diagram = SystemDiagram::new(name.to_string());
+        let busbar = diagram.add_component(ComponentType::Busbar, "主母线".to_string());
+        for (floor, box_id) in [(1, "box-1"), (2, "box-2")] {
+            let label = format!("{}AL1", floor);
+            let id = diagram.add_component(ComponentType::DistributionBox, format!("{}\n楼层:{}", label, floor));
+            diagram.place_component(id, Some(floor), Some(riser_key(&label)));
+            diagram.link_box(id, box_id);
+            diagram.add_connection(busbar, id);
+        }
+        DiagramLayout::layout(&mut diagram, &LayoutConfig::default());
+        diagram
+    }
+
+    #[test]
+    fn test_component_at() {
+        let diagram = sample("配电干线图");
+        let size = RoutingConfig::default().component_size;
+        let (x, y) = diagram.components[1].position;
+        assert_eq!(component_at(&diagram, (x + 70.0, y - 30.0), size), Some(1));
+        assert_eq!(component_at(&diagram, (x + 90.0, y), size), None);
+        // 母线只在线附近可拾取
+        let (bx, by) = diagram.components[0].position;
+        assert_eq!(component_at(&diagram, (bx, by + 5.0), size), Some(0));
+        assert_eq!(component_at(&diagram, (bx, by + 30.0), size), None);
+    }
+
+    #[test]
+    fn test_zoom_keeps_anchor() {
+        let mut viewer = DiagramViewer { pan: Vec2::new(40.0, 10.0), ..DiagramViewer::default() };
+        let anchor = Vec2::new(300.0, 200.0);
+        let before = viewer.to_diagram(anchor);
+        viewer.zoom_at(2.0, anchor);
+        assert_eq!(viewer.zoom, 2.0);
+        assert_eq!(viewer.to_diagram(anchor), before);
+        viewer.zoom_at(100.0, anchor);
+        assert_eq!(viewer.zoom, MAX_ZOOM);
+    }
+
+    #[test]
+    fn test_manual_position_survives_regeneration() {
+        let mut diagram = sample("配电干线图");
+        diagram.move_component(2, (500.0, 60.0));
+        assert_eq!(diagram.overrides.len(), 1);
+
+        // 重新生成后组件按配电箱标识找回手动位置，其余组件仍按自动布局
+        let mut regenerated = sample("配电干线图");
+        let automatic = regenerated.components[1].position;
+        assert_eq!(regenerated.restore_overrides(&[diagram]), 1);
+        assert_eq!(regenerated.components[2].position, (500.0, 60.0));
+        assert_eq!(regenerated.components[1].position, automatic);
+        assert_eq!(sample("其他系统图").restore_overrides(&[regenerated]), 0);
+    }
+}
@@ -1,4 +1,4 @@
-use eframe::egui::{self, Ui, ScrollArea, CollapsingHeader}; 
+use eframe::egui::{Ui, ScrollArea, CollapsingHeader}; 
 use std::collections::HashMap; 
 use crate::editor::business::{ElectricNodeTemplate}; 
 use crate::editor::UIUserState; 
@@ -7,10 +7,10 @@ use crate::editor::UIUserState;
 /// 支持按类别分组和实时搜索
 pub fn enhanced_node_finder(
     ui: &mut Ui,
-    templates: &[impl ElectricNodeTemplate],
+    templates: &[ElectricNodeTemplate],
     search_query: &mut String,
-    user_state: &mut UIUserState,
-    selected_template: &mut Option<Box<dyn ElectricNodeTemplate>>,
+    _user_state: &mut UIUserState,
+    selected_template: &mut Option<ElectricNodeTemplate>,
 ) -> bool {
     // 绘制搜索框
     ui.horizontal(|ui| {
@@ -37,7 +37,7 @@ pub fn enhanced_node_finder(
         categories
             .entry(category)
             .or_insert_with(Vec::new)
-            .push(template.clone());
+            .push(**template);
     }
 
     // 显示分类后的节点列表
@@ -48,12 +48,11 @@ pub fn enhanced_node_finder(
                 .default_open(true)
                 .show(ui, |ui| {
                     for template in category_templates {
-                        let template_clone = template.clone();
                         let is_selected = selected_template.as_ref()
-                            .map_or(false, |t| t.node_label() == template.node_label());
+                            .is_some_and(|t| *t == template);
                             
-                        if ui.selectable_label(is_selected, &template.node_label()).clicked() {
-                            *selected_template = Some(template_clone);
+                        if ui.selectable_label(is_selected, template.node_label()).clicked() {
+                            *selected_template = Some(template);
                             selected = true;
                         }
                     }
@@ -1,4 +1,4 @@
-/// 编辑器UI组件模块
+//! 编辑器UI组件模块
 
 pub mod node_editor;
 pub mod property_panel;
@@ -10,6 +10,7 @@ pub mod enhanced_node_finder;
 pub mod node_search;
 pub mod debug_tools;
 pub mod performance_optimization;
+pub mod diagram_viewer;
 
 // 重新导出常用组件
 pub use node_editor::NodeEditor;
@@ -18,4 +19,8 @@ pub use toolbar::Toolbar;
 pub use status_bar::StatusBar;
 pub use custom_connections::draw_custom_connection;
 pub use node_groups::{NodeGroupManager, NodeGroup, Annotation};
-pub use enhanced_node_finder::enhanced_node_finder;
\ No newline at end of file
+pub use enhanced_node_finder::enhanced_node_finder;
+pub use diagram_viewer::{DiagramViewer, DiagramViewerResponse};
+pub use node_search::{NodeSearcher, node_search_ui};
+pub use debug_tools::Logger;
+pub use performance_optimization::{PerformanceOptimizer, performance_settings_ui};
@@ -1,12 +1,25 @@
-/// 节点编辑器UI组件
-/// 实现配电系统图的节点编辑器界面
+//! 节点编辑器UI组件
+//! 实现配电系统图的节点编辑器界面
 
-use eframe::egui::{self, Ui, ScrollArea, Window}; 
-use egui_node_graph::{draw_graph_editor, NodeResponse}; 
-use crate::editor::{DataType, UIValueType, UIUserState, UIResponse};
-use crate::editor::business::{PowerGraphNode}; 
+use eframe::egui::{self, Ui, ScrollArea, Window, Color32, Stroke};
+use egui_node_graph::{NodeId, NodeResponse, NodeTemplateTrait};
+use crate::application::AppState;
+use crate::editor::UIResponse;
+use crate::editor::business::PowerGraphNode;
 use crate::editor::graph::PowerDistributionGraphEditorState;
-use crate::editor::business::{ElectricNodeTemplate, get_all_node_templates};
+use crate::editor::business::{AllElectricTemplates, ElectricNodeTemplate, get_all_node_templates};
+
+/// 网格间距（像素）
+const GRID_SPACING: f32 = 25.0;
+
+/// 节点编辑器渲染结果
+#[derive(Debug, Clone, Default)]
+pub struct NodeEditorResponse {
+    /// 本帧选中节点发生变化时为Some，内部为新的选中节点（None表示取消选择）
+    pub selected_node_id: Option<Option<String>>,
+    /// 鼠标在画布中的位置
+    pub mouse_pos: Option<(f32, f32)>,
+}
 
 /// 节点编辑器UI组件
 pub struct NodeEditor {
@@ -16,6 +29,10 @@ pub struct NodeEditor {
     pub node_search_text: String,
     /// 是否显示节点查找器
     pub show_node_finder: bool,
+    /// 缩放级别
+    pub zoom: f32,
+    /// 是否显示网格
+    pub show_grid: bool,
 }
 
 impl Default for NodeEditor {
@@ -24,6 +41,8 @@ impl Default for NodeEditor {
             editor_state: PowerDistributionGraphEditorState::default(),
             node_search_text: String::new(),
             show_node_finder: false,
+            zoom: 1.0,
+            show_grid: true,
         }
     }
 }
@@ -33,7 +52,17 @@ impl NodeEditor {
     pub fn new() -> Self {
         Self::default()
     }
-    
+
+    /// 设置缩放级别
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom = zoom;
+    }
+
+    /// 设置是否显示网格
+    pub fn set_show_grid(&mut self, show_grid: bool) {
+        self.show_grid = show_grid;
+    }
+
     /// 显示节点编辑器UI
     pub fn show(&mut self, ctx: &egui::Context) {
         // 创建节点编辑器窗口
@@ -43,7 +72,62 @@ impl NodeEditor {
                 self.show_ui(ui);
             });
     }
-    
+
+    /// 使用外部节点图状态渲染节点编辑器
+    ///
+    /// # 参数
+    /// * `ui` - UI上下文
+    /// * `_state` - 应用状态
+    /// * `selected_node_type` - 工具栏中选中的节点类型，双击画布空白处时按该类型添加节点
+    /// * `graph_state` - 节点图编辑器状态
+    ///
+    /// # 返回值
+    /// 选中节点变化和鼠标位置
+    pub fn render_with_graph(
+        &mut self,
+        ui: &mut Ui,
+        _state: &mut AppState,
+        selected_node_type: &str,
+        graph_state: &mut PowerDistributionGraphEditorState,
+    ) -> NodeEditorResponse {
+        let mut result = NodeEditorResponse::default();
+        let previous_selection = graph_state.selected_node();
+
+        graph_state.editor_state.pan_zoom.zoom = self.zoom;
+        if self.show_grid {
+            draw_grid(ui, self.zoom);
+        }
+
+        let graph_response = graph_state.editor_state.draw_graph_editor(
+            ui,
+            AllElectricTemplates,
+            &mut graph_state.user_state,
+            Vec::new(),
+        );
+        graph_state.handle_node_responses(graph_response.node_responses);
+        if graph_state.editor_state.selected_nodes.is_empty() {
+            graph_state.selected_node_id = None;
+        }
+
+        // 双击画布空白处添加工具栏选中类型的节点
+        let pointer = ui.input(|i| i.pointer.interact_pos());
+        let double_clicked = ui.input(|i| i.pointer.button_double_clicked(egui::PointerButton::Primary));
+        if let Some(pos) = pointer {
+            result.mouse_pos = Some((pos.x, pos.y));
+            if double_clicked && graph_response.cursor_in_editor && !graph_response.cursor_in_finder
+                && let Some(template) = template_for_tool(selected_node_type) {
+                let node_id = graph_state.add_template_node(template, pos - graph_state.editor_state.pan_zoom.pan);
+                graph_state.set_selected_node(Some(node_id));
+            }
+        }
+
+        let selection = graph_state.selected_node();
+        if selection != previous_selection {
+            result.selected_node_id = Some(selection.map(|node_id| format!("{:?}", node_id)));
+        }
+        result
+    }
+
     /// 显示节点编辑器的内容UI
     fn show_ui(&mut self, ui: &mut Ui) {
         // 顶部工具栏
@@ -52,145 +136,154 @@ impl NodeEditor {
             if ui.button("添加节点").clicked() {
                 self.show_node_finder = true;
             }
-            
+
             // 清除按钮
             if ui.button("清除").clicked() {
-                self.editor_state.graph.clear();
+                self.editor_state.clear();
             }
         });
-        
+
         ui.separator();
-        
+
         // 节点图编辑区域
-        ScrollArea::both()
-            .auto_shrink([false, false])
-            .show(ui, |ui| {
-                // 绘制节点编辑器
-                let responses = draw_graph_editor(
-                    ui,
-                    &mut self.editor_state.graph,
-                    &mut self.editor_state.editor_state,
-                    &mut self.editor_state.user_state,
-                    None, // 可选的背景
-                );
-                
-                // 处理节点响应
-                self.handle_node_responses(responses);
-            });
-            
+        let graph_response = self.editor_state.editor_state.draw_graph_editor(
+            ui,
+            AllElectricTemplates,
+            &mut self.editor_state.user_state,
+            Vec::new(),
+        );
+
+        // 处理节点响应
+        self.handle_node_responses(graph_response.node_responses);
+
         // 如果需要显示节点查找器
         if self.show_node_finder {
             self.show_node_finder(ui);
         }
-        
+
         // 显示选中节点的详细信息（如果有）
         if let Some(node_id) = self.editor_state.selected_node_id {
             self.show_node_details(ui, node_id);
         }
     }
-    
+
     /// 处理节点响应
     fn handle_node_responses(&mut self, responses: Vec<NodeResponse<UIResponse, PowerGraphNode>>) {
         self.editor_state.handle_node_responses(responses);
     }
-    
+
     /// 显示节点查找器
     fn show_node_finder(&mut self, ui: &mut Ui) {
         // 创建节点查找器弹窗
         let mut close_finder = false;
-        
+
         Window::new("选择节点类型")
             .default_size([300.0, 400.0])
             .show(ui.ctx(), |ui| {
                 // 搜索框
                 ui.text_edit_singleline(&mut self.node_search_text);
                 ui.separator();
-                
+
                 // 显示所有可用的节点模板
                 ScrollArea::vertical().show(ui, |ui| {
                     let node_templates = get_all_node_templates();
-                    
+
                     for template in node_templates {
                         let label = template.node_finder_label(&mut self.editor_state.user_state);
-                        
+
                         // 如果有搜索文本，过滤结果
-                        if !self.node_search_text.is_empty() {
-                            if !label.contains(&self.node_search_text) {
-                                continue;
-                            }
+                        if !self.node_search_text.is_empty() && !label.contains(&self.node_search_text) {
+                            continue;
                         }
-                        
+
                         // 显示节点模板按钮
-                        if ui.button(label).clicked() {
-                            // 使用模板创建新节点
-                            let node_id = self.editor_state.graph.add_node_from_template(
-                                template,
-                                &mut self.editor_state.user_state,
-                                ui.next_widget_position() - egui::vec2(50.0, 50.0), // 居中放置节点
-                            );
-                            
-                            // 将节点添加到编辑器状态中
-                            self.editor_state.selected_node_id = Some(node_id);
+                        if ui.button(label.as_ref()).clicked() {
+                            // 使用模板创建新节点，放在视图左上角附近
+                            let position = egui::pos2(100.0, 100.0) - self.editor_state.editor_state.pan_zoom.pan;
+                            let node_id = self.editor_state.add_template_node(template, position);
+
+                            // 选中新节点
+                            self.editor_state.set_selected_node(Some(node_id));
                             close_finder = true;
                         }
                     }
                 });
-                
+
                 // 关闭按钮
                 if ui.button("取消").clicked() {
                     close_finder = true;
                 }
             });
-            
+
         if close_finder {
             self.show_node_finder = false;
             self.node_search_text.clear();
         }
     }
-    
+
     /// 显示选中节点的详细信息
-    fn show_node_details(&self, ui: &mut Ui, node_id: crate::editor::graph::egui_node_graph::NodeId) {
+    fn show_node_details(&self, ui: &mut Ui, node_id: NodeId) {
         // 获取节点信息
-        if let Some(node) = self.editor_state.graph.nodes.get(node_id) {
+        let graph = self.editor_state.graph();
+        if let Some(node) = graph.nodes.get(node_id) {
             Window::new("节点详情")
                 .default_size([300.0, 400.0])
                 .show(ui.ctx(), |ui| {
                     ui.label(format!("节点ID: {:?}", node_id));
-                    ui.label(format!("节点类型: {:?}", node.node_type));
-                    ui.label(format!("节点名称: {}", node.name));
-                    ui.label(format!("节点描述: {}", node.description));
-                    
+                    ui.label(format!("节点类型: {}", node.user_data.type_name()));
+                    ui.label(format!("节点名称: {}", node.label));
+
                     ui.separator();
-                    
+
                     // 显示节点输入参数
                     ui.label("输入参数:");
-                    for (input_id, input) in &self.editor_state.graph.inputs {
-                        if input.node == node_id {
-                            ui.label(format!("- {} ({:?})", 
-                                input.name, 
-                                input.data_type
-                            ));
-                            
-                            // 如果有连接，显示连接的输出
-                            if let Some(connected_output_id) = self.editor_state.graph.connections.get(input_id) {
-                                ui.label(format!("  已连接到输出ID: {:?}", connected_output_id));
-                            }
+                    for (name, input_id) in &node.inputs {
+                        ui.label(format!("- {} ({:?})", name, graph.get_input(*input_id).typ));
+
+                        // 如果有连接，显示连接的输出
+                        if let Some(connected_output_id) = graph.connection(*input_id) {
+                            ui.label(format!("  已连接到输出ID: {:?}", connected_output_id));
                         }
                     }
-                    
+
                     ui.separator();
-                    
+
                     // 显示节点输出参数
                     ui.label("输出参数:");
-                    for (output_id, output) in &self.editor_state.graph.outputs {
-                        if output.node == node_id {
-                            ui.label(format!("- {} ({:?})", 
-                                output.name, 
-                                output.data_type
-                            ));
-                        }
+                    for (name, output_id) in &node.outputs {
+                        ui.label(format!("- {} ({:?})", name, graph.get_output(*output_id).typ));
                     }
                 });
         }
     }
-}
\ No newline at end of file
+}
+
+/// 工具栏节点类型对应的节点模板
+fn template_for_tool(node_type: &str) -> Option<ElectricNodeTemplate> {
+    match node_type {
+        "distribution_box" => Some(ElectricNodeTemplate::DistributionBoxNode),
+        "load" => Some(ElectricNodeTemplate::CircuitNode),
+        "cable" => Some(ElectricNodeTemplate::FeederLineNode),
+        "power_source" => Some(ElectricNodeTemplate::PowerSourceNode),
+        _ => None,
+    }
+}
+
+/// 在画布背景上绘制网格
+fn draw_grid(ui: &Ui, zoom: f32) {
+    let rect = ui.max_rect();
+    let spacing = GRID_SPACING * zoom.max(0.1);
+    let painter = ui.painter_at(rect);
+    let stroke = Stroke::new(0.5, Color32::from_gray(60));
+
+    let mut x = rect.left();
+    while x <= rect.right() {
+        painter.vline(x, rect.y_range(), stroke);
+        x += spacing;
+    }
+    let mut y = rect.top();
+    while y <= rect.bottom() {
+        painter.hline(rect.x_range(), y, stroke);
+        y += spacing;
+    }
+}
@@ -1,6 +1,6 @@
-use eframe::egui::{self, Ui, Painter, Pos2, Color32, Rect, Align2, FontId, Stroke, Id}; 
+use eframe::egui::{self, Ui, Pos2, Color32, Rect, Align2, FontId, Stroke}; 
 use egui_node_graph::NodeId; 
-use slotmap::{new_key_type, Key}; 
+use slotmap::{new_key_type, Key, KeyData}; 
 
 // 定义分组和注释的唯一标识符
 new_key_type! {
@@ -76,7 +76,7 @@ impl Default for NodeGroupManager {
 impl NodeGroupManager {
     /// 创建新的节点分组
     pub fn create_group(&mut self, label: &str, color: Color32) -> GroupId {
-        let id = GroupId::from_usize(self.next_group_id);
+        let id = GroupId::from(KeyData::from_ffi(self.next_group_id as u64));
         self.next_group_id += 1;
         
         let new_group = NodeGroup {
@@ -94,7 +94,7 @@ impl NodeGroupManager {
     
     /// 创建新的注释
     pub fn create_annotation(&mut self, text: &str, position: Pos2) -> AnnotationId {
-        let id = AnnotationId::from_usize(self.next_annotation_id);
+        let id = AnnotationId::from(KeyData::from_ffi(self.next_annotation_id as u64));
         self.next_annotation_id += 1;
         
         let new_annotation = Annotation {
@@ -112,10 +112,9 @@ impl NodeGroupManager {
     
     /// 将节点添加到分组
     pub fn add_node_to_group(&mut self, group_id: GroupId, node_id: NodeId) {
-        if let Some(group) = self.groups.iter_mut().find(|g| g.id == group_id) {
-            if !group.node_ids.contains(&node_id) {
-                group.node_ids.push(node_id);
-            }
+        if let Some(group) = self.groups.iter_mut().find(|g| g.id == group_id)
+            && !group.node_ids.contains(&node_id) {
+            group.node_ids.push(node_id);
         }
     }
     
@@ -141,6 +140,7 @@ impl NodeGroupManager {
                 8.0, // 圆角半径
                 group.color,
                 Stroke::new(1.0, Color32::WHITE),
+                egui::StrokeKind::Inside,
             );
             painter.text(
                 group.rect.min,
@@ -159,6 +159,7 @@ impl NodeGroupManager {
                 4.0, // 圆角半径
                 annotation.color,
                 Stroke::new(1.0, Color32::BLACK),
+                egui::StrokeKind::Inside,
             );
             painter.text(
                 rect.center(),
@@ -1,21 +1,24 @@
-/// 节点搜索和过滤功能实现
-/// 用于快速定位和筛选图中的节点
+//! 节点搜索和过滤功能实现
+//! 用于快速定位和筛选图中的节点
 
-use egui::{Ui, TextEdit, Checkbox, ScrollArea, Response, Button};
-use egui_node_graph::{NodeId, Graph, GraphEditorState};
+use egui::{Ui, ScrollArea};
+use egui_node_graph::{Node, NodeId, Graph};
 use std::collections::HashSet;
 use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
 use std::sync::LazyLock;
 
-use crate::editor::business::{PowerGraphNode, ElectricNodeTemplate};
-use crate::editor::{DataType, UIValueType, UIUserState};
+use crate::editor::business::PowerGraphNode;
+use crate::editor::graph::graph_editor_state::PowerGraphEditorState;
+use crate::editor::{DataType, UIValueType};
 
 /// 静态模糊匹配器
 static FUZZY_MATCHER: LazyLock<SkimMatcherV2> = LazyLock::new(SkimMatcherV2::default);
 
-/// 搜索结果排序方式\#[derive(Debug, Clone, PartialEq, Eq)]
-enum SearchResultOrder {
+/// 搜索结果排序方式
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum SearchResultOrder {
     /// 按相关性排序
+    #[default]
     Relevance,
     /// 按节点类型排序
     Type,
@@ -23,7 +26,8 @@ enum SearchResultOrder {
     Alphabetical,
 }
 
-/// 节点搜索器\#[derive(Debug, Default)]
+/// 节点搜索器
+#[derive(Debug, Default)]
 pub struct NodeSearcher {
     /// 搜索关键词
     pub search_text: String,
@@ -39,10 +43,11 @@ pub struct NodeSearcher {
     pub last_filters: NodeFilters,
 }
 
-/// 节点过滤条件\#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// 节点过滤条件
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct NodeFilters {
-    /// 按节点类型过滤
-    pub node_types: HashSet<ElectricNodeTemplate>,
+    /// 按节点类型名称过滤
+    pub node_types: HashSet<String>,
     /// 按数据类型过滤（基于节点的输入/输出）
     pub data_types: HashSet<DataType>,
     /// 只显示选中的节点
@@ -64,9 +69,9 @@ impl NodeSearcher {
     /// 更新搜索结果
     pub fn update_search(
         &mut self,
-        graph: &Graph<PowerGraphNode, DataType, UIValueType>,
-        editor_state: &GraphEditorState<PowerGraphNode, DataType, UIValueType, UIUserState>,
+        editor_state: &PowerGraphEditorState,
     ) {
+        let graph = &editor_state.graph;
         // 检查搜索条件是否有变化
         let search_changed = 
             self.search_text != self.last_search_text || 
@@ -97,7 +102,7 @@ impl NodeSearcher {
         // 遍历所有节点进行搜索和过滤
         for (node_id, node) in &graph.nodes {
             // 应用过滤器
-            if !self.apply_filters(node_id, node, graph, editor_state) {
+            if !self.apply_filters(node, graph, editor_state) {
                 continue;
             }
             
@@ -105,30 +110,27 @@ impl NodeSearcher {
             let score = self.calculate_match_score(node);
             
             // 只添加有匹配分数的结果
-            if score.is_some() {
-                self.results.push((node_id, score.unwrap()));
+            if let Some(score) = score {
+                self.results.push((node_id, score));
             }
         }
         
         // 排序结果
-        self.sort_results();
+        self.sort_results(graph);
     }
     
     /// 应用过滤条件
     fn apply_filters(
         &self,
-        node_id: &NodeId,
-        node: &PowerGraphNode,
+        node: &Node<PowerGraphNode>,
         graph: &Graph<PowerGraphNode, DataType, UIValueType>,
-        editor_state: &GraphEditorState<PowerGraphNode, DataType, UIValueType, UIUserState>,
+        editor_state: &PowerGraphEditorState,
     ) -> bool {
         // 按节点类型过滤
-        if !self.filters.node_types.is_empty() {
-            // 获取节点类型（需要根据实际情况实现）
-            let node_type = self.get_node_type(node);
-            if !self.filters.node_types.contains(&node_type) {
-                return false;
-            }
+        if !self.filters.node_types.is_empty()
+            && !self.filters.node_types.contains(node.user_data.type_name())
+        {
+            return false;
         }
         
         // 按数据类型过滤
@@ -136,8 +138,8 @@ impl NodeSearcher {
             let mut has_matching_data_type = false;
             
             // 检查输入端口数据类型
-            for (_input_id, input) in &node.inputs {
-                if self.filters.data_types.contains(&input.data_type) {
+            for (_name, input_id) in &node.inputs {
+                if self.filters.data_types.contains(&graph.inputs[*input_id].typ) {
                     has_matching_data_type = true;
                     break;
                 }
@@ -145,8 +147,8 @@ impl NodeSearcher {
             
             // 检查输出端口数据类型
             if !has_matching_data_type {
-                for (_output_id, output) in &node.outputs {
-                    if self.filters.data_types.contains(&output.data_type) {
+                for (_name, output_id) in &node.outputs {
+                    if self.filters.data_types.contains(&graph.outputs[*output_id].typ) {
                         has_matching_data_type = true;
                         break;
                     }
@@ -160,16 +162,15 @@ impl NodeSearcher {
         
         // 只显示选中的节点
         if self.filters.only_selected && 
-           !editor_state.selected_nodes.contains(node_id) {
+           !editor_state.selected_nodes.contains(&node.id) {
             return false;
         }
         
         // 只显示有连接的节点
         if self.filters.only_connected {
-            let has_connections = graph.input_connections.iter()
-                .any(|(input_node_id, _, _)| input_node_id == node_id) ||
-                graph.output_connections.iter()
-                .any(|(output_node_id, _, _, _)| output_node_id == node_id);
+            let has_connections = graph.iter_connections().any(|(input_id, output_id)| {
+                graph.inputs[input_id].node == node.id || graph.outputs[output_id].node == node.id
+            });
                 
             if !has_connections {
                 return false;
@@ -187,7 +188,7 @@ impl NodeSearcher {
     }
     
     /// 计算节点与搜索关键词的匹配分数
-    fn calculate_match_score(&self, node: &PowerGraphNode) -> Option<i64> {
+    fn calculate_match_score(&self, node: &Node<PowerGraphNode>) -> Option<i64> {
         if self.search_text.is_empty() {
             return Some(0); // 没有搜索关键词时，所有通过过滤器的节点都有基础分数
         }
@@ -200,52 +201,51 @@ impl NodeSearcher {
     }
     
     /// 排序搜索结果
-    fn sort_results(&mut self) {
+    fn sort_results(&mut self, graph: &Graph<PowerGraphNode, DataType, UIValueType>) {
+        let label = |node_id: &NodeId| graph.nodes.get(*node_id).map(|node| node.label.clone()).unwrap_or_default();
+        let type_name = |node_id: &NodeId| graph.nodes.get(*node_id).map(|node| node.user_data.type_name().to_string()).unwrap_or_default();
         match self.order {
             SearchResultOrder::Relevance => {
                 // 按匹配分数降序排序
-                self.results.sort_by(|a, b| b.1.cmp(&a.1));
+                self.results.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
             },
             SearchResultOrder::Type => {
-                // 按节点类型排序（需要实现节点类型比较）
-                // 这里简化为按节点ID排序
-                self.results.sort_by(|a, b| a.0.cmp(&b.0));
+                // 按节点类型排序
+                self.results.sort_by_key(|(node_id, _)| type_name(node_id));
             },
             SearchResultOrder::Alphabetical => {
-                // 按字母顺序排序（需要实现节点名称比较）
-                // 这里简化为按节点ID排序
-                self.results.sort_by(|a, b| a.0.cmp(&b.0));
+                // 按节点名称排序
+                self.results.sort_by_key(|(node_id, _)| label(node_id));
             },
         }
     }
     
-    /// 获取节点类型（需要根据实际情况实现）
-    fn get_node_type(&self, node: &PowerGraphNode) -> ElectricNodeTemplate {
-        // 这是一个简化实现，实际应该根据node.data来判断
-        ElectricNodeTemplate::CircuitNode
-    }
-    
-    /// 获取节点的搜索文本（名称、描述等）
-    fn get_node_search_text(&self, node: &PowerGraphNode) -> String {
-        // 组合节点ID和其他属性作为搜索文本
-        format!("{} {:?}", node.id, node.data)
+    /// 获取节点的搜索文本
+    fn get_node_search_text(&self, node: &Node<PowerGraphNode>) -> String {
+        // 组合节点标签、名称和类型作为搜索文本
+        format!("{} {} {}", node.label, node.user_data.display_name(), node.user_data.type_name())
     }
     
     /// 检查节点是否有错误
-    fn has_node_errors(&self, _node: &PowerGraphNode) -> bool {
-        // 简化实现，实际应该检查节点的错误状态
-        false
+    fn has_node_errors(&self, node: &Node<PowerGraphNode>) -> bool {
+        match &node.user_data {
+            PowerGraphNode::TrunkLineNode(system_node) => !system_node.errors.is_empty(),
+            PowerGraphNode::DistributionBoxNode(box_data) => !box_data.coordination_log.is_empty(),
+            _ => false,
+        }
     }
 }
 
 /// 搜索UI组件
+///
+/// # 返回值
+/// 用户点击"查看"的节点，调用方负责选中并居中显示该节点
 pub fn node_search_ui(
     ui: &mut Ui,
     searcher: &mut NodeSearcher,
-    graph: &Graph<PowerGraphNode, DataType, UIValueType>,
-    editor_state: &mut GraphEditorState<PowerGraphNode, DataType, UIValueType, UIUserState>,
-) -> Response {
-    let mut response = Response::default();
+    editor_state: &PowerGraphEditorState,
+) -> Option<NodeId> {
+    let mut focused = None;
     
     ui.group(|ui| {
         ui.heading("节点搜索");
@@ -255,7 +255,7 @@ pub fn node_search_ui(
             ui.label("搜索:");
             if ui.text_edit_singleline(&mut searcher.search_text).changed() {
                 // 搜索文本变化时更新搜索
-                searcher.update_search(graph, editor_state);
+                searcher.update_search(editor_state);
             }
             
             // 清除按钮
@@ -269,29 +269,26 @@ pub fn node_search_ui(
         ui.horizontal(|ui| {
             ui.label("排序:");
             
-            if ui.radio(searcher.order == SearchResultOrder::Relevance, "相关性").clicked() {
-                searcher.order = SearchResultOrder::Relevance;
-                searcher.sort_results();
-            }
-            
-            if ui.radio(searcher.order == SearchResultOrder::Type, "类型").clicked() {
-                searcher.order = SearchResultOrder::Type;
-                searcher.sort_results();
-            }
-            
-            if ui.radio(searcher.order == SearchResultOrder::Alphabetical, "字母顺序").clicked() {
-                searcher.order = SearchResultOrder::Alphabetical;
-                searcher.sort_results();
+            for (order, label) in [
+                (SearchResultOrder::Relevance, "相关性"),
+                (SearchResultOrder::Type, "类型"),
+                (SearchResultOrder::Alphabetical, "字母顺序"),
+            ] {
+                if ui.radio(searcher.order == order, label).clicked() {
+                    searcher.order = order;
+                    searcher.sort_results(&editor_state.graph);
+                }
             }
         });
         
         // 过滤选项
         ui.collapsing("过滤选项", |ui| {
-            ui.checkbox(&mut searcher.filters.only_selected, "只显示选中节点");
-            ui.checkbox(&mut searcher.filters.only_connected, "只显示有连接的节点");
-            ui.checkbox(&mut searcher.filters.only_errors, "只显示错误节点");
-            
-            // TODO: 添加更多过滤选项
+            let mut changed = ui.checkbox(&mut searcher.filters.only_selected, "只显示选中节点").changed();
+            changed |= ui.checkbox(&mut searcher.filters.only_connected, "只显示有连接的节点").changed();
+            changed |= ui.checkbox(&mut searcher.filters.only_errors, "只显示错误节点").changed();
+            if changed {
+                searcher.update_search(editor_state);
+            }
         });
         
         // 搜索结果
@@ -299,30 +296,23 @@ pub fn node_search_ui(
         ui.label(format!("找到 {} 个结果", searcher.results.len()));
         
         ScrollArea::vertical().show(ui, |ui| {
-            for (node_id, score) in &searcher.results {
-                let node_response = ui.horizontal(|ui| {
+            for (node_id, _score) in &searcher.results {
+                ui.horizontal(|ui| {
                     // 显示节点信息
-                    let node_name = if let Some(node) = graph.nodes.get(node_id) {
-                        node.id.clone()
-                    } else {
-                        "未知节点".to_string()
+                    let node_name = match editor_state.graph.nodes.get(*node_id) {
+                        Some(node) => format!("{}（{}）", node.label, node.user_data.type_name()),
+                        None => "未知节点".to_string(),
                     };
                     
                     ui.label(node_name);
                     
-                    // 显示匹配分数（仅用于调试）
                     if ui.button("查看").clicked() {
-                        // 选中并居中显示节点
-                        editor_state.selected_nodes.clear();
-                        editor_state.selected_nodes.insert(*node_id);
-                        // TODO: 实现居中显示节点的功能
+                        focused = Some(*node_id);
                     }
                 });
-                
-                response = response.union(node_response.response);
             }
         });
     });
     
-    response
-}
\ No newline at end of file
+    focused
+}
@@ -1,15 +1,17 @@
-/// 性能优化模块
-/// 用于提高大规模节点图的渲染和交互性能
+//! 性能优化模块
+//! 用于提高大规模节点图的渲染和交互性能
 
-use egui::{Ui, Checkbox, Slider, CollapsingHeader, Label, Button};
-use egui_node_graph::{Graph, GraphEditorState, NodeId};
+use egui::{Ui, Slider, CollapsingHeader};
+use egui_node_graph::{Graph, NodeId};
 use std::time::{Instant, Duration};
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use crate::editor::business::PowerGraphNode;
-use crate::editor::{DataType, UIValueType, UIUserState};
+use crate::editor::graph::graph_editor_state::PowerGraphEditorState;
+use crate::editor::{DataType, UIValueType};
 
-/// 性能优化设置\#[derive(Debug, Clone, Default)]
+/// 性能优化设置
+#[derive(Debug, Clone, Default)]
 pub struct PerformanceSettings {
     /// 启用节点视图范围裁剪
     pub enable_view_culling: bool,
@@ -33,7 +35,8 @@ pub struct PerformanceSettings {
     pub show_node_details_on_hover_only: bool,
 }
 
-/// 可见性缓存条目\#[derive(Debug, Clone)]
+/// 可见性缓存条目
+#[derive(Debug, Clone)]
 struct VisibilityCacheEntry {
     /// 是否可见
     visible: bool,
@@ -43,19 +46,19 @@ struct VisibilityCacheEntry {
     detail_level: NodeDetailLevel,
 }
 
-/// 节点细节级别\#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-enum NodeDetailLevel {
+/// 节点细节级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NodeDetailLevel {
     /// 完整细节
     Full,
-    /// 中等细节
-    Medium,
     /// 低细节
     Low,
     /// 最小细节（仅轮廓）
     Minimal,
 }
 
-/// 性能优化管理器\#[derive(Debug, Default)]
+/// 性能优化管理器
+#[derive(Debug, Default)]
 pub struct PerformanceOptimizer {
     /// 性能设置
     pub settings: PerformanceSettings,
@@ -71,7 +74,8 @@ pub struct PerformanceOptimizer {
     performance_stats: PerformanceStats,
 }
 
-/// 性能统计数据\#[derive(Debug, Default, Clone)]
+/// 性能统计数据
+#[derive(Debug, Default, Clone)]
 pub struct PerformanceStats {
     /// 渲染的节点总数
     rendered_nodes: usize,
@@ -90,23 +94,25 @@ pub struct PerformanceStats {
     /// 更新耗时（毫秒）
     update_time_ms: f64,
     /// 当前FPS
-    current_fps: f32,
+    pub current_fps: f32,
 }
 
 impl PerformanceOptimizer {
     /// 创建新的性能优化管理器
     pub fn new() -> Self {
-        let mut settings = PerformanceSettings::default();
         // 设置默认优化参数
-        settings.enable_view_culling = true;
-        settings.enable_lod = true;
-        settings.enable_connection_optimization = true;
-        settings.enable_interaction_throttling = true;
-        settings.culling_margin = 200.0;
-        settings.low_detail_distance = 300.0;
-        settings.minimal_detail_distance = 600.0;
-        settings.interaction_throttle_ms = 50;
-        settings.visibility_cache_ttl_ms = 100;
+        let settings = PerformanceSettings {
+            enable_view_culling: true,
+            enable_lod: true,
+            enable_connection_optimization: true,
+            enable_interaction_throttling: true,
+            culling_margin: 200.0,
+            low_detail_distance: 300.0,
+            minimal_detail_distance: 600.0,
+            interaction_throttle_ms: 50,
+            visibility_cache_ttl_ms: 100,
+            ..Default::default()
+        };
         
         Self {
             settings,
@@ -218,7 +224,7 @@ impl PerformanceOptimizer {
         
         // 两个节点都可见或一个可见，则渲染连接
         self.performance_stats.rendered_connections += 1;
-        return true;
+        true
     }
     
     /// 检查交互是否应该被处理（考虑节流）
@@ -330,7 +336,7 @@ impl PerformanceOptimizer {
     pub fn optimize_nodes_batch(
         &mut self,
         graph: &Graph<PowerGraphNode, DataType, UIValueType>,
-        editor_state: &GraphEditorState<PowerGraphNode, DataType, UIValueType, UIUserState>,
+        editor_state: &PowerGraphEditorState,
     ) -> (HashSet<NodeId>, HashMap<NodeId, NodeDetailLevel>) {
         let mut visible_nodes = HashSet::new();
         let mut detail_levels = HashMap::new();
@@ -339,17 +345,17 @@ impl PerformanceOptimizer {
         self.reset_stats();
         
         // 对每个节点进行可见性和细节级别计算
-        for (node_id, _) in &graph.nodes {
+        for node_id in graph.iter_nodes() {
             if let Some(pos) = editor_state.node_positions.get(node_id) {
                 // 估计节点大小
                 let node_size = egui::Vec2::new(200.0, 150.0);
                 
                 // 检查是否可见以及细节级别
-                let (visible, detail_level) = self.should_render_node(*node_id, *pos, node_size);
+                let (visible, detail_level) = self.should_render_node(node_id, *pos, node_size);
                 
                 if visible {
-                    visible_nodes.insert(*node_id);
-                    detail_levels.insert(*node_id, detail_level);
+                    visible_nodes.insert(node_id);
+                    detail_levels.insert(node_id, detail_level);
                 }
             }
         }
@@ -366,54 +372,44 @@ pub fn performance_settings_ui(ui: &mut Ui, optimizer: &mut PerformanceOptimizer
         ui.heading("性能设置");
         
         // 启用/禁用选项
-        ui.checkbox(&mut optimizer.settings.enable_view_culling, "启用视图裁剪")
-            .on_change(|_| changed = true);
+        changed |= ui.checkbox(&mut optimizer.settings.enable_view_culling, "启用视图裁剪").changed();
         
-        ui.checkbox(&mut optimizer.settings.enable_lod, "启用细节级别")
-            .on_change(|_| changed = true);
+        changed |= ui.checkbox(&mut optimizer.settings.enable_lod, "启用细节级别").changed();
         
-        ui.checkbox(&mut optimizer.settings.enable_connection_optimization, "启用连接优化")
-            .on_change(|_| changed = true);
+        changed |= ui.checkbox(&mut optimizer.settings.enable_connection_optimization, "启用连接优化").changed();
         
-        ui.checkbox(&mut optimizer.settings.enable_interaction_throttling, "启用交互节流")
-            .on_change(|_| changed = true);
+        changed |= ui.checkbox(&mut optimizer.settings.enable_interaction_throttling, "启用交互节流").changed();
         
-        ui.checkbox(&mut optimizer.settings.show_node_details_on_hover_only, "仅在悬停时显示节点详情")
-            .on_change(|_| changed = true);
+        changed |= ui.checkbox(&mut optimizer.settings.show_node_details_on_hover_only, "仅在悬停时显示节点详情").changed();
         
         // 裁剪设置
         if optimizer.settings.enable_view_culling {
             ui.add_space(10.0);
             ui.label("视图裁剪边距:");
-            ui.add(Slider::new(&mut optimizer.settings.culling_margin, 0.0..=500.0))
-                .on_change(|_| changed = true);
+            changed |= ui.add(Slider::new(&mut optimizer.settings.culling_margin, 0.0..=500.0)).changed();
         }
         
         // LOD设置
         if optimizer.settings.enable_lod {
             ui.add_space(10.0);
             ui.label("低细节距离阈值:");
-            ui.add(Slider::new(&mut optimizer.settings.low_detail_distance, 100.0..=1000.0))
-                .on_change(|_| changed = true);
+            changed |= ui.add(Slider::new(&mut optimizer.settings.low_detail_distance, 100.0..=1000.0)).changed();
             
             ui.label("最小细节距离阈值:");
-            ui.add(Slider::new(&mut optimizer.settings.minimal_detail_distance, 200.0..=2000.0))
-                .on_change(|_| changed = true);
+            changed |= ui.add(Slider::new(&mut optimizer.settings.minimal_detail_distance, 200.0..=2000.0)).changed();
         }
         
         // 交互节流设置
         if optimizer.settings.enable_interaction_throttling {
             ui.add_space(10.0);
             ui.label("交互节流间隔 (毫秒):");
-            ui.add(Slider::new(&mut optimizer.settings.interaction_throttle_ms, 10..=200))
-                .on_change(|_| changed = true);
+            changed |= ui.add(Slider::new(&mut optimizer.settings.interaction_throttle_ms, 10..=200)).changed();
         }
         
         // 缓存设置
         ui.add_space(10.0);
         ui.label("可见性缓存有效期 (毫秒):");
-        ui.add(Slider::new(&mut optimizer.settings.visibility_cache_ttl_ms, 50..=500))
-            .on_change(|_| changed = true);
+        changed |= ui.add(Slider::new(&mut optimizer.settings.visibility_cache_ttl_ms, 50..=500)).changed();
         
         // 清除缓存按钮
         if ui.button("清除所有缓存").clicked() {
@@ -462,7 +458,8 @@ pub fn performance_stats_ui(ui: &mut Ui, optimizer: &PerformanceOptimizer) {
     });
 }
 
-/// 视图优化管理器\#[derive(Debug, Default)]
+/// 视图优化管理器
+#[derive(Debug, Default)]
 pub struct ViewOptimizer {
     /// 可见区域历史记录
     view_history: VecDeque<egui::Rect>,
@@ -8,6 +8,12 @@ pub struct PropertyPanel {
     pub show_advanced_properties: bool,
 }
 
+impl Default for PropertyPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PropertyPanel {
     /// 创建新的属性面板
     pub fn new() -> Self {
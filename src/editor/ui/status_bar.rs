@@ -14,6 +14,12 @@ pub struct StatusBar {
     pub project_status: String,
 }
 
+impl Default for StatusBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl StatusBar {
     /// 创建新的状态栏
     pub fn new() -> Self {
@@ -100,7 +106,7 @@ impl StatusBar {
     }
     
     /// 显示临时消息
-    pub fn show_temp_message(&mut self, message: &str, duration: std::time::Duration) {
+    pub fn show_temp_message(&mut self, message: &str, _duration: std::time::Duration) {
         // 在实际应用中，这里应该启动一个定时器来恢复原始状态
         self.project_status = message.to_string();
         
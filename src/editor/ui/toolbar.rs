@@ -12,6 +12,12 @@ pub struct Toolbar {
     pub zoom_level: f32,
 }
 
+impl Default for Toolbar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Toolbar {
     /// 创建新的工具栏
     pub fn new() -> Self {
@@ -82,46 +88,39 @@ impl Toolbar {
             }
             
             // 导出部分
-            if ui.button("导出")
-                .on_hover_text("导出项目或图表")
-                .clicked() {
+            ui.menu_button("导出", |ui| {
                 self.show_export_menu(ui, state);
-            }
+            })
+            .response
+            .on_hover_text("导出项目或图表");
         });
     }
     
     /// 渲染节点类型按钮
     fn node_type_button(&self, ui: &mut egui::Ui, label: &str, node_type: &str) -> bool {
-        ui.button(label)
-            .fill(if self.selected_node_type == node_type {
-                Some(ui.visuals().widgets.active.bg_fill)
-            } else {
-                None
-            })
+        ui.selectable_label(self.selected_node_type == node_type, label)
             .clicked()
     }
     
     /// 显示导出菜单
     fn show_export_menu(&self, ui: &mut egui::Ui, _state: &mut AppState) {
-        egui::menu::popup_menu(ui, "export_menu", |ui| {
-            if ui.button("导出为图片").clicked() {
-                // 实现导出为图片功能
-            }
-            
-            if ui.button("导出为PDF").clicked() {
-                // 实现导出为PDF功能
-            }
-            
-            if ui.button("导出为JSON").clicked() {
-                // 实现导出为JSON功能
-            }
-            
-            ui.separator();
-            
-            if ui.button("导出计算报告").clicked() {
-                // 实现导出计算报告功能
-            }
-        });
+        if ui.button("导出为图片").clicked() {
+            // 实现导出为图片功能
+        }
+        
+        if ui.button("导出为PDF").clicked() {
+            // 实现导出为PDF功能
+        }
+        
+        if ui.button("导出为JSON").clicked() {
+            // 实现导出为JSON功能
+        }
+        
+        ui.separator();
+        
+        if ui.button("导出计算报告").clicked() {
+            // 实现导出计算报告功能
+        }
     }
     
     /// 获取当前选中的节点类型
@@ -141,6 +140,6 @@ impl Toolbar {
     
     /// 设置缩放级别
     pub fn set_zoom_level(&mut self, zoom: f32) {
-        self.zoom_level = zoom.max(0.1).min(3.0); // 限制缩放范围
+        self.zoom_level = zoom.clamp(0.1, 3.0); // 限制缩放范围
     }
 }
\ No newline at end of file
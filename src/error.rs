@@ -148,7 +148,7 @@ pub fn unknown_error(msg: impl Into<String>) -> PdsdError {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use egui_node_graph::{EguiGraphError, AnyParameterId, NodeId};
+    use egui_node_graph::{EguiGraphError, NodeId};
 
     #[test]
     fn test_error_creation() {
@@ -166,7 +166,7 @@ impl ErrorHandler for PdsdErrorHandler {
                         UiErrorMessage::error(
                             "计算错误",
                             description.clone(),
-                            None::<&str>,
+                            Some("请检查输入参数是否在有效范围内"),
                             Some("CORE-CALC"),
                         )
                     },
@@ -194,11 +194,6 @@ impl ErrorHandler for PdsdErrorHandler {
                             Some("CORE-TYPE"),
                         )
                     },
-                    // 确保覆盖所有可能的CoreError变体
-                    crate::error::CoreError::DataType(_) => {
-                        // 已经在上面处理过，这里不会执行
-                        unreachable!()
-                    }
                 }
             },
             PdsdError::Application(app_error) => {
@@ -228,22 +223,6 @@ impl ErrorHandler for PdsdErrorHandler {
                             Some("APP-CONF"),
                         )
                     },
-                    crate::error::ApplicationError::Configuration(message) => {
-                        UiErrorMessage::error(
-                            "配置错误",
-                            message.clone(),
-                            None::<&str>,
-                            Some("APP-CONF"),
-                        )
-                    },
-                    // error.rs中的ApplicationError没有NotImplemented变体，暂时移除
-                    // 添加一个通配符匹配来处理所有其他情况
-                    _ => UiErrorMessage::error(
-                        "应用程序错误",
-                        app_error.to_string(),
-                        None::<&str>,
-                        Some("APP-GENERAL"),
-                    )
                 }
             },
             PdsdError::NodeGraph(node_graph_error) => {
@@ -366,7 +345,7 @@ impl ErrorHandler for PdsdErrorHandler {
     
     fn show_error_dialog(&self, ctx: &egui::Context, error: &UiErrorMessage) {
         // 显示模态对话框
-        egui::Window::new(format!("{}", error.level_text()))
+        egui::Window::new(error.level_text().to_string())
             .collapsible(false)
             .resizable(true)
             .default_width(450.0)
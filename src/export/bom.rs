@@ -0,0 +1,404 @@
+//! 材料表
+//!
+//! 汇总各配电箱的主要材料：回路及功能模块中的元器件按型号规格计数（断路器按类型、壳架、
+//! 极数及整定值区分），箱体按外形尺寸计数，线缆按规格累计长度，穿管按管材及管径累计长度。
+//! 材料表可按全工程、楼层或系统（消防/非消防）分组，导出为CSV或XLSX。
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::drawing::breaker_text_lines;
+use super::error::{ExportError, Result};
+use super::system_diagram::SystemDiagram;
+use super::xlsx::{write_csv_rows, CellValue, Workbook};
+use crate::core_lib::data_types::{CableInfo, ElectricComponent, LayingMethod, PipeSpecification, Pole};
+use crate::editor::business::building::level_name;
+use crate::editor::business::{BoxData, DistributionBoxCalculator};
+
+/// 材料表表头
+pub const BOM_COLUMNS: [&str; 6] = ["序号", "类别", "型号", "规格", "单位", "数量"];
+
+/// 汇总表名称
+const SUMMARY_SHEET: &str = "材料汇总";
+
+/// 材料类别（按材料表中的排列顺序）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BomCategory {
+    Enclosure,       // 配电箱箱体
+    Breaker,         // 断路器
+    Isolator,        // 隔离开关
+    DualPowerSwitch, // 双电源自动转换开关
+    Contactor,       // 接触器
+    EnergyMeter,     // 电能表
+    SurgeProtector,  // 浪涌保护器
+    Cable,           // 线缆
+    Conduit,         // 穿线管
+}
+
+impl BomCategory {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            BomCategory::Enclosure => "配电箱箱体",
+            BomCategory::Breaker => "断路器",
+            BomCategory::Isolator => "隔离开关",
+            BomCategory::DualPowerSwitch => "双电源自动转换开关",
+            BomCategory::Contactor => "接触器",
+            BomCategory::EnergyMeter => "电能表",
+            BomCategory::SurgeProtector => "浪涌保护器",
+            BomCategory::Cable => "线缆",
+            BomCategory::Conduit => "穿线管",
+        }
+    }
+
+    /// 计量单位
+    pub fn unit(&self) -> &'static str {
+        match self {
+            BomCategory::Enclosure => "台",
+            BomCategory::Cable | BomCategory::Conduit => "m",
+            _ => "个",
+        }
+    }
+}
+
+/// 材料表分组方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BomGrouping {
+    #[default]
+    Project, // 全工程合并
+    Floor,   // 按楼层
+    System,  // 按系统（消防/非消防）
+}
+
+impl BomGrouping {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            BomGrouping::Project => "全工程",
+            BomGrouping::Floor => "按楼层",
+            BomGrouping::System => "按系统",
+        }
+    }
+
+    /// 配电箱所属分组的排序键及名称
+    fn group_of(&self, box_data: &BoxData) -> (i32, String) {
+        match self {
+            BomGrouping::Project => (0, "全工程".to_string()),
+            BomGrouping::Floor => (box_data.floor, format!("{}层", level_name(box_data.floor))),
+            BomGrouping::System if box_data.is_fire_load() => (0, "消防配电系统".to_string()),
+            BomGrouping::System => (1, "非消防配电系统".to_string()),
+        }
+    }
+}
+
+/// 材料项
+#[derive(Debug, Clone, PartialEq)]
+pub struct BomItem {
+    pub category: BomCategory,
+    pub model: String,  // 型号
+    pub spec: String,   // 规格
+    pub quantity: f64,  // 数量（元器件、箱体为个数，线缆、穿管为长度m）
+}
+
+impl BomItem {
+    fn cells(&self, index: usize) -> Vec<CellValue> {
+        let decimals = if self.category.unit() == "m" { 1 } else { 0 };
+        vec![
+            CellValue::Number(index as f64),
+            CellValue::text(self.category.to_str()),
+            CellValue::text(&self.model),
+            CellValue::text(&self.spec),
+            CellValue::text(self.category.unit()),
+            CellValue::number(self.quantity, decimals),
+        ]
+    }
+}
+
+/// 材料累加器，按(类别, 型号, 规格)合并数量
+#[derive(Debug, Default)]
+struct BomAccumulator {
+    items: BTreeMap<(BomCategory, String, String), f64>,
+}
+
+impl BomAccumulator {
+    fn add(&mut self, category: BomCategory, model: &str, spec: String, quantity: f64) {
+        *self.items.entry((category, model.to_string(), spec)).or_insert(0.0) += quantity;
+    }
+
+    /// 统计一个配电箱的箱体、元器件、线缆及穿管
+    fn add_box(&mut self, box_data: &BoxData) {
+        let dimensions = &box_data.dimensions;
+        self.add(
+            BomCategory::Enclosure,
+            "",
+            format!("{:.0}×{:.0}×{:.0}", dimensions.width, dimensions.height, dimensions.depth),
+            1.0,
+        );
+
+        // 进线断路器按进线整定电流选型，超出断路器整定范围时不列入
+        if let Some(breaker) = DistributionBoxCalculator::select_breaker(box_data.incoming_current, Pole::P4)
+            .filter(|_| box_data.incoming_current > 0.0)
+        {
+            self.add_component(&ElectricComponent::Breaker(breaker), true);
+        }
+        if let Some(cable) = &box_data.incoming_cable {
+            // 线缆未统计长度时采用手动输入或估算的进线长度
+            let length = if cable.length > 0.0 { cable.length } else { box_data.incoming_length().map_or(0.0, |(l, _)| l) };
+            self.add_cable(cable, length);
+        }
+
+        for circuit in &box_data.circuits {
+            for component in &circuit.components {
+                self.add_component(component, circuit.three_phase);
+            }
+            if let Some(cable) = &circuit.cable {
+                // 线缆未统计长度时采用回路填写的长度
+                let length = if cable.length > 0.0 { cable.length } else { circuit.length.unwrap_or(0.0) };
+                self.add_cable(cable, length);
+            }
+        }
+
+        for module in &box_data.modules {
+            for component in module.components().iter().filter_map(|c| c.component.as_ref()) {
+                self.add_component(component, true);
+            }
+        }
+    }
+
+    fn add_component(&mut self, component: &ElectricComponent, three_phase: bool) {
+        let (category, spec) = match component {
+            ElectricComponent::Breaker(breaker) => {
+                (BomCategory::Breaker, breaker_text_lines(breaker, three_phase).join(" "))
+            }
+            ElectricComponent::Isolator(isolator) => (
+                BomCategory::Isolator,
+                format!("{} {} {}A", isolator.frame_current.to_str(), isolator.pole.to_str(), isolator.rated_current.to_str()),
+            ),
+            ElectricComponent::DualPowerSwitch(switch) => (
+                BomCategory::DualPowerSwitch,
+                format!("{} {} {}A", switch.frame_current.to_str(), switch.pole.to_str(), switch.rated_current.to_str()),
+            ),
+            ElectricComponent::Contactor(contactor) => (
+                BomCategory::Contactor,
+                format!("{} {}A {:.0}V", contactor.pole.to_str(), contactor.rated_current.to_str(), contactor.control_voltage),
+            ),
+            ElectricComponent::EnergyMeter(meter) => (
+                BomCategory::EnergyMeter,
+                format!("{}({})A {}级 {}", meter.rated_current.to_str(), meter.max_current.to_str(), meter.accuracy_class, meter.communication),
+            ),
+            ElectricComponent::SurgeProtector(spd) => (
+                BomCategory::SurgeProtector,
+                format!("{} Imax={}kA Up={}kV {}", spd.test_class, spd.max_discharge_current, spd.protection_level, spd.pole.to_str()),
+            ),
+        };
+        self.add(category, component.get_model(), spec, 1.0);
+    }
+
+    /// 线缆按规格累计长度，穿管敷设时按管材及管径累计穿管长度
+    fn add_cable(&mut self, cable: &CableInfo, length: f64) {
+        let spec = SystemDiagram::cable_annotation(&CableInfo { laying_method: String::new(), ..cable.clone() });
+        self.add(BomCategory::Cable, &cable.model, spec, length);
+
+        let method = LayingMethod::from_annotation(&cable.laying_method).filter(LayingMethod::is_conduit);
+        let pipe = PipeSpecification::all().into_iter().find(|p| p.to_str() == cable.pipe_diameter.trim());
+        if let (Some(method), Some(pipe)) = (method, pipe) {
+            self.add(BomCategory::Conduit, &format!("{}{}", method.to_str(), pipe.to_str()), format!("管径{}mm", pipe.to_str()), length);
+        }
+    }
+
+    fn into_items(self) -> Vec<BomItem> {
+        self.items
+            .into_iter()
+            .map(|((category, model, spec), quantity)| BomItem { category, model, spec, quantity })
+            .collect()
+    }
+}
+
+/// 材料表分组
+#[derive(Debug, Clone, PartialEq)]
+pub struct BomGroup {
+    pub name: String,
+    pub items: Vec<BomItem>,
+}
+
+impl BomGroup {
+    /// 分组材料表单元格：表头及材料行
+    pub fn table(&self) -> Vec<Vec<CellValue>> {
+        let mut table = vec![BOM_COLUMNS.iter().map(|c| CellValue::text(*c)).collect()];
+        table.extend(self.items.iter().enumerate().map(|(i, item)| item.cells(i + 1)));
+        table
+    }
+
+    /// 指定类别的数量合计
+    pub fn total(&self, category: BomCategory) -> f64 {
+        self.items.iter().filter(|item| item.category == category).map(|item| item.quantity).sum()
+    }
+}
+
+impl Default for BomGroup {
+    fn default() -> Self {
+        Self { name: SUMMARY_SHEET.to_string(), items: Vec::new() }
+    }
+}
+
+/// 材料表
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BillOfMaterials {
+    pub grouping: BomGrouping,
+    /// 全工程合计
+    pub summary: BomGroup,
+    /// 各分组材料，全工程合并时为空
+    pub groups: Vec<BomGroup>,
+}
+
+impl BillOfMaterials {
+    /// 统计各配电箱材料
+    ///
+    /// # 参数
+    /// * `boxes` - 参与统计的配电箱
+    /// * `grouping` - 分组方式
+    ///
+    /// # 返回值
+    /// 返回全工程合计及各分组材料；线缆长度为0（尚未统计）时仍列出规格，数量为0
+    pub fn from_boxes(boxes: &[&BoxData], grouping: BomGrouping) -> Self {
+        let mut summary = BomAccumulator::default();
+        let mut groups: BTreeMap<(i32, String), BomAccumulator> = BTreeMap::new();
+        for box_data in boxes {
+            summary.add_box(box_data);
+            if grouping != BomGrouping::Project {
+                groups.entry(grouping.group_of(box_data)).or_default().add_box(box_data);
+            }
+        }
+
+        Self {
+            grouping,
+            summary: BomGroup { items: summary.into_items(), ..BomGroup::default() },
+            groups: groups
+                .into_iter()
+                .map(|((_, name), accumulator)| BomGroup { name, items: accumulator.into_items() })
+                .collect(),
+        }
+    }
+
+    /// 生成CSV文本（UTF-8带BOM），先输出全工程合计，再依次输出各分组
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("\u{FEFF}");
+        for (i, group) in std::iter::once(&self.summary).chain(&self.groups).enumerate() {
+            if i > 0 {
+                csv.push_str("\r\n");
+            }
+            write_csv_rows(&mut csv, &[vec![CellValue::text(&group.name)]]);
+            write_csv_rows(&mut csv, &group.table());
+        }
+        csv
+    }
+
+    /// 生成工作簿：材料汇总表加每个分组一张工作表
+    pub fn to_workbook(&self) -> Workbook {
+        let mut workbook = Workbook::new();
+        workbook.add_sheet(&self.summary.name, self.summary.table(), 1);
+        for group in &self.groups {
+            workbook.add_sheet(&group.name, group.table(), 1);
+        }
+        workbook
+    }
+
+    /// 导出为CSV文件
+    ///
+    /// # 参数
+    /// * `path` - 输出文件路径
+    ///
+    /// # 返回值
+    /// * `Ok(())` - 导出成功
+    /// * `Err(ExportError)` - 无材料或写文件失败
+    pub fn export_csv(&self, path: &Path) -> Result<()> {
+        self.ensure_not_empty()?;
+        std::fs::write(path, self.to_csv())
+            .map_err(|e| ExportError::file_operation("写入", path.display().to_string(), e.to_string()))
+    }
+
+    /// 导出为XLSX文件
+    ///
+    /// # 参数
+    /// * `path` - 输出文件路径
+    ///
+    /// # 返回值
+    /// * `Ok(())` - 导出成功
+    /// * `Err(ExportError)` - 无材料或写文件失败
+    pub fn export_xlsx(&self, path: &Path) -> Result<()> {
+        self.ensure_not_empty()?;
+        self.to_workbook().save(path)
+    }
+
+    fn ensure_not_empty(&self) -> Result<()> {
+        if self.summary.items.is_empty() {
+            return Err(ExportError::data_error("材料表", "没有可导出的材料"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_lib::data_types::{Breaker, LoadClass, LoadLevel};
+    use crate::editor::business::CircuitInfo;
+
+    fn circuit(id: &str, length: f64) -> CircuitInfo {
+        let mut circuit = CircuitInfo::new(id.to_string(), "照明".to_string(), 2.0, 10.0);
+        circuit.phase = Some('1');
+        circuit.components.push(ElectricComponent::Breaker(Breaker::default()));
+        circuit.cable = Some(CableInfo { length, ..CableInfo::default() });
+        circuit
+    }
+
+    fn sample_boxes() -> (BoxData, BoxData) {
+        let mut lighting = BoxData::new("AL1".to_string(), 1);
+        lighting.circuits = vec![circuit("c1", 20.0), circuit("c2", 15.5)];
+        lighting.incoming_current = 32.0;
+        let mut fire = BoxData::new("APE1".to_string(), 2);
+        fire.load_class = LoadClass::fire(LoadLevel::default());
+        fire.circuits = vec![circuit("c1", 10.0)];
+        (lighting, fire)
+    }
+
+    #[test]
+    fn test_counts_components_and_lengths() {
+        let (lighting, fire) = sample_boxes();
+        let bom = BillOfMaterials::from_boxes(&[&lighting, &fire], BomGrouping::Project);
+        assert!(bom.groups.is_empty());
+
+        let summary = &bom.summary;
+        assert_eq!(summary.items[0].category, BomCategory::Enclosure);
+        assert_eq!(summary.items[0].spec, "400×600×200");
+        assert_eq!(summary.total(BomCategory::Enclosure), 2.0);
+        // 3个回路断路器规格相同合并为一项，另有1个进线断路器
+        let breakers: Vec<&BomItem> = summary.items.iter().filter(|i| i.category == BomCategory::Breaker).collect();
+        assert_eq!(breakers.len(), 2);
+        assert_eq!(breakers.iter().map(|i| i.quantity).sum::<f64>(), 4.0);
+        let incoming = breakers.iter().find(|i| i.model == "iC65N").unwrap();
+        assert_eq!(incoming.spec, "MCB63 4P-C 32A");
+        assert!((summary.total(BomCategory::Cable) - 45.5).abs() < 1e-9);
+        let conduit = summary.items.iter().find(|i| i.category == BomCategory::Conduit).unwrap();
+        assert_eq!(conduit.model, "SC20");
+        assert_eq!(conduit.spec, "管径20mm");
+        assert!((conduit.quantity - 45.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_grouping_and_export() {
+        let (lighting, fire) = sample_boxes();
+        let by_floor = BillOfMaterials::from_boxes(&[&fire, &lighting], BomGrouping::Floor);
+        let names: Vec<&str> = by_floor.groups.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, ["1层", "2层"]);
+
+        let by_system = BillOfMaterials::from_boxes(&[&lighting, &fire], BomGrouping::System);
+        assert_eq!(by_system.groups[0].name, "消防配电系统");
+        assert!((by_system.groups[0].total(BomCategory::Cable) - 10.0).abs() < 1e-9);
+
+        let csv = by_system.to_csv();
+        assert!(csv.starts_with("\u{FEFF}材料汇总\r\n序号,类别,型号,规格,单位,数量\r\n1,配电箱箱体,"));
+        let sheets: Vec<String> = by_system.to_workbook().sheets.into_iter().map(|s| s.name).collect();
+        assert_eq!(sheets, ["材料汇总", "消防配电系统", "非消防配电系统"]);
+
+        assert!(BillOfMaterials::default().export_csv(Path::new("unused.csv")).is_err());
+    }
+}
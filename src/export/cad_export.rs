@@ -1 +1,232 @@
-///CAD导出
\ No newline at end of file
+//! CAD导出
+//!
+//! 将配电箱系统图写出为DXF(R12 ASCII)文件。图层、文字样式与
+//! assets/CAD/【系统图工具】.dwg模板一致使用txt.shx + gbcbig.shx，
+//! 中文以\U+XXXX转义写出，可直接在AutoCAD中打开或插入模板。
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use super::drawing::{Drawing, Layer, Primitive, TextAlign};
+use super::error::{ExportError, Result};
+use super::system_diagram::SystemDiagram;
+use crate::editor::business::BoxData;
+
+/// 系统图文字样式名
+pub const DXF_TEXT_STYLE: &str = "PDSD";
+
+/// DXF写出工具
+pub struct DxfExporter;
+
+impl DxfExporter {
+    /// 将图纸写出为DXF文本
+    ///
+    /// 图纸y轴向下，写出时翻转为CAD的y轴向上
+    pub fn to_dxf(drawing: &Drawing) -> String {
+        let mut out = String::new();
+        Self::write_header(&mut out, drawing);
+        Self::write_tables(&mut out);
+
+        Self::pair(&mut out, 0, "SECTION");
+        Self::pair(&mut out, 2, "ENTITIES");
+        for primitive in &drawing.primitives {
+            Self::write_entity(&mut out, primitive);
+        }
+        Self::pair(&mut out, 0, "ENDSEC");
+        Self::pair(&mut out, 0, "EOF");
+        out
+    }
+
+    /// 将配电箱系统图导出为DXF文件
+    ///
+    /// # 参数
+    /// * `boxes` - 要导出的配电箱，自左向右依次排列
+    /// * `path` - 输出文件路径
+    ///
+    /// # 返回值
+    /// * `Ok(())` - 导出成功
+    /// * `Err(ExportError)` - 无可导出的配电箱或写文件失败
+    pub fn export_boxes(boxes: &[&BoxData], path: &Path) -> Result<()> {
+        if boxes.is_empty() {
+            return Err(ExportError::data_error("配电箱", "没有可导出的配电箱"));
+        }
+        let dxf = Self::to_dxf(&SystemDiagram::layout_boxes(boxes));
+        std::fs::write(path, dxf)
+            .map_err(|e| ExportError::file_operation("写入", path.display().to_string(), e.to_string()))
+    }
+
+    fn pair(out: &mut String, code: i32, value: impl std::fmt::Display) {
+        let _ = write!(out, "{:>3}\r\n{}\r\n", code, value);
+    }
+
+    fn coord(out: &mut String, code: i32, value: f64) {
+        Self::pair(out, code, format!("{:.4}", value));
+    }
+
+    fn write_header(out: &mut String, drawing: &Drawing) {
+        Self::pair(out, 0, "SECTION");
+        Self::pair(out, 2, "HEADER");
+        Self::pair(out, 9, "$ACADVER");
+        Self::pair(out, 1, "AC1009");
+        Self::pair(out, 9, "$DWGCODEPAGE");
+        Self::pair(out, 3, "ANSI_936");
+        if let Some((min, max)) = drawing.bounds() {
+            Self::pair(out, 9, "$EXTMIN");
+            Self::coord(out, 10, min.x);
+            Self::coord(out, 20, -max.y);
+            Self::coord(out, 30, 0.0);
+            Self::pair(out, 9, "$EXTMAX");
+            Self::coord(out, 10, max.x);
+            Self::coord(out, 20, -min.y);
+            Self::coord(out, 30, 0.0);
+        }
+        Self::pair(out, 0, "ENDSEC");
+    }
+
+    fn write_tables(out: &mut String) {
+        Self::pair(out, 0, "SECTION");
+        Self::pair(out, 2, "TABLES");
+
+        Self::pair(out, 0, "TABLE");
+        Self::pair(out, 2, "LTYPE");
+        Self::pair(out, 70, 1);
+        Self::pair(out, 0, "LTYPE");
+        Self::pair(out, 2, "CONTINUOUS");
+        Self::pair(out, 70, 0);
+        Self::pair(out, 3, "Solid line");
+        Self::pair(out, 72, 65);
+        Self::pair(out, 73, 0);
+        Self::coord(out, 40, 0.0);
+        Self::pair(out, 0, "ENDTAB");
+
+        let layers = Layer::all();
+        Self::pair(out, 0, "TABLE");
+        Self::pair(out, 2, "LAYER");
+        Self::pair(out, 70, layers.len() + 1);
+        Self::write_layer(out, "0", 7);
+        for layer in layers {
+            Self::write_layer(out, layer.name(), layer.color());
+        }
+        Self::pair(out, 0, "ENDTAB");
+
+        Self::pair(out, 0, "TABLE");
+        Self::pair(out, 2, "STYLE");
+        Self::pair(out, 70, 1);
+        Self::pair(out, 0, "STYLE");
+        Self::pair(out, 2, DXF_TEXT_STYLE);
+        Self::pair(out, 70, 0);
+        Self::coord(out, 40, 0.0);
+        Self::coord(out, 41, 0.7);
+        Self::coord(out, 50, 0.0);
+        Self::pair(out, 71, 0);
+        Self::pair(out, 3, "txt.shx");
+        Self::pair(out, 4, "gbcbig.shx");
+        Self::pair(out, 0, "ENDTAB");
+
+        Self::pair(out, 0, "ENDSEC");
+    }
+
+    fn write_layer(out: &mut String, name: &str, color: u8) {
+        Self::pair(out, 0, "LAYER");
+        Self::pair(out, 2, name);
+        Self::pair(out, 70, 0);
+        Self::pair(out, 62, color);
+        Self::pair(out, 6, "CONTINUOUS");
+    }
+
+    fn write_entity(out: &mut String, primitive: &Primitive) {
+        match primitive {
+            Primitive::Line { layer, start, end } => {
+                Self::pair(out, 0, "LINE");
+                Self::pair(out, 8, layer.name());
+                Self::coord(out, 10, start.x);
+                Self::coord(out, 20, -start.y);
+                Self::coord(out, 30, 0.0);
+                Self::coord(out, 11, end.x);
+                Self::coord(out, 21, -end.y);
+                Self::coord(out, 31, 0.0);
+            }
+            Primitive::Text { layer, position, height, align, content } => {
+                let horizontal = match align {
+                    TextAlign::Left => 0,
+                    TextAlign::Center => 1,
+                    TextAlign::Right => 2,
+                };
+                Self::pair(out, 0, "TEXT");
+                Self::pair(out, 8, layer.name());
+                Self::coord(out, 10, position.x);
+                Self::coord(out, 20, -position.y);
+                Self::coord(out, 30, 0.0);
+                Self::coord(out, 40, *height);
+                Self::pair(out, 1, Self::encode_text(content));
+                Self::pair(out, 7, DXF_TEXT_STYLE);
+                Self::pair(out, 72, horizontal);
+                Self::coord(out, 11, position.x);
+                Self::coord(out, 21, -position.y);
+                Self::coord(out, 31, 0.0);
+                // 垂直居中
+                Self::pair(out, 73, 2);
+            }
+            Primitive::Circle { layer, center, radius } => {
+                Self::pair(out, 0, "CIRCLE");
+                Self::pair(out, 8, layer.name());
+                Self::coord(out, 10, center.x);
+                Self::coord(out, 20, -center.y);
+                Self::coord(out, 30, 0.0);
+                Self::coord(out, 40, *radius);
+            }
+        }
+    }
+
+    /// 文字内容转义：非ASCII字符写为\U+XXXX，避免依赖代码页
+    pub fn encode_text(text: &str) -> String {
+        let mut encoded = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '\r' | '\n' => encoded.push(' '),
+                c if c.is_ascii() => encoded.push(c),
+                c if (c as u32) <= 0xFFFF => {
+                    let _ = write!(encoded, "\\U+{:04X}", c as u32);
+                }
+                _ => encoded.push('?'),
+            }
+        }
+        encoded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::drawing::Point;
+
+    #[test]
+    fn test_encode_text() {
+        assert_eq!(DxfExporter::encode_text("AL1 系统图"), "AL1 \\U+7CFB\\U+7EDF\\U+56FE");
+        assert_eq!(DxfExporter::encode_text("3P+N-C 20A"), "3P+N-C 20A");
+    }
+
+    #[test]
+    fn test_dxf_structure() {
+        let mut drawing = Drawing::new();
+        drawing.line(Layer::Busbar, Point::new(0.0, 0.0), Point::new(0.0, 100.0));
+        drawing.text(Layer::Title, Point::new(10.0, 20.0), 5.0, TextAlign::Left, "母线");
+        let dxf = DxfExporter::to_dxf(&drawing);
+
+        assert!(dxf.contains("AC1009"));
+        for layer in Layer::all() {
+            assert!(dxf.contains(&format!("  2\r\n{}\r\n", layer.name())));
+        }
+        assert!(dxf.contains("  0\r\nLINE\r\n  8\r\nPDSD-BUSBAR\r\n"));
+        // y轴翻转
+        assert!(dxf.contains(" 21\r\n-100.0000\r\n"));
+        assert!(dxf.contains("\\U+6BCD\\U+7EBF"));
+        assert!(dxf.ends_with("  0\r\nEOF\r\n"));
+    }
+
+    #[test]
+    fn test_export_requires_boxes() {
+        let path = std::env::temp_dir().join("pdsd_empty.dxf");
+        assert!(matches!(DxfExporter::export_boxes(&[], &path), Err(ExportError::DataError { .. })));
+    }
+}
@@ -0,0 +1,699 @@
+//! 图纸图元模型
+//!
+//! 与界面渲染无关的线段、文字图元和图层定义，以及画布与导出共用的图形几何
+//! （断路器、隔离开关、回路线、配电箱图例、功能模块符号）。图形函数通过[`DiagramPainter`]绘制，
+//! 画布和各导出格式（DXF、PDF、SVG）因此使用同一套几何。坐标沿用画布约定：x向右、y向下。
+
+use super::painter::DiagramPainter;
+use crate::core_lib::data_types::{Breaker, BreakerType, Curve, FrameCurrent, Pole};
+use crate::editor::business::ModuleSymbol;
+
+/// 图纸上的点
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    /// 按偏移量平移
+    pub fn offset(&self, dx: f64, dy: f64) -> Self {
+        Self::new(self.x + dx, self.y + dy)
+    }
+}
+
+/// 系统图图层
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Layer {
+    Incoming,     // 进线
+    Busbar,       // 母线
+    Circuit,      // 出线回路
+    Breaker,      // 断路器图形
+    Disconnector, // 隔离开关图形
+    BreakerText,  // 断路器标注
+    Cable,        // 线缆标注
+    CircuitText,  // 回路编号、相序及用途
+    LoadTable,    // 负荷表
+    Title,        // 配电箱名称
+    Equipment,    // 干线图设备
+    Frame,        // 图框及标题栏
+}
+
+impl Layer {
+    /// 全部图层（写出图层表时使用）
+    pub fn all() -> [Layer; 12] {
+        [
+            Layer::Incoming,
+            Layer::Busbar,
+            Layer::Circuit,
+            Layer::Breaker,
+            Layer::Disconnector,
+            Layer::BreakerText,
+            Layer::Cable,
+            Layer::CircuitText,
+            Layer::LoadTable,
+            Layer::Title,
+            Layer::Equipment,
+            Layer::Frame,
+        ]
+    }
+
+    /// 图层名（仅使用ASCII字符，保证各版本CAD均能识别）
+    pub fn name(&self) -> &'static str {
+        match self {
+            Layer::Incoming => "PDSD-INCOMING",
+            Layer::Busbar => "PDSD-BUSBAR",
+            Layer::Circuit => "PDSD-CIRCUIT",
+            Layer::Breaker => "PDSD-BREAKER",
+            Layer::Disconnector => "PDSD-DISCONNECTOR",
+            Layer::BreakerText => "PDSD-BREAKER-TEXT",
+            Layer::Cable => "PDSD-CABLE",
+            Layer::CircuitText => "PDSD-CIRCUIT-TEXT",
+            Layer::LoadTable => "PDSD-LOAD-TABLE",
+            Layer::Title => "PDSD-TITLE",
+            Layer::Equipment => "PDSD-EQUIPMENT",
+            Layer::Frame => "PDSD-FRAME",
+        }
+    }
+
+    /// 图层说明
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Layer::Incoming => "进线",
+            Layer::Busbar => "母线",
+            Layer::Circuit => "出线回路",
+            Layer::Breaker => "断路器",
+            Layer::Disconnector => "隔离开关",
+            Layer::BreakerText => "断路器标注",
+            Layer::Cable => "线缆标注",
+            Layer::CircuitText => "回路标注",
+            Layer::LoadTable => "负荷表",
+            Layer::Title => "配电箱名称",
+            Layer::Equipment => "干线图设备",
+            Layer::Frame => "图框",
+        }
+    }
+
+    /// AutoCAD颜色索引(ACI)
+    pub fn color(&self) -> u8 {
+        match self {
+            Layer::Incoming => 1,     // 红
+            Layer::Busbar => 1,       // 红
+            Layer::Circuit => 7,      // 白
+            Layer::Breaker => 3,      // 绿
+            Layer::Disconnector => 3, // 绿
+            Layer::BreakerText => 7,  // 白
+            Layer::Cable => 4,        // 青
+            Layer::CircuitText => 2,  // 黄
+            Layer::LoadTable => 8,    // 灰
+            Layer::Title => 6,        // 洋红
+            Layer::Equipment => 3,    // 绿
+            Layer::Frame => 7,        // 白
+        }
+    }
+
+    /// 深色背景下的显示颜色(R, G, B)，按AutoCAD颜色索引取值
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        match self.color() {
+            1 => (255, 0, 0),
+            2 => (255, 255, 0),
+            3 => (0, 255, 0),
+            4 => (0, 255, 255),
+            5 => (0, 0, 255),
+            6 => (255, 0, 255),
+            8 => (128, 128, 128),
+            _ => (255, 255, 255),
+        }
+    }
+
+    /// 线宽（绘图单位，与图形基准尺寸100对应）
+    pub fn stroke_width(&self) -> f64 {
+        match self {
+            Layer::Busbar => 8.0,
+            Layer::Incoming | Layer::Circuit | Layer::Disconnector => 5.0,
+            Layer::Breaker | Layer::Equipment => 3.0,
+            _ => 1.0,
+        }
+    }
+}
+
+/// 文字水平对齐方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+/// 图元
+#[derive(Debug, Clone, PartialEq)]
+pub enum Primitive {
+    /// 线段
+    Line { layer: Layer, start: Point, end: Point },
+    /// 单行文字，position为对齐点（垂直方向居中）
+    Text {
+        layer: Layer,
+        position: Point,
+        height: f64,
+        align: TextAlign,
+        content: String,
+    },
+    /// 圆
+    Circle { layer: Layer, center: Point, radius: f64 },
+}
+
+impl Primitive {
+    /// 图元所在图层
+    pub fn layer(&self) -> Layer {
+        match self {
+            Primitive::Line { layer, .. } | Primitive::Text { layer, .. } | Primitive::Circle { layer, .. } => *layer,
+        }
+    }
+}
+
+/// 图纸：图元的有序集合
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Drawing {
+    pub primitives: Vec<Primitive>,
+}
+
+impl Drawing {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加线段
+    pub fn line(&mut self, layer: Layer, start: Point, end: Point) {
+        self.primitives.push(Primitive::Line { layer, start, end });
+    }
+
+    /// 添加单行文字，空文字忽略
+    pub fn text(&mut self, layer: Layer, position: Point, height: f64, align: TextAlign, content: impl Into<String>) {
+        let content = content.into();
+        if content.is_empty() {
+            return;
+        }
+        self.primitives.push(Primitive::Text {
+            layer,
+            position,
+            height,
+            align,
+            content,
+        });
+    }
+
+    /// 添加圆
+    pub fn circle(&mut self, layer: Layer, center: Point, radius: f64) {
+        self.primitives.push(Primitive::Circle { layer, center, radius });
+    }
+
+    /// 按顺序将全部图元交给绘制器绘制
+    pub fn paint<P: DiagramPainter + ?Sized>(&self, painter: &mut P) {
+        for primitive in &self.primitives {
+            match primitive {
+                Primitive::Line { layer, start, end } => painter.draw_line(*layer, *start, *end),
+                Primitive::Text { layer, position, height, align, content } => {
+                    painter.draw_text(*layer, *position, *height, *align, content)
+                }
+                Primitive::Circle { layer, center, radius } => painter.draw_circle(*layer, *center, *radius),
+            }
+        }
+    }
+
+    /// 将另一张图纸平移后并入
+    pub fn append(&mut self, other: Drawing, dx: f64, dy: f64) {
+        self.append_scaled(other, 1.0, dx, dy);
+    }
+
+    /// 将另一张图纸按比例缩放（以原点为基点，文字高度同步缩放）并平移后并入
+    pub fn append_scaled(&mut self, other: Drawing, scale: f64, dx: f64, dy: f64) {
+        let transform = |p: Point| Point::new(p.x * scale + dx, p.y * scale + dy);
+        self.primitives.extend(other.primitives.into_iter().map(|p| match p {
+            Primitive::Line { layer, start, end } => Primitive::Line {
+                layer,
+                start: transform(start),
+                end: transform(end),
+            },
+            Primitive::Text { layer, position, height, align, content } => Primitive::Text {
+                layer,
+                position: transform(position),
+                height: height * scale,
+                align,
+                content,
+            },
+            Primitive::Circle { layer, center, radius } => Primitive::Circle {
+                layer,
+                center: transform(center),
+                radius: radius * scale,
+            },
+        }));
+    }
+
+    /// 添加矩形（四条线段）
+    pub fn rect(&mut self, layer: Layer, min: Point, max: Point) {
+        self.line(layer, min, Point::new(max.x, min.y));
+        self.line(layer, Point::new(max.x, min.y), max);
+        self.line(layer, max, Point::new(min.x, max.y));
+        self.line(layer, Point::new(min.x, max.y), min);
+    }
+
+    /// 图元外包范围(左上角, 右下角)，文字按插入点计，空图纸返回None
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        let mut points = self.primitives.iter().flat_map(|p| match p {
+            Primitive::Line { start, end, .. } => vec![*start, *end],
+            Primitive::Text { position, .. } => vec![*position],
+            Primitive::Circle { center, radius, .. } => {
+                vec![center.offset(-radius, -radius), center.offset(*radius, *radius)]
+            }
+        });
+        let first = points.next()?;
+        Some(points.fold((first, first), |(min, max), p| {
+            (Point::new(min.x.min(p.x), min.y.min(p.y)), Point::new(max.x.max(p.x), max.y.max(p.y)))
+        }))
+    }
+
+    /// 指定图层上的图元数量
+    pub fn count_on(&self, layer: Layer) -> usize {
+        self.primitives.iter().filter(|p| p.layer() == layer).count()
+    }
+}
+
+/// 断路器图形线段（基准尺寸100时的坐标），画布与导出图纸均经[`breaker_shape`]绘制
+pub const BREAKER_SEGMENTS: [[(f64, f64); 2]; 6] = [
+    [(0.0, 0.0), (37.5, 0.0)],
+    [(112.5, 0.0), (150.0, 0.0)],
+    [(37.5, 10.0), (37.5, -10.0)],
+    [(12.4, 7.1), (26.6, -7.1)],
+    [(26.6, 7.1), (12.4, -7.1)],
+    [(30.0, 37.5), (112.5, 0.0)],
+];
+
+/// 断路器图形宽度（基准尺寸100时）
+pub const BREAKER_WIDTH: f64 = 150.0;
+
+/// 隔离开关图形线段（基准尺寸100时的坐标）
+pub const DISCONNECTOR_SEGMENTS: [[(f64, f64); 2]; 4] = [
+    [(0.0, 0.0), (22.5, 0.0)],
+    [(112.5, 0.0), (150.0, 0.0)],
+    [(22.5, 10.0), (22.5, -10.0)],
+    [(30.0, 37.5), (112.5, 0.0)],
+];
+
+/// 出线回路线段（基准尺寸100时的x坐标），断路器位于160~310之间
+pub const CIRCUIT_SEGMENTS: [(f64, f64); 3] = [(0.0, 160.0), (310.0, 1170.0), (1200.0, 1380.0)];
+
+/// 出线回路中绘制为粗线的线段数，其后为回路编号、相序下方的细线
+pub const CIRCUIT_HEAVY_SEGMENTS: usize = 2;
+
+fn draw_segments<P: DiagramPainter + ?Sized>(painter: &mut P, layer: Layer, segments: &[[(f64, f64); 2]], origin: Point, scale: f64) {
+    for [(x1, y1), (x2, y2)] in segments {
+        painter.draw_line(layer, origin.offset(x1 * scale, y1 * scale), origin.offset(x2 * scale, y2 * scale));
+    }
+}
+
+/// 绘制断路器图形
+///
+/// # 参数
+/// * `painter` - 绘制器（画布或图纸）
+/// * `origin` - 断路器左端（进线端）位置
+/// * `size` - 基准尺寸，100时图形宽150
+///
+/// # 返回值
+/// 返回断路器右端（出线端）位置
+pub fn breaker_symbol<P: DiagramPainter + ?Sized>(painter: &mut P, origin: Point, size: f64) -> Point {
+    let scale = size / 100.0;
+    draw_segments(painter, Layer::Breaker, &BREAKER_SEGMENTS, origin, scale);
+    origin.offset(BREAKER_WIDTH * scale, 0.0)
+}
+
+/// 绘制断路器图形及上方两行标注，画布draw_breaker_shape与导出图纸共用
+///
+/// # 参数
+/// * `painter` - 绘制器（画布或图纸）
+/// * `origin` - 断路器左端（进线端）位置
+/// * `size` - 基准尺寸，100时图形宽150
+/// * `lines` - 标注文字，为None时只绘制图形
+///
+/// # 返回值
+/// 返回断路器右端（出线端）位置
+pub fn breaker_shape<P: DiagramPainter + ?Sized>(painter: &mut P, origin: Point, size: f64, lines: Option<&[String; 2]>) -> Point {
+    let end = breaker_symbol(painter, origin, size);
+    if let Some(lines) = lines {
+        breaker_text(painter, origin, size, lines);
+    }
+    end
+}
+
+/// 绘制隔离开关图形（断口处画圆）
+///
+/// # 返回值
+/// 返回隔离开关右端（出线端）位置
+pub fn disconnector_symbol<P: DiagramPainter + ?Sized>(painter: &mut P, origin: Point, size: f64) -> Point {
+    let scale = size / 100.0;
+    draw_segments(painter, Layer::Disconnector, &DISCONNECTOR_SEGMENTS, origin, scale);
+    painter.draw_circle(Layer::Disconnector, origin.offset(30.0 * scale, 0.0), 7.5 * scale);
+    origin.offset(BREAKER_WIDTH * scale, 0.0)
+}
+
+/// 绘制出线回路线
+///
+/// # 返回值
+/// 返回(断路器进线端, 线缆末端)位置
+pub fn circuit_line<P: DiagramPainter + ?Sized>(painter: &mut P, origin: Point, size: f64) -> (Point, Point) {
+    let scale = size / 100.0;
+    for (i, (x1, x2)) in CIRCUIT_SEGMENTS.into_iter().enumerate() {
+        let layer = if i < CIRCUIT_HEAVY_SEGMENTS { Layer::Circuit } else { Layer::CircuitText };
+        painter.draw_line(layer, origin.offset(x1 * scale, 0.0), origin.offset(x2 * scale, 0.0));
+    }
+    (origin.offset(CIRCUIT_SEGMENTS[0].1 * scale, 0.0), origin.offset(CIRCUIT_SEGMENTS[1].1 * scale, 0.0))
+}
+
+/// 绘制配电箱图例（矩形加对角线）
+pub fn box_legend<P: DiagramPainter + ?Sized>(painter: &mut P, origin: Point, size: f64) {
+    let scale = size / 100.0;
+    let min = origin.offset(0.0, -12.0 * scale);
+    let max = origin.offset(60.0 * scale, 12.0 * scale);
+    painter.draw_line(Layer::Equipment, min, Point::new(max.x, min.y));
+    painter.draw_line(Layer::Equipment, Point::new(max.x, min.y), max);
+    painter.draw_line(Layer::Equipment, max, Point::new(min.x, max.y));
+    painter.draw_line(Layer::Equipment, Point::new(min.x, max.y), min);
+    painter.draw_line(Layer::Equipment, min, max);
+}
+
+/// 功能模块符号高度（基准尺寸100时），与配电箱图例同高
+pub const MODULE_SYMBOL_HEIGHT: f64 = 24.0;
+
+fn draw_rect<P: DiagramPainter + ?Sized>(painter: &mut P, layer: Layer, min: Point, max: Point) {
+    painter.draw_line(layer, min, Point::new(max.x, min.y));
+    painter.draw_line(layer, Point::new(max.x, min.y), max);
+    painter.draw_line(layer, max, Point::new(min.x, max.y));
+    painter.draw_line(layer, Point::new(min.x, max.y), min);
+}
+
+/// 绘制功能模块符号
+///
+/// # 参数
+/// * `painter` - 绘制器（画布或图纸）
+/// * `symbol` - 模块符号
+/// * `center` - 符号中心，串接在线路上的符号中心位于线上
+/// * `size` - 基准尺寸，100时符号高24
+pub fn module_symbol<P: DiagramPainter + ?Sized>(painter: &mut P, symbol: ModuleSymbol, center: Point, size: f64) {
+    let scale = size / 100.0;
+    let half = MODULE_SYMBOL_HEIGHT / 2.0 * scale;
+    let square = (center.offset(-half, -half), center.offset(half, half));
+    let layer = Layer::Equipment;
+    match symbol {
+        ModuleSymbol::DualPowerSwitch => {
+            // 方框内转换触点，左侧两路进线
+            draw_rect(painter, layer, square.0, square.1);
+            draw_segments(painter, layer, &[[(-18.0, -6.0), (-12.0, -6.0)], [(-18.0, 6.0), (-12.0, 6.0)], [(-6.0, 6.0), (6.0, -6.0)]], center, scale);
+        },
+        ModuleSymbol::EnergyMeter => {
+            // 方框上部横线为电能表读数窗
+            draw_rect(painter, layer, square.0, square.1);
+            draw_segments(painter, layer, &[[(-12.0, -4.0), (12.0, -4.0)]], center, scale);
+        },
+        ModuleSymbol::ResidualCurrentTransformer => {
+            // 穿线互感器及二次引线
+            painter.draw_circle(layer, center, 8.0 * scale);
+            draw_segments(painter, layer, &[[(0.0, 8.0), (0.0, 12.0)]], center, scale);
+        },
+        ModuleSymbol::TemperatureSensor => {
+            painter.draw_circle(layer, center, 8.0 * scale);
+            draw_segments(painter, layer, &[[(-5.0, 5.0), (5.0, -5.0)], [(5.0, -5.0), (9.0, -5.0)]], center, scale);
+        },
+        ModuleSymbol::VoltageCurrentSensor => {
+            draw_rect(painter, layer, center.offset(-half, -8.0 * scale), center.offset(half, 8.0 * scale));
+            painter.draw_circle(layer, center, 5.0 * scale);
+        },
+        ModuleSymbol::ShuntTrip => {
+            // 分励线圈
+            draw_rect(painter, layer, center.offset(-6.0 * scale, -half), center.offset(6.0 * scale, half));
+            draw_segments(painter, layer, &[[(-6.0, 12.0), (6.0, -12.0)]], center, scale);
+        },
+        ModuleSymbol::SurgeProtector => {
+            // 方框内放电折线
+            draw_rect(painter, layer, center.offset(-6.0 * scale, -half), center.offset(6.0 * scale, half));
+            draw_segments(painter, layer, &[[(0.0, -8.0), (-3.0, 0.0)], [(-3.0, 0.0), (3.0, 0.0)], [(3.0, 0.0), (0.0, 8.0)]], center, scale);
+        },
+        ModuleSymbol::PeTerminalBar => {
+            // 端子排及接地引线
+            draw_segments(painter, layer, &[[(-12.0, 0.0), (12.0, 0.0)], [(0.0, 0.0), (0.0, 12.0)], [(-6.0, 12.0), (6.0, 12.0)]], center, scale);
+            for x in [-8.0, 0.0, 8.0] {
+                painter.draw_circle(layer, center.offset(x * scale, 0.0), 2.5 * scale);
+            }
+        },
+        ModuleSymbol::Generic => draw_rect(painter, layer, square.0, square.1),
+    }
+}
+
+/// 节点图形行高度（画布节点底部与画布SVG导出一致）
+pub const NODE_SYMBOL_HEIGHT: f64 = 24.0;
+
+/// 节点连线折线近似的分段数
+pub const CONNECTION_CURVE_SEGMENTS: usize = 16;
+
+/// 节点内绘制的图形
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeSymbol {
+    Circuit,                           // 出线回路线及断路器
+    DistributionBox(Vec<ModuleSymbol>), // 配电箱图例及功能模块符号
+}
+
+impl NodeSymbol {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            NodeSymbol::Circuit => "出线回路",
+            NodeSymbol::DistributionBox(_) => "配电箱",
+        }
+    }
+
+    /// 在给定宽度内绘制时的基准尺寸
+    pub fn size(&self, width: f64) -> f64 {
+        match self {
+            NodeSymbol::Circuit => width / CIRCUIT_SEGMENTS[CIRCUIT_SEGMENTS.len() - 1].1 * 100.0,
+            NodeSymbol::DistributionBox(_) => NODE_SYMBOL_HEIGHT / 24.0 * 100.0,
+        }
+    }
+
+    /// 绘制节点图形
+    ///
+    /// # 参数
+    /// * `painter` - 绘制器（画布或图纸）
+    /// * `origin` - 图形行左端中点
+    /// * `width` - 图形行宽度，回路线铺满整行；配电箱图例与其后的模块符号整体水平居中，
+    ///   放不下的模块符号不绘制
+    pub fn paint<P: DiagramPainter + ?Sized>(&self, painter: &mut P, origin: Point, width: f64) {
+        let size = self.size(width);
+        match self {
+            NodeSymbol::Circuit => {
+                let (breaker_start, _) = circuit_line(painter, origin, size);
+                breaker_symbol(painter, breaker_start, size);
+            },
+            NodeSymbol::DistributionBox(modules) => {
+                let scale = size / 100.0;
+                // 每个模块符号连同间隙占宽30
+                let fitting = ((width / scale - 60.0) / 30.0).max(0.0) as usize;
+                let modules = &modules[..modules.len().min(fitting)];
+                let start = origin.offset((width - (60.0 + 30.0 * modules.len() as f64) * scale) / 2.0, 0.0);
+                box_legend(painter, start, size);
+                for (i, symbol) in modules.iter().enumerate() {
+                    module_symbol(painter, *symbol, start.offset((78.0 + 30.0 * i as f64) * scale, 0.0), size);
+                }
+            },
+        }
+    }
+}
+
+/// 绘制节点连线
+///
+/// 与节点编辑器的连线相同：三次贝塞尔曲线，控制点水平偏移取两端水平距离的一半且不小于30，
+/// 按[`CONNECTION_CURVE_SEGMENTS`]段折线近似
+pub fn connection_curve<P: DiagramPainter + ?Sized>(painter: &mut P, layer: Layer, start: Point, end: Point) {
+    let control = ((end.x - start.x) / 2.0).max(30.0);
+    let (c1, c2) = (start.offset(control, 0.0), end.offset(-control, 0.0));
+    let mut previous = start;
+    for i in 1..=CONNECTION_CURVE_SEGMENTS {
+        let t = i as f64 / CONNECTION_CURVE_SEGMENTS as f64;
+        let u = 1.0 - t;
+        let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+        let point = Point::new(
+            a * start.x + b * c1.x + c * c2.x + d * end.x,
+            a * start.y + b * c1.y + c * c2.y + d * end.y,
+        );
+        painter.draw_line(layer, previous, point);
+        previous = point;
+    }
+}
+
+/// 断路器两行标注文字，格式与画布draw_breaker_text一致
+///
+/// 第一行为"类型+壳架电流"（如"MCB63"），第二行为"极数-脱扣曲线 整定值A"（如"1P+N-C 20A"）。
+/// 未指定的参数按画布的默认规则补全：类型按电流选择，极数按单/三相取1P+N或3P+N，曲线取C。
+///
+/// # 参数
+/// * `breaker` - 回路断路器
+/// * `three_phase` - 是否为三相回路
+pub fn breaker_text_lines(breaker: &Breaker, three_phase: bool) -> [String; 2] {
+    let rated_current = breaker.rated_current.to_f64();
+    let breaker_type = breaker.breaker_type.unwrap_or(if rated_current <= 63.0 {
+        BreakerType::MCB
+    } else if rated_current <= 250.0 {
+        BreakerType::MCCB
+    } else {
+        BreakerType::ACB
+    });
+    let pole = breaker.pole.unwrap_or(if three_phase { Pole::P3N } else { Pole::P1N });
+    let curve = breaker.curve.unwrap_or(Curve::C);
+
+    [
+        format!("{}{}", breaker_type.to_str(), breaker.frame_current.to_str()),
+        format!("{}-{} {}A", pole.to_str(), curve.to_str(), breaker.rated_current.to_str()),
+    ]
+}
+
+/// 进线断路器两行标注文字（按进线整定电流）
+pub fn incoming_breaker_text_lines(incoming_current: f64) -> [String; 2] {
+    let breaker_type = if incoming_current <= 63.0 { BreakerType::MCB } else { BreakerType::MCCB };
+    let frame = [
+        FrameCurrent::A63,
+        FrameCurrent::A100,
+        FrameCurrent::A160,
+        FrameCurrent::A250,
+        FrameCurrent::A400,
+        FrameCurrent::A630,
+        FrameCurrent::A800,
+    ]
+    .into_iter()
+    .find(|f| f.to_f64() >= incoming_current)
+    .unwrap_or(FrameCurrent::A800);
+
+    [
+        format!("{}{}", breaker_type.to_str(), frame.to_str()),
+        format!("{} {:.0}A", Pole::P4.to_str(), incoming_current),
+    ]
+}
+
+fn label_above<P: DiagramPainter + ?Sized>(painter: &mut P, origin: Point, size: f64, font_ratio: f64, lines: &[String; 2]) {
+    let width = BREAKER_WIDTH * size / 100.0;
+    let height = width * font_ratio;
+    let first = Point::new(origin.x + width / 2.0, origin.y - width * 0.25);
+    painter.draw_text(Layer::BreakerText, first, height, TextAlign::Center, &lines[0]);
+    painter.draw_text(Layer::BreakerText, first.offset(0.0, height), height, TextAlign::Center, &lines[1]);
+}
+
+/// 在断路器上方放置两行标注文字
+///
+/// 字高为图形宽度的0.13倍，第一行位于基准线上方0.25倍图形宽度处
+pub fn breaker_text<P: DiagramPainter + ?Sized>(painter: &mut P, origin: Point, size: f64, lines: &[String; 2]) {
+    label_above(painter, origin, size, 0.13, lines);
+}
+
+/// 在隔离开关上方放置两行标注文字（字高为图形宽度的0.1倍）
+pub fn disconnector_text<P: DiagramPainter + ?Sized>(painter: &mut P, origin: Point, size: f64, lines: &[String; 2]) {
+    label_above(painter, origin, size, 0.1, lines);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_lib::data_types::SettingValue;
+
+    #[test]
+    fn test_breaker_shape() {
+        let lines = ["C65N".to_string(), "1P 16A".to_string()];
+        let mut drawing = Drawing::new();
+        let end = breaker_shape(&mut drawing, Point::new(0.0, 0.0), 100.0, Some(&lines));
+        assert_eq!(end, breaker_symbol(&mut Drawing::new(), Point::new(0.0, 0.0), 100.0));
+        assert_eq!(drawing.count_on(Layer::Breaker), BREAKER_SEGMENTS.len());
+        assert_eq!(drawing.count_on(Layer::BreakerText), 2);
+
+        let mut bare = Drawing::new();
+        breaker_shape(&mut bare, Point::new(0.0, 0.0), 100.0, None);
+        assert_eq!(bare.count_on(Layer::BreakerText), 0);
+    }
+
+    #[test]
+    fn test_breaker_symbol() {
+        let mut drawing = Drawing::new();
+        let end = breaker_symbol(&mut drawing, Point::new(100.0, 50.0), 200.0);
+        assert_eq!(end, Point::new(400.0, 50.0));
+        assert_eq!(drawing.count_on(Layer::Breaker), 6);
+
+        let (min, max) = drawing.bounds().unwrap();
+        assert_eq!(min, Point::new(100.0, 30.0));
+        assert_eq!(max, Point::new(400.0, 125.0));
+    }
+
+    #[test]
+    fn test_shared_shapes() {
+        let mut drawing = Drawing::new();
+        let (breaker_start, cable_end) = circuit_line(&mut drawing, Point::new(0.0, 0.0), 50.0);
+        assert_eq!(breaker_start, Point::new(80.0, 0.0));
+        assert_eq!(cable_end, Point::new(585.0, 0.0));
+        assert_eq!(drawing.count_on(Layer::Circuit), 2);
+        assert_eq!(drawing.count_on(Layer::CircuitText), 1);
+        assert_eq!(Layer::CircuitText.stroke_width(), 1.0);
+
+        disconnector_symbol(&mut drawing, breaker_start, 50.0);
+        assert_eq!(drawing.count_on(Layer::Disconnector), 5);
+        assert_eq!(drawing.count_on(Layer::Breaker), 0);
+        assert_eq!(Layer::Disconnector.stroke_width(), 5.0);
+        assert!(drawing.primitives.contains(&Primitive::Circle {
+            layer: Layer::Disconnector,
+            center: Point::new(95.0, 0.0),
+            radius: 3.75,
+        }));
+
+        box_legend(&mut drawing, Point::new(0.0, 100.0), 100.0);
+        assert_eq!(drawing.count_on(Layer::Equipment), 5);
+    }
+
+    #[test]
+    fn test_breaker_text_lines() {
+        let breaker = Breaker {
+            breaker_type: None,
+            frame_current: FrameCurrent::A63,
+            rated_current: SettingValue::A20,
+            pole: None,
+            curve: None,
+            ..Breaker::default()
+        };
+        assert_eq!(breaker_text_lines(&breaker, false), ["MCB63".to_string(), "1P+N-C 20A".to_string()]);
+        assert_eq!(incoming_breaker_text_lines(125.0), ["MCCB160".to_string(), "4P 125A".to_string()]);
+    }
+
+    #[test]
+    fn test_node_symbol_and_connection() {
+        let mut drawing = Drawing::new();
+        NodeSymbol::Circuit.paint(&mut drawing, Point::new(0.0, 0.0), 184.0);
+        assert_eq!(drawing.count_on(Layer::Circuit), CIRCUIT_HEAVY_SEGMENTS);
+        assert_eq!(drawing.count_on(Layer::Breaker), BREAKER_SEGMENTS.len());
+        let (_, max) = drawing.bounds().unwrap();
+        assert!((max.x - 184.0).abs() < 1e-9);
+
+        let mut drawing = Drawing::new();
+        NodeSymbol::DistributionBox(Vec::new()).paint(&mut drawing, Point::new(0.0, 0.0), 184.0);
+        let (min, max) = drawing.bounds().unwrap();
+        assert!((min.x - 62.0).abs() < 1e-9 && (max.x - 122.0).abs() < 1e-9);
+        assert!((max.y - min.y - NODE_SYMBOL_HEIGHT).abs() < 1e-9);
+
+        // 模块符号排在图例之后，整体居中，放不下的不绘制
+        let mut drawing = Drawing::new();
+        let modules = vec![ModuleSymbol::DualPowerSwitch; 5];
+        NodeSymbol::DistributionBox(modules).paint(&mut drawing, Point::new(0.0, 0.0), 184.0);
+        let (min, max) = drawing.bounds().unwrap();
+        assert!((min.x - 2.0).abs() < 1e-9 && (max.x - 182.0).abs() < 1e-9);
+        assert!((max.y - min.y - NODE_SYMBOL_HEIGHT).abs() < 1e-9);
+
+        let mut drawing = Drawing::new();
+        connection_curve(&mut drawing, Layer::Circuit, Point::new(0.0, 0.0), Point::new(100.0, 50.0));
+        assert_eq!(drawing.count_on(Layer::Circuit), CONNECTION_CURVE_SEGMENTS);
+        let (min, max) = drawing.bounds().unwrap();
+        assert_eq!((min, max), (Point::new(0.0, 0.0), Point::new(100.0, 50.0)));
+    }
+}
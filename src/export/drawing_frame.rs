@@ -0,0 +1,250 @@
+//! 图框
+//!
+//! 图纸幅面、图框线和标题栏。图框尺寸按GB/T 14689横式幅面：装订边25mm，
+//! A3及以下其余边5mm，A2及以上其余边10mm；标题栏位于图框右下角。
+//! 图框坐标单位为mm，原点为图纸左上角，y向下。
+
+use super::drawing::{Drawing, Layer, Point, TextAlign};
+
+/// 图纸幅面（横式）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SheetSize {
+    #[default]
+    A3,
+    A2,
+    A1,
+}
+
+impl SheetSize {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            SheetSize::A3 => "A3",
+            SheetSize::A2 => "A2",
+            SheetSize::A1 => "A1",
+        }
+    }
+
+    /// 幅面尺寸(宽, 高)，mm
+    pub fn dimensions(&self) -> (f64, f64) {
+        match self {
+            SheetSize::A3 => (420.0, 297.0),
+            SheetSize::A2 => (594.0, 420.0),
+            SheetSize::A1 => (841.0, 594.0),
+        }
+    }
+
+    /// 图框线距纸边距离(装订边, 其余边)，mm
+    pub fn margins(&self) -> (f64, f64) {
+        match self {
+            SheetSize::A3 => (25.0, 5.0),
+            SheetSize::A2 | SheetSize::A1 => (25.0, 10.0),
+        }
+    }
+}
+
+/// 标题栏内容
+#[derive(Debug, Clone, PartialEq)]
+pub struct TitleBlock {
+    pub project_name: String,   // 工程名称
+    pub drawing_number: String, // 图号
+    pub designer: String,       // 设计
+    pub date: String,           // 日期
+    pub scale: String,          // 比例
+}
+
+impl Default for TitleBlock {
+    fn default() -> Self {
+        Self {
+            project_name: String::new(),
+            drawing_number: "电施-01".to_string(),
+            designer: String::new(),
+            date: chrono::Local::now().format("%Y.%m").to_string(),
+            scale: "不按比例".to_string(),
+        }
+    }
+}
+
+/// 标题栏宽度(mm)
+pub const TITLE_BLOCK_WIDTH: f64 = 180.0;
+/// 标题栏行高(mm)
+const TITLE_BLOCK_ROW: f64 = 8.0;
+/// 标题栏行数
+const TITLE_BLOCK_ROWS: usize = 5;
+/// 标题栏字高(mm)
+const TITLE_BLOCK_TEXT: f64 = 3.5;
+/// 图纸内容距图框线的留白(mm)
+const CONTENT_PADDING: f64 = 5.0;
+
+/// 图框
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DrawingFrame {
+    pub sheet: SheetSize,
+    pub title_block: TitleBlock,
+}
+
+impl DrawingFrame {
+    pub fn new(sheet: SheetSize, title_block: TitleBlock) -> Self {
+        Self { sheet, title_block }
+    }
+
+    /// 标题栏高度(mm)
+    pub fn title_block_height() -> f64 {
+        TITLE_BLOCK_ROW * TITLE_BLOCK_ROWS as f64
+    }
+
+    /// 图框线范围(左上角, 右下角)
+    pub fn border(&self) -> (Point, Point) {
+        let (width, height) = self.sheet.dimensions();
+        let (binding, other) = self.sheet.margins();
+        (Point::new(binding, other), Point::new(width - other, height - other))
+    }
+
+    /// 可排布图纸内容的区域(左上角, 右下角)，位于标题栏上方
+    pub fn content_area(&self) -> (Point, Point) {
+        let (min, max) = self.border();
+        (
+            min.offset(CONTENT_PADDING, CONTENT_PADDING),
+            Point::new(max.x - CONTENT_PADDING, max.y - Self::title_block_height() - CONTENT_PADDING),
+        )
+    }
+
+    /// 将内容按宽度缩放到内容区
+    ///
+    /// # 参数
+    /// * `content_width` - 内容宽度（绘图单位）
+    ///
+    /// # 返回值
+    /// 返回绘图单位到mm的比例
+    pub fn fit_scale(&self, content_width: f64) -> f64 {
+        let (min, max) = self.content_area();
+        if content_width > 0.0 { (max.x - min.x) / content_width } else { 1.0 }
+    }
+
+    /// 内容区可容纳的内容高度（绘图单位）
+    pub fn content_height(&self, scale: f64) -> f64 {
+        let (min, max) = self.content_area();
+        (max.y - min.y) / scale
+    }
+
+    /// 绘制一页图纸：图框、标题栏及缩放后放入内容区的图纸内容
+    ///
+    /// # 参数
+    /// * `content` - 图纸内容（绘图单位，左上角为原点）
+    /// * `scale` - 绘图单位到mm的比例
+    /// * `drawing_title` - 图名
+    /// * `page` - 页次（从1开始）
+    /// * `pages` - 总页数
+    ///
+    /// # 返回值
+    /// 返回以mm为单位的整页图元
+    pub fn compose(&self, content: Drawing, scale: f64, drawing_title: &str, page: usize, pages: usize) -> Drawing {
+        let mut sheet = Drawing::new();
+        let (width, height) = self.sheet.dimensions();
+        sheet.rect(Layer::Frame, Point::new(0.0, 0.0), Point::new(width, height));
+        let (border_min, border_max) = self.border();
+        sheet.rect(Layer::Frame, border_min, border_max);
+        self.draw_title_block(&mut sheet, drawing_title, page, pages);
+
+        let (area_min, _) = self.content_area();
+        let offset = content.bounds().map_or(Point::new(0.0, 0.0), |(min, _)| min);
+        sheet.append_scaled(content, scale, area_min.x - offset.x * scale, area_min.y - offset.y * scale);
+        sheet
+    }
+
+    /// 图号，多页时追加页次后缀，如"电施-01-2"
+    pub fn drawing_number(&self, page: usize, pages: usize) -> String {
+        if pages > 1 {
+            format!("{}-{}", self.title_block.drawing_number, page)
+        } else {
+            self.title_block.drawing_number.clone()
+        }
+    }
+
+    /// 标题栏：工程名称、图名、图号/比例、设计/日期、页次
+    fn draw_title_block(&self, sheet: &mut Drawing, drawing_title: &str, page: usize, pages: usize) {
+        let (_, border_max) = self.border();
+        let min = Point::new(border_max.x - TITLE_BLOCK_WIDTH, border_max.y - Self::title_block_height());
+        sheet.rect(Layer::Frame, min, border_max);
+        for i in 1..TITLE_BLOCK_ROWS {
+            let y = min.y + i as f64 * TITLE_BLOCK_ROW;
+            sheet.line(Layer::Frame, Point::new(min.x, y), Point::new(border_max.x, y));
+        }
+
+        // 列：标签25 | 内容65 | 标签25 | 内容65
+        let label_width = 25.0;
+        let value_width = 65.0;
+        sheet.line(Layer::Frame, Point::new(min.x + label_width, min.y), Point::new(min.x + label_width, border_max.y));
+        let split_top = min.y + 2.0 * TITLE_BLOCK_ROW;
+        let split_bottom = min.y + 4.0 * TITLE_BLOCK_ROW;
+        for x in [label_width + value_width, 2.0 * label_width + value_width] {
+            sheet.line(Layer::Frame, Point::new(min.x + x, split_top), Point::new(min.x + x, split_bottom));
+        }
+
+        let block = &self.title_block;
+        let cells: [(usize, f64, &str, String); 8] = [
+            (0, 0.0, "工程名称", block.project_name.clone()),
+            (1, 0.0, "图名", drawing_title.to_string()),
+            (2, 0.0, "图号", self.drawing_number(page, pages)),
+            (2, label_width + value_width, "比例", block.scale.clone()),
+            (3, 0.0, "设计", block.designer.clone()),
+            (3, label_width + value_width, "日期", block.date.clone()),
+            (4, 0.0, "页次", format!("第{}页 共{}页", page, pages)),
+            (4, label_width + value_width, "", self.sheet.to_str().to_string()),
+        ];
+        for (row, x, label, value) in cells {
+            let y = min.y + (row as f64 + 0.5) * TITLE_BLOCK_ROW;
+            sheet.text(Layer::Frame, Point::new(min.x + x + label_width / 2.0, y), TITLE_BLOCK_TEXT, TextAlign::Center, label);
+            sheet.text(Layer::Frame, Point::new(min.x + x + label_width + 2.0, y), TITLE_BLOCK_TEXT, TextAlign::Left, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::drawing::Primitive;
+
+    #[test]
+    fn test_frame_geometry() {
+        let frame = DrawingFrame::new(SheetSize::A3, TitleBlock::default());
+        let (min, max) = frame.border();
+        assert_eq!((min.x, min.y, max.x, max.y), (25.0, 5.0, 415.0, 292.0));
+
+        let (area_min, area_max) = frame.content_area();
+        assert_eq!(area_max.y, 292.0 - 40.0 - 5.0);
+        assert!((frame.fit_scale(3600.0) * 3600.0 - (area_max.x - area_min.x)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_compose_places_content_and_title_block() {
+        let frame = DrawingFrame::new(
+            SheetSize::A2,
+            TitleBlock {
+                project_name: "某办公楼".to_string(),
+                drawing_number: "电施-05".to_string(),
+                ..TitleBlock::default()
+            },
+        );
+        let mut content = Drawing::new();
+        content.line(Layer::Circuit, Point::new(100.0, 100.0), Point::new(1100.0, 100.0));
+        let sheet = frame.compose(content, 0.1, "AL1 系统图", 2, 3);
+
+        let (area_min, _) = frame.content_area();
+        assert!(sheet.primitives.contains(&Primitive::Line {
+            layer: Layer::Circuit,
+            start: area_min,
+            end: area_min.offset(100.0, 0.0),
+        }));
+        let texts: Vec<&str> = sheet
+            .primitives
+            .iter()
+            .filter_map(|p| match p {
+                Primitive::Text { content, .. } => Some(content.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert!(texts.contains(&"某办公楼"));
+        assert!(texts.contains(&"电施-05-2"));
+        assert!(texts.contains(&"第2页 共3页"));
+    }
+}
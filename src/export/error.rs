@@ -57,7 +57,7 @@ pub enum ExportError {
     },
     
     /// 导出过程取消
-    #[error("导出过程已取消: {reason}")]
+    #[error("导出过程已取消: {reason:?}")]
     Cancelled {
         /// 取消原因
         reason: Option<String>,
@@ -0,0 +1,344 @@
+//! 负荷一览表
+//!
+//! 遍历各配电箱及其回路生成负荷一览表：每回路列出编号、用途、相序、Pe、Kx、cosφ、
+//! Pjs、Ijs、断路器规格、线缆规格和敷设方式，每箱给出合计及三相负荷。可导出为CSV
+//! （汇总在前、各箱依次排列）或XLSX（汇总表加每箱一张工作表）。
+
+use std::path::Path;
+
+use super::drawing::breaker_text_lines;
+use super::error::{ExportError, Result};
+use super::system_diagram::SystemDiagram;
+use super::xlsx::{write_csv_rows, CellValue, Workbook};
+use crate::core_lib::data_types::{CableInfo, CircuitNumber};
+use crate::editor::business::building::level_name;
+use crate::editor::business::{BoxData, CircuitInfo, DistributionBoxCalculator};
+
+/// 回路明细表头
+pub const CIRCUIT_COLUMNS: [&str; 11] = [
+    "回路编号", "用途", "相序", "Pe(kW)", "Kx", "cosφ", "Pjs(kW)", "Ijs(A)", "断路器规格", "线缆规格", "敷设方式",
+];
+
+/// 汇总表表头
+pub const SUMMARY_COLUMNS: [&str; 12] = [
+    "配电箱", "楼层", "回路数", "Pe(kW)", "Pjs(kW)", "cosφ", "Ijs(A)", "进线整定(A)", "L1(kW)", "L2(kW)", "L3(kW)", "不平衡度(%)",
+];
+
+/// 汇总表名称
+const SUMMARY_SHEET: &str = "汇总";
+
+/// 回路负荷行
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadScheduleRow {
+    pub number: String,        // 回路编号
+    pub purpose: String,       // 用途
+    pub phase: String,         // 相序
+    pub pe: f64,               // 设备容量(kW)
+    pub kx: f64,               // 需要系数
+    pub cos_phi: f64,          // 功率因数
+    pub pjs: f64,              // 计算功率(kW)
+    pub ijs: f64,              // 计算电流(A)
+    pub breaker: String,       // 断路器规格
+    pub cable: String,         // 线缆规格
+    pub laying_method: String, // 敷设方式
+}
+
+impl LoadScheduleRow {
+    /// 由配电箱回路生成负荷行，未选型的断路器、线缆留空
+    pub fn from_circuit(circuit: &CircuitInfo) -> Self {
+        let three_phase = circuit.three_phase;
+        Self {
+            number: CircuitNumber::new(circuit.number).to_str(),
+            purpose: circuit.purpose_or_name().to_string(),
+            phase: SystemDiagram::phase_label(circuit),
+            pe: circuit.power,
+            kx: circuit.demand_coefficient,
+            cos_phi: circuit.power_factor,
+            pjs: circuit.calculated_power(),
+            ijs: circuit.current,
+            breaker: circuit
+                .find_breaker()
+                .map(|b| breaker_text_lines(b, three_phase).join(" "))
+                .unwrap_or_default(),
+            cable: circuit
+                .cable
+                .as_ref()
+                .map(|c| SystemDiagram::cable_annotation(&CableInfo { laying_method: String::new(), ..c.clone() }))
+                .unwrap_or_default(),
+            laying_method: circuit.cable.as_ref().map(|c| c.laying_method.clone()).unwrap_or_default(),
+        }
+    }
+
+    fn cells(&self) -> Vec<CellValue> {
+        vec![
+            CellValue::text(&self.number),
+            CellValue::text(&self.purpose),
+            CellValue::text(&self.phase),
+            CellValue::number(self.pe, 2),
+            CellValue::number(self.kx, 2),
+            CellValue::number(self.cos_phi, 2),
+            CellValue::number(self.pjs, 2),
+            CellValue::number(self.ijs, 1),
+            CellValue::text(&self.breaker),
+            CellValue::text(&self.cable),
+            CellValue::text(&self.laying_method),
+        ]
+    }
+}
+
+/// 单个配电箱的负荷表
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxLoadSchedule {
+    pub name: String,
+    pub floor: i32,
+    pub rows: Vec<LoadScheduleRow>,
+    /// 设备容量合计(kW)
+    pub total_pe: f64,
+    /// 计算功率合计(kW)
+    pub total_pjs: f64,
+    /// 综合功率因数（按各回路有功、无功合成）
+    pub power_factor: f64,
+    /// 配电箱计算电流(A)，按计算功率合计及综合功率因数计算，与配电箱重新计算的结果一致
+    pub total_current: f64,
+    /// 进线整定电流(A)
+    pub incoming_current: f64,
+    /// L1、L2、L3各相负荷(kW)
+    pub phase_loads: [f64; 3],
+    /// 三相不平衡度(%)
+    pub unbalance: f64,
+}
+
+impl BoxLoadSchedule {
+    /// 由配电箱生成负荷表，回路按编号排列
+    pub fn from_box(box_data: &BoxData) -> Self {
+        let mut circuits: Vec<&CircuitInfo> = box_data.circuits.iter().collect();
+        circuits.sort_by_key(|c| c.number);
+        let rows: Vec<LoadScheduleRow> = circuits.iter().map(|c| LoadScheduleRow::from_circuit(c)).collect();
+
+        let total_pe = rows.iter().map(|r| r.pe).sum();
+        let total_pjs = DistributionBoxCalculator::calculate_demand_power(&box_data.circuits);
+        let power_factor = DistributionBoxCalculator::combined_power_factor(&box_data.circuits).unwrap_or(0.0);
+        let total_current = if power_factor > 0.0 {
+            DistributionBoxCalculator::calculate_total_current(total_pjs, power_factor).unwrap_or(0.0)
+        } else {
+            0.0
+        };
+
+        Self {
+            name: box_data.name.clone(),
+            floor: box_data.floor,
+            rows,
+            total_pe,
+            total_pjs,
+            power_factor,
+            total_current,
+            incoming_current: box_data.incoming_current,
+            phase_loads: box_data.phase_loads,
+            unbalance: DistributionBoxCalculator::calculate_balance_degree(&box_data.phase_loads) * 100.0,
+        }
+    }
+
+    /// 综合需要系数 ΣPjs/ΣPe
+    pub fn demand_coefficient(&self) -> f64 {
+        if self.total_pe > 0.0 { self.total_pjs / self.total_pe } else { 0.0 }
+    }
+
+    /// 负荷表单元格：表头、回路行、合计行及三相负荷
+    pub fn table(&self) -> Vec<Vec<CellValue>> {
+        let mut table = vec![CIRCUIT_COLUMNS.iter().map(|c| CellValue::text(*c)).collect()];
+        table.extend(self.rows.iter().map(LoadScheduleRow::cells));
+        table.push(vec![
+            CellValue::text("合计"),
+            CellValue::Empty,
+            CellValue::Empty,
+            CellValue::number(self.total_pe, 2),
+            CellValue::number(self.demand_coefficient(), 2),
+            CellValue::number(self.power_factor, 2),
+            CellValue::number(self.total_pjs, 2),
+            CellValue::number(self.total_current, 1),
+            CellValue::text(format!("进线整定{:.0}A", self.incoming_current)),
+        ]);
+        table.push(Vec::new());
+        for (i, load) in self.phase_loads.iter().enumerate() {
+            table.push(vec![CellValue::text(format!("L{}相负荷(kW)", i + 1)), CellValue::number(*load, 2)]);
+        }
+        table.push(vec![CellValue::text("三相不平衡度(%)"), CellValue::number(self.unbalance, 1)]);
+        table
+    }
+
+    fn summary_cells(&self) -> Vec<CellValue> {
+        vec![
+            CellValue::text(&self.name),
+            CellValue::text(level_name(self.floor)),
+            CellValue::Number(self.rows.len() as f64),
+            CellValue::number(self.total_pe, 2),
+            CellValue::number(self.total_pjs, 2),
+            CellValue::number(self.power_factor, 2),
+            CellValue::number(self.total_current, 1),
+            CellValue::number(self.incoming_current, 0),
+            CellValue::number(self.phase_loads[0], 2),
+            CellValue::number(self.phase_loads[1], 2),
+            CellValue::number(self.phase_loads[2], 2),
+            CellValue::number(self.unbalance, 1),
+        ]
+    }
+}
+
+/// 负荷一览表
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadSchedule {
+    pub boxes: Vec<BoxLoadSchedule>,
+}
+
+impl LoadSchedule {
+    /// 按楼层、名称顺序生成各配电箱负荷表
+    pub fn from_boxes(boxes: &[&BoxData]) -> Self {
+        let mut boxes: Vec<BoxLoadSchedule> = boxes.iter().map(|b| BoxLoadSchedule::from_box(b)).collect();
+        boxes.sort_by(|a, b| a.floor.cmp(&b.floor).then_with(|| a.name.cmp(&b.name)));
+        Self { boxes }
+    }
+
+    /// 汇总表单元格：每箱一行
+    pub fn summary_table(&self) -> Vec<Vec<CellValue>> {
+        let mut table = vec![SUMMARY_COLUMNS.iter().map(|c| CellValue::text(*c)).collect()];
+        table.extend(self.boxes.iter().map(BoxLoadSchedule::summary_cells));
+        table
+    }
+
+    /// 生成CSV文本（UTF-8带BOM，便于Excel直接打开中文）
+    ///
+    /// 先输出汇总表，再依次输出各配电箱负荷表，各表之间空一行
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("\u{FEFF}");
+        write_csv_rows(&mut csv, &[vec![CellValue::text(SUMMARY_SHEET)]]);
+        write_csv_rows(&mut csv, &self.summary_table());
+        for schedule in &self.boxes {
+            csv.push_str("\r\n");
+            write_csv_rows(
+                &mut csv,
+                &[vec![
+                    CellValue::text("配电箱"),
+                    CellValue::text(&schedule.name),
+                    CellValue::text("楼层"),
+                    CellValue::text(level_name(schedule.floor)),
+                ]],
+            );
+            write_csv_rows(&mut csv, &schedule.table());
+        }
+        csv
+    }
+
+    /// 生成工作簿：汇总表加每个配电箱一张工作表
+    pub fn to_workbook(&self) -> Workbook {
+        let mut workbook = Workbook::new();
+        workbook.add_sheet(SUMMARY_SHEET, self.summary_table(), 1);
+        for schedule in &self.boxes {
+            workbook.add_sheet(&schedule.name, schedule.table(), 1);
+        }
+        workbook
+    }
+
+    /// 导出为CSV文件
+    ///
+    /// # 参数
+    /// * `path` - 输出文件路径
+    ///
+    /// # 返回值
+    /// * `Ok(())` - 导出成功
+    /// * `Err(ExportError)` - 无配电箱或写文件失败
+    pub fn export_csv(&self, path: &Path) -> Result<()> {
+        self.ensure_not_empty()?;
+        std::fs::write(path, self.to_csv())
+            .map_err(|e| ExportError::file_operation("写入", path.display().to_string(), e.to_string()))
+    }
+
+    /// 导出为XLSX文件
+    ///
+    /// # 参数
+    /// * `path` - 输出文件路径
+    ///
+    /// # 返回值
+    /// * `Ok(())` - 导出成功
+    /// * `Err(ExportError)` - 无配电箱或写文件失败
+    pub fn export_xlsx(&self, path: &Path) -> Result<()> {
+        self.ensure_not_empty()?;
+        self.to_workbook().save(path)
+    }
+
+    fn ensure_not_empty(&self) -> Result<()> {
+        if self.boxes.is_empty() {
+            return Err(ExportError::data_error("负荷一览表", "没有可导出的配电箱"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_lib::data_types::{Breaker, ElectricComponent, Pole};
+
+    fn sample_box() -> BoxData {
+        let mut box_data = BoxData::new("AL1".to_string(), 1);
+        let mut lighting = CircuitInfo::new("c1".to_string(), "照明".to_string(), 2.0, 10.0);
+        lighting.number = 2;
+        lighting.phase = Some('1');
+        lighting.demand_coefficient = 0.8;
+        lighting.power_factor = 0.9;
+        lighting.cable = Some(CableInfo::default());
+        let mut fan = CircuitInfo::new("c2".to_string(), "风机,排烟".to_string(), 10.0, 19.0);
+        fan.number = 1;
+        fan.power_factor = 0.8;
+        fan.three_phase = true;
+        box_data.circuits = vec![lighting, fan];
+        box_data.total_current = 25.0;
+        box_data.incoming_current = 32.0;
+        box_data.phase_loads = [5.0, 2.5, 2.5];
+        box_data
+    }
+
+    #[test]
+    fn test_box_schedule_totals() {
+        let schedule = BoxLoadSchedule::from_box(&sample_box());
+        assert_eq!(schedule.rows[0].number, "WL1");
+        assert_eq!(schedule.rows[0].phase, "L1L2L3");
+        assert_eq!(schedule.rows[1].phase, "L1");
+        assert_eq!(schedule.rows[1].cable, "BV-3x2.5");
+        assert_eq!(schedule.rows[1].laying_method, "SC20");
+        assert!((schedule.total_pjs - 11.6).abs() < 1e-9);
+        assert!((schedule.demand_coefficient() - 11.6 / 12.0).abs() < 1e-9);
+        // 综合功率因数介于各回路功率因数之间
+        assert!(schedule.power_factor > 0.8 && schedule.power_factor < 0.9);
+        // 合计行Ijs按合计Pjs及综合功率因数计算，不取配电箱中未重新计算的旧值
+        let ijs = DistributionBoxCalculator::calculate_total_current(11.6, schedule.power_factor).unwrap();
+        assert!((schedule.total_current - ijs).abs() < 1e-9);
+        assert!((schedule.unbalance - 75.0).abs() < 1e-6);
+
+        // 尚未分配相的单相回路按单相标注断路器极数
+        let mut socket = CircuitInfo::new("c3".to_string(), "插座".to_string(), 1.0, 5.0);
+        socket.components.push(ElectricComponent::Breaker(Breaker { pole: None, ..Default::default() }));
+        let row = LoadScheduleRow::from_circuit(&socket);
+        assert_eq!(row.phase, "");
+        assert!(row.breaker.contains(Pole::P1N.to_str()));
+    }
+
+    #[test]
+    fn test_csv_and_workbook() {
+        let box_data = sample_box();
+        let mut other = BoxData::new("AP1".to_string(), 0);
+        other.circuits.push(CircuitInfo::new("c1".to_string(), "水泵".to_string(), 5.5, 11.0));
+        let schedule = LoadSchedule::from_boxes(&[&box_data, &other]);
+        assert_eq!(schedule.boxes[0].name, "AP1");
+
+        let csv = schedule.to_csv();
+        assert!(csv.starts_with("\u{FEFF}汇总\r\n配电箱,楼层,回路数"));
+        assert!(csv.contains("\"风机,排烟\""));
+        assert!(csv.contains("合计,,,12,0.97,"));
+
+        let workbook = schedule.to_workbook();
+        let names: Vec<&str> = workbook.sheets.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["汇总", "AP1", "AL1"]);
+
+        assert!(LoadSchedule::default().export_xlsx(Path::new("unused.xlsx")).is_err());
+    }
+}
@@ -0,0 +1,28 @@
+//! 导出模块
+//! 包含系统图图元模型、绘制抽象、图框、CAD导出、PDF导出、SVG导出、负荷一览表、材料表和报告导出
+
+pub mod bom;
+pub mod cad_export;
+pub mod drawing;
+pub mod drawing_frame;
+pub mod error;
+pub mod load_schedule;
+pub mod painter;
+pub mod pdf_export;
+pub mod report;
+pub mod svg_export;
+pub mod system_diagram;
+pub mod xlsx;
+
+// 重新导出常用组件
+pub use bom::{BillOfMaterials, BomCategory, BomGroup, BomGrouping, BomItem};
+pub use cad_export::DxfExporter;
+pub use drawing::{Drawing, Layer, NodeSymbol, Point, Primitive, TextAlign};
+pub use drawing_frame::{DrawingFrame, SheetSize, TitleBlock};
+pub use error::{ExportError, Result};
+pub use load_schedule::{BoxLoadSchedule, LoadSchedule, LoadScheduleRow};
+pub use painter::{DiagramPainter, EguiPainter};
+pub use pdf_export::{PdfExporter, Sheet};
+pub use svg_export::{CanvasSnapshot, SvgExporter, SvgPainter, SvgTheme};
+pub use system_diagram::{SystemDiagram, TrunkDiagram};
+pub use xlsx::{write_csv_rows, CellValue, Workbook, Worksheet};
@@ -0,0 +1,103 @@
+//! 图形绘制抽象
+//!
+//! 图形几何（断路器、回路线等）只通过[`DiagramPainter`]输出线段、文字和圆。
+//! [`Drawing`]记录图元供文件导出，[`EguiPainter`]直接绘制到画布，
+//! [`super::svg_export::SvgPainter`]写出SVG元素，屏幕与文件输出因此始终一致。
+
+use super::drawing::{Drawing, Layer, Point, TextAlign};
+use egui::{Align2, Color32, FontId, Pos2, Stroke, Vec2};
+
+/// 图形绘制器
+pub trait DiagramPainter {
+    /// 绘制线段
+    fn draw_line(&mut self, layer: Layer, start: Point, end: Point);
+    /// 绘制单行文字，position为对齐点（垂直方向居中）
+    fn draw_text(&mut self, layer: Layer, position: Point, height: f64, align: TextAlign, content: &str);
+    /// 绘制圆
+    fn draw_circle(&mut self, layer: Layer, center: Point, radius: f64);
+}
+
+impl DiagramPainter for Drawing {
+    fn draw_line(&mut self, layer: Layer, start: Point, end: Point) {
+        self.line(layer, start, end);
+    }
+
+    fn draw_text(&mut self, layer: Layer, position: Point, height: f64, align: TextAlign, content: &str) {
+        self.text(layer, position, height, align, content);
+    }
+
+    fn draw_circle(&mut self, layer: Layer, center: Point, radius: f64) {
+        self.circle(layer, center, radius);
+    }
+}
+
+/// 画布绘制器：将图形坐标按原点和缩放比例转换为屏幕坐标后绘制到egui画布
+pub struct EguiPainter<'a> {
+    painter: &'a egui::Painter,
+    /// 图形坐标原点对应的屏幕位置
+    origin: Pos2,
+    /// 图形坐标到屏幕坐标的缩放比例
+    scale: f32,
+    /// 线宽比例（图形基准尺寸/100）
+    stroke_scale: f32,
+}
+
+impl<'a> EguiPainter<'a> {
+    /// 创建画布绘制器
+    ///
+    /// # 参数
+    /// * `painter` - egui画布
+    /// * `origin` - 图形坐标原点对应的屏幕位置
+    /// * `scale` - 缩放比例（图形坐标已包含缩放时取1.0）
+    pub fn new(painter: &'a egui::Painter, origin: Pos2, scale: f32) -> Self {
+        Self { painter, origin, scale, stroke_scale: 1.0 }
+    }
+
+    /// 设置线宽比例，使线宽随图形基准尺寸变化（与画布原有图形一致）
+    pub fn with_stroke_scale(mut self, stroke_scale: f32) -> Self {
+        self.stroke_scale = stroke_scale;
+        self
+    }
+
+    /// 图形坐标转换为屏幕坐标
+    pub fn to_screen(&self, point: Point) -> Pos2 {
+        self.origin + Vec2::new(point.x as f32 * self.scale, point.y as f32 * self.scale)
+    }
+
+    fn color(layer: Layer) -> Color32 {
+        let (r, g, b) = layer.rgb();
+        Color32::from_rgb(r, g, b)
+    }
+
+    /// 线宽按图形基准尺寸100给出，随缩放和线宽比例同步变化
+    fn stroke(&self, layer: Layer) -> Stroke {
+        Stroke::new(layer.stroke_width() as f32 * self.scale * self.stroke_scale, Self::color(layer))
+    }
+}
+
+impl DiagramPainter for EguiPainter<'_> {
+    fn draw_line(&mut self, layer: Layer, start: Point, end: Point) {
+        self.painter
+            .line_segment([self.to_screen(start), self.to_screen(end)], self.stroke(layer));
+    }
+
+    fn draw_text(&mut self, layer: Layer, position: Point, height: f64, align: TextAlign, content: &str) {
+        let anchor = match align {
+            TextAlign::Left => Align2::LEFT_CENTER,
+            TextAlign::Center => Align2::CENTER_CENTER,
+            TextAlign::Right => Align2::RIGHT_CENTER,
+        };
+        self.painter.text(
+            self.to_screen(position),
+            anchor,
+            content,
+            FontId::proportional(height as f32 * self.scale),
+            Self::color(layer),
+        );
+    }
+
+    fn draw_circle(&mut self, layer: Layer, center: Point, radius: f64) {
+        self.painter
+            .circle_stroke(self.to_screen(center), radius as f32 * self.scale, self.stroke(layer));
+    }
+}
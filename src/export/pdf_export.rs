@@ -0,0 +1,366 @@
+//! PDF导出
+//!
+//! 不依赖外部服务和字体文件的矢量PDF写出。中文使用PDF阅读器内置的
+//! STSong-Light（Adobe-GB1，UniGB-UCS2-H编码），图元按图框排布在A3/A2/A1幅面上，
+//! 配电箱回路数超出一页时自动分页。
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use super::drawing::{Drawing, Layer, Primitive, TextAlign};
+use super::drawing_frame::{DrawingFrame, SheetSize};
+use super::error::{ExportError, Result};
+use super::system_diagram::{SystemDiagram, TrunkDiagram};
+use crate::editor::business::main_system_node::SystemDiagram as TrunkSystemDiagram;
+use crate::editor::business::BoxData;
+
+/// mm到PDF点(pt)的换算系数
+const MM_TO_PT: f64 = 72.0 / 25.4;
+
+/// 一页图纸
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sheet {
+    /// 图名（写入标题栏）
+    pub title: String,
+    /// 图纸内容（绘图单位，左上角为原点）
+    pub content: Drawing,
+    /// 绘图单位到mm的比例
+    pub scale: f64,
+}
+
+/// PDF写出工具
+pub struct PdfExporter;
+
+impl PdfExporter {
+    /// 配电箱系统图分页
+    ///
+    /// 系统图按宽度缩放到图框内容区，按内容区高度确定每页回路数
+    ///
+    /// # 参数
+    /// * `boxes` - 配电箱
+    /// * `frame` - 图框
+    ///
+    /// # 返回值
+    /// 返回各页图纸，按配电箱顺序排列
+    pub fn box_sheets(boxes: &[&BoxData], frame: &DrawingFrame) -> Vec<Sheet> {
+        let scale = frame.fit_scale(SystemDiagram::box_width());
+        let rows_per_page = SystemDiagram::rows_fitting(frame.content_height(scale));
+
+        boxes
+            .iter()
+            .flat_map(|box_data| {
+                let title = format!("{} 系统图", box_data.name);
+                SystemDiagram::layout_box_pages(box_data, rows_per_page)
+                    .into_iter()
+                    .map(move |content| Sheet { title: title.clone(), content, scale })
+            })
+            .collect()
+    }
+
+    /// 干线系统图分页，每张干线图一页，按宽度和高度缩放到图框内容区
+    pub fn trunk_sheets(diagrams: &[TrunkSystemDiagram], frame: &DrawingFrame) -> Vec<Sheet> {
+        diagrams
+            .iter()
+            .map(|diagram| {
+                let content = TrunkDiagram::layout(diagram);
+                let scale = content.bounds().map_or(1.0, |(min, max)| {
+                    let width_scale = frame.fit_scale(max.x - min.x);
+                    let (area_min, area_max) = frame.content_area();
+                    let height = max.y - min.y;
+                    if height > 0.0 { width_scale.min((area_max.y - area_min.y) / height) } else { width_scale }
+                });
+                Sheet { title: diagram.name.clone(), content, scale }
+            })
+            .collect()
+    }
+
+    /// 将各页图纸套入图框并写出为PDF
+    ///
+    /// # 参数
+    /// * `sheets` - 各页图纸
+    /// * `frame` - 图框（幅面及标题栏）
+    ///
+    /// # 返回值
+    /// 返回PDF文件内容
+    pub fn to_pdf(sheets: &[Sheet], frame: &DrawingFrame) -> Vec<u8> {
+        let pages = sheets.len();
+        let contents: Vec<String> = sheets
+            .iter()
+            .enumerate()
+            .map(|(i, sheet)| {
+                let composed = frame.compose(sheet.content.clone(), sheet.scale, &sheet.title, i + 1, pages);
+                Self::content_stream(&composed, frame.sheet)
+            })
+            .collect();
+        let title = sheets.first().map_or(String::new(), |s| Self::document_title(&frame.title_block.project_name, &s.title));
+        Self::write_document(&contents, frame.sheet, &title)
+    }
+
+    /// 文档标题：项目名称与首页图名以" - "分隔，未填写项目名称时只用图名
+    fn document_title(project_name: &str, sheet_title: &str) -> String {
+        if project_name.is_empty() {
+            sheet_title.to_string()
+        } else {
+            format!("{} - {}", project_name, sheet_title)
+        }
+    }
+
+    /// 将配电箱系统图导出为PDF文件
+    ///
+    /// # 参数
+    /// * `boxes` - 要导出的配电箱
+    /// * `frame` - 图框（幅面及标题栏）
+    /// * `path` - 输出文件路径
+    ///
+    /// # 返回值
+    /// * `Ok(())` - 导出成功
+    /// * `Err(ExportError)` - 无可导出的配电箱或写文件失败
+    pub fn export_boxes(boxes: &[&BoxData], frame: &DrawingFrame, path: &Path) -> Result<()> {
+        if boxes.is_empty() {
+            return Err(ExportError::data_error("配电箱", "没有可导出的配电箱"));
+        }
+        Self::write_file(&Self::to_pdf(&Self::box_sheets(boxes, frame), frame), path)
+    }
+
+    /// 将干线系统图导出为PDF文件
+    pub fn export_trunk(diagrams: &[TrunkSystemDiagram], frame: &DrawingFrame, path: &Path) -> Result<()> {
+        if diagrams.is_empty() {
+            return Err(ExportError::data_error("干线系统图", "没有可导出的干线系统图"));
+        }
+        Self::write_file(&Self::to_pdf(&Self::trunk_sheets(diagrams, frame), frame), path)
+    }
+
+    fn write_file(bytes: &[u8], path: &Path) -> Result<()> {
+        std::fs::write(path, bytes)
+            .map_err(|e| ExportError::file_operation("写入", path.display().to_string(), e.to_string()))
+    }
+
+    /// 图层线宽(mm)
+    fn line_width(layer: Layer) -> f64 {
+        match layer {
+            Layer::Busbar => 0.8,
+            Layer::Incoming | Layer::Frame => 0.5,
+            _ => 0.25,
+        }
+    }
+
+    /// 图层线宽与当前线宽不同时写出线宽设置
+    fn set_line_width(out: &mut String, current: &mut Option<f64>, layer: Layer) {
+        let width = Self::line_width(layer);
+        if *current != Some(width) {
+            let _ = writeln!(out, "{:.2} w", width * MM_TO_PT);
+            *current = Some(width);
+        }
+    }
+
+    /// 估算文字宽度：ASCII字符按半角、其余按全角
+    pub fn text_width(text: &str, height: f64) -> f64 {
+        text.chars().map(|c| if c.is_ascii() { 0.5 } else { 1.0 }).sum::<f64>() * height
+    }
+
+    /// 文字编码为UTF-16BE十六进制串（UniGB-UCS2-H），非BMP字符以"?"代替
+    pub fn encode_text(text: &str) -> String {
+        let mut hex = String::with_capacity(text.len() * 4);
+        for c in text.chars() {
+            let code = if (c as u32) <= 0xFFFF { c as u32 } else { '?' as u32 };
+            let _ = write!(hex, "{:04X}", code);
+        }
+        hex
+    }
+
+    /// 生成一页的内容流（mm、y向下转换为pt、y向上）
+    fn content_stream(drawing: &Drawing, sheet: SheetSize) -> String {
+        let (_, page_height) = sheet.dimensions();
+        let x = |v: f64| v * MM_TO_PT;
+        let y = |v: f64| (page_height - v) * MM_TO_PT;
+
+        let mut out = String::from("1 J 1 j\n");
+        let mut current_width = None;
+        for primitive in &drawing.primitives {
+            match primitive {
+                Primitive::Line { layer, start, end } => {
+                    Self::set_line_width(&mut out, &mut current_width, *layer);
+                    let _ = writeln!(out, "{:.2} {:.2} m {:.2} {:.2} l S", x(start.x), y(start.y), x(end.x), y(end.y));
+                }
+                Primitive::Text { position, height, align, content, .. } => {
+                    let width = Self::text_width(content, *height);
+                    let left = match align {
+                        TextAlign::Left => position.x,
+                        TextAlign::Center => position.x - width / 2.0,
+                        TextAlign::Right => position.x - width,
+                    };
+                    // 对齐点为文字垂直中心，基线下移0.35倍字高
+                    let baseline = position.y + height * 0.35;
+                    let _ = writeln!(
+                        out,
+                        "BT /F1 {:.2} Tf {:.2} {:.2} Td <{}> Tj ET",
+                        height * MM_TO_PT,
+                        x(left),
+                        y(baseline),
+                        Self::encode_text(content)
+                    );
+                }
+                Primitive::Circle { layer, center, radius } => {
+                    Self::set_line_width(&mut out, &mut current_width, *layer);
+                    // 四段三次贝塞尔曲线近似圆
+                    let (cx, cy, r) = (x(center.x), y(center.y), radius * MM_TO_PT);
+                    let k = r * 0.552_284_75;
+                    let _ = writeln!(
+                        out,
+                        "{:.2} {:.2} m {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c \
+                         {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c S",
+                        cx + r, cy,
+                        cx + r, cy + k, cx + k, cy + r, cx, cy + r,
+                        cx - k, cy + r, cx - r, cy + k, cx - r, cy,
+                        cx - r, cy - k, cx - k, cy - r, cx, cy - r,
+                        cx + k, cy - r, cx + r, cy - k, cx + r, cy
+                    );
+                }
+            }
+        }
+        out
+    }
+
+    /// 组装PDF文件：目录、页树、字体、各页及内容流、文档信息和交叉引用表
+    fn write_document(contents: &[String], sheet: SheetSize, title: &str) -> Vec<u8> {
+        let (width, height) = sheet.dimensions();
+        let page_count = contents.len();
+        // 对象编号：1目录 2页树 3字体 4CID字体 5字体描述 6文档信息，之后每页两个对象（页、内容流）
+        let first_page = 7;
+        let kids: Vec<String> = (0..page_count).map(|i| format!("{} 0 R", first_page + 2 * i)).collect();
+
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} /MediaBox [0 0 {:.2} {:.2}] >>",
+                kids.join(" "),
+                page_count,
+                width * MM_TO_PT,
+                height * MM_TO_PT
+            ),
+            "<< /Type /Font /Subtype /Type0 /BaseFont /STSong-Light-UniGB-UCS2-H /Encoding /UniGB-UCS2-H /DescendantFonts [4 0 R] >>"
+                .to_string(),
+            "<< /Type /Font /Subtype /CIDFontType0 /BaseFont /STSong-Light /CIDSystemInfo << /Registry (Adobe) /Ordering (GB1) /Supplement 2 >> /FontDescriptor 5 0 R /DW 1000 /W [1 95 500] >>"
+                .to_string(),
+            "<< /Type /FontDescriptor /FontName /STSong-Light /Flags 6 /FontBBox [-25 -254 1000 880] /ItalicAngle 0 /Ascent 880 /Descent -120 /CapHeight 880 /StemV 93 >>"
+                .to_string(),
+            format!("<< /Producer (PDSD) /Title <FEFF{}> >>", Self::encode_text(title)),
+        ];
+        for (i, content) in contents.iter().enumerate() {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                first_page + 2 * i + 1
+            ));
+            objects.push(format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content));
+        }
+
+        let mut pdf: Vec<u8> = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).as_bytes());
+        }
+
+        let xref = pdf.len();
+        let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(trailer, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(
+            trailer,
+            "trailer\n<< /Size {} /Root 1 0 R /Info 6 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        );
+        pdf.extend_from_slice(trailer.as_bytes());
+        pdf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::business::CircuitInfo;
+    use crate::editor::business::main_system_node::ComponentType;
+    use crate::export::drawing_frame::TitleBlock;
+
+    fn sample_box(circuits: u32) -> BoxData {
+        let mut box_data = BoxData::new("AL1".to_string(), 1);
+        for i in 1..=circuits {
+            let mut circuit = CircuitInfo::new(format!("c{}", i), format!("照明{}", i), 1.0, 5.0);
+            circuit.number = i;
+            box_data.circuits.push(circuit);
+        }
+        box_data
+    }
+
+    #[test]
+    fn test_encode_text() {
+        assert_eq!(PdfExporter::encode_text("A1系"), "004100317CFB");
+        assert_eq!(PdfExporter::text_width("AL1系统图", 4.0), 1.5 * 4.0 + 3.0 * 4.0);
+    }
+
+    #[test]
+    fn test_box_pagination() {
+        let frame = DrawingFrame::new(SheetSize::A3, TitleBlock::default());
+        let rows_per_page = SystemDiagram::rows_fitting(frame.content_height(frame.fit_scale(SystemDiagram::box_width())));
+        assert!(rows_per_page >= 10);
+
+        let small = sample_box(3);
+        let large = sample_box(rows_per_page as u32 * 2 + 1);
+        let sheets = PdfExporter::box_sheets(&[&small, &large], &frame);
+        assert_eq!(sheets.len(), 4);
+
+        // 每页内容缩放后不超出内容区
+        let (area_min, area_max) = frame.content_area();
+        for sheet in &sheets {
+            let (min, max) = sheet.content.bounds().unwrap();
+            assert!((max.x - min.x) * sheet.scale <= area_max.x - area_min.x + 1e-6);
+            assert!((max.y - min.y) * sheet.scale <= area_max.y - area_min.y + 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_pdf_structure() {
+        let frame = DrawingFrame::default();
+        let pdf = PdfExporter::to_pdf(&PdfExporter::box_sheets(&[&sample_box(2)], &frame), &frame);
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(text.contains("/Count 1"));
+        assert!(text.contains("/MediaBox [0 0 1190.55 841.89]"));
+        assert!(text.contains("/Encoding /UniGB-UCS2-H"));
+        assert!(text.trim_end().ends_with("%%EOF"));
+
+        // 交叉引用表中的偏移量指向对象起始位置（按字节计）
+        let tail = std::str::from_utf8(&pdf[pdf.len() - 40..]).unwrap();
+        let xref: usize = tail.split("startxref\n").nth(1).unwrap().lines().next().unwrap().parse().unwrap();
+        let table = std::str::from_utf8(&pdf[xref..]).unwrap();
+        assert!(table.starts_with("xref"));
+        let obj2: usize = table.lines().nth(4).unwrap()[..10].parse().unwrap();
+        assert!(pdf[obj2..].starts_with(b"2 0 obj"));
+    }
+
+    #[test]
+    fn test_document_title() {
+        assert_eq!(PdfExporter::document_title("某办公楼", "AL1 系统图"), "某办公楼 - AL1 系统图");
+        assert_eq!(PdfExporter::document_title("", "AL1 系统图"), "AL1 系统图");
+    }
+
+    #[test]
+    fn test_trunk_sheet() {
+        let mut diagram = TrunkSystemDiagram::new("配电干线图".to_string());
+        let busbar = diagram.add_component(ComponentType::Busbar, "主母线".to_string());
+        let box_id = diagram.add_component(ComponentType::DistributionBox, "AL1\n1F".to_string());
+        diagram.add_connection(busbar, box_id);
+        diagram.components[0].position = (100.0, 100.0);
+        diagram.components[1].position = (300.0, 300.0);
+
+        let frame = DrawingFrame::new(SheetSize::A2, TitleBlock::default());
+        let sheets = PdfExporter::trunk_sheets(std::slice::from_ref(&diagram), &frame);
+        assert_eq!(sheets.len(), 1);
+        assert_eq!(sheets[0].content.count_on(Layer::Equipment), 4 + 2 + 1);
+        assert_eq!(sheets[0].content.count_on(Layer::Circuit), 3);
+        assert!(PdfExporter::export_trunk(&[], &frame, Path::new("unused.pdf")).is_err());
+    }
+}
@@ -1 +1,572 @@
-///报告导出
\ No newline at end of file
+//! 报告导出
+//!
+//! 计算书生成：对每个回路和配电箱写出所用公式、代入数值、计算结果以及断路器、线缆的
+//! 选型依据，可输出为Markdown、HTML和PDF（PDF套用图框，按页排布文字）。
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use super::drawing::{breaker_text_lines, Drawing, Layer, Point, TextAlign};
+use super::drawing_frame::DrawingFrame;
+use super::error::{ExportError, Result};
+use super::load_schedule::BoxLoadSchedule;
+use super::pdf_export::{PdfExporter, Sheet};
+use super::system_diagram::SystemDiagram;
+use crate::core_lib::algorithm::protection_coordination::{ConductorInsulation, CoordinationInput, ProtectionCoordinator};
+use crate::core_lib::data_types::{BreakerType, CableInfo, CircuitNumber};
+use crate::editor::business::building::level_name;
+use crate::editor::business::{BoxData, CircuitInfo, LengthSource};
+
+/// 单相电压(V)
+const SINGLE_PHASE_VOLTAGE: f64 = 220.0;
+/// 三相线电压(V)
+const THREE_PHASE_VOLTAGE: f64 = 380.0;
+/// √3（与配电箱计算一致取1.732）
+const SQRT_3: f64 = 1.732;
+
+/// 计算依据
+const CALCULATION_BASIS: [&str; 3] = [
+    "GB 51348-2019《民用建筑电气设计标准》",
+    "GB 50054-2011《低压配电设计规范》",
+    "保护配合：Ib ≤ In ≤ Iz，I2 ≤ 1.45Iz（微型断路器I2=1.45In，塑壳断路器I2=1.30In）",
+];
+
+/// PDF正文字高(mm)
+const PDF_TEXT_HEIGHT: f64 = 3.5;
+/// PDF正文行距(mm)
+const PDF_LINE_SPACING: f64 = 6.0;
+
+/// 计算步骤
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalcStep {
+    pub item: String,         // 计算项目
+    pub formula: String,      // 公式
+    pub substitution: String, // 代入数值
+    pub result: String,       // 结果
+}
+
+impl CalcStep {
+    fn new(item: &str, formula: impl Into<String>, substitution: impl Into<String>, result: impl Into<String>) -> Self {
+        Self {
+            item: item.to_string(),
+            formula: formula.into(),
+            substitution: substitution.into(),
+            result: result.into(),
+        }
+    }
+}
+
+/// 计算小节（一个回路或一个配电箱的合计）
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalcSection {
+    pub title: String,
+    pub steps: Vec<CalcStep>,
+    /// 选型说明及校验记录
+    pub notes: Vec<String>,
+}
+
+/// 单个配电箱的计算
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxCalculation {
+    pub name: String,
+    pub floor: i32,
+    pub circuits: Vec<CalcSection>,
+    pub total: CalcSection,
+}
+
+/// 计算书
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalculationBook {
+    pub project_name: String,
+    /// 编制日期
+    pub date: String,
+    pub boxes: Vec<BoxCalculation>,
+}
+
+impl CalculationBook {
+    /// 生成计算书，配电箱按楼层、名称排列，回路按编号排列
+    ///
+    /// # 参数
+    /// * `project_name` - 工程名称
+    /// * `boxes` - 配电箱
+    pub fn from_boxes(project_name: &str, boxes: &[&BoxData]) -> Self {
+        let mut sorted: Vec<&BoxData> = boxes.to_vec();
+        sorted.sort_by(|a, b| a.floor.cmp(&b.floor).then_with(|| a.name.cmp(&b.name)));
+        Self {
+            project_name: project_name.to_string(),
+            date: chrono::Local::now().format("%Y.%m.%d").to_string(),
+            boxes: sorted.into_iter().map(Self::box_calculation).collect(),
+        }
+    }
+
+    fn box_calculation(box_data: &BoxData) -> BoxCalculation {
+        let mut circuits: Vec<&CircuitInfo> = box_data.circuits.iter().collect();
+        circuits.sort_by_key(|c| c.number);
+        BoxCalculation {
+            name: box_data.name.clone(),
+            floor: box_data.floor,
+            circuits: circuits.into_iter().map(Self::circuit_section).collect(),
+            total: Self::box_section(box_data),
+        }
+    }
+
+    /// 回路计算：Pjs、Ijs、断路器及线缆选型
+    pub fn circuit_section(circuit: &CircuitInfo) -> CalcSection {
+        let three_phase = circuit.three_phase;
+        let pjs = circuit.calculated_power();
+        let mut steps = vec![CalcStep::new(
+            "计算功率",
+            "Pjs = Pe·Kx",
+            format!("{:.2}×{:.2}", circuit.power, circuit.demand_coefficient),
+            format!("{:.2} kW", pjs),
+        )];
+        let mut notes = Vec::new();
+
+        let ijs = Self::current_step(&mut steps, pjs, circuit.power_factor, three_phase);
+        if circuit.current > 0.0 && (circuit.current - ijs).abs() > 0.01 * ijs.max(1.0) {
+            notes.push(format!("回路记录电流{:.1}A与计算值{:.1}A不一致，请重新计算", circuit.current, ijs));
+        }
+
+        match circuit.find_breaker() {
+            Some(breaker) => {
+                let rated_current = breaker.rated_current.to_f64();
+                let spec = breaker_text_lines(breaker, three_phase).join(" ");
+                let verdict = if rated_current >= ijs { "满足" } else { "不满足，应加大整定值" };
+                steps.push(CalcStep::new(
+                    "断路器",
+                    "In ≥ Ijs",
+                    format!("{:.0} ≥ {:.2}", rated_current, ijs),
+                    format!("{}，{}", spec, verdict),
+                ));
+                let tripping_factor = match breaker.breaker_type {
+                    Some(BreakerType::MCB) | Some(BreakerType::RCBO) => 1.45,
+                    _ => 1.30,
+                };
+                match &circuit.cable {
+                    Some(cable) => Self::cable_step(&mut steps, &mut notes, cable, ijs, rated_current, tripping_factor),
+                    None => notes.push("线缆未选型，未进行保护配合校验".to_string()),
+                }
+            }
+            None => notes.push("断路器未选型，未进行保护配合校验".to_string()),
+        }
+
+        CalcSection {
+            title: format!("{} {}", CircuitNumber::new(circuit.number).to_str(), circuit.purpose_or_name()),
+            steps,
+            notes,
+        }
+    }
+
+    /// 配电箱合计：ΣPe、Pjs、综合cosφ、Ijs、进线保护及三相不平衡度
+    pub fn box_section(box_data: &BoxData) -> CalcSection {
+        let schedule = BoxLoadSchedule::from_box(box_data);
+        let mut steps = Vec::new();
+        let mut notes = Vec::new();
+
+        let pe_terms: Vec<String> = schedule.rows.iter().map(|r| format!("{:.2}", r.pe)).collect();
+        steps.push(CalcStep::new(
+            "设备容量",
+            "ΣPe",
+            if pe_terms.is_empty() { "0".to_string() } else { pe_terms.join(" + ") },
+            format!("{:.2} kW", schedule.total_pe),
+        ));
+        let pjs_terms: Vec<String> = schedule.rows.iter().map(|r| format!("{:.2}×{:.2}", r.pe, r.kx)).collect();
+        steps.push(CalcStep::new(
+            "计算功率",
+            "Pjs = Σ(Pe·Kx)",
+            if pjs_terms.is_empty() { "0".to_string() } else { pjs_terms.join(" + ") },
+            format!("{:.2} kW", schedule.total_pjs),
+        ));
+
+        let qjs = if schedule.power_factor > 0.0 {
+            schedule.total_pjs * schedule.power_factor.acos().tan()
+        } else {
+            0.0
+        };
+        steps.push(CalcStep::new(
+            "综合功率因数",
+            "cosφ = Pjs / √(Pjs² + Qjs²)，Qjs = Σ(Pjs·tanφ)",
+            format!("{:.2} / √({:.2}² + {:.2}²)", schedule.total_pjs, schedule.total_pjs, qjs),
+            format!("{:.2}", schedule.power_factor),
+        ));
+
+        let ijs = Self::current_step(&mut steps, schedule.total_pjs, schedule.power_factor, true);
+        if box_data.incoming_current > 0.0 {
+            let verdict = if box_data.incoming_current >= ijs { "满足" } else { "不满足，应加大整定值" };
+            steps.push(CalcStep::new(
+                "进线断路器",
+                "In ≥ Ijs",
+                format!("{:.0} ≥ {:.2}", box_data.incoming_current, ijs),
+                format!("{:.0}A，{}", box_data.incoming_current, verdict),
+            ));
+            let tripping_factor = if box_data.incoming_current <= 63.0 { 1.45 } else { 1.30 };
+            if let Some(cable) = &box_data.incoming_cable {
+                Self::cable_step(&mut steps, &mut notes, cable, ijs, box_data.incoming_current, tripping_factor);
+            }
+        }
+        Self::feeder_length_step(&mut steps, box_data);
+
+        let [l1, l2, l3] = schedule.phase_loads;
+        let max = l1.max(l2).max(l3);
+        let min = l1.min(l2).min(l3);
+        steps.push(CalcStep::new(
+            "三相不平衡度",
+            "δ = (Pmax − Pmin) / Pavg",
+            format!("({:.2} − {:.2}) / {:.2}", max, min, (l1 + l2 + l3) / 3.0),
+            format!("{:.1}%", schedule.unbalance),
+        ));
+
+        notes.extend(box_data.coordination_log.iter().cloned());
+        CalcSection { title: "配电箱合计".to_string(), steps, notes }
+    }
+
+    /// 进线电缆长度步骤，注明长度为手动输入还是估算，估算值列出各段长度供校核
+    fn feeder_length_step(steps: &mut Vec<CalcStep>, box_data: &BoxData) {
+        let Some((length, source)) = box_data.incoming_length() else { return };
+        let (formula, substitution) = match (source, &box_data.feeder_estimate) {
+            (LengthSource::Estimated, Some(estimate)) => (
+                "L = Lh + Lv + Ld",
+                format!("{:.1} + {:.1} + {:.1}", estimate.horizontal, estimate.riser, estimate.allowance),
+            ),
+            _ => ("L = 手动输入", format!("{:.1}", length)),
+        };
+        steps.push(CalcStep::new("进线电缆长度", formula, substitution, format!("{:.1}m（{}）", length, source.to_str())));
+    }
+
+    /// 计算电流步骤，返回Ijs(A)
+    fn current_step(steps: &mut Vec<CalcStep>, pjs: f64, power_factor: f64, three_phase: bool) -> f64 {
+        let (formula, voltage, factor, factor_text) = if three_phase {
+            ("Ijs = Pjs / (√3·U·cosφ)", THREE_PHASE_VOLTAGE, SQRT_3, "1.732×")
+        } else {
+            ("Ijs = Pjs / (U·cosφ)", SINGLE_PHASE_VOLTAGE, 1.0, "")
+        };
+        let ijs = if power_factor > 0.0 { pjs * 1000.0 / (factor * voltage * power_factor) } else { 0.0 };
+        steps.push(CalcStep::new(
+            "计算电流",
+            formula,
+            format!("{:.2}×1000 / ({}{:.0}×{:.2})", pjs, factor_text, voltage, power_factor),
+            format!("{:.2} A", ijs),
+        ));
+        ijs
+    }
+
+    /// 线缆选型步骤：查载流量并做保护配合校验
+    fn cable_step(
+        steps: &mut Vec<CalcStep>,
+        notes: &mut Vec<String>,
+        cable: &CableInfo,
+        design_current: f64,
+        rated_current: f64,
+        tripping_factor: f64,
+    ) {
+        let annotation = SystemDiagram::cable_annotation(cable);
+        let Ok(section) = cable.size.trim_end_matches("mm²").parse::<f64>() else {
+            notes.push(format!("无法识别线缆规格{}，未进行保护配合校验", cable.size));
+            return;
+        };
+        let insulation = ConductorInsulation::from_model(&cable.model);
+        let iz = ProtectionCoordinator::ampacity(section, insulation).unwrap_or(0.0);
+
+        let input = CoordinationInput {
+            design_current: design_current.max(f64::EPSILON),
+            rated_current,
+            tripping_factor,
+            section,
+            insulation,
+            derating_factor: 1.0,
+            fault: None,
+        };
+        let verdict = match ProtectionCoordinator::coordinate(&input) {
+            Ok(result) => {
+                notes.extend(result.log);
+                if (result.section - section).abs() < 1e-6 {
+                    "满足".to_string()
+                } else {
+                    format!("不满足，应加大至{}mm²", result.section)
+                }
+            }
+            Err(e) => {
+                notes.push(e.to_string());
+                "不满足".to_string()
+            }
+        };
+        steps.push(CalcStep::new(
+            "线缆",
+            "In ≤ Iz，I2 = k·In ≤ 1.45Iz",
+            format!(
+                "{:.0} ≤ {:.0}，{:.2}×{:.0} = {:.1} ≤ 1.45×{:.0} = {:.1}",
+                rated_current,
+                iz,
+                tripping_factor,
+                rated_current,
+                tripping_factor * rated_current,
+                iz,
+                1.45 * iz
+            ),
+            format!("{}（Iz={:.0}A），{}", annotation, iz, verdict),
+        ));
+    }
+
+    /// 输出为Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        let _ = writeln!(md, "# {} 计算书\n", self.project_name);
+        let _ = writeln!(md, "编制日期：{}\n", self.date);
+        md.push_str("## 1 计算依据\n\n");
+        for basis in CALCULATION_BASIS {
+            let _ = writeln!(md, "- {}", basis);
+        }
+        md.push('\n');
+
+        for (i, box_calc) in self.boxes.iter().enumerate() {
+            let _ = writeln!(md, "## {} {}（{}层）\n", i + 2, box_calc.name, level_name(box_calc.floor));
+            for (j, section) in box_calc.circuits.iter().chain([&box_calc.total]).enumerate() {
+                let _ = writeln!(md, "### {}.{} {}\n", i + 2, j + 1, section.title);
+                md.push_str("| 项目 | 公式 | 代入数值 | 结果 |\n|---|---|---|---|\n");
+                for step in &section.steps {
+                    let _ = writeln!(
+                        md,
+                        "| {} | {} | {} | {} |",
+                        Self::markdown_cell(&step.item),
+                        Self::markdown_cell(&step.formula),
+                        Self::markdown_cell(&step.substitution),
+                        Self::markdown_cell(&step.result)
+                    );
+                }
+                md.push('\n');
+                if !section.notes.is_empty() {
+                    md.push_str("选型说明：\n\n");
+                    for note in &section.notes {
+                        let _ = writeln!(md, "- {}", note);
+                    }
+                    md.push('\n');
+                }
+            }
+        }
+        md
+    }
+
+    /// 输出为HTML
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html lang=\"zh-CN\">\n<head>\n<meta charset=\"utf-8\">\n");
+        let _ = writeln!(html, "<title>{} 计算书</title>", Self::escape_html(&self.project_name));
+        html.push_str(concat!(
+            "<style>\n",
+            "body { font-family: SimSun, 'Songti SC', serif; margin: 2em; }\n",
+            "table { border-collapse: collapse; margin: 0.5em 0 1em; }\n",
+            "th, td { border: 1px solid #444; padding: 4px 8px; }\n",
+            "th { background: #eee; }\n",
+            ".note { color: #555; }\n",
+            "</style>\n</head>\n<body>\n"
+        ));
+        let _ = writeln!(html, "<h1>{} 计算书</h1>", Self::escape_html(&self.project_name));
+        let _ = writeln!(html, "<p>编制日期：{}</p>", Self::escape_html(&self.date));
+        html.push_str("<h2>1 计算依据</h2>\n<ul>\n");
+        for basis in CALCULATION_BASIS {
+            let _ = writeln!(html, "<li>{}</li>", Self::escape_html(basis));
+        }
+        html.push_str("</ul>\n");
+
+        for (i, box_calc) in self.boxes.iter().enumerate() {
+            let _ = writeln!(html, "<h2>{} {}（{}层）</h2>", i + 2, Self::escape_html(&box_calc.name), level_name(box_calc.floor));
+            for (j, section) in box_calc.circuits.iter().chain([&box_calc.total]).enumerate() {
+                let _ = writeln!(html, "<h3>{}.{} {}</h3>", i + 2, j + 1, Self::escape_html(&section.title));
+                html.push_str("<table>\n<tr><th>项目</th><th>公式</th><th>代入数值</th><th>结果</th></tr>\n");
+                for step in &section.steps {
+                    let _ = writeln!(
+                        html,
+                        "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                        Self::escape_html(&step.item),
+                        Self::escape_html(&step.formula),
+                        Self::escape_html(&step.substitution),
+                        Self::escape_html(&step.result)
+                    );
+                }
+                html.push_str("</table>\n");
+                if !section.notes.is_empty() {
+                    html.push_str("<ul class=\"note\">\n");
+                    for note in &section.notes {
+                        let _ = writeln!(html, "<li>{}</li>", Self::escape_html(note));
+                    }
+                    html.push_str("</ul>\n");
+                }
+            }
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    /// 计算书文字行（PDF排版使用）
+    fn text_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("{} 计算书", self.project_name), format!("编制日期：{}", self.date), String::new()];
+        lines.push("1 计算依据".to_string());
+        lines.extend(CALCULATION_BASIS.iter().map(|b| format!("  · {}", b)));
+
+        for (i, box_calc) in self.boxes.iter().enumerate() {
+            lines.push(String::new());
+            lines.push(format!("{} {}（{}层）", i + 2, box_calc.name, level_name(box_calc.floor)));
+            for (j, section) in box_calc.circuits.iter().chain([&box_calc.total]).enumerate() {
+                lines.push(format!("{}.{} {}", i + 2, j + 1, section.title));
+                for step in &section.steps {
+                    lines.push(format!("  {}：{} = {} = {}", step.item, step.formula, step.substitution, step.result));
+                }
+                lines.extend(section.notes.iter().map(|n| format!("  · {}", n)));
+            }
+        }
+        lines
+    }
+
+    /// 计算书PDF分页：文字行按内容区宽度折行、按高度分页
+    pub fn pdf_sheets(&self, frame: &DrawingFrame) -> Vec<Sheet> {
+        let (area_min, area_max) = frame.content_area();
+        let width = area_max.x - area_min.x;
+        let lines_per_page = (((area_max.y - area_min.y) / PDF_LINE_SPACING).floor() as usize).max(1);
+
+        let mut wrapped = Vec::new();
+        for line in self.text_lines() {
+            let mut current = String::new();
+            for c in line.chars() {
+                current.push(c);
+                if PdfExporter::text_width(&current, PDF_TEXT_HEIGHT) > width {
+                    current.pop();
+                    wrapped.push(std::mem::take(&mut current));
+                    current.push_str("    ");
+                    current.push(c);
+                }
+            }
+            wrapped.push(current);
+        }
+
+        let title = format!("{} 计算书", self.project_name);
+        wrapped
+            .chunks(lines_per_page)
+            .map(|page| {
+                let mut content = Drawing::new();
+                for (i, line) in page.iter().enumerate() {
+                    let y = (i as f64 + 0.5) * PDF_LINE_SPACING;
+                    content.text(Layer::Title, Point::new(0.0, y), PDF_TEXT_HEIGHT, TextAlign::Left, line.clone());
+                }
+                Sheet { title: title.clone(), content, scale: 1.0 }
+            })
+            .collect()
+    }
+
+    /// 导出为Markdown文件
+    pub fn export_markdown(&self, path: &Path) -> Result<()> {
+        self.ensure_not_empty()?;
+        Self::write_file(self.to_markdown().as_bytes(), path)
+    }
+
+    /// 导出为HTML文件
+    pub fn export_html(&self, path: &Path) -> Result<()> {
+        self.ensure_not_empty()?;
+        Self::write_file(self.to_html().as_bytes(), path)
+    }
+
+    /// 导出为PDF文件
+    ///
+    /// # 参数
+    /// * `frame` - 图框（幅面及标题栏）
+    /// * `path` - 输出文件路径
+    ///
+    /// # 返回值
+    /// * `Ok(())` - 导出成功
+    /// * `Err(ExportError)` - 无配电箱或写文件失败
+    pub fn export_pdf(&self, frame: &DrawingFrame, path: &Path) -> Result<()> {
+        self.ensure_not_empty()?;
+        Self::write_file(&PdfExporter::to_pdf(&self.pdf_sheets(frame), frame), path)
+    }
+
+    fn ensure_not_empty(&self) -> Result<()> {
+        if self.boxes.is_empty() {
+            return Err(ExportError::data_error("计算书", "没有可计算的配电箱"));
+        }
+        Ok(())
+    }
+
+    fn write_file(bytes: &[u8], path: &Path) -> Result<()> {
+        std::fs::write(path, bytes)
+            .map_err(|e| ExportError::file_operation("写入", path.display().to_string(), e.to_string()))
+    }
+
+    fn markdown_cell(text: &str) -> String {
+        text.replace('|', "\\|")
+    }
+
+    fn escape_html(text: &str) -> String {
+        text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_lib::data_types::{Breaker, ElectricComponent, FrameCurrent, SettingValue};
+    use crate::editor::business::CableLengthEstimate;
+
+    fn lighting_circuit() -> CircuitInfo {
+        let mut circuit = CircuitInfo::new("c1".to_string(), "照明".to_string(), 2.0, 8.56);
+        circuit.number = 1;
+        circuit.phase = Some('1');
+        circuit.demand_coefficient = 0.8;
+        circuit.power_factor = 0.85;
+        circuit.components.push(ElectricComponent::Breaker(Breaker {
+            breaker_type: Some(BreakerType::MCB),
+            frame_current: FrameCurrent::A63,
+            rated_current: SettingValue::A16,
+            pole: None,
+            curve: None,
+            ..Breaker::default()
+        }));
+        circuit.cable = Some(CableInfo::default());
+        circuit
+    }
+
+    #[test]
+    fn test_circuit_section_formulas() {
+        let section = CalculationBook::circuit_section(&lighting_circuit());
+        assert_eq!(section.title, "WL1 照明");
+        assert_eq!(section.steps[0].result, "1.60 kW");
+        // 单相：1.6kW×1000/(220×0.85) = 8.56A
+        assert_eq!(section.steps[1].formula, "Ijs = Pjs / (U·cosφ)");
+        assert_eq!(section.steps[1].substitution, "1.60×1000 / (220×0.85)");
+        assert_eq!(section.steps[1].result, "8.56 A");
+        assert!(section.steps[2].result.starts_with("MCB63 1P+N-C 16A，满足"));
+        // 16A配2.5mm²(21A)：16 ≤ 21，1.45×16=23.2 ≤ 30.45
+        assert!(section.steps[3].result.starts_with("BV-3x2.5 SC20（Iz=21A），满足"));
+        assert!(section.notes.iter().any(|n| n.contains("满足要求")));
+    }
+
+    #[test]
+    fn test_outputs() {
+        let mut box_data = BoxData::new("AL1".to_string(), 1);
+        box_data.circuits.push(lighting_circuit());
+        box_data.phase_loads = [1.6, 0.0, 0.0];
+        box_data.feeder_estimate = Some(CableLengthEstimate { horizontal: 20.0, riser: 8.0, allowance: 3.0 });
+        let book = CalculationBook::from_boxes("某办公楼", &[&box_data]);
+        assert_eq!(book.boxes[0].total.steps[0].result, "2.00 kW");
+
+        let md = book.to_markdown();
+        assert!(md.starts_with("# 某办公楼 计算书"));
+        assert!(md.contains("### 2.1 WL1 照明"));
+        assert!(md.contains("### 2.2 配电箱合计"));
+        assert!(md.contains("| 计算电流 | Ijs = Pjs / (U·cosφ) | 1.60×1000 / (220×0.85) | 8.56 A |"));
+        assert!(md.contains("| 进线电缆长度 | L = Lh + Lv + Ld | 20.0 + 8.0 + 3.0 | 31.0m（估算） |"));
+
+        let html = book.to_html();
+        assert!(html.contains("<h3>2.1 WL1 照明</h3>"));
+        assert!(html.contains("<td>Ijs = Pjs / (√3·U·cosφ)</td>"));
+
+        let sheets = book.pdf_sheets(&DrawingFrame::default());
+        assert!(!sheets.is_empty());
+        let (area_min, area_max) = DrawingFrame::default().content_area();
+        for sheet in &sheets {
+            let (_, max) = sheet.content.bounds().unwrap();
+            assert!(max.y <= area_max.y - area_min.y);
+        }
+
+        let empty = CalculationBook::from_boxes("某办公楼", &[]);
+        assert!(empty.export_markdown(Path::new("unused.md")).is_err());
+    }
+}
@@ -0,0 +1,447 @@
+//! SVG导出
+//!
+//! 通过[`SvgPainter`]将图元写出为SVG，用于浏览器查看和嵌入报告。支持导出
+//! 干线图节点生成的各张系统图，以及节点编辑器画布（节点框、端口和连线）。
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use egui_node_graph::{Graph, NodeId};
+use slotmap::SecondaryMap;
+
+use super::drawing::{connection_curve, Drawing, Layer, NodeSymbol, Point, TextAlign, NODE_SYMBOL_HEIGHT};
+use super::error::{ExportError, Result};
+use super::painter::DiagramPainter;
+use super::system_diagram::TrunkDiagram;
+use crate::editor::business::main_system_node::SystemDiagram as TrunkSystemDiagram;
+use crate::editor::business::PowerGraphNode;
+
+/// SVG配色
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SvgTheme {
+    /// 白底黑线（与出图一致）
+    #[default]
+    Light,
+    /// 黑底图层色（与画布一致）
+    Dark,
+}
+
+/// SVG绘制器：逐个图元写出SVG元素，元素的class为图层名
+pub struct SvgPainter {
+    theme: SvgTheme,
+    body: String,
+}
+
+impl SvgPainter {
+    pub fn new(theme: SvgTheme) -> Self {
+        Self { theme, body: String::new() }
+    }
+
+    fn color(&self, layer: Layer) -> String {
+        match self.theme {
+            SvgTheme::Light => "#000000".to_string(),
+            SvgTheme::Dark => {
+                let (r, g, b) = layer.rgb();
+                format!("#{:02X}{:02X}{:02X}", r, g, b)
+            }
+        }
+    }
+
+    /// 生成完整的SVG文档，视口为图元外包范围外加留白
+    pub fn finish(self, bounds: Option<(Point, Point)>, padding: f64) -> String {
+        let (min, max) = bounds.unwrap_or((Point::new(0.0, 0.0), Point::new(0.0, 0.0)));
+        let (x, y) = (min.x - padding, min.y - padding);
+        let (width, height) = (max.x - min.x + 2.0 * padding, max.y - min.y + 2.0 * padding);
+        let background = match self.theme {
+            SvgTheme::Light => "#FFFFFF",
+            SvgTheme::Dark => "#000000",
+        };
+
+        let mut svg = String::new();
+        let _ = writeln!(svg, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{:.2} {:.2} {:.2} {:.2}" width="{:.0}" height="{:.0}" font-family="SimSun, 'Songti SC', serif">"#,
+            x, y, width, height, width, height
+        );
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}"/>"#,
+            x, y, width, height, background
+        );
+        svg.push_str(&self.body);
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// XML文本转义
+    pub fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+}
+
+impl DiagramPainter for SvgPainter {
+    fn draw_line(&mut self, layer: Layer, start: Point, end: Point) {
+        let color = self.color(layer);
+        let _ = writeln!(
+            self.body,
+            r#"<line class="{}" x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="{}" stroke-width="{}" stroke-linecap="round"/>"#,
+            layer.name(),
+            start.x,
+            start.y,
+            end.x,
+            end.y,
+            color,
+            layer.stroke_width()
+        );
+    }
+
+    fn draw_text(&mut self, layer: Layer, position: Point, height: f64, align: TextAlign, content: &str) {
+        let anchor = match align {
+            TextAlign::Left => "start",
+            TextAlign::Center => "middle",
+            TextAlign::Right => "end",
+        };
+        let color = self.color(layer);
+        let _ = writeln!(
+            self.body,
+            r#"<text class="{}" x="{:.2}" y="{:.2}" font-size="{:.2}" text-anchor="{}" dominant-baseline="central" fill="{}">{}</text>"#,
+            layer.name(),
+            position.x,
+            position.y,
+            height,
+            anchor,
+            color,
+            Self::escape(content)
+        );
+    }
+
+    fn draw_circle(&mut self, layer: Layer, center: Point, radius: f64) {
+        let color = self.color(layer);
+        let _ = writeln!(
+            self.body,
+            r#"<circle class="{}" cx="{:.2}" cy="{:.2}" r="{:.2}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+            layer.name(),
+            center.x,
+            center.y,
+            radius,
+            color,
+            layer.stroke_width()
+        );
+    }
+}
+
+/// 画布节点宽度（与节点编辑器最大节点宽度一致）
+const CANVAS_NODE_WIDTH: f64 = 200.0;
+/// 画布节点标题栏高度
+const CANVAS_HEADER_HEIGHT: f64 = 28.0;
+/// 画布节点端口行高
+const CANVAS_PORT_ROW: f64 = 22.0;
+/// 画布节点内边距
+const CANVAS_NODE_PADDING: f64 = 8.0;
+
+/// 画布节点快照
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanvasNode {
+    pub label: String,
+    /// 节点左上角位置（画布坐标）
+    pub position: Point,
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+    /// 节点底部图形（与画布节点一致）
+    pub symbol: Option<NodeSymbol>,
+}
+
+impl CanvasNode {
+    fn port_rows(&self) -> usize {
+        self.inputs.len().max(self.outputs.len()).max(1)
+    }
+
+    /// 节点高度
+    pub fn height(&self) -> f64 {
+        let symbol = if self.symbol.is_some() { NODE_SYMBOL_HEIGHT } else { 0.0 };
+        CANVAS_HEADER_HEIGHT + self.port_rows() as f64 * CANVAS_PORT_ROW + symbol
+    }
+
+    /// 输入端口位置（节点左边）
+    pub fn input_port(&self, index: usize) -> Point {
+        self.position.offset(0.0, CANVAS_HEADER_HEIGHT + (index as f64 + 0.5) * CANVAS_PORT_ROW)
+    }
+
+    /// 输出端口位置（节点右边）
+    pub fn output_port(&self, index: usize) -> Point {
+        self.position
+            .offset(CANVAS_NODE_WIDTH, CANVAS_HEADER_HEIGHT + (index as f64 + 0.5) * CANVAS_PORT_ROW)
+    }
+
+    /// 绘制节点：节点框、标题、端口及底部图形
+    pub fn paint<P: DiagramPainter + ?Sized>(&self, painter: &mut P) {
+        let (min, max) = (self.position, self.position.offset(CANVAS_NODE_WIDTH, self.height()));
+        painter.draw_line(Layer::Equipment, min, Point::new(max.x, min.y));
+        painter.draw_line(Layer::Equipment, Point::new(max.x, min.y), max);
+        painter.draw_line(Layer::Equipment, max, Point::new(min.x, max.y));
+        painter.draw_line(Layer::Equipment, Point::new(min.x, max.y), min);
+        painter.draw_line(
+            Layer::Equipment,
+            min.offset(0.0, CANVAS_HEADER_HEIGHT),
+            min.offset(CANVAS_NODE_WIDTH, CANVAS_HEADER_HEIGHT),
+        );
+        painter.draw_text(
+            Layer::Title,
+            min.offset(CANVAS_NODE_PADDING, CANVAS_HEADER_HEIGHT / 2.0),
+            14.0,
+            TextAlign::Left,
+            &self.label,
+        );
+        for (i, name) in self.inputs.iter().enumerate() {
+            let port = self.input_port(i);
+            painter.draw_circle(Layer::Circuit, port, 4.0);
+            painter.draw_text(Layer::CircuitText, port.offset(CANVAS_NODE_PADDING, 0.0), 12.0, TextAlign::Left, name);
+        }
+        for (i, name) in self.outputs.iter().enumerate() {
+            let port = self.output_port(i);
+            painter.draw_circle(Layer::Circuit, port, 4.0);
+            painter.draw_text(Layer::CircuitText, port.offset(-CANVAS_NODE_PADDING, 0.0), 12.0, TextAlign::Right, name);
+        }
+        if let Some(symbol) = &self.symbol {
+            let rows = self.port_rows() as f64 * CANVAS_PORT_ROW;
+            let origin = min.offset(CANVAS_NODE_PADDING, CANVAS_HEADER_HEIGHT + rows + NODE_SYMBOL_HEIGHT / 2.0);
+            symbol.paint(painter, origin, CANVAS_NODE_WIDTH - 2.0 * CANVAS_NODE_PADDING);
+        }
+    }
+}
+
+/// 画布连线快照（节点序号及端口序号）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CanvasConnection {
+    pub from_node: usize,
+    pub output: usize,
+    pub to_node: usize,
+    pub input: usize,
+}
+
+/// 节点编辑器画布快照
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CanvasSnapshot {
+    pub nodes: Vec<CanvasNode>,
+    pub connections: Vec<CanvasConnection>,
+}
+
+impl CanvasSnapshot {
+    /// 从节点图及节点位置生成快照，未记录位置的节点按原点放置
+    ///
+    /// # 参数
+    /// * `graph` - 节点图
+    /// * `positions` - 节点位置（编辑器状态中的node_positions）
+    pub fn from_graph<DataType, ValueType>(
+        graph: &Graph<PowerGraphNode, DataType, ValueType>,
+        positions: &SecondaryMap<NodeId, egui::Pos2>,
+    ) -> Self {
+        let ids: Vec<NodeId> = graph.iter_nodes().collect();
+        let nodes = ids
+            .iter()
+            .map(|&id| {
+                let node = &graph.nodes[id];
+                let position = positions.get(id).map_or(Point::new(0.0, 0.0), |p| Point::new(p.x as f64, p.y as f64));
+                CanvasNode {
+                    label: node.label.clone(),
+                    position,
+                    inputs: node.inputs.iter().map(|(name, _)| name.clone()).collect(),
+                    outputs: node.outputs.iter().map(|(name, _)| name.clone()).collect(),
+                    symbol: node.user_data.symbol(),
+                }
+            })
+            .collect();
+
+        let connections = graph
+            .iter_connections()
+            .filter_map(|(input, output)| {
+                let to = graph.inputs.get(input)?.node;
+                let from = graph.outputs.get(output)?.node;
+                Some(CanvasConnection {
+                    from_node: ids.iter().position(|&id| id == from)?,
+                    output: graph.nodes[from].outputs.iter().position(|(_, id)| *id == output)?,
+                    to_node: ids.iter().position(|&id| id == to)?,
+                    input: graph.nodes[to].inputs.iter().position(|(_, id)| *id == input)?,
+                })
+            })
+            .collect();
+
+        Self { nodes, connections }
+    }
+
+    /// 绘制画布：各节点及节点间连线，节点图形和连线与画布共用同一套几何
+    pub fn paint<P: DiagramPainter + ?Sized>(&self, painter: &mut P) {
+        for node in &self.nodes {
+            node.paint(painter);
+        }
+        for connection in &self.connections {
+            let (Some(from), Some(to)) = (self.nodes.get(connection.from_node), self.nodes.get(connection.to_node)) else {
+                continue;
+            };
+            connection_curve(painter, Layer::Circuit, from.output_port(connection.output), to.input_port(connection.input));
+        }
+    }
+
+    /// 排布画布图元
+    pub fn layout(&self) -> Drawing {
+        let mut drawing = Drawing::new();
+        self.paint(&mut drawing);
+        drawing
+    }
+}
+
+/// SVG写出工具
+pub struct SvgExporter;
+
+impl SvgExporter {
+    /// 将图纸写出为SVG文本
+    pub fn to_svg(drawing: &Drawing, theme: SvgTheme) -> String {
+        let mut painter = SvgPainter::new(theme);
+        drawing.paint(&mut painter);
+        painter.finish(drawing.bounds(), 40.0)
+    }
+
+    /// 干线图节点生成的单张系统图的SVG文本
+    pub fn diagram_svg(diagram: &TrunkSystemDiagram, theme: SvgTheme) -> String {
+        Self::to_svg(&TrunkDiagram::layout(diagram), theme)
+    }
+
+    /// 将干线图节点生成的各张系统图分别导出为SVG文件
+    ///
+    /// 文件名为"序号-系统图名称.svg"，名称中不能用于文件名的字符替换为"_"
+    ///
+    /// # 参数
+    /// * `diagrams` - 系统图（MainSystemNodeUI::auto_map_distribution_boxes的结果）
+    /// * `dir` - 输出目录
+    /// * `theme` - 配色
+    ///
+    /// # 返回值
+    /// * `Ok(Vec<PathBuf>)` - 写出的文件路径
+    /// * `Err(ExportError)` - 无可导出的系统图或写文件失败
+    pub fn export_diagrams(diagrams: &[TrunkSystemDiagram], dir: &Path, theme: SvgTheme) -> Result<Vec<PathBuf>> {
+        if diagrams.is_empty() {
+            return Err(ExportError::data_error("系统图", "没有可导出的系统图"));
+        }
+        diagrams
+            .iter()
+            .enumerate()
+            .map(|(i, diagram)| {
+                let path = dir.join(format!("{:02}-{}.svg", i + 1, Self::file_stem(&diagram.name)));
+                Self::write_file(&Self::diagram_svg(diagram, theme), &path)?;
+                Ok(path)
+            })
+            .collect()
+    }
+
+    /// 将节点编辑器画布导出为SVG文件
+    pub fn export_canvas(snapshot: &CanvasSnapshot, path: &Path, theme: SvgTheme) -> Result<()> {
+        if snapshot.nodes.is_empty() {
+            return Err(ExportError::data_error("画布", "画布中没有节点"));
+        }
+        Self::write_file(&Self::to_svg(&snapshot.layout(), theme), path)
+    }
+
+    fn file_stem(name: &str) -> String {
+        name.chars()
+            .map(|c| if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control() { '_' } else { c })
+            .collect()
+    }
+
+    fn write_file(svg: &str, path: &Path) -> Result<()> {
+        std::fs::write(path, svg).map_err(|e| ExportError::file_operation("写入", path.display().to_string(), e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::business::main_system_node::ComponentType;
+    use crate::export::drawing::{breaker_symbol, CONNECTION_CURVE_SEGMENTS};
+
+    #[test]
+    fn test_painter_matches_drawing() {
+        // 同一几何经SvgPainter直接绘制与经Drawing回放结果一致
+        let mut direct = SvgPainter::new(SvgTheme::Dark);
+        breaker_symbol(&mut direct, Point::new(0.0, 0.0), 100.0);
+
+        let mut drawing = Drawing::new();
+        breaker_symbol(&mut drawing, Point::new(0.0, 0.0), 100.0);
+        let mut replayed = SvgPainter::new(SvgTheme::Dark);
+        drawing.paint(&mut replayed);
+
+        assert_eq!(direct.body, replayed.body);
+        assert_eq!(direct.body.matches("<line").count(), 6);
+        assert!(direct.body.contains(r##"stroke="#00FF00""##));
+    }
+
+    #[test]
+    fn test_diagram_svg() {
+        let mut diagram = TrunkSystemDiagram::new("配电干线图".to_string());
+        let busbar = diagram.add_component(ComponentType::Busbar, "主母线".to_string());
+        let box_id = diagram.add_component(ComponentType::DistributionBox, "AL1<1F>".to_string());
+        diagram.add_connection(busbar, box_id);
+        diagram.components[1].position = (200.0, 0.0);
+
+        let svg = SvgExporter::diagram_svg(&diagram, SvgTheme::Light);
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains("viewBox="));
+        assert!(svg.contains("AL1&lt;1F&gt;"));
+        assert!(svg.contains(r#"class="PDSD-BUSBAR""#));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_canvas_snapshot_layout() {
+        let snapshot = CanvasSnapshot {
+            nodes: vec![
+                CanvasNode {
+                    label: "回路".to_string(),
+                    position: Point::new(0.0, 0.0),
+                    inputs: vec![],
+                    outputs: vec!["输出".to_string()],
+                    symbol: Some(NodeSymbol::Circuit),
+                },
+                CanvasNode {
+                    label: "配电箱".to_string(),
+                    position: Point::new(400.0, 100.0),
+                    inputs: vec!["回路1".to_string(), "回路2".to_string()],
+                    outputs: vec![],
+                    symbol: Some(NodeSymbol::DistributionBox(Vec::new())),
+                },
+            ],
+            connections: vec![CanvasConnection { from_node: 0, output: 0, to_node: 1, input: 1 }],
+        };
+        let drawing = snapshot.layout();
+        // 端口3个、回路图形粗线2段、连线
+        assert_eq!(drawing.count_on(Layer::Circuit), 3 + 2 + CONNECTION_CURVE_SEGMENTS);
+        assert_eq!(drawing.count_on(Layer::Breaker), 6);
+
+        // 连线首尾落在端口上（连线在节点之后绘制）
+        let lines: Vec<(Point, Point)> = drawing
+            .primitives
+            .iter()
+            .filter_map(|p| match p {
+                crate::export::drawing::Primitive::Line { layer: Layer::Circuit, start, end } => Some((*start, *end)),
+                _ => None,
+            })
+            .collect();
+        let curve = &lines[lines.len() - CONNECTION_CURVE_SEGMENTS..];
+        assert_eq!(curve.first().unwrap().0, snapshot.nodes[0].output_port(0));
+        let last = curve.last().unwrap().1;
+        let port = snapshot.nodes[1].input_port(1);
+        assert!((last.x - port.x).abs() < 1e-9 && (last.y - port.y).abs() < 1e-9);
+
+        // 经SvgPainter直接绘制与经Drawing回放结果一致
+        let mut direct = SvgPainter::new(SvgTheme::Light);
+        snapshot.paint(&mut direct);
+        let mut replayed = SvgPainter::new(SvgTheme::Light);
+        drawing.paint(&mut replayed);
+        assert_eq!(direct.body, replayed.body);
+
+        assert!(SvgExporter::export_canvas(&CanvasSnapshot::default(), Path::new("unused.svg"), SvgTheme::Light).is_err());
+    }
+}
@@ -0,0 +1,540 @@
+//! 配电箱系统图布局
+//!
+//! 按画布系统图的图形尺寸，将配电箱的进线、母线、出线回路（断路器图形及标注、
+//! 线缆标注、回路编号、相序）、功能模块符号和负荷表排布为图元，供各导出格式使用。
+
+use super::drawing::{breaker_shape, circuit_line, breaker_text_lines, incoming_breaker_text_lines, module_symbol, Drawing, Layer, Point, TextAlign};
+use crate::core_lib::algorithm::conduit_sizing::ConductorSpec;
+use crate::core_lib::data_types::{CableInfo, CircuitNumber};
+use crate::editor::business::main_system_node::{ComponentType, SystemDiagram as TrunkSystemDiagram};
+use crate::editor::business::{BoxData, CircuitInfo, ModuleComponent};
+
+/// 图形基准尺寸（与画布draw_circuit_shape、draw_breaker_shape的size一致）
+const SHAPE_SIZE: f64 = 100.0;
+/// 回路行距
+const ROW_SPACING: f64 = 120.0;
+/// 第一条回路距配电箱名称的距离
+const FIRST_ROW_Y: f64 = 200.0;
+/// 进线段长度（母线左侧）
+const INCOMING_LENGTH: f64 = 760.0;
+/// 出线回路总长（与draw_circuit_shape一致）
+const CIRCUIT_LENGTH: f64 = 1380.0;
+/// 负荷表距回路末端的距离
+const TABLE_GAP: f64 = 80.0;
+/// 负荷表行高
+const TABLE_ROW_HEIGHT: f64 = ROW_SPACING;
+/// 负荷表各列（标题, 宽度）
+const TABLE_COLUMNS: [(&str, f64); 5] = [("回路编号", 180.0), ("相序", 120.0), ("功率(kW)", 180.0), ("电流(A)", 180.0), ("用途", 360.0)];
+/// 相邻配电箱系统图的水平间距
+const BOX_SPACING: f64 = 300.0;
+/// 标注字高
+const TEXT_HEIGHT: f64 = 30.0;
+/// 配电箱名称字高
+const TITLE_HEIGHT: f64 = 60.0;
+/// 干线图设备框尺寸（宽, 高）
+const EQUIPMENT_SIZE: (f64, f64) = (160.0, 80.0);
+/// 干线图设备文字字高
+const EQUIPMENT_TEXT_HEIGHT: f64 = 16.0;
+/// 功能模块符号基准尺寸
+const MODULE_SIZE: f64 = 120.0;
+/// 进线上相邻模块符号的最大间距
+const MODULE_SPACING: f64 = 80.0;
+/// 出线回路上模块符号的起始位置（自右向左排列）
+const CIRCUIT_MODULE_X: f64 = 1100.0;
+/// 干线分支连接点半径
+const JUNCTION_RADIUS: f64 = 3.0;
+
+/// 配电箱系统图
+pub struct SystemDiagram;
+
+impl SystemDiagram {
+    /// 线缆标注，如"YJV-5x16 SC50"
+    ///
+    /// 单相回路按三芯、三相回路按五芯标注（与穿管管径选择一致），规格无法识别时按原样标注
+    pub fn cable_annotation(cable: &CableInfo) -> String {
+        let spec = match cable.size.trim_end_matches("mm²").parse::<f64>() {
+            Ok(section) => {
+                let cores = if cable.is_three_phase { 5 } else { 3 };
+                ConductorSpec::new(&cable.model, cores, section, None).to_spec_str()
+            }
+            Err(_) => format!("{}-{}", cable.model, cable.size),
+        };
+        if cable.laying_method.is_empty() {
+            spec
+        } else {
+            format!("{} {}", spec, cable.laying_method)
+        }
+    }
+
+    /// 相序标注：三相回路标注L1L2L3，尚未分配相的单相回路留空
+    pub fn phase_label(circuit: &CircuitInfo) -> String {
+        match circuit.phase {
+            _ if circuit.three_phase => "L1L2L3".to_string(),
+            Some(phase) => format!("L{}", phase),
+            None => String::new(),
+        }
+    }
+
+    /// 单个配电箱系统图总宽度
+    pub fn box_width() -> f64 {
+        INCOMING_LENGTH + CIRCUIT_LENGTH + TABLE_GAP + TABLE_COLUMNS.iter().map(|(_, w)| w).sum::<f64>()
+    }
+
+    /// 排布单个配电箱系统图，原点为图形左上角
+    ///
+    /// # 参数
+    /// * `box_data` - 配电箱数据
+    ///
+    /// # 返回值
+    /// 返回系统图图元
+    pub fn layout_box(box_data: &BoxData) -> Drawing {
+        let circuits = Self::sorted_circuits(box_data);
+        Self::layout_page(box_data, &circuits, format!("{} 系统图", box_data.name))
+    }
+
+    /// 按每页回路数分页排布配电箱系统图
+    ///
+    /// 每页均绘出进线、母线和负荷表合计行，页名标注"(页次/总页数)"；回路数不超过每页回路数时只有一页
+    ///
+    /// # 参数
+    /// * `box_data` - 配电箱数据
+    /// * `rows_per_page` - 每页回路数（至少为1）
+    ///
+    /// # 返回值
+    /// 返回各页系统图图元
+    pub fn layout_box_pages(box_data: &BoxData, rows_per_page: usize) -> Vec<Drawing> {
+        let circuits = Self::sorted_circuits(box_data);
+        let rows_per_page = rows_per_page.max(1);
+        if circuits.len() <= rows_per_page {
+            return vec![Self::layout_page(box_data, &circuits, format!("{} 系统图", box_data.name))];
+        }
+
+        let pages = circuits.len().div_ceil(rows_per_page);
+        circuits
+            .chunks(rows_per_page)
+            .enumerate()
+            .map(|(i, chunk)| Self::layout_page(box_data, chunk, format!("{} 系统图 ({}/{})", box_data.name, i + 1, pages)))
+            .collect()
+    }
+
+    /// 指定回路数时系统图的高度（含负荷表）
+    pub fn box_height(rows: usize) -> f64 {
+        TITLE_HEIGHT / 2.0 + FIRST_ROW_Y + (rows.max(1) as f64 + 0.5) * ROW_SPACING
+    }
+
+    /// 给定高度内可排布的最大回路数
+    pub fn rows_fitting(height: f64) -> usize {
+        let rows = (height - TITLE_HEIGHT / 2.0 - FIRST_ROW_Y) / ROW_SPACING - 0.5;
+        if rows < 1.0 { 1 } else { rows.floor() as usize }
+    }
+
+    fn sorted_circuits(box_data: &BoxData) -> Vec<&CircuitInfo> {
+        let mut circuits: Vec<&CircuitInfo> = box_data.circuits.iter().collect();
+        circuits.sort_by_key(|c| c.number);
+        circuits
+    }
+
+    fn layout_page(box_data: &BoxData, circuits: &[&CircuitInfo], title: String) -> Drawing {
+        let mut drawing = Drawing::new();
+        drawing.text(Layer::Title, Point::new(0.0, 0.0), TITLE_HEIGHT, TextAlign::Left, title);
+
+        let rows = circuits.len().max(1);
+        let busbar_top = FIRST_ROW_Y - ROW_SPACING / 2.0;
+        let busbar_bottom = FIRST_ROW_Y + (rows as f64 - 0.5) * ROW_SPACING;
+        drawing.line(Layer::Busbar, Point::new(INCOMING_LENGTH, busbar_top), Point::new(INCOMING_LENGTH, busbar_bottom));
+
+        let components: Vec<ModuleComponent> = box_data.modules.iter().flat_map(|m| m.components()).collect();
+        let (box_components, circuit_components): (Vec<&ModuleComponent>, Vec<&ModuleComponent>) =
+            components.iter().partition(|c| c.circuit_id.is_none());
+        Self::layout_incoming(&mut drawing, box_data, (busbar_top + busbar_bottom) / 2.0);
+        Self::layout_box_modules(&mut drawing, &box_components, (busbar_top + busbar_bottom) / 2.0);
+
+        for (i, circuit) in circuits.iter().enumerate() {
+            let origin = Point::new(INCOMING_LENGTH, FIRST_ROW_Y + i as f64 * ROW_SPACING);
+            Self::layout_circuit(&mut drawing, circuit, origin);
+            let on_circuit = circuit_components.iter().filter(|c| c.circuit_id.as_deref() == Some(circuit.circuit_id.as_str()));
+            for (j, component) in on_circuit.enumerate() {
+                module_symbol(&mut drawing, component.symbol, origin.offset(CIRCUIT_MODULE_X - j as f64 * MODULE_SPACING, 0.0), MODULE_SIZE);
+            }
+        }
+
+        Self::layout_load_table(&mut drawing, box_data, circuits);
+        drawing
+    }
+
+    /// 排布多个配电箱系统图，自左向右依次排列
+    pub fn layout_boxes(boxes: &[&BoxData]) -> Drawing {
+        let mut drawing = Drawing::new();
+        for (i, box_data) in boxes.iter().enumerate() {
+            drawing.append(Self::layout_box(box_data), i as f64 * (Self::box_width() + BOX_SPACING), 0.0);
+        }
+        drawing
+    }
+
+    /// 进线：进线电缆、进线断路器及至母线的连线
+    fn layout_incoming(drawing: &mut Drawing, box_data: &BoxData, y: f64) {
+        let breaker_start = Point::new(160.0, y);
+        drawing.line(Layer::Incoming, Point::new(0.0, y), breaker_start);
+        let lines = (box_data.incoming_current > 0.0).then(|| incoming_breaker_text_lines(box_data.incoming_current));
+        let breaker_end = breaker_shape(drawing, breaker_start, SHAPE_SIZE, lines.as_ref());
+        drawing.line(Layer::Incoming, breaker_end, Point::new(INCOMING_LENGTH, y));
+        if let Some(cable) = &box_data.incoming_cable {
+            drawing.text(
+                Layer::Cable,
+                Point::new(breaker_end.x + 20.0, y + TEXT_HEIGHT),
+                TEXT_HEIGHT,
+                TextAlign::Left,
+                Self::cable_annotation(cable),
+            );
+        }
+        drawing.text(
+            Layer::Cable,
+            Point::new(0.0, y + TEXT_HEIGHT),
+            TEXT_HEIGHT,
+            TextAlign::Left,
+            format!("Pe={:.1}kW Ie={:.1}A", box_data.total_power, box_data.total_current),
+        );
+    }
+
+    /// 箱体及进线上的模块：符号串接在进线断路器与母线之间，标注自进线电缆标注下方逐行列出
+    fn layout_box_modules(drawing: &mut Drawing, components: &[&ModuleComponent], y: f64) {
+        if components.is_empty() {
+            return;
+        }
+        let first_x = 160.0 + SHAPE_SIZE * 1.5 + MODULE_SPACING / 2.0;
+        let spacing = MODULE_SPACING.min((INCOMING_LENGTH - first_x - MODULE_SPACING / 2.0) / components.len() as f64);
+        for (i, component) in components.iter().enumerate() {
+            module_symbol(drawing, component.symbol, Point::new(first_x + i as f64 * spacing, y), MODULE_SIZE);
+            drawing.text(
+                Layer::Equipment,
+                Point::new(first_x - MODULE_SPACING / 2.0 + 20.0, y + (i as f64 + 2.0) * TEXT_HEIGHT),
+                TEXT_HEIGHT,
+                TextAlign::Left,
+                format!("{} {}", component.symbol.to_str(), component.label),
+            );
+        }
+    }
+
+    /// 出线回路：线段与draw_circuit_shape一致，断路器位于160~310之间
+    fn layout_circuit(drawing: &mut Drawing, circuit: &CircuitInfo, origin: Point) {
+        let three_phase = circuit.three_phase;
+
+        let (breaker_start, _) = circuit_line(drawing, origin, SHAPE_SIZE);
+        let lines = circuit.find_breaker().map(|breaker| breaker_text_lines(breaker, three_phase));
+        breaker_shape(drawing, breaker_start, SHAPE_SIZE, lines.as_ref());
+
+        if let Some(cable) = &circuit.cable {
+            drawing.text(
+                Layer::Cable,
+                origin.offset(330.0, -TEXT_HEIGHT),
+                TEXT_HEIGHT,
+                TextAlign::Left,
+                Self::cable_annotation(cable),
+            );
+        }
+        drawing.text(
+            Layer::CircuitText,
+            origin.offset(1190.0, -TEXT_HEIGHT),
+            TEXT_HEIGHT,
+            TextAlign::Right,
+            CircuitNumber::new(circuit.number).to_str(),
+        );
+        drawing.text(
+            Layer::CircuitText,
+            origin.offset(1290.0, -TEXT_HEIGHT),
+            TEXT_HEIGHT,
+            TextAlign::Center,
+            Self::phase_label(circuit),
+        );
+    }
+
+    /// 负荷表：表头、每回路一行及合计行
+    fn layout_load_table(drawing: &mut Drawing, box_data: &BoxData, circuits: &[&CircuitInfo]) {
+        let left = INCOMING_LENGTH + CIRCUIT_LENGTH + TABLE_GAP;
+        let width: f64 = TABLE_COLUMNS.iter().map(|(_, w)| w).sum();
+        // 表头行位于第一条回路上方，数据行与回路对齐
+        let top = FIRST_ROW_Y - 1.5 * TABLE_ROW_HEIGHT;
+        let row_count = circuits.len() + 2;
+
+        for i in 0..=row_count {
+            let y = top + i as f64 * TABLE_ROW_HEIGHT;
+            drawing.line(Layer::LoadTable, Point::new(left, y), Point::new(left + width, y));
+        }
+        let bottom = top + row_count as f64 * TABLE_ROW_HEIGHT;
+        let mut x = left;
+        drawing.line(Layer::LoadTable, Point::new(x, top), Point::new(x, bottom));
+        for (_, w) in TABLE_COLUMNS {
+            x += w;
+            drawing.line(Layer::LoadTable, Point::new(x, top), Point::new(x, bottom));
+        }
+
+        let header: Vec<String> = TABLE_COLUMNS.iter().map(|(title, _)| title.to_string()).collect();
+        let mut rows = vec![header];
+        rows.extend(circuits.iter().map(|c| {
+            vec![
+                CircuitNumber::new(c.number).to_str(),
+                Self::phase_label(c),
+                format!("{:.2}", c.power),
+                format!("{:.1}", c.current),
+                c.purpose_or_name().to_string(),
+            ]
+        }));
+        rows.push(vec![
+            "合计".to_string(),
+            String::new(),
+            format!("{:.2}", box_data.total_power),
+            format!("{:.1}", box_data.total_current),
+            format!("进线整定{:.0}A", box_data.incoming_current),
+        ]);
+
+        for (i, row) in rows.iter().enumerate() {
+            let y = top + (i as f64 + 0.5) * TABLE_ROW_HEIGHT;
+            let mut x = left;
+            for ((_, w), cell) in TABLE_COLUMNS.iter().zip(row) {
+                drawing.text(Layer::LoadTable, Point::new(x + w / 2.0, y), TEXT_HEIGHT, TextAlign::Center, cell.clone());
+                x += w;
+            }
+        }
+    }
+}
+
+/// 干线系统图
+pub struct TrunkDiagram;
+
+impl TrunkDiagram {
+    /// 排布干线系统图
+    ///
+    /// 设备按干线图节点自动布局给出的位置（设备中心）绘制：母线、母线槽绘为水平线，
+    /// 其余设备绘为设备框；已布线的连线按保存的正交折线绘制并在干线分支处画连接点，
+    /// 未布线的连线自上级设备引出，位于同一行时水平连接，否则按竖-横-竖折线连接。
+    ///
+    /// # 参数
+    /// * `diagram` - 干线图节点生成的系统图
+    ///
+    /// # 返回值
+    /// 返回干线系统图图元
+    pub fn layout(diagram: &TrunkSystemDiagram) -> Drawing {
+        let mut drawing = Drawing::new();
+        let (width, height) = EQUIPMENT_SIZE;
+        let top = diagram
+            .components
+            .iter()
+            .map(|c| c.position.1)
+            .fold(f64::INFINITY, f64::min);
+        let top = if top.is_finite() { top } else { 0.0 };
+        drawing.text(Layer::Title, Point::new(0.0, top - height - TITLE_HEIGHT), TITLE_HEIGHT, TextAlign::Left, diagram.name.clone());
+
+        for component in &diagram.components {
+            let center = Point::new(component.position.0, component.position.1);
+            let lines: Vec<&str> = component.label.lines().collect();
+            match component.component_type {
+                ComponentType::Busbar | ComponentType::Busway => {
+                    drawing.line(Layer::Busbar, center.offset(-width / 2.0, 0.0), center.offset(width / 2.0, 0.0));
+                    for (i, line) in lines.iter().enumerate() {
+                        let y = -EQUIPMENT_TEXT_HEIGHT * (lines.len() - i) as f64;
+                        drawing.text(Layer::Equipment, center.offset(0.0, y), EQUIPMENT_TEXT_HEIGHT, TextAlign::Center, *line);
+                    }
+                }
+                _ => {
+                    drawing.rect(
+                        Layer::Equipment,
+                        center.offset(-width / 2.0, -height / 2.0),
+                        center.offset(width / 2.0, height / 2.0),
+                    );
+                    let first = -EQUIPMENT_TEXT_HEIGHT * 1.2 * (lines.len() as f64 - 1.0) / 2.0;
+                    for (i, line) in lines.iter().enumerate() {
+                        let y = first + EQUIPMENT_TEXT_HEIGHT * 1.2 * i as f64;
+                        drawing.text(Layer::Equipment, center.offset(0.0, y), EQUIPMENT_TEXT_HEIGHT, TextAlign::Center, *line);
+                    }
+                }
+            }
+        }
+
+        for connection in &diagram.connections {
+            if connection.route.len() >= 2 {
+                for segment in connection.route.windows(2) {
+                    drawing.line(Layer::Circuit, Point::new(segment[0].0, segment[0].1), Point::new(segment[1].0, segment[1].1));
+                }
+                continue;
+            }
+            let (Some(from), Some(to)) = (
+                diagram.components.iter().find(|c| c.id == connection.from),
+                diagram.components.iter().find(|c| c.id == connection.to),
+            ) else {
+                continue;
+            };
+            let start = Point::new(from.position.0, from.position.1);
+            let end = Point::new(to.position.0, to.position.1);
+            let half_height = |t: &ComponentType| match t {
+                ComponentType::Busbar | ComponentType::Busway => 0.0,
+                _ => height / 2.0,
+            };
+
+            if (start.y - end.y).abs() < 1e-6 {
+                let dir = if end.x >= start.x { 1.0 } else { -1.0 };
+                drawing.line(Layer::Circuit, start.offset(dir * width / 2.0, 0.0), end.offset(-dir * width / 2.0, 0.0));
+            } else {
+                let dir = if end.y >= start.y { 1.0 } else { -1.0 };
+                let a = start.offset(0.0, dir * half_height(&from.component_type));
+                let d = end.offset(0.0, -dir * half_height(&to.component_type));
+                let mid_y = (a.y + d.y) / 2.0;
+                let b = Point::new(a.x, mid_y);
+                let c = Point::new(d.x, mid_y);
+                drawing.line(Layer::Circuit, a, b);
+                if (b.x - c.x).abs() > 1e-6 {
+                    drawing.line(Layer::Circuit, b, c);
+                }
+                drawing.line(Layer::Circuit, c, d);
+            }
+        }
+        for junction in &diagram.junctions {
+            drawing.circle(Layer::Circuit, Point::new(junction.0, junction.1), JUNCTION_RADIUS);
+        }
+
+        drawing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::drawing::Primitive;
+
+    fn sample_box() -> BoxData {
+        let mut box_data = BoxData::new("AL1".to_string(), 1);
+        for i in 1..=3 {
+            let mut circuit = CircuitInfo::new(format!("c{}", i), format!("照明{}", i), 1.5, 7.6);
+            circuit.number = i;
+            circuit.phase = Some(char::from_digit(i, 10).unwrap());
+            circuit.cable = Some(CableInfo::default());
+            box_data.circuits.push(circuit);
+        }
+        box_data.total_power = 4.5;
+        box_data.total_current = 8.2;
+        box_data.incoming_current = 16.0;
+        box_data
+    }
+
+    fn texts(drawing: &Drawing) -> Vec<&str> {
+        drawing
+            .primitives
+            .iter()
+            .filter_map(|p| match p {
+                Primitive::Text { content, .. } => Some(content.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cable_annotation() {
+        let cable = CableInfo::default();
+        assert_eq!(SystemDiagram::cable_annotation(&cable), "BV-3x2.5 SC20");
+
+        let cable = CableInfo {
+            model: "YJV".to_string(),
+            size: "16mm²".to_string(),
+            laying_method: "CT".to_string(),
+            is_three_phase: true,
+            ..CableInfo::default()
+        };
+        assert_eq!(SystemDiagram::cable_annotation(&cable), "YJV-5x16 CT");
+    }
+
+    #[test]
+    fn test_layout_box() {
+        let drawing = SystemDiagram::layout_box(&sample_box());
+
+        // 进线断路器 + 3个回路断路器
+        assert_eq!(drawing.count_on(Layer::Breaker), 4 * 6);
+        assert_eq!(drawing.count_on(Layer::Busbar), 1);
+        assert_eq!(drawing.count_on(Layer::Circuit), 3 * 2);
+
+        let texts = texts(&drawing);
+        assert!(texts.contains(&"AL1 系统图"));
+        assert!(texts.contains(&"WL3"));
+        assert!(texts.contains(&"L2"));
+        assert!(texts.contains(&"BV-3x2.5 SC20"));
+        assert!(texts.contains(&"进线整定16A"));
+        assert_eq!(drawing.count_on(Layer::Equipment), 0);
+    }
+
+    #[test]
+    fn test_layout_box_modules() {
+        use crate::editor::business::fire_power_monitoring::FireCutOffModule;
+        use crate::editor::business::{BoxModule, ModuleKind};
+
+        let mut box_data = sample_box();
+        box_data.add_module(BoxModule::default_for(ModuleKind::DualPowerSwitch));
+        box_data.add_module(BoxModule::FireCutOff(FireCutOffModule { address: None, shunt_trip_circuits: vec!["c2".to_string()] }));
+        let drawing = SystemDiagram::layout_box(&box_data);
+
+        // 双电源开关：方框4条边加3条线；分励脱扣器：方框4条边加1条线
+        let lines: Vec<(Point, Point)> = drawing
+            .primitives
+            .iter()
+            .filter_map(|p| match p {
+                Primitive::Line { layer: Layer::Equipment, start, end } => Some((*start, *end)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(lines.len(), 7 + 5);
+        let incoming_y = FIRST_ROW_Y + ROW_SPACING;
+        assert!(lines[..7].iter().all(|(a, b)| a.x > 310.0 && b.x < INCOMING_LENGTH && (a.y - incoming_y).abs() <= 18.0));
+        // 分励脱扣器画在第二条回路上
+        let circuit_y = FIRST_ROW_Y + ROW_SPACING;
+        assert!(lines[7..].iter().all(|(a, _)| a.x > INCOMING_LENGTH + 1000.0 && (a.y - circuit_y).abs() <= 18.0));
+
+        let texts = texts(&drawing);
+        assert!(texts.iter().any(|t| t.starts_with("ATSE ")));
+        assert!(!texts.iter().any(|t| t.starts_with("分励脱扣器")));
+    }
+
+    #[test]
+    fn test_layout_boxes_side_by_side() {
+        let first = sample_box();
+        let mut second = sample_box();
+        second.name = "AL2".to_string();
+        let drawing = SystemDiagram::layout_boxes(&[&first, &second]);
+
+        let (min, max) = drawing.bounds().unwrap();
+        assert_eq!(min.x, 0.0);
+        assert!(max.x > SystemDiagram::box_width() + BOX_SPACING);
+    }
+
+    #[test]
+    fn test_layout_box_pages() {
+        let box_data = sample_box();
+        assert_eq!(SystemDiagram::layout_box_pages(&box_data, 10).len(), 1);
+
+        let pages = SystemDiagram::layout_box_pages(&box_data, 2);
+        assert_eq!(pages.len(), 2);
+        assert!(texts(&pages[1]).contains(&"AL1 系统图 (2/2)"));
+        assert!(texts(&pages[1]).contains(&"WL3"));
+        assert_eq!(pages[1].count_on(Layer::Circuit), 2);
+
+        assert_eq!(SystemDiagram::rows_fitting(SystemDiagram::box_height(7)), 7);
+    }
+
+    #[test]
+    fn test_trunk_draws_saved_routes() {
+        use crate::editor::business::diagram_routing::{DiagramRouter, RoutingConfig};
+
+        let mut diagram = TrunkSystemDiagram::new("配电干线图".to_string());
+        let busbar = diagram.add_component(ComponentType::Busbar, "主母线".to_string());
+        diagram.components[busbar].position = (300.0, 550.0);
+        for (i, y) in [400.0, 250.0].into_iter().enumerate() {
+            let id = diagram.add_component(ComponentType::DistributionBox, format!("{}AL1", i + 1));
+            diagram.components[id].position = (300.0, y);
+            diagram.add_connection(busbar, id);
+        }
+        assert_eq!(DiagramRouter::route(&mut diagram, &RoutingConfig::default()), 0);
+
+        let drawing = TrunkDiagram::layout(&diagram);
+        let segments: usize = diagram.connections.iter().map(|c| c.route.len() - 1).sum();
+        let circles = drawing.primitives.iter().filter(|p| matches!(p, Primitive::Circle { .. })).count();
+        assert_eq!(circles, diagram.junctions.len());
+        assert!(circles >= 1);
+        assert_eq!(drawing.count_on(Layer::Circuit), segments + circles);
+    }
+}
@@ -0,0 +1,412 @@
+//! XLSX写出
+//!
+//! 不依赖外部库的最小XLSX(Office Open XML)写出：单元格为文字或数值，文字以内联字符串
+//! 写出，首行可加粗，列宽按内容估算。各部件以不压缩(stored)方式打包为ZIP。
+//! 同一单元格模型也可按CSV写出。
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use super::error::{ExportError, Result};
+
+/// 单元格值
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellValue {
+    Empty,
+    Text(String),
+    Number(f64),
+}
+
+impl CellValue {
+    pub fn text(value: impl Into<String>) -> Self {
+        CellValue::Text(value.into())
+    }
+
+    /// 数值按指定小数位数取整后写出
+    pub fn number(value: f64, decimals: i32) -> Self {
+        let factor = 10f64.powi(decimals);
+        CellValue::Number((value * factor).round() / factor)
+    }
+
+    /// 显示文字（CSV及列宽估算使用）
+    pub fn to_display_string(&self) -> String {
+        match self {
+            CellValue::Empty => String::new(),
+            CellValue::Text(text) => text.clone(),
+            CellValue::Number(value) => value.to_string(),
+        }
+    }
+}
+
+/// 工作表
+#[derive(Debug, Clone, PartialEq)]
+pub struct Worksheet {
+    pub name: String,
+    pub rows: Vec<Vec<CellValue>>,
+    /// 加粗显示的表头行数
+    pub header_rows: usize,
+}
+
+/// 工作簿
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Workbook {
+    pub sheets: Vec<Worksheet>,
+}
+
+/// 工作表名称最大长度
+const MAX_SHEET_NAME: usize = 31;
+
+impl Workbook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加工作表
+    ///
+    /// 名称中Excel不允许的字符替换为"_"，超长截断，重名时追加序号
+    pub fn add_sheet(&mut self, name: &str, rows: Vec<Vec<CellValue>>, header_rows: usize) {
+        let base: String = name
+            .chars()
+            .map(|c| if matches!(c, '[' | ']' | ':' | '*' | '?' | '/' | '\\') { '_' } else { c })
+            .take(MAX_SHEET_NAME)
+            .collect();
+        let base = if base.trim().is_empty() { format!("Sheet{}", self.sheets.len() + 1) } else { base };
+
+        let mut name = base.clone();
+        let mut index = 2;
+        while self.sheets.iter().any(|s| s.name.eq_ignore_ascii_case(&name)) {
+            let suffix = format!("({})", index);
+            let keep = MAX_SHEET_NAME - suffix.chars().count();
+            name = base.chars().take(keep).collect::<String>() + &suffix;
+            index += 1;
+        }
+        self.sheets.push(Worksheet { name, rows, header_rows });
+    }
+
+    /// 生成XLSX文件内容
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut zip = ZipWriter::default();
+        zip.add("[Content_Types].xml", self.content_types().as_bytes());
+        zip.add("_rels/.rels", ROOT_RELS.as_bytes());
+        zip.add("xl/workbook.xml", self.workbook_xml().as_bytes());
+        zip.add("xl/_rels/workbook.xml.rels", self.workbook_rels().as_bytes());
+        zip.add("xl/styles.xml", STYLES.as_bytes());
+        for (i, sheet) in self.sheets.iter().enumerate() {
+            zip.add(&format!("xl/worksheets/sheet{}.xml", i + 1), Self::sheet_xml(sheet).as_bytes());
+        }
+        zip.finish()
+    }
+
+    /// 写出XLSX文件
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if self.sheets.is_empty() {
+            return Err(ExportError::data_error("工作簿", "工作簿中没有工作表"));
+        }
+        std::fs::write(path, self.to_bytes())
+            .map_err(|e| ExportError::file_operation("写入", path.display().to_string(), e.to_string()))
+    }
+
+    fn content_types(&self) -> String {
+        let mut xml = String::from(XML_HEADER);
+        xml.push_str(r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#);
+        xml.push_str(r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#);
+        xml.push_str(r#"<Default Extension="xml" ContentType="application/xml"/>"#);
+        xml.push_str(r#"<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#);
+        xml.push_str(r#"<Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>"#);
+        for i in 1..=self.sheets.len() {
+            let _ = write!(
+                xml,
+                r#"<Override PartName="/xl/worksheets/sheet{}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+                i
+            );
+        }
+        xml.push_str("</Types>");
+        xml
+    }
+
+    fn workbook_xml(&self) -> String {
+        let mut xml = String::from(XML_HEADER);
+        xml.push_str(r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>"#);
+        for (i, sheet) in self.sheets.iter().enumerate() {
+            let _ = write!(
+                xml,
+                r#"<sheet name="{}" sheetId="{}" r:id="rId{}"/>"#,
+                escape_xml(&sheet.name),
+                i + 1,
+                i + 1
+            );
+        }
+        xml.push_str("</sheets></workbook>");
+        xml
+    }
+
+    fn workbook_rels(&self) -> String {
+        let mut xml = String::from(XML_HEADER);
+        xml.push_str(r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#);
+        for i in 1..=self.sheets.len() {
+            let _ = write!(
+                xml,
+                r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{}.xml"/>"#,
+                i, i
+            );
+        }
+        let _ = write!(
+            xml,
+            r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>"#,
+            self.sheets.len() + 1
+        );
+        xml.push_str("</Relationships>");
+        xml
+    }
+
+    fn sheet_xml(sheet: &Worksheet) -> String {
+        let mut xml = String::from(XML_HEADER);
+        xml.push_str(r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#);
+
+        let widths = Self::column_widths(sheet);
+        if !widths.is_empty() {
+            xml.push_str("<cols>");
+            for (i, width) in widths.iter().enumerate() {
+                let _ = write!(xml, r#"<col min="{}" max="{}" width="{:.1}" customWidth="1"/>"#, i + 1, i + 1, width);
+            }
+            xml.push_str("</cols>");
+        }
+
+        xml.push_str("<sheetData>");
+        for (r, row) in sheet.rows.iter().enumerate() {
+            let _ = write!(xml, r#"<row r="{}">"#, r + 1);
+            let style = if r < sheet.header_rows { r#" s="1""# } else { "" };
+            for (c, cell) in row.iter().enumerate() {
+                let reference = cell_reference(r, c);
+                match cell {
+                    CellValue::Empty => {}
+                    CellValue::Text(text) => {
+                        let _ = write!(
+                            xml,
+                            r#"<c r="{}" t="inlineStr"{}><is><t xml:space="preserve">{}</t></is></c>"#,
+                            reference,
+                            style,
+                            escape_xml(text)
+                        );
+                    }
+                    CellValue::Number(value) if value.is_finite() => {
+                        let _ = write!(xml, r#"<c r="{}"{}><v>{}</v></c>"#, reference, style, value);
+                    }
+                    CellValue::Number(_) => {}
+                }
+            }
+            xml.push_str("</row>");
+        }
+        xml.push_str("</sheetData></worksheet>");
+        xml
+    }
+
+    /// 按各列最长内容估算列宽（中文按两个字符宽）
+    fn column_widths(sheet: &Worksheet) -> Vec<f64> {
+        let columns = sheet.rows.iter().map(Vec::len).max().unwrap_or(0);
+        (0..columns)
+            .map(|c| {
+                let chars = sheet
+                    .rows
+                    .iter()
+                    .filter_map(|row| row.get(c))
+                    .map(|cell| cell.to_display_string().chars().map(|ch| if ch.is_ascii() { 1 } else { 2 }).sum::<usize>())
+                    .max()
+                    .unwrap_or(0);
+                (chars as f64 + 2.0).clamp(8.0, 50.0)
+            })
+            .collect()
+    }
+}
+
+const XML_HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\r\n";
+
+const ROOT_RELS: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\r\n",
+    r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+    r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>"#,
+    "</Relationships>"
+);
+
+/// 样式：0为常规，1为加粗（表头）
+const STYLES: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\r\n",
+    r#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+    r#"<fonts count="2"><font><sz val="11"/><name val="宋体"/></font><font><b/><sz val="11"/><name val="宋体"/></font></fonts>"#,
+    r#"<fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills>"#,
+    r#"<borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders>"#,
+    r#"<cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs>"#,
+    r#"<cellXfs count="2"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="0" fontId="1" fillId="0" borderId="0" xfId="0" applyFont="1"/></cellXfs>"#,
+    r#"<cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles>"#,
+    "</styleSheet>"
+);
+
+/// 按CSV格式写出表格行，每行以CRLF结尾
+pub fn write_csv_rows(csv: &mut String, rows: &[Vec<CellValue>]) {
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|cell| csv_field(&cell.to_display_string())).collect();
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+}
+
+/// CSV字段：含逗号、引号或换行时加引号，引号加倍
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 单元格引用，如(0, 0)为"A1"，(1, 27)为"AB2"
+pub fn cell_reference(row: usize, column: usize) -> String {
+    let mut letters = Vec::new();
+    let mut n = column + 1;
+    while n > 0 {
+        let rem = (n - 1) % 26;
+        letters.push((b'A' + rem as u8) as char);
+        n = (n - 1) / 26;
+    }
+    letters.iter().rev().collect::<String>() + &(row + 1).to_string()
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            // XML 1.0不允许的控制字符
+            c if (c as u32) < 0x20 && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// 不压缩的ZIP打包
+#[derive(Default)]
+struct ZipWriter {
+    data: Vec<u8>,
+    central: Vec<u8>,
+    entries: u16,
+}
+
+impl ZipWriter {
+    /// ZIP时间字段：1980-01-01 00:00
+    const DOS_DATE: u16 = 0x0021;
+    /// 通用标志：文件名为UTF-8
+    const UTF8_FLAG: u16 = 0x0800;
+
+    fn add(&mut self, name: &str, content: &[u8]) {
+        let offset = self.data.len() as u32;
+        let crc = crc32(content);
+        let size = content.len() as u32;
+
+        push_u32(&mut self.data, 0x0403_4b50);
+        push_u16(&mut self.data, 20);
+        push_u16(&mut self.data, Self::UTF8_FLAG);
+        push_u16(&mut self.data, 0);
+        push_u16(&mut self.data, 0);
+        push_u16(&mut self.data, Self::DOS_DATE);
+        push_u32(&mut self.data, crc);
+        push_u32(&mut self.data, size);
+        push_u32(&mut self.data, size);
+        push_u16(&mut self.data, name.len() as u16);
+        push_u16(&mut self.data, 0);
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(content);
+
+        push_u32(&mut self.central, 0x0201_4b50);
+        push_u16(&mut self.central, 20);
+        push_u16(&mut self.central, 20);
+        push_u16(&mut self.central, Self::UTF8_FLAG);
+        push_u16(&mut self.central, 0);
+        push_u16(&mut self.central, 0);
+        push_u16(&mut self.central, Self::DOS_DATE);
+        push_u32(&mut self.central, crc);
+        push_u32(&mut self.central, size);
+        push_u32(&mut self.central, size);
+        push_u16(&mut self.central, name.len() as u16);
+        push_u16(&mut self.central, 0);
+        push_u16(&mut self.central, 0);
+        push_u16(&mut self.central, 0);
+        push_u16(&mut self.central, 0);
+        push_u32(&mut self.central, 0);
+        push_u32(&mut self.central, offset);
+        self.central.extend_from_slice(name.as_bytes());
+
+        self.entries += 1;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let central_offset = self.data.len() as u32;
+        let central_size = self.central.len() as u32;
+        self.data.append(&mut self.central);
+
+        push_u32(&mut self.data, 0x0605_4b50);
+        push_u16(&mut self.data, 0);
+        push_u16(&mut self.data, 0);
+        push_u16(&mut self.data, self.entries);
+        push_u16(&mut self.data, self.entries);
+        push_u32(&mut self.data, central_size);
+        push_u32(&mut self.data, central_offset);
+        push_u16(&mut self.data, 0);
+        self.data
+    }
+}
+
+fn push_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+/// CRC-32（IEEE 802.3，ZIP使用）
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32_and_reference() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(cell_reference(0, 0), "A1");
+        assert_eq!(cell_reference(1, 27), "AB2");
+    }
+
+    #[test]
+    fn test_workbook_package() {
+        let mut workbook = Workbook::new();
+        workbook.add_sheet("AL1/1F", vec![vec![CellValue::text("回路编号"), CellValue::number(1.234, 2)]], 1);
+        workbook.add_sheet("AL1_1F", vec![], 0);
+        assert_eq!(workbook.sheets[0].name, "AL1_1F");
+        assert_eq!(workbook.sheets[1].name, "AL1_1F(2)");
+
+        let bytes = workbook.to_bytes();
+        assert_eq!(&bytes[..4], b"PK\x03\x04");
+        // 目录结束记录：条目数 = 5个固定部件 + 2个工作表
+        let end = bytes.len() - 22;
+        assert_eq!(&bytes[end..end + 4], b"PK\x05\x06");
+        assert_eq!(u16::from_le_bytes([bytes[end + 10], bytes[end + 11]]), 7);
+
+        let sheet = Workbook::sheet_xml(&workbook.sheets[0]);
+        assert!(sheet.contains(r#"<c r="A1" t="inlineStr" s="1"><is><t xml:space="preserve">回路编号</t></is></c>"#));
+        assert!(sheet.contains("<v>1.23</v>"));
+    }
+}
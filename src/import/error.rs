@@ -0,0 +1,54 @@
+use thiserror::Error;
+
+/// 导入功能错误类型
+///
+/// 定义读取外部表格及解析负荷清单时的错误
+#[derive(Debug, Error)]
+pub enum ImportError {
+    /// 文件操作错误
+    #[error("文件操作错误: {path}: {reason}")]
+    FileOperation {
+        /// 文件路径
+        path: String,
+        /// 错误原因
+        reason: String,
+    },
+
+    /// 文件格式错误
+    #[error("文件格式错误: {format} - {description}")]
+    Format {
+        /// 文件格式
+        format: String,
+        /// 错误描述
+        description: String,
+    },
+
+    /// 缺少必需的列
+    #[error("缺少必需的列: {0}")]
+    MissingColumns(String),
+
+    /// 表格为空
+    #[error("表格中没有数据")]
+    EmptyTable,
+}
+
+impl ImportError {
+    /// 创建文件操作错误
+    pub fn file_operation(path: impl Into<String>, reason: impl Into<String>) -> Self {
+        ImportError::FileOperation {
+            path: path.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// 创建文件格式错误
+    pub fn format(format: impl Into<String>, description: impl Into<String>) -> Self {
+        ImportError::Format {
+            format: format.into(),
+            description: description.into(),
+        }
+    }
+}
+
+/// 导入功能的结果类型别名
+pub type Result<T> = std::result::Result<T, ImportError>;
@@ -0,0 +1,257 @@
+//! DEFLATE解压
+//!
+//! 按RFC 1951实现的原始DEFLATE数据流解压，支持不压缩块、固定哈夫曼块和动态哈夫曼块，
+//! 用于读取XLSX（ZIP）中压缩存放的部件，不依赖外部库。
+
+use super::error::{ImportError, Result};
+
+/// 长度码257~285对应的基础长度
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+/// 长度码的附加位数
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+/// 距离码0~29对应的基础距离
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577,
+];
+/// 距离码的附加位数
+const DISTANCE_EXTRA: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+/// 动态块中码长码的排列顺序
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+/// 哈夫曼码最大位数
+const MAX_BITS: usize = 15;
+
+fn corrupted(description: &str) -> ImportError {
+    ImportError::format("DEFLATE", description)
+}
+
+/// 按最低位优先读取位流
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    bit: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0, bit: 0 }
+    }
+
+    fn bits(&mut self, count: u32) -> Result<u32> {
+        let mut value = 0;
+        for i in 0..count {
+            let byte = *self.data.get(self.position).ok_or_else(|| corrupted("数据意外结束"))?;
+            value |= (((byte >> self.bit) & 1) as u32) << i;
+            self.bit += 1;
+            if self.bit == 8 {
+                self.bit = 0;
+                self.position += 1;
+            }
+        }
+        Ok(value)
+    }
+
+    /// 跳到下一个字节边界
+    fn align(&mut self) {
+        if self.bit > 0 {
+            self.bit = 0;
+            self.position += 1;
+        }
+    }
+
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8]> {
+        let end = self.position + count;
+        let bytes = self.data.get(self.position..end).ok_or_else(|| corrupted("数据意外结束"))?;
+        self.position = end;
+        Ok(bytes)
+    }
+}
+
+/// 规范哈夫曼码表
+struct Huffman {
+    /// 各码长的符号数量
+    counts: [u16; MAX_BITS + 1],
+    /// 按码长、符号值排序的符号
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut offsets = [0u16; MAX_BITS + 2];
+        for length in 1..=MAX_BITS {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+        Self { counts, symbols }
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for length in 1..=MAX_BITS {
+            code |= reader.bits(1)? as i32;
+            let count = self.counts[length] as i32;
+            if code - count < first {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(corrupted("无效的哈夫曼码"))
+    }
+}
+
+/// 解压原始DEFLATE数据流
+///
+/// # 参数
+/// * `data` - 压缩数据（不含zlib/gzip头）
+///
+/// # 返回值
+/// * `Ok(Vec<u8>)` - 解压后的数据
+/// * `Err(ImportError)` - 数据损坏
+pub fn inflate(data: &[u8]) -> Result<Vec<u8>> {
+    let mut reader = BitReader::new(data);
+    let mut out = Vec::with_capacity(data.len() * 4);
+    loop {
+        let last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => stored_block(&mut reader, &mut out)?,
+            1 => {
+                let (literal, distance) = fixed_tables();
+                codes(&mut reader, &mut out, &literal, &distance)?;
+            }
+            2 => {
+                let (literal, distance) = dynamic_tables(&mut reader)?;
+                codes(&mut reader, &mut out, &literal, &distance)?;
+            }
+            _ => return Err(corrupted("无效的块类型")),
+        }
+        if last {
+            return Ok(out);
+        }
+    }
+}
+
+fn stored_block(reader: &mut BitReader, out: &mut Vec<u8>) -> Result<()> {
+    reader.align();
+    let header = reader.bytes(4)?;
+    let length = u16::from_le_bytes([header[0], header[1]]);
+    let complement = u16::from_le_bytes([header[2], header[3]]);
+    if length != !complement {
+        return Err(corrupted("不压缩块长度校验失败"));
+    }
+    out.extend_from_slice(reader.bytes(length as usize)?);
+    Ok(())
+}
+
+fn fixed_tables() -> (Huffman, Huffman) {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+    (Huffman::new(&lengths), Huffman::new(&[5; 30]))
+}
+
+fn dynamic_tables(reader: &mut BitReader) -> Result<(Huffman, Huffman)> {
+    let literal_count = reader.bits(5)? as usize + 257;
+    let distance_count = reader.bits(5)? as usize + 1;
+    let code_length_count = reader.bits(4)? as usize + 4;
+
+    let mut code_lengths = [0u8; 19];
+    for &index in &CODE_LENGTH_ORDER[..code_length_count] {
+        code_lengths[index] = reader.bits(3)? as u8;
+    }
+    let code_length_table = Huffman::new(&code_lengths);
+
+    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let symbol = code_length_table.decode(reader)?;
+        let (value, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => (*lengths.last().ok_or_else(|| corrupted("重复码缺少前值"))?, 3 + reader.bits(2)?),
+            17 => (0, 3 + reader.bits(3)?),
+            18 => (0, 11 + reader.bits(7)?),
+            _ => return Err(corrupted("无效的码长码")),
+        };
+        lengths.extend(std::iter::repeat_n(value, repeat as usize));
+    }
+    if lengths.len() > literal_count + distance_count {
+        return Err(corrupted("码长数量超出"));
+    }
+    let (literal, distance) = lengths.split_at(literal_count);
+    Ok((Huffman::new(literal), Huffman::new(distance)))
+}
+
+fn codes(reader: &mut BitReader, out: &mut Vec<u8>, literal: &Huffman, distance: &Huffman) -> Result<()> {
+    loop {
+        let symbol = literal.decode(reader)? as usize;
+        match symbol {
+            0..=255 => out.push(symbol as u8),
+            256 => return Ok(()),
+            _ => {
+                let index = symbol - 257;
+                if index >= LENGTH_BASE.len() {
+                    return Err(corrupted("无效的长度码"));
+                }
+                let length = LENGTH_BASE[index] as usize + reader.bits(LENGTH_EXTRA[index] as u32)? as usize;
+                let index = distance.decode(reader)? as usize;
+                if index >= DISTANCE_BASE.len() {
+                    return Err(corrupted("无效的距离码"));
+                }
+                let back = DISTANCE_BASE[index] as usize + reader.bits(DISTANCE_EXTRA[index] as u32)? as usize;
+                if back > out.len() {
+                    return Err(corrupted("回溯距离超出已解压数据"));
+                }
+                let start = out.len() - back;
+                for i in 0..length {
+                    out.push(out[start + i]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stored_and_fixed_blocks() {
+        // 不压缩块："abc"
+        assert_eq!(inflate(&[0x01, 0x03, 0x00, 0xFC, 0xFF, b'a', b'b', b'c']).unwrap(), b"abc");
+        // 固定哈夫曼块（zlib压缩"aaaaaaaaaa"去掉头尾）
+        assert_eq!(inflate(&[0x4B, 0x4C, 0x84, 0x01, 0x00]).unwrap(), b"aaaaaaaaaa");
+        assert!(inflate(&[0x07]).is_err());
+    }
+
+    #[test]
+    fn test_dynamic_block() {
+        // 动态哈夫曼块（raw deflate压缩64字节偏斜分布的数据，BTYPE=2）
+        let compressed = [
+            0x35, 0x8A, 0x87, 0x09, 0x00, 0x30, 0x00, 0xC2, 0x6E, 0x75, 0xFD, 0xFF, 0x42, 0x53, 0x4A, 0x85, 0x80, 0x11,
+            0x25, 0x59, 0x60, 0x27, 0xB3, 0x82, 0x15, 0x59, 0xC3, 0x0A, 0x2E, 0xE3, 0xA6, 0x97, 0x7B, 0xE8, 0xEF, 0xC6,
+            0x7C, 0x00,
+        ];
+        assert_eq!((compressed[0] >> 1) & 0b11, 2);
+        let alphabet = b"aaaaaaaabbbbccde";
+        let expected: Vec<u8> = (0..64usize).map(|i| alphabet[(i * i * i + i * 5 + (i >> 2)) % 16]).collect();
+        assert_eq!(inflate(&compressed).unwrap(), expected);
+        // 截断的动态块
+        assert!(inflate(&compressed[..20]).is_err());
+    }
+}
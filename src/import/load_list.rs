@@ -0,0 +1,634 @@
+//! 负荷清单导入
+//!
+//! 将表格形式的负荷清单（配电箱、回路名称、用途、Pe、Kx、cosφ、相别、长度、敷设方式）批量转换为
+//! 回路并挂接到对应配电箱：表头按常用写法自动识别，也可手动指定列；逐行校验并计算回路
+//! 电流，有错误的行不导入。导入前先生成预览（各箱执行自动编号和三相平衡后的结果及逐行
+//! 错误），确认后再写入项目。
+//!
+//! 导入的回路保存在配电箱数据（`BoxData::circuits`）中，不另建回路图节点：配电箱的编号、
+//! 三相平衡、元器件选型和保护配合都按箱内回路计算，项目文件也只保存配电箱，独立的回路节点
+//! 无法与箱内回路保持同步。
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::error::{ImportError, Result};
+use crate::application::project::ProjectFile;
+use crate::core_lib::algorithm::current_calculation::CurrentCalculator;
+use crate::core_lib::data_types::{ElectricComponent, LayingMethod};
+use crate::core_lib::utils::IdUtils;
+use crate::editor::business::building::{level_name, parse_level};
+use crate::editor::business::{BoxData, CircuitInfo, DistributionBoxNodeUI};
+
+/// 默认需要系数
+const DEFAULT_KX: f64 = 1.0;
+/// 默认功率因数
+const DEFAULT_COS_PHI: f64 = 0.85;
+
+/// 负荷清单字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LoadListField {
+    /// 配电箱名称
+    BoxName,
+    /// 楼层（仅用于新建的配电箱）
+    Floor,
+    /// 回路名称
+    CircuitName,
+    /// 用途
+    Purpose,
+    /// 设备容量Pe（kW）
+    Pe,
+    /// 需要系数Kx
+    Kx,
+    /// 功率因数cosφ
+    CosPhi,
+    /// 相别（单相/三相）
+    PhaseType,
+    /// 线缆长度（m）
+    Length,
+    /// 敷设方式（SC、JDG、CT等）
+    LayingMethod,
+    /// 外部标识（设备编号），重新导入时优先按此匹配回路
+    SourceId,
+}
+
+impl LoadListField {
+    /// 全部字段
+    pub const ALL: [LoadListField; 11] = [
+        LoadListField::BoxName,
+        LoadListField::Floor,
+        LoadListField::CircuitName,
+        LoadListField::Purpose,
+        LoadListField::Pe,
+        LoadListField::Kx,
+        LoadListField::CosPhi,
+        LoadListField::PhaseType,
+        LoadListField::Length,
+        LoadListField::LayingMethod,
+        LoadListField::SourceId,
+    ];
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            LoadListField::BoxName => "配电箱",
+            LoadListField::Floor => "楼层",
+            LoadListField::CircuitName => "回路名称",
+            LoadListField::Purpose => "用途",
+            LoadListField::Pe => "Pe",
+            LoadListField::Kx => "Kx",
+            LoadListField::CosPhi => "cosφ",
+            LoadListField::PhaseType => "相别",
+            LoadListField::Length => "长度",
+            LoadListField::LayingMethod => "敷设方式",
+            LoadListField::SourceId => "外部编号",
+        }
+    }
+
+    /// 是否为必需字段
+    pub fn is_required(&self) -> bool {
+        matches!(self, LoadListField::BoxName | LoadListField::CircuitName | LoadListField::Pe)
+    }
+
+    /// 表头的常用写法（已规范化）
+    fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            LoadListField::BoxName => &["配电箱", "配电箱名称", "配电箱编号", "箱名", "箱号", "box"],
+            LoadListField::Floor => &["楼层", "层", "所在楼层", "floor"],
+            LoadListField::CircuitName => &["回路名称", "回路", "回路名", "负荷名称", "设备名称", "name", "circuit"],
+            LoadListField::Purpose => &["用途", "回路用途", "负荷类型", "负荷性质", "purpose"],
+            LoadListField::Pe => &["pe", "设备容量", "安装容量", "额定功率", "功率", "power"],
+            LoadListField::Kx => &["kx", "需要系数", "需用系数"],
+            LoadListField::CosPhi => &["cosφ", "cos", "cosphi", "功率因数"],
+            LoadListField::PhaseType => &["相别", "相数", "相", "电压类型", "phase"],
+            LoadListField::Length => &["长度", "线缆长度", "电缆长度", "回路长度", "length"],
+            LoadListField::LayingMethod => &["敷设方式", "敷设", "线缆敷设方式", "laying"],
+            LoadListField::SourceId => &["外部编号", "设备编号", "标识", "id", "sourceid"],
+        }
+    }
+}
+
+/// 规范化表头：去除空白及括号中的单位，英文转小写
+fn normalize_header(header: &str) -> String {
+    let mut out = String::new();
+    let mut depth = 0;
+    for c in header.chars() {
+        match c {
+            '(' | '（' | '[' | '【' => depth += 1,
+            ')' | '）' | ']' | '】' => depth = (depth - 1).max(0),
+            _ if depth > 0 || c.is_whitespace() => {}
+            _ => out.extend(c.to_lowercase()),
+        }
+    }
+    out
+}
+
+/// 列映射：字段到列序号（从0开始）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColumnMapping {
+    pub columns: BTreeMap<LoadListField, usize>,
+}
+
+impl ColumnMapping {
+    /// 按表头自动识别列
+    ///
+    /// # 参数
+    /// * `header` - 表头行
+    ///
+    /// # 返回值
+    /// 返回识别出的列映射，同一字段对应多列时取第一列
+    pub fn detect(header: &[String]) -> Self {
+        let mut mapping = Self::default();
+        for (column, text) in header.iter().enumerate() {
+            let normalized = normalize_header(text);
+            let field = LoadListField::ALL.into_iter().find(|field| field.aliases().contains(&normalized.as_str()));
+            if let Some(field) = field {
+                mapping.columns.entry(field).or_insert(column);
+            }
+        }
+        mapping
+    }
+
+    /// 获取字段对应的列
+    pub fn get(&self, field: LoadListField) -> Option<usize> {
+        self.columns.get(&field).copied()
+    }
+
+    /// 指定字段对应的列
+    pub fn set(&mut self, field: LoadListField, column: usize) {
+        self.columns.insert(field, column);
+    }
+
+    /// 未映射的必需字段
+    pub fn missing_required(&self) -> Vec<LoadListField> {
+        LoadListField::ALL
+            .into_iter()
+            .filter(|field| field.is_required() && !self.columns.contains_key(field))
+            .collect()
+    }
+}
+
+/// 解析后的负荷清单行
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportRow {
+    /// 表格行号（从1开始，含表头）
+    pub row: usize,
+    pub box_name: String,
+    /// 楼层号（地下室为负数）
+    pub floor: Option<i32>,
+    pub name: String,
+    pub purpose: Option<String>,
+    /// 设备容量Pe（kW）
+    pub pe: f64,
+    pub kx: f64,
+    pub cos_phi: f64,
+    pub three_phase: bool,
+    /// 线缆长度（m）
+    pub length: Option<f64>,
+    /// 敷设方式，None表示未填写
+    pub laying_method: Option<LayingMethod>,
+    /// 计算电流（A）
+    pub current: f64,
+    pub source_id: Option<String>,
+}
+
+impl ImportRow {
+    /// 按该行创建新回路
+    pub fn to_circuit(&self) -> CircuitInfo {
+        let mut circuit = CircuitInfo::new(
+            IdUtils::new_id(),
+            self.name.clone(),
+            self.pe,
+            self.current,
+        );
+        self.update_circuit(&mut circuit);
+        circuit
+    }
+
+    /// 用该行更新回路的负荷参数
+    ///
+    /// 手动指定的相、断路器和线缆保持不变，其中线缆长度按新的回路长度更新；
+    /// 未手动指定的断路器和线缆被清除，由配电箱重新计算时按新负荷重新选型
+    pub fn update_circuit(&self, circuit: &mut CircuitInfo) {
+        circuit.name = self.name.clone();
+        circuit.power = self.pe;
+        circuit.current = self.current;
+        circuit.demand_coefficient = self.kx;
+        circuit.power_factor = self.cos_phi;
+        circuit.purpose = self.purpose.clone();
+        circuit.length = self.length;
+        circuit.laying_method = self.laying_method;
+        if self.source_id.is_some() {
+            circuit.source_id = self.source_id.clone();
+        }
+        if circuit.three_phase != self.three_phase {
+            circuit.three_phase = self.three_phase;
+            circuit.pin_phase(None);
+        }
+        if circuit.equipment_pinned {
+            if let (Some(cable), Some(length)) = (circuit.cable.as_mut(), self.length) {
+                cable.length = length;
+            }
+        } else {
+            circuit.components.retain(|comp| !matches!(comp, ElectricComponent::Breaker(_)));
+            circuit.cable = None;
+        }
+    }
+
+    /// 是否与回路对应：有外部编号时按编号匹配，否则按回路名称匹配
+    pub fn matches(&self, circuit: &CircuitInfo) -> bool {
+        match (&self.source_id, &circuit.source_id) {
+            (Some(id), Some(circuit_id)) => id == circuit_id,
+            (Some(_), None) | (None, _) => self.name == circuit.name,
+        }
+    }
+}
+
+/// 行级错误
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowError {
+    /// 表格行号（从1开始，含表头）
+    pub row: usize,
+    /// 出错的字段，None表示整行错误
+    pub field: Option<LoadListField>,
+    pub message: String,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.field {
+            Some(field) => write!(f, "第{}行 {}: {}", self.row, field.to_str(), self.message),
+            None => write!(f, "第{}行: {}", self.row, self.message),
+        }
+    }
+}
+
+/// 导入预览
+#[derive(Debug, Clone)]
+pub struct ImportPreview {
+    /// 使用的列映射
+    pub mapping: ColumnMapping,
+    /// 校验通过的行
+    pub rows: Vec<ImportRow>,
+    /// 行级错误，有错误的行不导入
+    pub errors: Vec<RowError>,
+    /// 导入后的配电箱（已有配电箱保留原回路，已执行重新计算）
+    pub boxes: Vec<DistributionBoxNodeUI>,
+}
+
+impl ImportPreview {
+    /// 预览文本：各箱新增回路、负荷及三相分配结果，随后列出错误
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "可导入回路{}个，涉及配电箱{}个，错误{}项\n",
+            self.rows.len(),
+            self.boxes.len(),
+            self.errors.len()
+        );
+        for node in &self.boxes {
+            let data = &node.data;
+            let added = self.rows.iter().filter(|row| row.box_name == data.name).count();
+            text.push_str(&format!(
+                "  {}（{}层）: 新增回路{}个，共{}个，总功率{:.2}kW，L1/L2/L3 = {:.2}/{:.2}/{:.2}kW\n",
+                data.name,
+                level_name(data.floor),
+                added,
+                data.circuits.len(),
+                data.total_power,
+                data.phase_loads[0],
+                data.phase_loads[1],
+                data.phase_loads[2]
+            ));
+            for error in &node.errors {
+                text.push_str(&format!("    {}\n", error));
+            }
+        }
+        for error in &self.errors {
+            text.push_str(&format!("  {}\n", error));
+        }
+        text
+    }
+
+    /// 将预览结果写入项目，同名配电箱被替换，其余追加到末尾
+    ///
+    /// # 返回值
+    /// 返回导入的回路数
+    pub fn apply(&self, project: &mut ProjectFile) -> usize {
+        for node in &self.boxes {
+            match project.boxes.iter_mut().find(|b| b.name == node.data.name) {
+                Some(existing) => *existing = node.data.clone(),
+                None => project.boxes.push(node.data.clone()),
+            }
+        }
+        self.rows.len()
+    }
+}
+
+/// 负荷清单导入器
+pub struct LoadListImporter;
+
+impl LoadListImporter {
+    /// 解析负荷清单并生成导入预览
+    ///
+    /// # 参数
+    /// * `table` - 表格内容，首行为表头
+    /// * `mapping` - 列映射，None表示按表头自动识别
+    /// * `existing` - 项目中已有的配电箱，同名配电箱在其回路之后追加
+    ///
+    /// # 返回值
+    /// * `Ok(ImportPreview)` - 预览结果（可能包含行级错误）
+    /// * `Err(ImportError)` - 表格为空或缺少必需的列
+    pub fn preview(table: &[Vec<String>], mapping: Option<ColumnMapping>, existing: &[BoxData]) -> Result<ImportPreview> {
+        let (mapping, parsed, mut errors) = Self::parse_table(table, mapping)?;
+        let mut rows = Vec::new();
+        for row in parsed {
+            let exists = existing
+                .iter()
+                .filter(|b| b.name == row.box_name)
+                .any(|b| b.circuits.iter().any(|c| row.matches(c)));
+            if exists {
+                errors.push(RowError {
+                    row: row.row,
+                    field: Some(LoadListField::CircuitName),
+                    message: format!("配电箱{}中已有该回路，请使用重新导入", row.box_name),
+                });
+            } else {
+                rows.push(row);
+            }
+        }
+
+        let boxes = Self::build_boxes(&rows, existing);
+        Ok(ImportPreview { mapping, rows, errors, boxes })
+    }
+
+    /// 解析整张表格：识别列、逐行校验，同一配电箱内回路名称或外部编号重复的行记为错误
+    ///
+    /// # 返回值
+    /// * `Ok((列映射, 校验通过的行, 行级错误))`
+    /// * `Err(ImportError)` - 表格为空或缺少必需的列
+    pub(crate) fn parse_table(
+        table: &[Vec<String>],
+        mapping: Option<ColumnMapping>,
+    ) -> Result<(ColumnMapping, Vec<ImportRow>, Vec<RowError>)> {
+        let (header, data_rows) = table.split_first().ok_or(ImportError::EmptyTable)?;
+        let mapping = mapping.unwrap_or_else(|| ColumnMapping::detect(header));
+        let missing = mapping.missing_required();
+        if !missing.is_empty() {
+            let names: Vec<&str> = missing.iter().map(|field| field.to_str()).collect();
+            return Err(ImportError::MissingColumns(names.join("、")));
+        }
+        if data_rows.is_empty() {
+            return Err(ImportError::EmptyTable);
+        }
+
+        let mut rows: Vec<ImportRow> = Vec::new();
+        let mut errors = Vec::new();
+        for (index, cells) in data_rows.iter().enumerate() {
+            let row_number = index + 2;
+            let row = match Self::parse_row(row_number, cells, &mapping) {
+                Ok(row) => row,
+                Err(row_errors) => {
+                    errors.extend(row_errors);
+                    continue;
+                }
+            };
+            let same_box = || rows.iter().filter(|r| r.box_name == row.box_name);
+            let duplicate = if same_box().any(|r| r.name == row.name) {
+                Some((LoadListField::CircuitName, "回路名称"))
+            } else if row.source_id.is_some() && same_box().any(|r| r.source_id == row.source_id) {
+                Some((LoadListField::SourceId, "外部编号"))
+            } else {
+                None
+            };
+            match duplicate {
+                Some((field, label)) => errors.push(RowError {
+                    row: row_number,
+                    field: Some(field),
+                    message: format!("{}在配电箱{}中重复", label, row.box_name),
+                }),
+                None => rows.push(row),
+            }
+        }
+        Ok((mapping, rows, errors))
+    }
+
+    /// 解析并校验单行，返回该行全部错误
+    fn parse_row(row: usize, cells: &[String], mapping: &ColumnMapping) -> std::result::Result<ImportRow, Vec<RowError>> {
+        let mut errors = Vec::new();
+        let text = |field: LoadListField| {
+            mapping
+                .get(field)
+                .and_then(|column| cells.get(column))
+                .map(|cell| cell.trim())
+                .filter(|cell| !cell.is_empty())
+        };
+        let mut error = |field: LoadListField, message: String| {
+            errors.push(RowError { row, field: Some(field), message });
+        };
+        let mut number = |field: LoadListField, default: Option<f64>| match text(field) {
+            Some(value) => match value.parse::<f64>() {
+                Ok(number) if number.is_finite() => Some(number),
+                _ => {
+                    error(field, format!("无法识别的数值 \"{}\"", value));
+                    None
+                }
+            },
+            None if default.is_none() && field.is_required() => {
+                error(field, "不能为空".to_string());
+                None
+            }
+            None => default,
+        };
+
+        let pe = number(LoadListField::Pe, None);
+        let kx = number(LoadListField::Kx, Some(DEFAULT_KX));
+        let cos_phi = number(LoadListField::CosPhi, Some(DEFAULT_COS_PHI));
+        let length = number(LoadListField::Length, None);
+
+        let box_name = text(LoadListField::BoxName).map(str::to_string);
+        let name = text(LoadListField::CircuitName).map(str::to_string);
+        if box_name.is_none() {
+            error(LoadListField::BoxName, "不能为空".to_string());
+        }
+        if name.is_none() {
+            error(LoadListField::CircuitName, "不能为空".to_string());
+        }
+        let three_phase = match text(LoadListField::PhaseType) {
+            None => Some(false),
+            Some(value) => match value.to_lowercase().as_str() {
+                "单相" | "1" | "1p" | "220" | "220v" | "l" => Some(false),
+                "三相" | "3" | "3p" | "380" | "380v" | "3l" => Some(true),
+                _ => {
+                    error(LoadListField::PhaseType, format!("无法识别的相别 \"{}\"", value));
+                    None
+                }
+            },
+        };
+        let floor = match text(LoadListField::Floor) {
+            None => None,
+            Some(value) => {
+                let floor = parse_level(value);
+                if floor.is_none() {
+                    error(LoadListField::Floor, format!("无法识别的楼层 \"{}\"", value));
+                }
+                floor
+            }
+        };
+        if pe.is_some_and(|pe| pe <= 0.0) {
+            error(LoadListField::Pe, "必须大于0".to_string());
+        }
+        if kx.is_some_and(|kx| kx <= 0.0 || kx > 1.0) {
+            error(LoadListField::Kx, "必须在0~1之间".to_string());
+        }
+        if cos_phi.is_some_and(|cos_phi| cos_phi <= 0.0 || cos_phi > 1.0) {
+            error(LoadListField::CosPhi, "必须在0~1之间".to_string());
+        }
+        if length.is_some_and(|length| length < 0.0) {
+            error(LoadListField::Length, "不能为负数".to_string());
+        }
+        let laying_method = text(LoadListField::LayingMethod).and_then(|value| {
+            let method = LayingMethod::from_annotation(value);
+            if method.is_none() {
+                error(LoadListField::LayingMethod, format!("无法识别的敷设方式 \"{}\"", value));
+            }
+            method
+        });
+
+        let (Some(box_name), Some(name), Some(pe), Some(kx), Some(cos_phi), Some(three_phase)) =
+            (box_name, name, pe, kx, cos_phi, three_phase)
+        else {
+            return Err(errors);
+        };
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let current = if three_phase {
+            CurrentCalculator::three_phase_current_calculation(pe as f32, kx as f32, cos_phi as f32)
+        } else {
+            CurrentCalculator::single_phase_current_calculation(pe as f32, kx as f32, cos_phi as f32)
+        } as f64;
+
+        Ok(ImportRow {
+            row,
+            box_name,
+            floor,
+            name,
+            purpose: text(LoadListField::Purpose).map(str::to_string),
+            pe,
+            kx,
+            cos_phi,
+            three_phase,
+            length,
+            laying_method,
+            current,
+            source_id: text(LoadListField::SourceId).map(str::to_string),
+        })
+    }
+
+    /// 按配电箱分组创建回路，并对各箱执行重新计算（编号、三相平衡及选型）
+    fn build_boxes(rows: &[ImportRow], existing: &[BoxData]) -> Vec<DistributionBoxNodeUI> {
+        let mut boxes: Vec<BoxData> = Vec::new();
+        for row in rows {
+            let index = match boxes.iter().position(|b| b.name == row.box_name) {
+                Some(index) => index,
+                None => {
+                    let box_data = existing
+                        .iter()
+                        .find(|b| b.name == row.box_name)
+                        .cloned()
+                        .unwrap_or_else(|| BoxData::new(row.box_name.clone(), row.floor.unwrap_or(1)));
+                    boxes.push(box_data);
+                    boxes.len() - 1
+                }
+            };
+            boxes[index].circuits.push(row.to_circuit());
+        }
+
+        boxes
+            .into_iter()
+            .map(|data| {
+                let mut node = DistributionBoxNodeUI::new(data);
+                node.recalculate();
+                node
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::table::parse_csv;
+
+    const LOAD_LIST: &str = "配电箱,楼层,回路名称,用途,Pe(kW),Kx,cosφ,相别,长度(m),敷设方式\n\
+        AL1,2,照明1,照明,2,,,单相,25,JDG\n\
+        AL1,2,照明2,照明,3,0.9,0.9,单相,,\n\
+        AL1,2,空调,空调,12,0.8,0.8,三相,40,CT\n\
+        AL1,2,照明1,照明,1,,,单相,,\n\
+        AP1,3F,水泵,水泵,abc,,,三相,,\n\
+        AP1,3F,风机,风机,5,1.5,,3P,,\n";
+
+    #[test]
+    fn test_detect_columns() {
+        let header: Vec<String> = ["箱名", "回路", "设备容量（kW）", " COS φ ", "相数", "备注"].iter().map(|s| s.to_string()).collect();
+        let mapping = ColumnMapping::detect(&header);
+        assert_eq!(mapping.get(LoadListField::BoxName), Some(0));
+        assert_eq!(mapping.get(LoadListField::Pe), Some(2));
+        assert_eq!(mapping.get(LoadListField::CosPhi), Some(3));
+        assert_eq!(mapping.get(LoadListField::PhaseType), Some(4));
+        assert!(mapping.missing_required().is_empty());
+
+        // "编号"常指回路编号，不识别为外部编号
+        let header: Vec<String> = ["编号", "设备编号"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(ColumnMapping::detect(&header).get(LoadListField::SourceId), Some(1));
+
+        let table = parse_csv("配电箱,功率\nAL1,2\n");
+        assert!(matches!(LoadListImporter::preview(&table, None, &[]), Err(ImportError::MissingColumns(_))));
+    }
+
+    #[test]
+    fn test_preview_rows_and_errors() {
+        let preview = LoadListImporter::preview(&parse_csv(LOAD_LIST), None, &[]).unwrap();
+        assert_eq!(preview.rows.len(), 3);
+        assert_eq!(preview.boxes.len(), 1);
+        // 重复回路名称、Pe非数值、Kx超出范围
+        let rows: Vec<usize> = preview.errors.iter().map(|e| e.row).collect();
+        assert_eq!(rows, vec![5, 6, 7]);
+        assert_eq!(preview.errors[1].field, Some(LoadListField::Pe));
+        assert_eq!(preview.errors[2].field, Some(LoadListField::Kx));
+
+        let node = &preview.boxes[0];
+        assert_eq!(node.data.floor, 2);
+        assert!(node.errors.is_empty());
+        // 按功率降序编号
+        let numbers: Vec<u32> = node.data.circuits.iter().map(|c| c.number).collect();
+        assert_eq!(numbers, vec![3, 2, 1]);
+        let air_conditioner = &node.data.circuits[2];
+        assert!(air_conditioner.three_phase);
+        assert_eq!(air_conditioner.phase, None);
+        assert_eq!(air_conditioner.length, Some(40.0));
+        // 敷设方式按负荷清单填写的方式选管，未填写时按SC
+        assert_eq!(air_conditioner.laying_method, Some(LayingMethod::CT));
+        assert_eq!(air_conditioner.cable.as_ref().unwrap().laying_method, "CT");
+        let lighting = node.data.circuits.iter().find(|c| c.name == "照明1").unwrap();
+        assert!(lighting.cable.as_ref().unwrap().laying_method.starts_with("JDG"));
+        let lighting = node.data.circuits.iter().find(|c| c.name == "照明2").unwrap();
+        assert!(lighting.cable.as_ref().unwrap().laying_method.starts_with("SC"));
+        assert!(node.data.phase_loads.iter().all(|load| *load >= 4.0));
+    }
+
+    #[test]
+    fn test_apply_appends_to_existing_box() {
+        let mut project = ProjectFile::new("导入测试");
+        let mut existing = BoxData::new("AL1".to_string(), 2);
+        existing.circuits.push(CircuitInfo::new("c0".to_string(), "插座".to_string(), 2.0, 10.0));
+        project.boxes.push(existing);
+
+        let preview = LoadListImporter::preview(&parse_csv(LOAD_LIST), None, &project.boxes).unwrap();
+        assert_eq!(preview.apply(&mut project), 3);
+        assert_eq!(project.boxes.len(), 1);
+        assert_eq!(project.boxes[0].circuits.len(), 4);
+        assert_eq!(project.boxes[0].circuits[0].circuit_id, "c0");
+        assert!(project.run_calculations().is_empty());
+    }
+}
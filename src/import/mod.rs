@@ -0,0 +1,14 @@
+//! 导入模块
+//! 包含CSV/XLSX表格读取、负荷清单批量导入和重新导入对比
+
+pub mod error;
+pub mod inflate;
+pub mod load_list;
+pub mod reimport;
+pub mod table;
+
+// 重新导出常用组件
+pub use error::{ImportError, Result};
+pub use load_list::{ColumnMapping, ImportPreview, ImportRow, LoadListField, LoadListImporter, RowError};
+pub use reimport::{ChangeKind, CircuitChange, FieldChange, ReimportDiff};
+pub use table::{parse_csv, read_table};
@@ -0,0 +1,413 @@
+//! 负荷清单重新导入
+//!
+//! 负荷清单更新后，将新表格与项目中已有回路逐一对比：有外部编号（设备编号）时按
+//! "配电箱+外部编号"匹配，否则按"配电箱+回路名称"匹配，得到新增、修改和删除三类变更。
+//! 每项变更可单独接受或拒绝，应用时更新负荷参数（Pe、Kx、cosφ、相别、用途、长度、敷设方式），
+//! 手动指定的相、断路器和线缆保持不变（线缆长度随回路长度更新），其余回路按新负荷重新选型。
+
+use std::fmt;
+
+use super::error::Result;
+use super::load_list::{ColumnMapping, ImportRow, LoadListField, LoadListImporter, RowError};
+use crate::application::project::ProjectFile;
+use crate::editor::business::{BoxData, CircuitInfo, DistributionBoxNodeUI};
+
+/// 变更类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// 新增回路
+    Added,
+    /// 负荷参数变化
+    Changed,
+    /// 负荷清单中已不存在的回路
+    Removed,
+}
+
+impl ChangeKind {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "新增",
+            ChangeKind::Changed => "修改",
+            ChangeKind::Removed => "删除",
+        }
+    }
+
+    /// 变更列表中的标记符号
+    fn symbol(&self) -> char {
+        match self {
+            ChangeKind::Added => '+',
+            ChangeKind::Changed => '~',
+            ChangeKind::Removed => '-',
+        }
+    }
+}
+
+/// 单个字段的变化
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: LoadListField,
+    pub old: String,
+    pub new: String,
+}
+
+/// 回路变更
+#[derive(Debug, Clone, PartialEq)]
+pub struct CircuitChange {
+    pub kind: ChangeKind,
+    pub box_name: String,
+    /// 回路名称（修改时为新名称）
+    pub circuit_name: String,
+    /// 已有回路的标识，新增时为None
+    pub circuit_id: Option<String>,
+    /// 负荷清单中的对应行，删除时为None
+    pub row: Option<ImportRow>,
+    /// 变化的字段（仅修改时有内容）
+    pub fields: Vec<FieldChange>,
+    /// 是否接受该变更，默认接受
+    pub accepted: bool,
+}
+
+impl fmt::Display for CircuitChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}/{} {}", self.kind.symbol(), self.box_name, self.circuit_name, self.kind.to_str())?;
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|change| format!("{} {} → {}", change.field.to_str(), change.old, change.new))
+            .collect();
+        if !fields.is_empty() {
+            write!(f, "：{}", fields.join("，"))?;
+        }
+        if !self.accepted {
+            write!(f, "（已拒绝）")?;
+        }
+        Ok(())
+    }
+}
+
+/// 重新导入的差异
+#[derive(Debug, Clone)]
+pub struct ReimportDiff {
+    /// 使用的列映射
+    pub mapping: ColumnMapping,
+    /// 回路变更，按配电箱及表格顺序排列
+    pub changes: Vec<CircuitChange>,
+    /// 负荷参数未变化的回路数
+    pub unchanged: usize,
+    /// 行级错误，有错误的行不参与对比
+    pub errors: Vec<RowError>,
+}
+
+impl ReimportDiff {
+    /// 接受或拒绝指定变更
+    ///
+    /// # 参数
+    /// * `index` - 变更序号（从0开始）
+    /// * `accepted` - 是否接受
+    ///
+    /// # 返回值
+    /// 序号有效返回true
+    pub fn set_accepted(&mut self, index: usize, accepted: bool) -> bool {
+        match self.changes.get_mut(index) {
+            Some(change) => {
+                change.accepted = accepted;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 接受或拒绝某一类变更（如拒绝全部删除）
+    pub fn set_kind_accepted(&mut self, kind: ChangeKind, accepted: bool) {
+        for change in self.changes.iter_mut().filter(|change| change.kind == kind) {
+            change.accepted = accepted;
+        }
+    }
+
+    /// 变更列表文本，每项前加序号（从1开始）
+    pub fn to_text(&self) -> String {
+        let count = |kind: ChangeKind| self.changes.iter().filter(|change| change.kind == kind).count();
+        let mut text = format!(
+            "新增{}个，修改{}个，删除{}个，未变化{}个，错误{}项\n",
+            count(ChangeKind::Added),
+            count(ChangeKind::Changed),
+            count(ChangeKind::Removed),
+            self.unchanged,
+            self.errors.len()
+        );
+        for (index, change) in self.changes.iter().enumerate() {
+            text.push_str(&format!("  [{}] {}\n", index + 1, change));
+        }
+        for error in &self.errors {
+            text.push_str(&format!("  {}\n", error));
+        }
+        text
+    }
+
+    /// 将已接受的变更写入项目，并对涉及的配电箱重新计算
+    ///
+    /// # 返回值
+    /// 返回实际应用的变更数
+    pub fn apply(&self, project: &mut ProjectFile) -> usize {
+        let mut applied = 0;
+        let mut touched: Vec<String> = Vec::new();
+        for change in self.changes.iter().filter(|change| change.accepted) {
+            let done = match (change.kind, &change.row, &change.circuit_id) {
+                (ChangeKind::Added, Some(row), _) => {
+                    let index = match project.boxes.iter().position(|b| b.name == row.box_name) {
+                        Some(index) => index,
+                        None => {
+                            project.boxes.push(BoxData::new(row.box_name.clone(), row.floor.unwrap_or(1)));
+                            project.boxes.len() - 1
+                        }
+                    };
+                    project.boxes[index].circuits.push(row.to_circuit());
+                    true
+                }
+                (ChangeKind::Changed, Some(row), Some(id)) => match find_circuit(project, &change.box_name, id) {
+                    Some(circuit) => {
+                        row.update_circuit(circuit);
+                        true
+                    }
+                    None => false,
+                },
+                (ChangeKind::Removed, _, Some(id)) => match project.boxes.iter_mut().find(|b| b.name == change.box_name) {
+                    Some(box_data) => {
+                        let before = box_data.circuits.len();
+                        box_data.circuits.retain(|c| &c.circuit_id != id);
+                        box_data.circuits.len() < before
+                    }
+                    None => false,
+                },
+                _ => false,
+            };
+            if done {
+                applied += 1;
+                if !touched.contains(&change.box_name) {
+                    touched.push(change.box_name.clone());
+                }
+            }
+        }
+
+        for box_data in project.boxes.iter_mut() {
+            if touched.contains(&box_data.name) {
+                let mut node = DistributionBoxNodeUI::new(box_data.clone());
+                node.recalculate();
+                *box_data = node.data;
+            }
+        }
+        applied
+    }
+}
+
+fn find_circuit<'a>(project: &'a mut ProjectFile, box_name: &str, circuit_id: &str) -> Option<&'a mut CircuitInfo> {
+    project
+        .boxes
+        .iter_mut()
+        .find(|b| b.name == box_name)?
+        .circuits
+        .iter_mut()
+        .find(|c| c.circuit_id == circuit_id)
+}
+
+/// 对比负荷参数，返回变化的字段
+fn changed_fields(row: &ImportRow, circuit: &CircuitInfo) -> Vec<FieldChange> {
+    fn phase(three_phase: bool) -> String {
+        if three_phase { "三相" } else { "单相" }.to_string()
+    }
+    fn optional<T: ToString>(value: &Option<T>) -> String {
+        value.as_ref().map_or_else(|| "-".to_string(), T::to_string)
+    }
+
+    let mut fields = Vec::new();
+    let mut compare = |field: LoadListField, old: String, new: String| {
+        if old != new {
+            fields.push(FieldChange { field, old, new });
+        }
+    };
+    compare(LoadListField::CircuitName, circuit.name.clone(), row.name.clone());
+    compare(LoadListField::Pe, circuit.power.to_string(), row.pe.to_string());
+    compare(LoadListField::Kx, circuit.demand_coefficient.to_string(), row.kx.to_string());
+    compare(LoadListField::CosPhi, circuit.power_factor.to_string(), row.cos_phi.to_string());
+    compare(LoadListField::PhaseType, phase(circuit.three_phase), phase(row.three_phase));
+    compare(LoadListField::Purpose, optional(&circuit.purpose), optional(&row.purpose));
+    compare(LoadListField::Length, optional(&circuit.length), optional(&row.length));
+    compare(
+        LoadListField::LayingMethod,
+        optional(&circuit.laying_method.map(|m| m.to_str())),
+        optional(&row.laying_method.map(|m| m.to_str())),
+    );
+    if row.source_id.is_some() {
+        compare(LoadListField::SourceId, optional(&circuit.source_id), optional(&row.source_id));
+    }
+    fields
+}
+
+impl LoadListImporter {
+    /// 将更新后的负荷清单与项目中已有回路对比
+    ///
+    /// 只对负荷清单中出现的配电箱判断删除，其余配电箱不受影响
+    ///
+    /// # 参数
+    /// * `table` - 表格内容，首行为表头
+    /// * `mapping` - 列映射，None表示按表头自动识别
+    /// * `existing` - 项目中已有的配电箱
+    ///
+    /// # 返回值
+    /// * `Ok(ReimportDiff)` - 差异（可能包含行级错误）
+    /// * `Err(ImportError)` - 表格为空或缺少必需的列
+    pub fn diff(table: &[Vec<String>], mapping: Option<ColumnMapping>, existing: &[BoxData]) -> Result<ReimportDiff> {
+        let (mapping, rows, errors) = Self::parse_table(table, mapping)?;
+        let mut changes = Vec::new();
+        let mut unchanged = 0;
+        let mut matched: Vec<&str> = Vec::new();
+
+        for row in rows {
+            let circuits = existing.iter().filter(|b| b.name == row.box_name).flat_map(|b| &b.circuits);
+            // 外部编号匹配优先于名称匹配
+            let circuit = circuits
+                .clone()
+                .find(|c| row.source_id.is_some() && c.source_id == row.source_id)
+                .or_else(|| circuits.clone().find(|c| !matched.contains(&c.circuit_id.as_str()) && row.matches(c)));
+            match circuit {
+                Some(circuit) => {
+                    matched.push(&circuit.circuit_id);
+                    let fields = changed_fields(&row, circuit);
+                    if fields.is_empty() {
+                        unchanged += 1;
+                        continue;
+                    }
+                    changes.push(CircuitChange {
+                        kind: ChangeKind::Changed,
+                        box_name: row.box_name.clone(),
+                        circuit_name: row.name.clone(),
+                        circuit_id: Some(circuit.circuit_id.clone()),
+                        row: Some(row),
+                        fields,
+                        accepted: true,
+                    });
+                }
+                None => changes.push(CircuitChange {
+                    kind: ChangeKind::Added,
+                    box_name: row.box_name.clone(),
+                    circuit_name: row.name.clone(),
+                    circuit_id: None,
+                    row: Some(row),
+                    fields: Vec::new(),
+                    accepted: true,
+                }),
+            }
+        }
+
+        // 有错误的行对应的回路不判为删除，避免表格录入错误导致误删
+        let error_rows: Vec<usize> = errors.iter().map(|e| e.row).collect();
+        let erroneous = |box_name: &str, circuit: &CircuitInfo| {
+            table.iter().enumerate().filter(|(i, _)| error_rows.contains(&(i + 1))).any(|(_, cells)| {
+                let cell = |field| mapping.get(field).and_then(|c| cells.get(c)).map(|s| s.trim());
+                cell(LoadListField::BoxName) == Some(box_name)
+                    && (cell(LoadListField::CircuitName) == Some(circuit.name.as_str())
+                        || circuit.source_id.is_some() && cell(LoadListField::SourceId) == circuit.source_id.as_deref())
+            })
+        };
+        let listed: Vec<String> = changes.iter().map(|c| c.box_name.clone()).collect();
+        for box_data in existing {
+            let in_list = listed.contains(&box_data.name)
+                || box_data.circuits.iter().any(|c| matched.contains(&c.circuit_id.as_str()));
+            if !in_list {
+                continue;
+            }
+            for circuit in &box_data.circuits {
+                if matched.contains(&circuit.circuit_id.as_str()) || erroneous(&box_data.name, circuit) {
+                    continue;
+                }
+                changes.push(CircuitChange {
+                    kind: ChangeKind::Removed,
+                    box_name: box_data.name.clone(),
+                    circuit_name: circuit.name.clone(),
+                    circuit_id: Some(circuit.circuit_id.clone()),
+                    row: None,
+                    fields: Vec::new(),
+                    accepted: true,
+                });
+            }
+        }
+
+        Ok(ReimportDiff { mapping, changes, unchanged, errors })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_lib::data_types::electric_data::{Breaker, CableInfo};
+    use crate::import::table::parse_csv;
+
+    fn imported_project() -> ProjectFile {
+        let table = parse_csv(
+            "配电箱,设备编号,回路名称,Pe,相别\n\
+             AL1,L-01,照明1,2,单相\n\
+             AL1,L-02,照明2,3,单相\n\
+             AL1,P-01,插座,4,单相\n",
+        );
+        let mut project = ProjectFile::new("重新导入测试");
+        LoadListImporter::preview(&table, None, &project.boxes).unwrap().apply(&mut project);
+        project
+    }
+
+    #[test]
+    fn test_diff_added_changed_removed() {
+        let project = imported_project();
+        // 照明1改名并加大功率（按设备编号匹配），照明2不变，插座删除，新增空调
+        let table = parse_csv(
+            "配电箱,设备编号,回路名称,Pe,相别\n\
+             AL1,L-01,走廊照明,2.5,单相\n\
+             AL1,L-02,照明2,3,单相\n\
+             AL1,AC-01,空调,9,三相\n",
+        );
+        let diff = LoadListImporter::diff(&table, None, &project.boxes).unwrap();
+        let kinds: Vec<ChangeKind> = diff.changes.iter().map(|c| c.kind).collect();
+        assert_eq!(kinds, vec![ChangeKind::Changed, ChangeKind::Added, ChangeKind::Removed]);
+        assert_eq!(diff.unchanged, 1);
+        let fields: Vec<LoadListField> = diff.changes[0].fields.iter().map(|f| f.field).collect();
+        assert_eq!(fields, vec![LoadListField::CircuitName, LoadListField::Pe]);
+        assert_eq!(diff.changes[2].circuit_name, "插座");
+    }
+
+    #[test]
+    fn test_apply_accepted_changes_keeps_overrides() {
+        let mut project = imported_project();
+        let lighting = &mut project.boxes[0].circuits[0];
+        lighting.pin_phase(Some('3'));
+        // 自选断路器和线缆替换自动选型结果
+        lighting.pin_equipment(Breaker::default(), CableInfo { size: "6mm²".to_string(), ..Default::default() });
+        let lighting_id = lighting.circuit_id.clone();
+        let socket_breaker = project.boxes[0].circuits[2].find_breaker().cloned().unwrap();
+
+        let table = parse_csv(
+            "配电箱,设备编号,回路名称,Pe,相别,长度\n\
+             AL1,L-01,照明1,2.5,单相,35\n\
+             AL1,P-01,插座,12,单相,20\n\
+             AL1,AC-01,空调,9,三相,40\n",
+        );
+        let mut diff = LoadListImporter::diff(&table, None, &project.boxes).unwrap();
+        diff.set_kind_accepted(ChangeKind::Removed, false);
+        assert!(diff.to_text().contains("（已拒绝）"));
+        assert_eq!(diff.apply(&mut project), 3);
+
+        let circuits = &project.boxes[0].circuits;
+        assert_eq!(circuits.len(), 4);
+        let lighting = circuits.iter().find(|c| c.circuit_id == lighting_id).unwrap();
+        assert_eq!(lighting.power, 2.5);
+        assert_eq!(lighting.phase, Some('3'));
+        assert!(lighting.phase_pinned);
+        assert_eq!(lighting.find_breaker(), Some(&Breaker::default()));
+        let cable = lighting.cable.as_ref().unwrap();
+        assert_eq!((cable.size.as_str(), cable.length), ("6mm²", 35.0));
+        // 未手动指定的回路按新负荷重新选型
+        let socket = circuits.iter().find(|c| c.source_id.as_deref() == Some("P-01")).unwrap();
+        let breaker = socket.find_breaker().unwrap();
+        assert!(breaker.rated_current.to_f64() > socket_breaker.rated_current.to_f64());
+        assert_eq!(socket.cable.as_ref().unwrap().length, 20.0);
+        assert!(circuits.iter().any(|c| c.source_id.as_deref() == Some("AC-01") && c.three_phase));
+    }
+}
@@ -0,0 +1,383 @@
+//! 表格读取
+//!
+//! 将CSV或XLSX文件读取为按行排列的文本单元格。CSV按UTF-8读取，支持引号包围的字段及字段内
+//! 换行；XLSX读取工作簿中的第一张工作表，共享字符串和内联字符串均按文本返回，数值保持
+//! 文件中的原始写法。
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::error::{ImportError, Result};
+use super::inflate::inflate;
+
+/// 读取表格文件，格式按扩展名确定（.csv 或 .xlsx）
+///
+/// # 参数
+/// * `path` - 表格文件路径
+///
+/// # 返回值
+/// * `Ok(Vec<Vec<String>>)` - 按行排列的单元格文本（首行通常为表头）
+/// * `Err(ImportError)` - 文件无法读取或格式不受支持
+pub fn read_table(path: &Path) -> Result<Vec<Vec<String>>> {
+    let bytes = std::fs::read(path).map_err(|e| ImportError::file_operation(path.display().to_string(), e.to_string()))?;
+    let extension = path.extension().map(|ext| ext.to_string_lossy().to_lowercase());
+    match extension.as_deref() {
+        Some("csv") => {
+            let text = String::from_utf8(bytes).map_err(|_| ImportError::format("CSV", "文件不是UTF-8编码"))?;
+            Ok(parse_csv(&text))
+        }
+        Some("xlsx") => parse_xlsx(&bytes),
+        _ => Err(ImportError::format(path.display().to_string(), "仅支持.csv或.xlsx文件")),
+    }
+}
+
+/// 解析CSV文本
+///
+/// 去除UTF-8 BOM，忽略全空行
+pub fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let text = text.strip_prefix('\u{FEFF}').unwrap_or(text);
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => quoted = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => quoted = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                row.push(std::mem::take(&mut field));
+                push_row(&mut rows, std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        push_row(&mut rows, row);
+    }
+    rows
+}
+
+fn push_row(rows: &mut Vec<Vec<String>>, row: Vec<String>) {
+    if row.iter().any(|cell| !cell.trim().is_empty()) {
+        rows.push(row);
+    }
+}
+
+/// 解析XLSX文件中的第一张工作表
+fn parse_xlsx(bytes: &[u8]) -> Result<Vec<Vec<String>>> {
+    let archive = ZipArchive::new(bytes)?;
+    let shared_strings = match archive.read("xl/sharedStrings.xml")? {
+        Some(xml) => shared_strings(&xml),
+        None => Vec::new(),
+    };
+    let sheet_path = first_sheet_path(&archive)?;
+    let sheet = archive
+        .read(&sheet_path)?
+        .ok_or_else(|| ImportError::format("XLSX", format!("缺少工作表 {}", sheet_path)))?;
+
+    let mut rows = Vec::new();
+    for row_element in elements(&sheet, "row") {
+        let mut row: Vec<String> = Vec::new();
+        for cell in elements(row_element.content, "c") {
+            let column = match cell.attribute("r") {
+                Some(reference) => column_index(&reference)
+                    .ok_or_else(|| ImportError::format("XLSX", format!("无效的单元格位置 {}", reference)))?,
+                None => row.len(),
+            };
+            let value = match cell.attribute("t").as_deref() {
+                Some("s") => cell
+                    .child_text("v")
+                    .and_then(|v| v.trim().parse::<usize>().ok())
+                    .and_then(|i| shared_strings.get(i).cloned())
+                    .unwrap_or_default(),
+                Some("inlineStr") => elements(cell.content, "t").map(|t| unescape(t.content)).collect(),
+                _ => cell.child_text("v").map(unescape).unwrap_or_default(),
+            };
+            if row.len() <= column {
+                row.resize(column + 1, String::new());
+            }
+            row[column] = value;
+        }
+        push_row(&mut rows, row);
+    }
+    Ok(rows)
+}
+
+/// 按工作簿及其关系文件定位第一张工作表
+fn first_sheet_path(archive: &ZipArchive) -> Result<String> {
+    const DEFAULT_SHEET: &str = "xl/worksheets/sheet1.xml";
+    let (Some(workbook), Some(rels)) = (archive.read("xl/workbook.xml")?, archive.read("xl/_rels/workbook.xml.rels")?) else {
+        return Ok(DEFAULT_SHEET.to_string());
+    };
+    let Some(id) = elements(&workbook, "sheet").next().and_then(|sheet| sheet.attribute("r:id")) else {
+        return Ok(DEFAULT_SHEET.to_string());
+    };
+    let target = elements(&rels, "Relationship")
+        .find(|rel| rel.attribute("Id").as_deref() == Some(id.as_str()))
+        .and_then(|rel| rel.attribute("Target"));
+    Ok(match target {
+        Some(target) if target.starts_with('/') => target.trim_start_matches('/').to_string(),
+        Some(target) => format!("xl/{}", target),
+        None => DEFAULT_SHEET.to_string(),
+    })
+}
+
+/// 读取共享字符串表，富文本中的多个文本段合并为一个字符串
+fn shared_strings(xml: &str) -> Vec<String> {
+    elements(xml, "si")
+        .map(|si| elements(si.content, "t").map(|t| unescape(t.content)).collect())
+        .collect()
+}
+
+/// XLSX最大列数（XFD列）
+const MAX_COLUMNS: usize = 16384;
+
+/// 列标（如"AB12"）转换为从0开始的列序号，缺少列字母或超出XFD列时返回None
+fn column_index(reference: &str) -> Option<usize> {
+    reference
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .try_fold(0, |index, c| {
+            let index = index * 26 + (c.to_ascii_uppercase() as usize - 'A' as usize + 1);
+            (index <= MAX_COLUMNS).then_some(index)
+        })
+        .and_then(|index: usize| index.checked_sub(1))
+}
+
+/// XML元素：起始标签中的属性部分及元素内容
+struct Element<'a> {
+    attributes: &'a str,
+    content: &'a str,
+}
+
+impl Element<'_> {
+    /// 读取属性值
+    fn attribute(&self, name: &str) -> Option<String> {
+        let pattern = format!("{}=\"", name);
+        let mut search = self.attributes;
+        while let Some(position) = search.find(&pattern) {
+            let value = &search[position + pattern.len()..];
+            if position == 0 || search[..position].ends_with(char::is_whitespace) {
+                return value.find('"').map(|end| unescape(&value[..end]));
+            }
+            search = value;
+        }
+        None
+    }
+
+    /// 第一个指定子元素的内容
+    fn child_text(&self, name: &str) -> Option<&str> {
+        elements(self.content, name).next().map(|child| child.content)
+    }
+}
+
+/// 依次返回指定名称的元素
+///
+/// 表格部件结构简单，按标签名扫描即可，不需要完整的XML解析
+fn elements<'a>(xml: &'a str, name: &str) -> impl Iterator<Item = Element<'a>> {
+    let open = format!("<{}", name);
+    let close = format!("</{}>", name);
+    let mut rest = xml;
+    std::iter::from_fn(move || loop {
+        let start = rest.find(&open)?;
+        let after = &rest[start + open.len()..];
+        // 排除名称前缀相同的其他元素（如<c>与<cols>）
+        if !after.starts_with(|c: char| c == '>' || c == '/' || c.is_whitespace()) {
+            rest = after;
+            continue;
+        }
+        let tag_end = after.find('>')?;
+        if after[..tag_end].ends_with('/') {
+            rest = &after[tag_end + 1..];
+            return Some(Element { attributes: &after[..tag_end - 1], content: "" });
+        }
+        let body = &after[tag_end + 1..];
+        let end = body.find(&close).unwrap_or(body.len());
+        rest = &body[(end + close.len()).min(body.len())..];
+        return Some(Element { attributes: &after[..tag_end], content: &body[..end] });
+    })
+}
+
+/// 还原XML实体
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        let entity_end = rest[start..].find(';').map(|i| start + i);
+        let decoded = entity_end.and_then(|end| match &rest[start + 1..end] {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            entity => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse::<u32>()))
+                .and_then(|code| code.ok())
+                .and_then(char::from_u32),
+        });
+        match (decoded, entity_end) {
+            (Some(c), Some(end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            _ => {
+                out.push('&');
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// ZIP压缩包中的文件条目
+struct ZipEntry {
+    method: u16,
+    compressed_size: usize,
+    local_header_offset: usize,
+}
+
+/// 只读ZIP压缩包，支持不压缩（0）和DEFLATE（8）两种存储方式
+struct ZipArchive<'a> {
+    data: &'a [u8],
+    entries: HashMap<String, ZipEntry>,
+}
+
+/// 中央目录结束记录签名
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+/// 中央目录文件头签名
+const CENTRAL_DIRECTORY_HEADER: u32 = 0x0201_4b50;
+/// 本地文件头签名
+const LOCAL_FILE_HEADER: u32 = 0x0403_4b50;
+
+fn zip_error(description: &str) -> ImportError {
+    ImportError::format("XLSX", description)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| zip_error("压缩包数据不完整"))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| zip_error("压缩包数据不完整"))
+}
+
+impl<'a> ZipArchive<'a> {
+    fn new(data: &'a [u8]) -> Result<Self> {
+        // 中央目录结束记录位于文件末尾，其后最多有65535字节的注释
+        let search_start = data.len().saturating_sub(22 + u16::MAX as usize);
+        let end = (search_start..data.len().saturating_sub(21))
+            .rev()
+            .find(|&i| read_u32(data, i).ok() == Some(END_OF_CENTRAL_DIRECTORY))
+            .ok_or_else(|| zip_error("不是有效的XLSX文件"))?;
+        let count = read_u16(data, end + 10)? as usize;
+        let mut offset = read_u32(data, end + 16)? as usize;
+
+        let mut entries = HashMap::new();
+        for _ in 0..count {
+            if read_u32(data, offset)? != CENTRAL_DIRECTORY_HEADER {
+                return Err(zip_error("中央目录损坏"));
+            }
+            let name_length = read_u16(data, offset + 28)? as usize;
+            let extra_length = read_u16(data, offset + 30)? as usize;
+            let comment_length = read_u16(data, offset + 32)? as usize;
+            let name = data
+                .get(offset + 46..offset + 46 + name_length)
+                .ok_or_else(|| zip_error("中央目录损坏"))?;
+            entries.insert(
+                String::from_utf8_lossy(name).into_owned(),
+                ZipEntry {
+                    method: read_u16(data, offset + 10)?,
+                    compressed_size: read_u32(data, offset + 20)? as usize,
+                    local_header_offset: read_u32(data, offset + 42)? as usize,
+                },
+            );
+            offset += 46 + name_length + extra_length + comment_length;
+        }
+        Ok(Self { data, entries })
+    }
+
+    /// 读取文本部件，部件不存在时返回None
+    fn read(&self, name: &str) -> Result<Option<String>> {
+        let Some(entry) = self.entries.get(name) else {
+            return Ok(None);
+        };
+        let offset = entry.local_header_offset;
+        if read_u32(self.data, offset)? != LOCAL_FILE_HEADER {
+            return Err(zip_error("本地文件头损坏"));
+        }
+        let start = offset + 30 + read_u16(self.data, offset + 26)? as usize + read_u16(self.data, offset + 28)? as usize;
+        let compressed = self
+            .data
+            .get(start..start + entry.compressed_size)
+            .ok_or_else(|| zip_error("压缩包数据不完整"))?;
+        let bytes = match entry.method {
+            0 => compressed.to_vec(),
+            8 => inflate(compressed)?,
+            method => return Err(zip_error(&format!("不支持的压缩方式 {}", method))),
+        };
+        String::from_utf8(bytes)
+            .map(Some)
+            .map_err(|_| zip_error(&format!("{} 不是UTF-8编码", name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::xlsx::{CellValue, Workbook};
+
+    #[test]
+    fn test_parse_csv() {
+        let rows = parse_csv("\u{FEFF}箱名,回路,备注\r\nAL1,\"照明,走廊\",\"含\"\"引号\"\"\n换行\"\r\n,,\r\nAL2,插座,");
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], vec!["箱名", "回路", "备注"]);
+        assert_eq!(rows[1], vec!["AL1", "照明,走廊", "含\"引号\"\n换行"]);
+        assert_eq!(rows[2], vec!["AL2", "插座", ""]);
+    }
+
+    #[test]
+    fn test_read_xlsx_written_by_exporter() {
+        let mut workbook = Workbook::new();
+        workbook.add_sheet(
+            "负荷清单",
+            vec![
+                vec![CellValue::text("箱名"), CellValue::text("Pe & Kx")],
+                vec![CellValue::text("AL1"), CellValue::Empty, CellValue::Number(2.5)],
+            ],
+            1,
+        );
+        let path = std::env::temp_dir().join(format!("pdsd_import_{}.xlsx", std::process::id()));
+        workbook.save(&path).unwrap();
+        let rows = read_table(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(rows, vec![vec!["箱名", "Pe & Kx"], vec!["AL1", "", "2.5"]]);
+        assert_eq!(column_index("AB12"), Some(27));
+        assert_eq!(column_index("XFD1"), Some(16383));
+        assert_eq!(column_index("XFE1"), None);
+        assert_eq!(column_index("AAAAAAAAAAAAAAAA1"), None);
+        assert_eq!(column_index("12"), None);
+    }
+}
@@ -2,6 +2,9 @@
 //! 
 //! 提供基于节点编辑器的建筑电气配电系统图设计功能
 
+// 包名沿用项目名称，库名随之为非蛇形命名
+#![allow(non_snake_case)]
+
 // 导出编辑器相关模块
 pub mod editor;
 
@@ -19,4 +22,7 @@ pub mod error_handler;
 pub mod config;
 
 // 导出导出功能
-pub mod export;
\ No newline at end of file
+pub mod export;
+
+// 导出导入功能
+pub mod import;
\ No newline at end of file
@@ -1,6 +1,8 @@
 use eframe::egui;
-pub mod application;
-use application::app::PDSDApp;
+use Power_Distribution_System_Diagram::application::app::PDSDApp;
+
+/// 中文字体文件路径
+const FONT_PATH: &str = "assets/fonts/msyh.ttc";
 
 /// 程序入口点
 fn main() -> Result<(), eframe::Error> {
@@ -28,29 +30,21 @@ fn main() -> Result<(), eframe::Error> {
             // 配置字体以支持中文
             let mut fonts = egui::FontDefinitions::default();
             
-            // 使用项目assets目录中的微软雅黑字体支持中文
-            fonts.font_data.insert(
-                "msyh_font".to_owned(),
-                egui::FontData::from_static(
-                    include_bytes!("../assets/fonts/msyh.ttc") as &[u8],
-                ).into(),
-            );
-            
-            // 将微软雅黑字体添加到默认字体家族
-            fonts.families.insert(
-                egui::FontFamily::Proportional,
-                vec![
-                    "msyh_font".to_owned(),
-                ],
-            );
-            
-            // 也添加到等宽字体家族以支持代码显示
-            fonts.families.insert(
-                egui::FontFamily::Monospace,
-                vec![
-                    "msyh_font".to_owned(),
-                ],
-            );
+            // 使用项目assets目录中的微软雅黑字体支持中文（字体文件不随仓库分发，缺失时使用默认字体）
+            match std::fs::read(FONT_PATH) {
+                Ok(font_bytes) => {
+                    fonts.font_data.insert(
+                        "msyh_font".to_owned(),
+                        egui::FontData::from_owned(font_bytes).into(),
+                    );
+                    
+                    // 将微软雅黑字体放在默认字体家族和等宽字体家族的首位
+                    for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+                        fonts.families.entry(family).or_default().insert(0, "msyh_font".to_owned());
+                    }
+                },
+                Err(err) => eprintln!("未找到中文字体{}（{}），使用默认字体", FONT_PATH, err),
+            }
             
             cc.egui_ctx.set_fonts(fonts);
 
@@ -4,23 +4,22 @@
 
 use crate::editor::business::distribution_box_parameters::{DistributionBoxNode, IncomingType};
 use crate::editor::business::main_system_node::{MainSystemNodeUI, MainSystemType, ConnectionType, ComponentType};
+use crate::editor::business::box_modules::{BoxModule, ModuleKind};
 
 /// 测试配电箱进线类型自动识别功能
 #[test]
 fn test_determine_incoming_type() {
     // 测试单电源配电箱
     let mut single_power_box = DistributionBoxNode::new("普通配电箱1号".to_string(), 1);
-    single_power_box.modules.push("断路器".to_string());
-    single_power_box.modules.push("浪涌保护器".to_string());
+    single_power_box.add_module(BoxModule::default_for(ModuleKind::Spd));
     
     // 测试双电源配电箱
     let mut dual_power_box = DistributionBoxNode::new("消防配电箱1号".to_string(), 1);
-    dual_power_box.modules.push("双电源切换".to_string());
-    dual_power_box.modules.push("断路器".to_string());
+    dual_power_box.add_module(BoxModule::default_for(ModuleKind::DualPowerSwitch));
     
     // 测试带英文标识的双电源配电箱
     let mut dual_power_box_en = DistributionBoxNode::new("Fire Distribution Box".to_string(), 2);
-    dual_power_box_en.modules.push("dual power transfer switch".to_string());
+    dual_power_box_en.add_module(BoxModule::from_legacy_name("dual power transfer switch").unwrap());
     
     // 验证识别结果
     assert_eq!(single_power_box.determine_incoming_type(), IncomingType::SinglePower);
@@ -42,17 +41,15 @@ fn test_auto_generate_connections() {
     let mut box1 = DistributionBoxNode::new("一层普通配电箱".to_string(), 1);
     box1.total_power = 50.0;
     box1.total_current = 75.8;
-    box1.modules.push("断路器".to_string());
     
     let mut box2 = DistributionBoxNode::new("二层消防配电箱".to_string(), 2);
     box2.total_power = 30.0;
     box2.total_current = 45.5;
-    box2.modules.push("双电源切换".to_string());
+    box2.add_module(BoxModule::default_for(ModuleKind::DualPowerSwitch));
     
     let mut box3 = DistributionBoxNode::new("三层普通配电箱".to_string(), 3);
     box3.total_power = 40.0;
     box3.total_current = 60.6;
-    box3.modules.push("断路器".to_string());
     
     // 收集配电箱引用
     let boxes = vec![&box1, &box2, &box3];
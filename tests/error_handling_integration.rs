@@ -53,11 +53,9 @@ fn test_error_handling_core_logic() {
     // 5. 测试错误代码处理
     let error_codes = vec![Some("GENERIC"), Some("IO-ERROR"), Some("UNKNOWN"), None];
     
-    for code in error_codes {
-        match code {
-            Some(c) => assert!(!c.is_empty()),
-            None => assert!(true), // None 也是有效的错误代码值
-        }
+    // None 也是有效的错误代码值
+    for c in error_codes.into_iter().flatten() {
+        assert!(!c.is_empty());
     }
 }
 
@@ -129,9 +127,9 @@ fn test_error_classification() {
         assert!(!error.category.is_empty());
         assert!(!error.message.is_empty());
         
-        match &error.context {
-            Some(ctx) => assert!(!ctx.is_empty()),
-            None => assert!(true), // None 也是有效的上下文值
+        // None 也是有效的上下文值
+        if let Some(ctx) = &error.context {
+            assert!(!ctx.is_empty());
         }
         
         // 验证不同类别的错误有不同的处理方式